@@ -9,7 +9,8 @@ typedef enum {
     STATUS_RECORDING = 0,
     STATUS_TRANSCRIBING = 1,
     STATUS_COMPLETED = 2,
-    STATUS_ERROR = 3
+    STATUS_ERROR = 3,
+    STATUS_PAUSED = 4
 } StatusType;
 
 /**
@@ -20,6 +20,7 @@ static const CGFloat STATUS_COLORS[][3] = {
     {59.0/255.0, 130.0/255.0, 246.0/255.0},   // Transcribing - Blue #3b82f6
     {16.0/255.0, 185.0/255.0, 129.0/255.0},   // Completed - Green #10b981
     {245.0/255.0, 158.0/255.0, 11.0/255.0},   // Error - Orange #f59e0b
+    {107.0/255.0, 114.0/255.0, 128.0/255.0},  // Paused - Gray #6b7280
 };
 
 // Global state
@@ -31,6 +31,7 @@ const Gdiplus::Color STATUS_COLORS[] = {
     Gdiplus::Color(255, 59, 130, 246),  // Transcribing - Blue
     Gdiplus::Color(255, 16, 185, 129),  // Completed - Green
     Gdiplus::Color(255, 245, 158, 11),  // Error - Orange
+    Gdiplus::Color(255, 107, 114, 128), // Paused - Gray
 };
 
 // Global state
@@ -0,0 +1,122 @@
+//! 当前前台窗口探测
+//!
+//! 返回前台应用名与窗口标题，供触发卡片按应用过滤等使用。各平台实现不引入额外依赖，
+//! 探测失败时返回 `None`。
+
+#[derive(Clone, Debug, Default)]
+pub struct ActiveWindow {
+    /// 应用名（Windows 为可执行文件名，macOS 为进程名，Linux 为进程 comm）
+    pub app_name: String,
+    pub title: String,
+}
+
+#[cfg(target_os = "windows")]
+pub fn detect() -> Option<ActiveWindow> {
+    use std::ffi::c_void;
+    use std::path::Path;
+
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetForegroundWindow() -> *mut c_void;
+        fn GetWindowTextW(hwnd: *mut c_void, text: *mut u16, max_count: i32) -> i32;
+        fn GetWindowThreadProcessId(hwnd: *mut c_void, process_id: *mut u32) -> u32;
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn OpenProcess(access: u32, inherit: i32, process_id: u32) -> *mut c_void;
+        fn QueryFullProcessImageNameW(
+            process: *mut c_void,
+            flags: u32,
+            name: *mut u16,
+            size: *mut u32,
+        ) -> i32;
+        fn CloseHandle(handle: *mut c_void) -> i32;
+    }
+
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd.is_null() {
+        return None;
+    }
+
+    let mut title = vec![0u16; 512];
+    let title_len = unsafe { GetWindowTextW(hwnd, title.as_mut_ptr(), title.len() as i32) };
+    let title = String::from_utf16_lossy(&title[..title_len.max(0) as usize]);
+
+    let mut process_id = 0u32;
+    unsafe { GetWindowThreadProcessId(hwnd, &mut process_id) };
+    let mut app_name = String::new();
+    if process_id != 0 {
+        let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id) };
+        if !process.is_null() {
+            let mut buffer = vec![0u16; 1024];
+            let mut size = buffer.len() as u32;
+            let ok =
+                unsafe { QueryFullProcessImageNameW(process, 0, buffer.as_mut_ptr(), &mut size) };
+            unsafe { CloseHandle(process) };
+            if ok != 0 {
+                let path = String::from_utf16_lossy(&buffer[..size as usize]);
+                app_name = Path::new(&path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or(path);
+            }
+        }
+    }
+    Some(ActiveWindow { app_name, title })
+}
+
+#[cfg(target_os = "macos")]
+pub fn detect() -> Option<ActiveWindow> {
+    const SCRIPT: &str = r#"tell application "System Events"
+    set frontApp to first application process whose frontmost is true
+    set appName to name of frontApp
+    set windowTitle to ""
+    try
+        set windowTitle to name of front window of frontApp
+    end try
+end tell
+return appName & linefeed & windowTitle"#;
+
+    let output = std::process::Command::new("osascript")
+        .arg("-e")
+        .arg(SCRIPT)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let app_name = lines.next()?.trim().to_string();
+    let title = lines.next().unwrap_or_default().trim().to_string();
+    Some(ActiveWindow { app_name, title })
+}
+
+/// X11 下通过 xdotool 查询；Wayland 没有通用接口，返回 None
+#[cfg(target_os = "linux")]
+pub fn detect() -> Option<ActiveWindow> {
+    let run = |args: &[&str]| {
+        let output = std::process::Command::new("xdotool")
+            .args(args)
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let title = run(&["getactivewindow", "getwindowname"])?;
+    let app_name = run(&["getactivewindow", "getwindowpid"])
+        .and_then(|pid| std::fs::read_to_string(format!("/proc/{pid}/comm")).ok())
+        .map(|comm| comm.trim().to_string())
+        .unwrap_or_default();
+    Some(ActiveWindow { app_name, title })
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub fn detect() -> Option<ActiveWindow> {
+    None
+}
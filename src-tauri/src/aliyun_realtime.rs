@@ -1,3 +1,4 @@
+use crate::provider_error::ProviderFailure;
 use crate::settings::Settings;
 use hound::{SampleFormat, WavReader};
 use serde_json::{json, Value};
@@ -29,6 +30,8 @@ pub enum AliyunRealtimeError {
     Io(String),
     #[error("WebSocket 错误: {0}")]
     WebSocket(String),
+    #[error("阿里云拒绝请求: {0}")]
+    Rejected(#[from] ProviderFailure),
 }
 
 #[derive(Clone, Copy)]
@@ -64,18 +67,22 @@ pub fn transcribe_paraformer(
     transcribe_realtime(settings, audio_path, ProviderKind::Paraformer)
 }
 
+/// 校验 Fun-ASR 的地域与 API Key 配置，不发起网络请求
+pub fn ensure_asr_config(settings: &Settings) -> Result<(), AliyunRealtimeError> {
+    ensure_config(settings, ProviderKind::FunAsr).map(|_| ())
+}
+
+/// 校验 Paraformer 的地域与 API Key 配置，不发起网络请求
+pub fn ensure_paraformer_config(settings: &Settings) -> Result<(), AliyunRealtimeError> {
+    ensure_config(settings, ProviderKind::Paraformer).map(|_| ())
+}
+
 fn transcribe_realtime(
     settings: &Settings,
     audio_path: &Path,
     provider: ProviderKind,
 ) -> Result<String, AliyunRealtimeError> {
-    let region = normalize_region(&settings.aliyun.region);
-    if matches!(provider, ProviderKind::Paraformer) && region == ALIYUN_REGION_SINGAPORE {
-        return Err(AliyunRealtimeError::Config(
-            "Paraformer 仅支持北京地域".to_string(),
-        ));
-    }
-    let api_key = resolve_api_key(settings, region)?;
+    let (region, api_key) = ensure_config(settings, provider)?;
     let endpoint = resolve_endpoint(region)?;
 
     let mut request = endpoint
@@ -91,8 +98,16 @@ fn transcribe_realtime(
         "X-DashScope-DataInspection",
         HeaderValue::from_static("disable"),
     );
-    let (mut socket, _) =
-        connect(request).map_err(|err| AliyunRealtimeError::WebSocket(err.to_string()))?;
+    // 鉴权失败、额度不足等在握手阶段以 HTTP 状态返回
+    let (mut socket, _) = connect(request).map_err(|err| match &err {
+        tungstenite::Error::Http(response) => {
+            ProviderFailure::classify(response.status().as_u16(), "").map_or_else(
+                || AliyunRealtimeError::WebSocket(err.to_string()),
+                AliyunRealtimeError::Rejected,
+            )
+        }
+        _ => AliyunRealtimeError::WebSocket(err.to_string()),
+    })?;
 
     let task_id = crate::util::timestamp_id();
     let start_message = build_run_task_message(settings, provider, &task_id);
@@ -128,6 +143,21 @@ fn transcribe_realtime(
     Ok(text)
 }
 
+/// 返回规范化后的地域与对应的 API Key
+fn ensure_config(
+    settings: &Settings,
+    provider: ProviderKind,
+) -> Result<(&str, String), AliyunRealtimeError> {
+    let region = normalize_region(&settings.aliyun.region);
+    if matches!(provider, ProviderKind::Paraformer) && region == ALIYUN_REGION_SINGAPORE {
+        return Err(AliyunRealtimeError::Config(
+            "Paraformer 仅支持北京地域".to_string(),
+        ));
+    }
+    let api_key = resolve_api_key(settings, region)?;
+    Ok((region, api_key))
+}
+
 fn resolve_api_key(settings: &Settings, region: &str) -> Result<String, AliyunRealtimeError> {
     let key = if region == ALIYUN_REGION_SINGAPORE {
         settings.aliyun.api_keys.singapore.trim()
@@ -0,0 +1,219 @@
+//! 留存录音的静态加密
+//!
+//! 离线待转写队列与失败分段重试队列中的录音保存在应用数据目录，使用设置密钥按块做 AES-256-GCM
+//! 流式加密（STREAM 构造：nonce 由随机前缀、块序号与末块标记组成，可检出块的重排与截断），
+//! 补转写前再解密到临时文件。没有文件头标记的旧版明文 WAV 按原样读取。
+
+use aes_gcm::aead::Aead;
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use rand::RngCore;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 8] = b"VTTAUD01";
+const NONCE_PREFIX_LEN: usize = 7;
+/// 每块明文长度
+const CHUNK_LEN: usize = 64 * 1024;
+const TAG_LEN: usize = 16;
+
+/// 将 `source` 的内容加密写入 `target`；失败时删除写了一半的文件
+pub fn encrypt_to_file(source: impl Read, target: &Path, key: &[u8; 32]) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let result = File::create(target)
+        .map_err(|err| err.to_string())
+        .and_then(|file| encrypt_stream(source, BufWriter::new(file), key));
+    if result.is_err() {
+        let _ = fs::remove_file(target);
+    }
+    result
+}
+
+/// 将加密录音解密到 `dir` 下的临时文件，供转写引擎按路径读取
+pub fn decrypt_to_temp(source: &Path, dir: &Path, key: &[u8; 32]) -> Result<DecryptedFile, String> {
+    fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+    let file = DecryptedFile {
+        path: dir.join(format!("decrypted-{}.wav", crate::util::timestamp_id())),
+    };
+    let reader = File::open(source).map_err(|err| err.to_string())?;
+    let writer = File::create(&file.path).map_err(|err| err.to_string())?;
+    decrypt_stream(reader, BufWriter::new(writer), key)?;
+    Ok(file)
+}
+
+/// 解密得到的临时明文文件，释放时删除
+pub struct DecryptedFile {
+    path: PathBuf,
+}
+
+impl DecryptedFile {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for DecryptedFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn encrypt_stream(
+    mut reader: impl Read,
+    mut writer: impl Write,
+    key: &[u8; 32],
+) -> Result<(), String> {
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|err| err.to_string())?;
+    let mut prefix = [0u8; NONCE_PREFIX_LEN];
+    rand::thread_rng().fill_bytes(&mut prefix);
+    writer.write_all(MAGIC).map_err(|err| err.to_string())?;
+    writer.write_all(&prefix).map_err(|err| err.to_string())?;
+
+    let mut current = vec![0u8; CHUNK_LEN];
+    let mut next = vec![0u8; CHUNK_LEN];
+    let mut len = read_full(&mut reader, &mut current).map_err(|err| err.to_string())?;
+    let mut counter = 0u32;
+    loop {
+        // 预读下一块以确定当前块是否为末块
+        let next_len = if len == CHUNK_LEN {
+            read_full(&mut reader, &mut next).map_err(|err| err.to_string())?
+        } else {
+            0
+        };
+        let last = next_len == 0;
+        let nonce = chunk_nonce(&prefix, counter, last);
+        let sealed = cipher
+            .encrypt(Nonce::from_slice(&nonce), &current[..len])
+            .map_err(|err| format!("录音加密失败: {err}"))?;
+        writer.write_all(&sealed).map_err(|err| err.to_string())?;
+        if last {
+            break;
+        }
+        counter = counter
+            .checked_add(1)
+            .ok_or_else(|| "录音过长，无法加密".to_string())?;
+        std::mem::swap(&mut current, &mut next);
+        len = next_len;
+    }
+    writer.flush().map_err(|err| err.to_string())
+}
+
+fn decrypt_stream(
+    mut reader: impl Read,
+    mut writer: impl Write,
+    key: &[u8; 32],
+) -> Result<(), String> {
+    let mut header = [0u8; MAGIC.len() + NONCE_PREFIX_LEN];
+    let header_len = read_full(&mut reader, &mut header).map_err(|err| err.to_string())?;
+    if header_len < MAGIC.len() || &header[..MAGIC.len()] != MAGIC {
+        // 加密功能加入前保存的明文录音
+        writer
+            .write_all(&header[..header_len])
+            .map_err(|err| err.to_string())?;
+        io::copy(&mut reader, &mut writer).map_err(|err| err.to_string())?;
+        return writer.flush().map_err(|err| err.to_string());
+    }
+    if header_len < header.len() {
+        return Err("加密录音文件已损坏".to_string());
+    }
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|err| err.to_string())?;
+    let prefix = &header[MAGIC.len()..];
+
+    let mut current = vec![0u8; CHUNK_LEN + TAG_LEN];
+    let mut next = vec![0u8; CHUNK_LEN + TAG_LEN];
+    let mut len = read_full(&mut reader, &mut current).map_err(|err| err.to_string())?;
+    let mut counter = 0u32;
+    loop {
+        let next_len = if len == current.len() {
+            read_full(&mut reader, &mut next).map_err(|err| err.to_string())?
+        } else {
+            0
+        };
+        let last = next_len == 0;
+        let nonce = chunk_nonce(prefix, counter, last);
+        let plain = cipher
+            .decrypt(Nonce::from_slice(&nonce), &current[..len])
+            .map_err(|_| "加密录音解密失败，文件已损坏或密钥不匹配".to_string())?;
+        writer.write_all(&plain).map_err(|err| err.to_string())?;
+        if last {
+            break;
+        }
+        counter = counter
+            .checked_add(1)
+            .ok_or_else(|| "加密录音文件已损坏".to_string())?;
+        std::mem::swap(&mut current, &mut next);
+        len = next_len;
+    }
+    writer.flush().map_err(|err| err.to_string())
+}
+
+/// nonce = 随机前缀(7) + 块序号(4, 大端) + 末块标记(1)
+fn chunk_nonce(prefix: &[u8], counter: u32, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LEN..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = u8::from(last);
+    nonce
+}
+
+/// 尽量读满缓冲区，返回实际读取的字节数；小于缓冲区长度表示已到文件末尾
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(plain: &[u8], key: &[u8; 32]) -> Result<Vec<u8>, String> {
+        let mut sealed = Vec::new();
+        encrypt_stream(plain, &mut sealed, key)?;
+        let mut opened = Vec::new();
+        decrypt_stream(sealed.as_slice(), &mut opened, key)?;
+        Ok(opened)
+    }
+
+    #[test]
+    fn stream_round_trips_across_chunk_boundaries() {
+        let key = [7u8; 32];
+        for len in [0, 1, CHUNK_LEN, CHUNK_LEN * 2 + 5] {
+            let plain = (0..len)
+                .map(|index| (index % 251) as u8)
+                .collect::<Vec<_>>();
+            assert_eq!(round_trip(&plain, &key).unwrap(), plain);
+        }
+    }
+
+    #[test]
+    fn stream_rejects_truncation_and_wrong_key() {
+        let key = [7u8; 32];
+        let plain = vec![1u8; CHUNK_LEN * 2];
+        let mut sealed = Vec::new();
+        encrypt_stream(plain.as_slice(), &mut sealed, &key).unwrap();
+
+        // 在块边界截断
+        let header = MAGIC.len() + NONCE_PREFIX_LEN;
+        let truncated = &sealed[..header + CHUNK_LEN + TAG_LEN];
+        assert!(decrypt_stream(truncated, &mut Vec::new(), &key).is_err());
+        assert!(decrypt_stream(sealed.as_slice(), &mut Vec::new(), &[8u8; 32]).is_err());
+    }
+
+    #[test]
+    fn legacy_plaintext_is_passed_through() {
+        let plain = b"RIFF legacy wav".to_vec();
+        let mut opened = Vec::new();
+        decrypt_stream(plain.as_slice(), &mut opened, &[7u8; 32]).unwrap();
+        assert_eq!(opened, plain);
+    }
+}
@@ -0,0 +1,135 @@
+//! 音频文件格式探测
+//!
+//! 导入的文件常被误标扩展名，因此按文件头魔数识别 WAV/MP3/OGG/FLAC/M4A，
+//! 仅在无法识别时才退回扩展名，供各转写提供商与批量导入共用。
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// 识别格式所需的文件头长度
+const SNIFF_HEADER_BYTES: usize = 12;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AudioFormat {
+    Wav,
+    Mp3,
+    Ogg,
+    Flac,
+    M4a,
+}
+
+impl AudioFormat {
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Wav => "wav",
+            Self::Mp3 => "mp3",
+            Self::Ogg => "ogg",
+            Self::Flac => "flac",
+            Self::M4a => "m4a",
+        }
+    }
+
+    pub const fn mime(self) -> &'static str {
+        match self {
+            Self::Wav => "audio/wav",
+            Self::Mp3 => "audio/mpeg",
+            Self::Ogg => "audio/ogg",
+            Self::Flac => "audio/flac",
+            Self::M4a => "audio/mp4",
+        }
+    }
+
+    fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "wav" => Some(Self::Wav),
+            "mp3" => Some(Self::Mp3),
+            "ogg" | "opus" => Some(Self::Ogg),
+            "flac" => Some(Self::Flac),
+            "m4a" | "mp4" => Some(Self::M4a),
+            _ => None,
+        }
+    }
+}
+
+/// 按文件头魔数识别格式
+pub fn sniff_bytes(header: &[u8]) -> Option<AudioFormat> {
+    match header {
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => Some(AudioFormat::Wav),
+        [b'O', b'g', b'g', b'S', ..] => Some(AudioFormat::Ogg),
+        [b'f', b'L', b'a', b'C', ..] => Some(AudioFormat::Flac),
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => Some(AudioFormat::M4a),
+        [b'I', b'D', b'3', ..] => Some(AudioFormat::Mp3),
+        // 无 ID3 标签的 MP3 以 11 位帧同步字开头
+        [0xFF, second, ..] if second & 0xE0 == 0xE0 => Some(AudioFormat::Mp3),
+        _ => None,
+    }
+}
+
+/// 读取文件头识别格式，文件无法读取或格式未知时返回 None
+pub fn sniff_file(path: &Path) -> Option<AudioFormat> {
+    let mut header = Vec::with_capacity(SNIFF_HEADER_BYTES);
+    File::open(path)
+        .ok()?
+        .take(SNIFF_HEADER_BYTES as u64)
+        .read_to_end(&mut header)
+        .ok()?;
+    sniff_bytes(&header)
+}
+
+/// 优先按文件内容识别格式，无法识别时退回扩展名
+pub fn detect_format(path: &Path) -> Option<AudioFormat> {
+    sniff_file(path).or_else(|| {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(AudioFormat::from_extension)
+    })
+}
+
+/// 上传用文件名：扩展名与实际格式一致，服务端按扩展名解码时不会误判
+pub fn upload_file_name(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("recording");
+    let format = detect_format(path).unwrap_or(AudioFormat::Wav);
+    format!("{stem}.{}", format.extension())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniff_bytes_recognizes_magic_numbers() {
+        assert_eq!(
+            sniff_bytes(b"RIFF\x24\0\0\0WAVEfmt "),
+            Some(AudioFormat::Wav)
+        );
+        assert_eq!(sniff_bytes(b"ID3\x04\0\0"), Some(AudioFormat::Mp3));
+        assert_eq!(
+            sniff_bytes(&[0xFF, 0xFB, 0x90, 0x00]),
+            Some(AudioFormat::Mp3)
+        );
+        assert_eq!(sniff_bytes(b"OggS\0\x02"), Some(AudioFormat::Ogg));
+        assert_eq!(sniff_bytes(b"fLaC\0\0\0\x22"), Some(AudioFormat::Flac));
+        assert_eq!(sniff_bytes(b"\0\0\0\x20ftypM4A "), Some(AudioFormat::M4a));
+        assert_eq!(sniff_bytes(b"RIFF\x24\0\0\0AVI "), None);
+        assert_eq!(sniff_bytes(b""), None);
+    }
+
+    #[test]
+    fn detect_format_prefers_content_over_extension() {
+        let dir = std::env::temp_dir().join("vtt-keyboard-audio-inspect-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let mislabeled = dir.join("clip.wav");
+        std::fs::write(&mislabeled, b"ID3\x04\0\0\0\0\0\0\0\0").unwrap();
+        assert_eq!(detect_format(&mislabeled), Some(AudioFormat::Mp3));
+        assert_eq!(upload_file_name(&mislabeled), "clip.mp3");
+
+        let unknown = dir.join("clip.flac");
+        std::fs::write(&unknown, b"not audio").unwrap();
+        assert_eq!(detect_format(&unknown), Some(AudioFormat::Flac));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
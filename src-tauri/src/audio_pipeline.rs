@@ -0,0 +1,525 @@
+//! 转写前的音频预处理链
+//!
+//! 按设置中的顺序依次执行裁剪静音、降噪（噪声门）、音量归一化、重采样与变速，
+//! 各步骤参数可调。样本为交错排列的 16-bit PCM。
+
+use crate::recorder::RecordedAudio;
+use crate::settings::{AudioPipelineSettings, AudioPipelineStep};
+
+/// 噪声门分析窗口
+const GATE_WINDOW_MS: u32 = 10;
+/// 噪声门对低于阈值窗口的衰减系数
+const GATE_ATTENUATION: f32 = 0.1;
+/// 归一化的最大增益（约 +20 dB），避免把近乎静音的录音放大成噪声
+const MAX_NORMALIZE_GAIN: f32 = 10.0;
+/// 寻找切点时的短时能量窗口
+const ENERGY_WINDOW_MS: u32 = 20;
+/// 变速的片段窗口，覆盖低沉男声的数个基音周期
+const TEMPO_WINDOW_MS: usize = 30;
+/// 变速时在名义位置前后寻找相似波形的范围，不小于一个基音周期
+const TEMPO_SEARCH_MS: usize = 10;
+/// 变速比较波形相似度时的抽样率
+const TEMPO_ANALYSIS_RATE: usize = 8_000;
+
+pub struct PipelineOutput {
+    pub audio: RecordedAudio,
+    /// 开头被裁掉的时长，用于将转写时间戳还原到原始录音
+    pub trimmed_start_ms: u64,
+    /// 变速的时长压缩比（原时长 / 变速后时长），转写时间戳乘以该值还原到原始录音；未变速时为 1
+    pub tempo: f64,
+}
+
+pub fn apply(audio: &RecordedAudio, settings: &AudioPipelineSettings) -> PipelineOutput {
+    let mut output = PipelineOutput {
+        audio: audio.clone(),
+        trimmed_start_ms: 0,
+        tempo: 1.0,
+    };
+    if !settings.enabled || audio.is_empty() || audio.channels == 0 {
+        return output;
+    }
+    // 溢出到磁盘的长录音不做整段预处理，避免重新载入内存
+    if audio.spill.is_some() {
+        return output;
+    }
+    for step in &settings.steps {
+        match *step {
+            AudioPipelineStep::Trim {
+                threshold_db,
+                padding_ms,
+            } => {
+                let trimmed_frames = trim_silence(&mut output.audio, threshold_db, padding_ms);
+                let trimmed_ms =
+                    trimmed_frames as u64 * 1000 / u64::from(output.audio.sample_rate.max(1));
+                // 变速之后裁掉的时长换算回原始录音的时长
+                output.trimmed_start_ms += (trimmed_ms as f64 * output.tempo).round() as u64;
+            }
+            AudioPipelineStep::Denoise { threshold_db } => {
+                noise_gate(&mut output.audio, threshold_db);
+            }
+            AudioPipelineStep::Normalize { target_db } => {
+                normalize_peak(&mut output.audio.samples, target_db);
+            }
+            AudioPipelineStep::Resample { sample_rate, mono } => {
+                if mono {
+                    downmix_to_mono(&mut output.audio);
+                }
+                resample(&mut output.audio, sample_rate);
+            }
+            AudioPipelineStep::Tempo { rate } => {
+                output.tempo *= compress_tempo(&mut output.audio, rate);
+            }
+        }
+    }
+    output
+}
+
+/// 按短时能量切分交错样本：每段不超过 `max_frames` 帧，并在每段末尾 `search_frames`
+/// 范围内选择能量最低的窗口作为切点，避免从词语中间截断。返回各段的结束帧位置；
+/// `search_frames` 为 0 时退化为固定长度切分
+pub fn quiet_split_points(
+    samples: &[i16],
+    channels: u16,
+    sample_rate: u32,
+    max_frames: usize,
+    search_frames: usize,
+) -> Vec<usize> {
+    let channels = usize::from(channels.max(1));
+    let total = samples.len() / channels;
+    let window = (sample_rate * ENERGY_WINDOW_MS / 1000).max(1) as usize;
+    let max_frames = max_frames.max(1);
+    let search_frames = search_frames.min(max_frames / 2);
+    let energy = |start: usize| -> u64 {
+        samples[start * channels..(start + window) * channels]
+            .iter()
+            .map(|sample| u64::from(sample.unsigned_abs()).pow(2))
+            .sum()
+    };
+    let mut points = Vec::new();
+    let mut start = 0;
+    while total - start > max_frames {
+        let limit = start + max_frames;
+        let mut best = limit;
+        let mut best_energy = u64::MAX;
+        let mut candidate = limit - search_frames;
+        while candidate + window <= limit {
+            // 能量相同时取更靠后的窗口，使分段尽量接近上限长度
+            let value = energy(candidate);
+            if value <= best_energy {
+                best_energy = value;
+                best = candidate + window / 2;
+            }
+            candidate += window;
+        }
+        points.push(best);
+        start = best;
+    }
+    if total > start {
+        points.push(total);
+    }
+    points
+}
+
+/// 按短时能量在名义切点附近切分交错样本：每 `segment_frames` 帧一个名义切点，在其前后
+/// `search_frames` 范围内选择能量最低的窗口中点作为切点，能量相同时取离名义切点最近的窗口，
+/// 避免从词语中间截断。每段最长约 `segment_frames + search_frames` 帧，返回各段的结束帧位置；
+/// `search_frames` 为 0 时退化为固定长度切分
+pub fn boundary_split_points(
+    samples: &[i16],
+    channels: u16,
+    sample_rate: u32,
+    segment_frames: usize,
+    search_frames: usize,
+) -> Vec<usize> {
+    let channels = usize::from(channels.max(1));
+    let total = samples.len() / channels;
+    let window = (sample_rate * ENERGY_WINDOW_MS / 1000).max(1) as usize;
+    let segment_frames = segment_frames.max(1);
+    let search_frames = search_frames.min(segment_frames / 2);
+    let energy = |start: usize| -> u64 {
+        samples[start * channels..(start + window) * channels]
+            .iter()
+            .map(|sample| u64::from(sample.unsigned_abs()).pow(2))
+            .sum()
+    };
+    let mut points = Vec::new();
+    let mut start = 0;
+    while total - start > segment_frames {
+        let nominal = start + segment_frames;
+        // 窗口须落在本段开头之后，且不超出录音结尾与搜索范围
+        let limit = total.min(nominal + search_frames);
+        let mut best = nominal;
+        let mut best_energy = u64::MAX;
+        // 由近及远比较，能量相同时保留离名义切点更近的窗口
+        for distance in (0..=search_frames / window).map(|step| step * window) {
+            for center in [nominal - distance, nominal + distance] {
+                let window_start = center.saturating_sub(window / 2);
+                if window_start <= start || window_start + window > limit {
+                    continue;
+                }
+                let value = energy(window_start);
+                if value < best_energy {
+                    best_energy = value;
+                    best = center;
+                }
+            }
+        }
+        points.push(best);
+        start = best;
+    }
+    if total > start {
+        points.push(total);
+    }
+    points
+}
+
+pub(crate) fn db_to_amplitude(db: f32) -> f32 {
+    f32::from(i16::MAX) * 10f32.powf(db / 20.0)
+}
+
+/// 裁掉首尾低于阈值的静音（保留 `padding_ms` 余量），返回开头裁掉的帧数
+fn trim_silence(audio: &mut RecordedAudio, threshold_db: f32, padding_ms: u32) -> usize {
+    let channels = usize::from(audio.channels);
+    let threshold = db_to_amplitude(threshold_db);
+    let is_loud = |frame: &[i16]| {
+        frame
+            .iter()
+            .any(|sample| f32::from(*sample).abs() > threshold)
+    };
+    let frames = audio.samples.chunks(channels).collect::<Vec<_>>();
+    let Some(first) = frames.iter().position(|frame| is_loud(frame)) else {
+        // 全部为静音时保留原样，交由后续转写判断
+        return 0;
+    };
+    let last = frames
+        .iter()
+        .rposition(|frame| is_loud(frame))
+        .unwrap_or(first);
+    let padding = (audio.sample_rate as usize * padding_ms as usize) / 1000;
+    let start = first.saturating_sub(padding);
+    let end = (last + 1 + padding).min(frames.len());
+    audio.samples = audio.samples[start * channels..end * channels].to_vec();
+    start
+}
+
+/// 简易噪声门：按窗口计算 RMS，低于阈值的窗口整体衰减
+pub(crate) fn noise_gate(audio: &mut RecordedAudio, threshold_db: f32) {
+    let channels = usize::from(audio.channels);
+    let window = (audio.sample_rate * GATE_WINDOW_MS / 1000).max(1) as usize * channels;
+    let threshold = db_to_amplitude(threshold_db);
+    for chunk in audio.samples.chunks_mut(window) {
+        let energy = chunk
+            .iter()
+            .map(|sample| f64::from(*sample).powi(2))
+            .sum::<f64>();
+        let rms = (energy / chunk.len() as f64).sqrt() as f32;
+        if rms < threshold {
+            for sample in chunk.iter_mut() {
+                *sample = (f32::from(*sample) * GATE_ATTENUATION) as i16;
+            }
+        }
+    }
+}
+
+/// 峰值归一化到目标电平
+pub(crate) fn normalize_peak(samples: &mut [i16], target_db: f32) {
+    let peak = samples
+        .iter()
+        .map(|sample| f32::from(*sample).abs())
+        .fold(0.0, f32::max);
+    if peak == 0.0 {
+        return;
+    }
+    let gain = (db_to_amplitude(target_db) / peak).min(MAX_NORMALIZE_GAIN);
+    for sample in samples.iter_mut() {
+        let scaled = f32::from(*sample) * gain;
+        *sample = scaled.clamp(f32::from(i16::MIN), f32::from(i16::MAX)) as i16;
+    }
+}
+
+fn downmix_to_mono(audio: &mut RecordedAudio) {
+    if audio.channels <= 1 {
+        return;
+    }
+    audio.samples = downmix_samples(&audio.samples, usize::from(audio.channels));
+    audio.channels = 1;
+}
+
+/// 将交错的多声道样本按帧取平均混为单声道
+pub(crate) fn downmix_samples(samples: &[i16], channels: usize) -> Vec<i16> {
+    samples
+        .chunks(channels.max(1))
+        .map(|frame| {
+            let sum = frame.iter().map(|sample| i32::from(*sample)).sum::<i32>();
+            (sum / frame.len() as i32) as i16
+        })
+        .collect()
+}
+
+fn resample(audio: &mut RecordedAudio, target_rate: u32) {
+    if target_rate == 0 || audio.sample_rate == target_rate || audio.sample_rate == 0 {
+        return;
+    }
+    let channels = usize::from(audio.channels);
+    if audio.samples.len() / channels == 0 {
+        return;
+    }
+    audio.samples = resample_samples(&audio.samples, channels, audio.sample_rate, target_rate);
+    audio.sample_rate = target_rate;
+}
+
+/// 线性插值重采样交错样本
+pub(crate) fn resample_samples(
+    samples: &[i16],
+    channels: usize,
+    source_rate: u32,
+    target_rate: u32,
+) -> Vec<i16> {
+    let source_frames = samples.len() / channels;
+    if source_frames == 0 {
+        return Vec::new();
+    }
+    let ratio = f64::from(source_rate) / f64::from(target_rate);
+    let target_frames = (source_frames as f64 / ratio).floor() as usize;
+    let mut output = Vec::with_capacity(target_frames * channels);
+    for frame in 0..target_frames {
+        let position = frame as f64 * ratio;
+        let index = position.floor() as usize;
+        let next = (index + 1).min(source_frames - 1);
+        let fraction = position - index as f64;
+        for channel in 0..channels {
+            let current = f64::from(samples[index * channels + channel]);
+            let following = f64::from(samples[next * channels + channel]);
+            output.push((current + (following - current) * fraction).round() as i16);
+        }
+    }
+    output
+}
+
+/// WSOLA 变速：按倍率压缩时长而不改变音高，返回实际的时长压缩比（原帧数 / 变速后帧数）。
+/// 每隔半个窗口输出一个加 Hann 窗的片段，片段在输入中的名义位置按倍率前进，
+/// 并在名义位置附近选取与上一片段的自然延续最相似的位置，避免叠加时相位抵消
+fn compress_tempo(audio: &mut RecordedAudio, rate: f32) -> f64 {
+    let channels = usize::from(audio.channels.max(1));
+    let sample_rate = audio.sample_rate as usize;
+    let window = (sample_rate * TEMPO_WINDOW_MS / 1000) & !1;
+    let hop = window / 2;
+    let search = sample_rate * TEMPO_SEARCH_MS / 1000;
+    let total = audio.samples.len() / channels;
+    if rate <= 1.0 || hop == 0 || total < window + search {
+        return 1.0;
+    }
+    // 相似度按约 8 kHz 抽样比较，高采样率的长录音不会明显变慢
+    let step = (sample_rate / TEMPO_ANALYSIS_RATE).max(1);
+    let mono = audio
+        .samples
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().map(|sample| f32::from(*sample)).sum::<f32>())
+        .collect::<Vec<_>>();
+    // 周期 Hann 窗在半窗重叠时逐点相加为 1
+    let hann = (0..window)
+        .map(|i| 0.5 - 0.5 * (std::f32::consts::TAU * i as f32 / window as f32).cos())
+        .collect::<Vec<_>>();
+    let max_start = total - window;
+    let capacity = (total as f64 / f64::from(rate)) as usize + window;
+    let mut output = vec![0f32; capacity * channels];
+    let mut weight = vec![0f32; capacity];
+    let mut previous = 0;
+    let mut length = 0;
+    for out_start in (0..).step_by(hop) {
+        let nominal = (out_start as f64 * f64::from(rate)).round() as usize;
+        if nominal > max_start || out_start + window > capacity {
+            break;
+        }
+        let start = if out_start == 0 {
+            0
+        } else {
+            best_match(&mono, previous + hop, nominal, search, hop, step, max_start)
+        };
+        for (offset, gain) in hann.iter().enumerate() {
+            let source = (start + offset) * channels;
+            let target = (out_start + offset) * channels;
+            for channel in 0..channels {
+                output[target + channel] += f32::from(audio.samples[source + channel]) * gain;
+            }
+            weight[out_start + offset] += gain;
+        }
+        previous = start;
+        length = out_start + window;
+    }
+    audio.samples = output[..length * channels]
+        .chunks_exact(channels)
+        .zip(&weight)
+        .flat_map(|(frame, weight)| {
+            frame.iter().map(move |value| {
+                let value = if *weight > f32::EPSILON {
+                    value / weight
+                } else {
+                    0.0
+                };
+                value
+                    .round()
+                    .clamp(f32::from(i16::MIN), f32::from(i16::MAX)) as i16
+            })
+        })
+        .collect();
+    total as f64 / length as f64
+}
+
+/// 在 `nominal` 前后 `search` 帧内寻找与 `target` 起 `len` 帧波形最相似（归一化互相关最大）的片段起点
+fn best_match(
+    mono: &[f32],
+    target: usize,
+    nominal: usize,
+    search: usize,
+    len: usize,
+    step: usize,
+    max_start: usize,
+) -> usize {
+    let reference = &mono[target..target + len];
+    let candidates = nominal.saturating_sub(search)..=(nominal + search).min(max_start);
+    let mut best = nominal;
+    let mut best_score = f32::MIN;
+    for candidate in candidates.step_by(step) {
+        let (dot, energy) = (0..len).step_by(step).fold((0.0, 0.0), |(dot, energy), i| {
+            let value = mono[candidate + i];
+            (dot + value * reference[i], energy + value * value)
+        });
+        let score = dot / (energy.sqrt() + 1.0);
+        if score > best_score {
+            best_score = score;
+            best = candidate;
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mono(samples: Vec<i16>, sample_rate: u32) -> RecordedAudio {
+        RecordedAudio {
+            samples,
+            sample_rate,
+            channels: 1,
+            spill: None,
+            tracks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn steps_run_in_configured_order() {
+        let mut samples = vec![0i16; 1000];
+        samples.extend(vec![5000i16; 1000]);
+        samples.extend(vec![0i16; 1000]);
+        let settings = AudioPipelineSettings {
+            enabled: true,
+            steps: vec![
+                AudioPipelineStep::Trim {
+                    threshold_db: -40.0,
+                    padding_ms: 0,
+                },
+                AudioPipelineStep::Normalize { target_db: -6.0 },
+                AudioPipelineStep::Resample {
+                    sample_rate: 500,
+                    mono: true,
+                },
+            ],
+        };
+        let output = apply(&mono(samples, 1000), &settings);
+        assert_eq!(output.trimmed_start_ms, 1000);
+        assert_eq!(output.audio.sample_rate, 500);
+        assert_eq!(output.audio.samples.len(), 500);
+        let expected = db_to_amplitude(-6.0) as i16;
+        assert!((output.audio.samples[0] - expected).abs() <= 1);
+    }
+
+    #[test]
+    fn tempo_keeps_pitch_and_maps_trimmed_start_to_source_time() {
+        // 16 kHz：0.6 秒静音后接 3 秒 220 Hz 正弦波
+        let mut samples = vec![0i16; 9600];
+        samples.extend((0..48_000).map(|i| {
+            ((std::f32::consts::TAU * 220.0 * i as f32 / 16_000.0).sin() * 10_000.0) as i16
+        }));
+        let settings = AudioPipelineSettings {
+            enabled: true,
+            steps: vec![
+                AudioPipelineStep::Tempo { rate: 1.5 },
+                AudioPipelineStep::Trim {
+                    threshold_db: -40.0,
+                    padding_ms: 0,
+                },
+            ],
+        };
+        let output = apply(&mono(samples, 16_000), &settings);
+        assert!((output.tempo - 1.5).abs() < 0.02);
+        assert!(output.trimmed_start_ms.abs_diff(600) <= 30);
+        let rising = output
+            .audio
+            .samples
+            .windows(2)
+            .filter(|pair| pair[0] < 0 && pair[1] >= 0)
+            .count();
+        let seconds = output.audio.samples.len() as f32 / 16_000.0;
+        assert!((rising as f32 / seconds - 220.0).abs() < 3.0);
+    }
+
+    #[test]
+    fn disabled_pipeline_returns_audio_unchanged() {
+        let audio = mono(vec![1, 2, 3], 16_000);
+        let output = apply(&audio, &AudioPipelineSettings::default());
+        assert_eq!(output.audio.samples, vec![1, 2, 3]);
+        assert_eq!(output.trimmed_start_ms, 0);
+    }
+
+    #[test]
+    fn boundary_split_points_search_both_sides_of_nominal_cut() {
+        // 1 kHz 单声道：名义切点 1 秒之后的 1.15~1.25 秒为静音
+        let mut samples = vec![3000i16; 1150];
+        samples.extend(vec![0i16; 100]);
+        samples.extend(vec![3000i16; 1750]);
+        assert_eq!(
+            boundary_split_points(&samples, 1, 1000, 1000, 400),
+            vec![1160, 2160, 3000]
+        );
+        assert_eq!(
+            boundary_split_points(&samples, 1, 1000, 1000, 0),
+            vec![1000, 2000, 3000]
+        );
+        assert!(boundary_split_points(&[], 1, 1000, 1000, 400).is_empty());
+    }
+
+    #[test]
+    fn quiet_split_points_cut_at_lowest_energy() {
+        // 1 kHz 单声道：0.7 秒处有一段静音，上限 1 秒
+        let mut samples = vec![3000i16; 700];
+        samples.extend(vec![0i16; 100]);
+        samples.extend(vec![3000i16; 1200]);
+        let points = quiet_split_points(&samples, 1, 1000, 1000, 400);
+        assert_eq!(points.len(), 3);
+        assert!((700..800).contains(&points[0]));
+        assert!(points.windows(2).all(|pair| pair[1] - pair[0] <= 1000));
+        assert_eq!(points.last(), Some(&2000));
+
+        assert_eq!(
+            quiet_split_points(&samples, 1, 1000, 1000, 0),
+            vec![1000, 2000]
+        );
+        assert!(quiet_split_points(&[], 1, 1000, 1000, 400).is_empty());
+    }
+
+    #[test]
+    fn downmix_averages_channels() {
+        let mut audio = RecordedAudio {
+            samples: vec![100, 300, -200, 0],
+            sample_rate: 16_000,
+            channels: 2,
+            spill: None,
+            tracks: Vec::new(),
+        };
+        downmix_to_mono(&mut audio);
+        assert_eq!(audio.channels, 1);
+        assert_eq!(audio.samples, vec![200, -100]);
+    }
+}
@@ -1,52 +1,847 @@
+use crate::audio_pipeline;
+use crate::events::{self, RecordingSegment, SegmentStatus};
+use crate::ogg_opus;
 use crate::recorder::RecordedAudio;
-use hound::{SampleFormat, WavSpec, WavWriter};
+use crate::settings::{RecordingSettings, SilenceTrimSettings, UploadFormat};
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use std::borrow::Cow;
 use std::fs;
+use std::io::{Cursor, Read};
 use std::path::{Path, PathBuf};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
 use thiserror::Error;
 
+const WAV_HEADER_BYTES: u64 = 44;
+/// 写入分段前要求额外保留的磁盘余量
+const DISK_SPACE_MARGIN_BYTES: u64 = 16 * 1024 * 1024;
+/// 在名义切点前后寻找低能量切点的范围
+const SILENCE_SEARCH_MS: usize = 3000;
+/// 频谱噪声门的分析帧时长，实际帧长取不小于该时长的 2 的幂
+const SPECTRAL_FRAME_MS: usize = 32;
+/// 转换为语音上传格式时的最高采样率，高于该值的录音降采样，低于该值的保持不变
+const SPEECH_SAMPLE_RATE: u32 = 16_000;
+/// 频谱噪声门的增益平滑系数（上一帧增益的权重），减少“音乐噪声”
+const SPECTRAL_GAIN_SMOOTHING: f32 = 0.6;
+/// 裁剪静音的分析窗口时长
+const SILENCE_WINDOW_MS: usize = 10;
+/// 裁剪首尾静音时保留的余量，避免切掉字的起音与尾音
+const SILENCE_PADDING_MS: usize = 150;
+/// 按上传大小上限计算分段时长时实际使用的比例，为 Opus 可变码率与封装开销留出余量
+const UPLOAD_LIMIT_USABLE_PERCENT: u64 = 90;
+
 #[derive(Debug, Error)]
 pub enum AudioProcessingError {
     #[error("无法写入录音文件: {0}")]
     Io(String),
+    #[error("录音编码失败: {0}")]
+    Encode(String),
+    #[error("无法解码音频文件: {0}")]
+    Decode(String),
+    #[error("录音临时目录空间不足: 需要约 {required_mb} MB，可用 {available_mb} MB ({dir})")]
+    InsufficientSpace {
+        dir: String,
+        required_mb: u64,
+        available_mb: u64,
+    },
+}
+
+/// 解析录音分段的临时目录，未配置时使用系统临时目录
+pub fn resolve_scratch_dir(configured: &str) -> PathBuf {
+    let configured = configured.trim();
+    if configured.is_empty() {
+        std::env::temp_dir().join("vtt-keyboard").join("recordings")
+    } else {
+        PathBuf::from(configured)
+    }
+}
+
+/// 写入临时目录的录音分段，时间相对于录音开头
+pub struct SegmentFile {
+    pub path: PathBuf,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// 推送给前端时间轴的分段边界
+    pub timeline: RecordingSegment,
+    /// 裁剪静音删去的片段：(在写入音频中的位置, 删去的时长)，单位毫秒，按位置升序
+    pub removed_ms: Vec<(u64, u64)>,
+    /// 整段均为静音，裁剪后没有需要转写的内容
+    pub silent: bool,
+    /// 写入的音频从 `start_ms` 之前多少毫秒开始，与上一段重叠
+    pub overlap_ms: u64,
 }
 
+impl SegmentFile {
+    /// 将写入音频中的时间换算为相对分段开头的原始录音时间，加回之前裁掉的静音
+    pub fn source_offset_ms(&self, offset_ms: u64) -> u64 {
+        offset_ms
+            + self
+                .removed_ms
+                .iter()
+                .take_while(|(at, _)| *at <= offset_ms)
+                .map(|(_, removed)| removed)
+                .sum::<u64>()
+    }
+}
+
+/// 将录音切分为约 `segment_seconds` 的音频文件，切点取每个名义切点前后 3 秒内能量最低处，
+/// 避免从词语中间截断；除第一段外，每段音频向前多写入 `overlap_ms` 与上一段重叠。
+/// 每写入一段推送一次分段边界事件。
+/// 溢出到磁盘的长录音逐段从临时文件读取，不整体载入内存；分段按 `format` 转换后再滤波、
+/// 裁剪静音，最后按 `upload_format` 编码写入。分段边界仍按原始录音计算
 pub fn write_segments(
     audio: &RecordedAudio,
     segment_seconds: u64,
-) -> Result<Vec<PathBuf>, AudioProcessingError> {
-    let dir = std::env::temp_dir().join("vtt-keyboard").join("recordings");
-    fs::create_dir_all(&dir).map_err(|err| AudioProcessingError::Io(err.to_string()))?;
+    overlap_ms: u32,
+    format: SegmentFormat,
+    filters: &FilterChain,
+    upload_format: UploadFormat,
+    dir: &Path,
+) -> Result<Vec<SegmentFile>, AudioProcessingError> {
+    fs::create_dir_all(dir).map_err(|err| AudioProcessingError::Io(err.to_string()))?;
+    ensure_disk_space(dir, audio, segment_seconds)?;
 
-    let total_samples = audio.samples.len();
-    let samples_per_second = audio.sample_rate as usize * audio.channels as usize;
-    let segment_samples = samples_per_second * segment_seconds as usize;
+    let sample_rate = audio.sample_rate as usize;
+    let channels = usize::from(audio.channels.max(1));
+    let search_frames = sample_rate * SILENCE_SEARCH_MS / 1000;
+    let points = split_points(audio, sample_rate * segment_seconds as usize, search_frames)?;
+    let frame_ms = |frame: usize| (frame as u64 * 1000) / sample_rate.max(1) as u64;
+    let overlap_frames = sample_rate * overlap_ms as usize / 1000;
 
-    let mut paths = Vec::new();
-    let mut offset = 0;
-    let mut index = 0;
-    while offset < total_samples {
-        let end = (offset + segment_samples).min(total_samples);
-        let path = dir.join(format!("segment-{index}.wav"));
-        write_wav(&path, audio, &audio.samples[offset..end])?;
-        paths.push(path);
-        offset = end;
-        index += 1;
+    // 每次分段使用独立前缀，避免听写与批量转写同时写入同一临时目录时互相覆盖
+    let batch_id = crate::util::timestamp_id();
+    let count = points.len();
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let extension = match upload_format {
+        UploadFormat::Wav => "wav",
+        UploadFormat::OggOpus => "ogg",
+    };
+    for (index, end) in points.into_iter().enumerate() {
+        let path = dir.join(format!("segment-{batch_id}-{index}.{extension}"));
+        let audio_start = start.saturating_sub(overlap_frames);
+        let samples = match &audio.spill {
+            Some(spill) => Cow::Owned(
+                spill
+                    .read_frames(audio_start, end)
+                    .map_err(AudioProcessingError::Io)?,
+            ),
+            None => Cow::Borrowed(&audio.samples[audio_start * channels..end * channels]),
+        };
+        let (samples, sample_rate, channels) =
+            format.convert(samples, audio.sample_rate, audio.channels);
+        let samples = if filters.is_empty() {
+            samples
+        } else {
+            let mut filtered = samples.into_owned();
+            filters.process(&mut filtered, sample_rate, channels);
+            Cow::Owned(filtered)
+        };
+        let (samples, removed) = filters.trim_silence(samples, sample_rate, channels);
+        let silent = samples.is_empty() && !removed.is_empty();
+        let to_ms = |frames: usize| frames as u64 * 1000 / u64::from(sample_rate.max(1));
+        match upload_format {
+            UploadFormat::Wav => write_wav(&path, sample_rate, channels, &samples)?,
+            UploadFormat::OggOpus => {
+                let bytes = ogg_opus::encode(&samples, sample_rate, channels)
+                    .map_err(AudioProcessingError::Encode)?;
+                fs::write(&path, bytes).map_err(|err| AudioProcessingError::Io(err.to_string()))?;
+            }
+        }
+        let timeline = RecordingSegment {
+            batch_id: batch_id.clone(),
+            index,
+            count,
+            start_frame: start as u64,
+            end_frame: end as u64,
+            sample_rate: audio.sample_rate,
+            start_ms: frame_ms(start),
+            end_ms: frame_ms(end),
+            status: SegmentStatus::Written,
+        };
+        events::emit_recording_segment(&timeline, SegmentStatus::Written);
+        segments.push(SegmentFile {
+            path,
+            start_ms: timeline.start_ms,
+            end_ms: timeline.end_ms,
+            timeline,
+            removed_ms: removed
+                .into_iter()
+                .map(|(at, len)| (to_ms(at), to_ms(len)))
+                .collect(),
+            silent,
+            overlap_ms: frame_ms(start) - frame_ms(audio_start),
+        });
+        start = end;
     }
 
-    Ok(paths)
+    Ok(segments)
 }
 
-fn write_wav(
-    path: &Path,
+/// 提供商单次请求的时长与文件大小上限下，分段名义时长的上限（秒），无上限时返回 None。
+/// 文件大小按转换与编码后的数据率估算；切点可能落在名义切点之后，且分段带有重叠音频，
+/// 这两部分的时长一并扣除。结果至少为 1 秒
+pub fn max_segment_seconds(
     audio: &RecordedAudio,
-    samples: &[i16],
+    format: SegmentFormat,
+    upload_format: UploadFormat,
+    overlap_ms: u32,
+    max_audio_seconds: Option<u32>,
+    max_upload_bytes: Option<u64>,
+) -> Option<u64> {
+    let (sample_rate, channels) = format.output_spec(audio.sample_rate, audio.channels);
+    let bytes_per_second = match upload_format {
+        UploadFormat::Wav => u64::from(sample_rate) * u64::from(channels) * 2,
+        UploadFormat::OggOpus => ogg_opus::bytes_per_second(channels),
+    };
+    let size_seconds = max_upload_bytes.map(|max_bytes| {
+        max_bytes.saturating_sub(WAV_HEADER_BYTES) * UPLOAD_LIMIT_USABLE_PERCENT
+            / 100
+            / bytes_per_second.max(1)
+    });
+    let limit = [max_audio_seconds.map(u64::from), size_seconds]
+        .into_iter()
+        .flatten()
+        .min()?;
+    let extension_seconds = (SILENCE_SEARCH_MS as u64 + u64::from(overlap_ms)).div_ceil(1000);
+    Some(limit.saturating_sub(extension_seconds).max(1))
+}
+
+/// 分段写入的音频格式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SegmentFormat {
+    /// 保持录音设备的原始声道数与采样率
+    Original,
+    /// 混为单声道并降采样到 16 kHz，48 kHz 立体声录音的上传数据量约减少为 1/6
+    #[default]
+    Speech,
+}
+
+impl SegmentFormat {
+    pub fn from_settings(settings: &RecordingSettings) -> Self {
+        if settings.keep_original_format {
+            Self::Original
+        } else {
+            Self::Speech
+        }
+    }
+
+    /// 转换后的采样率与声道数
+    fn output_spec(self, sample_rate: u32, channels: u16) -> (u32, u16) {
+        match self {
+            Self::Original => (sample_rate, channels.max(1)),
+            Self::Speech => (sample_rate.min(SPEECH_SAMPLE_RATE), 1),
+        }
+    }
+
+    /// 返回转换后的样本、采样率与声道数
+    fn convert<'a>(
+        self,
+        samples: Cow<'a, [i16]>,
+        sample_rate: u32,
+        channels: u16,
+    ) -> (Cow<'a, [i16]>, u32, u16) {
+        if self == Self::Original {
+            return (samples, sample_rate, channels);
+        }
+        let (samples, channels) = if channels > 1 {
+            let mono = audio_pipeline::downmix_samples(&samples, usize::from(channels));
+            (Cow::Owned(mono), 1)
+        } else {
+            (samples, channels)
+        };
+        if sample_rate <= SPEECH_SAMPLE_RATE {
+            return (samples, sample_rate, channels);
+        }
+        let resampled = audio_pipeline::resample_samples(
+            &samples,
+            usize::from(channels.max(1)),
+            sample_rate,
+            SPEECH_SAMPLE_RATE,
+        );
+        (Cow::Owned(resampled), SPEECH_SAMPLE_RATE, channels)
+    }
+}
+
+/// 分段写入前作用于录音样本的滤波器，样本为交错排列的 16-bit PCM
+pub trait AudioFilter: Send + Sync {
+    fn process(&self, samples: &mut [i16], sample_rate: u32, channels: u16);
+}
+
+/// 按设置启用、依次执行的滤波器链，滤波后按需裁剪静音；逐段处理，溢出到磁盘的长录音同样适用
+#[derive(Default)]
+pub struct FilterChain {
+    filters: Vec<Box<dyn AudioFilter>>,
+    silence_trim: Option<SilenceTrimmer>,
+}
+
+impl FilterChain {
+    pub fn from_settings(settings: &RecordingSettings) -> Self {
+        let mut filters: Vec<Box<dyn AudioFilter>> = Vec::new();
+        if settings.noise_suppression {
+            filters.push(Box::new(SpectralGate::default()));
+        }
+        // 降噪后再归一化，避免先放大底噪
+        if settings.auto_gain {
+            filters.push(Box::new(PeakNormalizer {
+                target_db: settings.auto_gain_target_db,
+            }));
+        }
+        let silence_trim = settings
+            .silence_trim
+            .enabled
+            .then(|| SilenceTrimmer::from_settings(&settings.silence_trim));
+        Self {
+            filters,
+            silence_trim,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    pub fn process(&self, samples: &mut [i16], sample_rate: u32, channels: u16) {
+        for filter in &self.filters {
+            filter.process(samples, sample_rate, channels);
+        }
+    }
+
+    /// 未开启裁剪静音时原样返回；删去的片段以写入音频中的帧位置与帧数表示
+    fn trim_silence<'a>(
+        &self,
+        samples: Cow<'a, [i16]>,
+        sample_rate: u32,
+        channels: u16,
+    ) -> (Cow<'a, [i16]>, Vec<(usize, usize)>) {
+        match &self.silence_trim {
+            Some(trimmer) => {
+                let (trimmed, removed) = trimmer.trim(&samples, sample_rate, channels);
+                (Cow::Owned(trimmed), removed)
+            }
+            None => (samples, Vec::new()),
+        }
+    }
+}
+
+/// 静音裁剪：按短时 RMS 找出静音区间，去掉首尾静音（保留少量余量），
+/// 并把超过 `max_pause_ms` 的停顿从中间截短到该时长，两侧各保留一半
+pub struct SilenceTrimmer {
+    /// 判定为静音的电平阈值（dBFS）
+    pub threshold_db: f32,
+    pub max_pause_ms: u32,
+}
+
+impl SilenceTrimmer {
+    pub fn from_settings(settings: &SilenceTrimSettings) -> Self {
+        Self {
+            threshold_db: settings.threshold_db,
+            max_pause_ms: settings.max_pause_ms,
+        }
+    }
+
+    /// 返回裁剪后的样本与删去的片段；整段均为静音时返回空样本
+    fn trim(
+        &self,
+        samples: &[i16],
+        sample_rate: u32,
+        channels: u16,
+    ) -> (Vec<i16>, Vec<(usize, usize)>) {
+        let channels = usize::from(channels.max(1));
+        let total = samples.len() / channels;
+        let window = (sample_rate as usize * SILENCE_WINDOW_MS / 1000).max(1);
+        let threshold = f64::from(audio_pipeline::db_to_amplitude(self.threshold_db));
+        let is_silent = |start: usize| {
+            let chunk = &samples[start * channels..(start + window).min(total) * channels];
+            let energy = chunk
+                .iter()
+                .map(|sample| f64::from(*sample).powi(2))
+                .sum::<f64>();
+            (energy / chunk.len() as f64).sqrt() < threshold
+        };
+
+        let mut runs = Vec::new();
+        let mut run_start = None;
+        for start in (0..total).step_by(window) {
+            match (is_silent(start), run_start) {
+                (true, None) => run_start = Some(start),
+                (false, Some(begin)) => {
+                    runs.push((begin, start));
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(begin) = run_start {
+            runs.push((begin, total));
+        }
+        if runs.first() == Some(&(0, total)) {
+            return (Vec::new(), vec![(0, total)]);
+        }
+
+        let padding = sample_rate as usize * SILENCE_PADDING_MS / 1000;
+        let max_pause = sample_rate as usize * self.max_pause_ms as usize / 1000;
+        let cuts = runs.into_iter().filter_map(|(start, end)| {
+            let (from, to) = if start == 0 {
+                (0, end.saturating_sub(padding))
+            } else if end == total {
+                ((start + padding).min(total), total)
+            } else {
+                (
+                    start + max_pause / 2,
+                    end.saturating_sub(max_pause - max_pause / 2),
+                )
+            };
+            (to > from).then_some((from, to))
+        });
+        let mut output = Vec::with_capacity(samples.len());
+        let mut removed = Vec::new();
+        let mut kept_until = 0;
+        for (from, to) in cuts {
+            output.extend_from_slice(&samples[kept_until * channels..from * channels]);
+            removed.push((output.len() / channels, to - from));
+            kept_until = to;
+        }
+        output.extend_from_slice(&samples[kept_until * channels..]);
+        (output, removed)
+    }
+}
+
+/// 自动增益：将每段的峰值归一化到目标电平，增益上限与预处理链的归一化步骤相同
+pub struct PeakNormalizer {
+    /// 目标峰值电平（dBFS）
+    pub target_db: f32,
+}
+
+impl AudioFilter for PeakNormalizer {
+    fn process(&self, samples: &mut [i16], _sample_rate: u32, _channels: u16) {
+        audio_pipeline::normalize_peak(samples, self.target_db);
+    }
+}
+
+/// 频谱噪声门：以能量最低的若干帧估计各频点的底噪，幅度未明显高于底噪的频点衰减，
+/// 适合风扇、空调等平稳噪声
+pub struct SpectralGate {
+    /// 频点幅度高于底噪该倍数时视为语音保留
+    threshold_ratio: f32,
+    /// 噪声频点保留的幅度比例
+    attenuation: f32,
+    /// 用于估计底噪的最安静帧比例
+    noise_fraction: f32,
+}
+
+impl Default for SpectralGate {
+    fn default() -> Self {
+        Self {
+            threshold_ratio: 2.0,
+            attenuation: 0.1,
+            noise_fraction: 0.1,
+        }
+    }
+}
+
+impl AudioFilter for SpectralGate {
+    fn process(&self, samples: &mut [i16], sample_rate: u32, channels: u16) {
+        let channels = usize::from(channels.max(1));
+        let frame_len = (sample_rate as usize * SPECTRAL_FRAME_MS / 1000)
+            .max(2)
+            .next_power_of_two();
+        // 过短的录音无法可靠估计底噪
+        if samples.len() / channels < frame_len * 2 {
+            return;
+        }
+        for channel in 0..channels {
+            let signal = samples
+                .iter()
+                .skip(channel)
+                .step_by(channels)
+                .map(|sample| f32::from(*sample))
+                .collect::<Vec<_>>();
+            let filtered = self.process_channel(&signal, frame_len);
+            for (index, value) in filtered.into_iter().enumerate() {
+                let value = value
+                    .round()
+                    .clamp(f32::from(i16::MIN), f32::from(i16::MAX));
+                samples[index * channels + channel] = value as i16;
+            }
+        }
+    }
+}
+
+impl SpectralGate {
+    /// 以 50% 重叠的周期 Hann 窗做短时傅里叶变换，逐帧施加增益后重叠相加
+    fn process_channel(&self, signal: &[f32], frame_len: usize) -> Vec<f32> {
+        let hop = frame_len / 2;
+        let bins = frame_len / 2 + 1;
+        let window = (0..frame_len)
+            .map(|index| {
+                let phase = 2.0 * std::f32::consts::PI * index as f32 / frame_len as f32;
+                0.5 - 0.5 * phase.cos()
+            })
+            .collect::<Vec<_>>();
+        let twiddles = fft_twiddles(frame_len);
+        let spectrum_of = |frame: &[f32]| {
+            let mut buffer = frame
+                .iter()
+                .zip(&window)
+                .map(|(value, weight)| Complex {
+                    re: value * weight,
+                    im: 0.0,
+                })
+                .collect::<Vec<_>>();
+            fft(&mut buffer, &twiddles, false);
+            buffer
+        };
+
+        let mut quiet = (0..=signal.len() - frame_len)
+            .step_by(hop)
+            .map(|start| {
+                let energy = signal[start..start + frame_len]
+                    .iter()
+                    .map(|value| value * value)
+                    .sum::<f32>();
+                (energy, start)
+            })
+            .collect::<Vec<_>>();
+        quiet.sort_by(|left, right| left.0.total_cmp(&right.0));
+        let noise_frames = ((quiet.len() as f32 * self.noise_fraction).ceil() as usize).max(1);
+        let mut noise = vec![0.0f32; bins];
+        for &(_, start) in &quiet[..noise_frames] {
+            let spectrum = spectrum_of(&signal[start..start + frame_len]);
+            for (level, value) in noise.iter_mut().zip(&spectrum) {
+                *level += value.norm() / noise_frames as f32;
+            }
+        }
+
+        // 前后补零，使每个样本都被两帧覆盖，窗函数重叠相加后增益为 1
+        let tail = hop + (hop - signal.len() % hop) % hop;
+        let mut padded = vec![0.0f32; hop];
+        padded.extend_from_slice(signal);
+        padded.resize(padded.len() + tail, 0.0);
+        let mut output = vec![0.0f32; padded.len()];
+        let mut gains = vec![1.0f32; bins];
+        for start in (0..=padded.len() - frame_len).step_by(hop) {
+            let mut spectrum = spectrum_of(&padded[start..start + frame_len]);
+            for bin in 0..bins {
+                let target = if spectrum[bin].norm() > noise[bin] * self.threshold_ratio {
+                    1.0
+                } else {
+                    self.attenuation
+                };
+                gains[bin] =
+                    SPECTRAL_GAIN_SMOOTHING * gains[bin] + (1.0 - SPECTRAL_GAIN_SMOOTHING) * target;
+                spectrum[bin].scale(gains[bin]);
+                if bin > 0 && bin < frame_len - bin {
+                    spectrum[frame_len - bin].scale(gains[bin]);
+                }
+            }
+            fft(&mut spectrum, &twiddles, true);
+            for (sum, value) in output[start..start + frame_len].iter_mut().zip(&spectrum) {
+                *sum += value.re;
+            }
+        }
+        output[hop..hop + signal.len()].to_vec()
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Complex {
+    re: f32,
+    im: f32,
+}
+
+impl Complex {
+    fn mul(self, other: Self) -> Self {
+        Self {
+            re: self.re * other.re - self.im * other.im,
+            im: self.re * other.im + self.im * other.re,
+        }
+    }
+
+    fn norm(self) -> f32 {
+        self.re.hypot(self.im)
+    }
+
+    fn scale(&mut self, factor: f32) {
+        self.re *= factor;
+        self.im *= factor;
+    }
+}
+
+/// 长度为 `len` 的 FFT 旋转因子 e^(-2πik/len)，k < len/2
+fn fft_twiddles(len: usize) -> Vec<Complex> {
+    (0..len / 2)
+        .map(|index| {
+            let angle = -2.0 * std::f64::consts::PI * index as f64 / len as f64;
+            Complex {
+                re: angle.cos() as f32,
+                im: angle.sin() as f32,
+            }
+        })
+        .collect()
+}
+
+/// 原地基 2 FFT，长度须为 2 的幂；`inverse` 时做逆变换并除以长度
+fn fft(buffer: &mut [Complex], twiddles: &[Complex], inverse: bool) {
+    let len = buffer.len();
+    let mut target = 0;
+    for index in 1..len {
+        let mut bit = len >> 1;
+        while target & bit != 0 {
+            target ^= bit;
+            bit >>= 1;
+        }
+        target |= bit;
+        if index < target {
+            buffer.swap(index, target);
+        }
+    }
+    let mut size = 2;
+    while size <= len {
+        let stride = len / size;
+        for start in (0..len).step_by(size) {
+            for offset in 0..size / 2 {
+                let mut twiddle = twiddles[offset * stride];
+                if inverse {
+                    twiddle.im = -twiddle.im;
+                }
+                let even = buffer[start + offset];
+                let odd = buffer[start + offset + size / 2].mul(twiddle);
+                buffer[start + offset] = Complex {
+                    re: even.re + odd.re,
+                    im: even.im + odd.im,
+                };
+                buffer[start + offset + size / 2] = Complex {
+                    re: even.re - odd.re,
+                    im: even.im - odd.im,
+                };
+            }
+        }
+        size <<= 1;
+    }
+    if inverse {
+        for value in buffer.iter_mut() {
+            value.scale(1.0 / len as f32);
+        }
+    }
+}
+
+/// 计算各段的结束帧；溢出到磁盘的录音每次只读取一段加搜索范围的窗口寻找切点，
+/// 与整段计算的结果一致
+fn split_points(
+    audio: &RecordedAudio,
+    segment_frames: usize,
+    search_frames: usize,
+) -> Result<Vec<usize>, AudioProcessingError> {
+    let Some(spill) = &audio.spill else {
+        return Ok(audio_pipeline::boundary_split_points(
+            &audio.samples,
+            audio.channels,
+            audio.sample_rate,
+            segment_frames,
+            search_frames,
+        ));
+    };
+    let total = spill.sample_count() / usize::from(audio.channels.max(1));
+    let mut points = Vec::new();
+    let mut start = 0;
+    while start < total {
+        let window_end = (start + segment_frames.max(1) + search_frames + 1).min(total);
+        let window = spill
+            .read_frames(start, window_end)
+            .map_err(AudioProcessingError::Io)?;
+        let end = audio_pipeline::boundary_split_points(
+            &window,
+            audio.channels,
+            audio.sample_rate,
+            segment_frames,
+            search_frames,
+        )
+        .first()
+        .map_or(window_end, |point| start + point);
+        points.push(end);
+        start = end;
+    }
+    Ok(points)
+}
+
+/// 写入前检查剩余空间，避免在磁盘写满时得到难以理解的 I/O 错误
+fn ensure_disk_space(
+    dir: &Path,
+    audio: &RecordedAudio,
+    segment_seconds: u64,
 ) -> Result<(), AudioProcessingError> {
+    let Some(available) = crate::system_state::available_disk_space(dir) else {
+        return Ok(());
+    };
+    let required = estimate_segments_bytes(audio, segment_seconds) + DISK_SPACE_MARGIN_BYTES;
+    if available >= required {
+        return Ok(());
+    }
+    Err(AudioProcessingError::InsufficientSpace {
+        dir: dir.to_string_lossy().to_string(),
+        required_mb: required.div_ceil(1024 * 1024),
+        available_mb: available / (1024 * 1024),
+    })
+}
+
+fn estimate_segments_bytes(audio: &RecordedAudio, segment_seconds: u64) -> u64 {
+    let samples_per_segment =
+        u64::from(audio.sample_rate) * u64::from(audio.channels) * segment_seconds.max(1);
+    let total_samples = audio.sample_count() as u64;
+    let segment_count = if samples_per_segment == 0 {
+        1
+    } else {
+        total_samples.div_ceil(samples_per_segment).max(1)
+    };
+    total_samples * 2 + segment_count * WAV_HEADER_BYTES
+}
+
+/// 将整段录音写为单个 WAV 文件
+pub fn write_wav_file(path: &Path, audio: &RecordedAudio) -> Result<(), AudioProcessingError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| AudioProcessingError::Io(err.to_string()))?;
+    }
+    match &audio.spill {
+        Some(spill) => fs::copy(spill.path(), path)
+            .map(|_| ())
+            .map_err(|err| AudioProcessingError::Io(err.to_string())),
+        None => write_wav(path, audio.sample_rate, audio.channels, &audio.samples),
+    }
+}
+
+/// 以 WAV 字节流读取整段录音，已溢出到磁盘的录音直接读取溢出文件
+pub fn wav_source(audio: &RecordedAudio) -> Result<Box<dyn Read>, AudioProcessingError> {
+    if let Some(spill) = &audio.spill {
+        let file = fs::File::open(spill.path())
+            .map_err(|err| AudioProcessingError::Io(err.to_string()))?;
+        return Ok(Box::new(file));
+    }
     let spec = WavSpec {
         channels: audio.channels,
         sample_rate: audio.sample_rate,
         bits_per_sample: 16,
         sample_format: SampleFormat::Int,
     };
+    let mut bytes = Cursor::new(Vec::new());
+    let mut writer = WavWriter::new(&mut bytes, spec)
+        .map_err(|err| AudioProcessingError::Io(err.to_string()))?;
+    for sample in &audio.samples {
+        writer
+            .write_sample(*sample)
+            .map_err(|err| AudioProcessingError::Io(err.to_string()))?;
+    }
+    writer
+        .finalize()
+        .map_err(|err| AudioProcessingError::Io(err.to_string()))?;
+    bytes.set_position(0);
+    Ok(Box::new(bytes))
+}
+
+/// 读取 16-bit PCM WAV 文件为录音数据
+pub fn read_wav_file(path: &Path) -> Result<RecordedAudio, AudioProcessingError> {
+    let mut reader =
+        WavReader::open(path).map_err(|err| AudioProcessingError::Io(err.to_string()))?;
+    let spec = reader.spec();
+    if spec.sample_format != SampleFormat::Int || spec.bits_per_sample != 16 {
+        return Err(AudioProcessingError::Io(
+            "仅支持 16-bit PCM WAV 文件".to_string(),
+        ));
+    }
+    let samples = reader
+        .samples::<i16>()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| AudioProcessingError::Io(err.to_string()))?;
+    Ok(RecordedAudio {
+        samples,
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+        spill: None,
+        tracks: Vec::new(),
+    })
+}
+
+/// 解码 MP3、M4A（AAC）、FLAC、Ogg Vorbis、WebM 等压缩音频为 16-bit PCM 录音数据；
+/// 不支持 Opus 编码，调用方可改为直接上传原文件
+pub fn decode_audio_file(path: &Path) -> Result<RecordedAudio, AudioProcessingError> {
+    let file = fs::File::open(path).map_err(|err| AudioProcessingError::Io(err.to_string()))?;
+    let source = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(extension);
+    }
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            source,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|err| AudioProcessingError::Decode(err.to_string()))?
+        .format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| AudioProcessingError::Decode("文件中没有音轨".to_string()))?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|err| AudioProcessingError::Decode(err.to_string()))?;
+    let mut samples = Vec::new();
+    let mut spec = None;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(err))
+                if err.kind() == std::io::ErrorKind::UnexpectedEof =>
+            {
+                break;
+            }
+            Err(err) => return Err(AudioProcessingError::Decode(err.to_string())),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // 个别损坏的数据包跳过，不中断整个文件
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(err) => return Err(AudioProcessingError::Decode(err.to_string())),
+        };
+        let signal = *decoded.spec();
+        let mut buffer = SampleBuffer::<i16>::new(decoded.capacity() as u64, signal);
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend_from_slice(buffer.samples());
+        spec.get_or_insert(signal);
+    }
+    let spec = spec.ok_or_else(|| AudioProcessingError::Decode("音频文件为空".to_string()))?;
+    Ok(RecordedAudio {
+        samples,
+        sample_rate: spec.rate,
+        channels: spec.channels.count() as u16,
+        spill: None,
+        tracks: Vec::new(),
+    })
+}
+
+fn write_wav(
+    path: &Path,
+    sample_rate: u32,
+    channels: u16,
+    samples: &[i16],
+) -> Result<(), AudioProcessingError> {
+    let spec = WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
     let mut writer =
         WavWriter::create(path, spec).map_err(|err| AudioProcessingError::Io(err.to_string()))?;
     for sample in samples {
@@ -59,3 +854,272 @@ fn write_wav(
         .map_err(|err| AudioProcessingError::Io(err.to_string()))?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_audio_file, estimate_segments_bytes, max_segment_seconds, write_segments,
+        AudioFilter, AudioProcessingError, FilterChain, SegmentFormat, SilenceTrimmer,
+        SpectralGate,
+    };
+    use crate::recorder::RecordedAudio;
+    use crate::settings::UploadFormat;
+    use std::borrow::Cow;
+
+    #[test]
+    fn decode_audio_file_rejects_unrecognized_data() {
+        let path = std::env::temp_dir().join(format!(
+            "vtt-keyboard-decode-{}.mp3",
+            crate::util::timestamp_id()
+        ));
+        std::fs::write(&path, b"not an audio file").unwrap();
+        let result = decode_audio_file(&path);
+        let _ = std::fs::remove_file(&path);
+        assert!(matches!(result, Err(AudioProcessingError::Decode(_))));
+    }
+
+    #[test]
+    fn estimate_segments_bytes_counts_samples_and_headers() {
+        let audio = RecordedAudio {
+            samples: vec![0; 16_000 * 3],
+            sample_rate: 16_000,
+            channels: 1,
+            spill: None,
+            tracks: Vec::new(),
+        };
+        assert_eq!(estimate_segments_bytes(&audio, 2), 16_000 * 3 * 2 + 2 * 44);
+    }
+
+    #[test]
+    fn max_segment_seconds_fits_upload_limit_after_conversion() {
+        let audio = RecordedAudio {
+            samples: Vec::new(),
+            sample_rate: 48_000,
+            channels: 2,
+            spill: None,
+            tracks: Vec::new(),
+        };
+        let limit = |format, upload_format, overlap_ms, max_audio_seconds| {
+            max_segment_seconds(
+                &audio,
+                format,
+                upload_format,
+                overlap_ms,
+                max_audio_seconds,
+                Some(25 * 1024 * 1024),
+            )
+        };
+        // 48 kHz 立体声 WAV 每秒 192000 字节，扣除切点搜索的 3 秒
+        assert_eq!(
+            limit(SegmentFormat::Original, UploadFormat::Wav, 0, None),
+            Some(119)
+        );
+        assert_eq!(
+            limit(SegmentFormat::Speech, UploadFormat::Wav, 0, None),
+            Some(734)
+        );
+        // Opus 体积远小于上限时以时长上限为准，重叠部分一并扣除
+        assert_eq!(
+            limit(
+                SegmentFormat::Speech,
+                UploadFormat::OggOpus,
+                1500,
+                Some(1500)
+            ),
+            Some(1495)
+        );
+        assert_eq!(
+            max_segment_seconds(
+                &audio,
+                SegmentFormat::Speech,
+                UploadFormat::Wav,
+                0,
+                None,
+                None
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn write_segments_reports_contiguous_frame_boundaries() {
+        let audio = RecordedAudio {
+            samples: vec![0; 16_000 * 5 / 2],
+            sample_rate: 16_000,
+            channels: 1,
+            spill: None,
+            tracks: Vec::new(),
+        };
+        let dir =
+            std::env::temp_dir().join(format!("vtt-keyboard-test-{}", crate::util::timestamp_id()));
+        let filters = FilterChain::default();
+        let segments = write_segments(
+            &audio,
+            1,
+            0,
+            SegmentFormat::Original,
+            &filters,
+            UploadFormat::Wav,
+            &dir,
+        )
+        .unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        let frames: Vec<(u64, u64)> = segments
+            .iter()
+            .map(|segment| (segment.timeline.start_frame, segment.timeline.end_frame))
+            .collect();
+        assert_eq!(
+            frames,
+            vec![(0, 16_000), (16_000, 32_000), (32_000, 40_000)]
+        );
+        assert!(segments.iter().all(|segment| segment.timeline.count == 3));
+        assert_eq!(segments[2].end_ms, 2_500);
+
+        // 重叠只影响写入的音频，分段边界不变
+        let segments = write_segments(
+            &audio,
+            1,
+            250,
+            SegmentFormat::Original,
+            &filters,
+            UploadFormat::Wav,
+            &dir,
+        )
+        .unwrap();
+        let bytes = std::fs::metadata(&segments[1].path).unwrap().len();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(
+            segments
+                .iter()
+                .map(|segment| segment.overlap_ms)
+                .collect::<Vec<_>>(),
+            vec![0, 250, 250]
+        );
+        assert_eq!(segments[1].start_ms, 1_000);
+        assert_eq!(bytes, 44 + (16_000 + 4_000) * 2);
+    }
+
+    #[test]
+    fn silence_trimmer_trims_edges_and_shortens_long_pauses() {
+        let trimmer = SilenceTrimmer {
+            threshold_db: -45.0,
+            max_pause_ms: 400,
+        };
+        // 1 kHz 采样：静音 500 ms、说话 200 ms、停顿 1000 ms、说话 200 ms、静音 500 ms
+        let speech = vec![8_000i16; 200];
+        let mut samples = vec![0i16; 500];
+        samples.extend(&speech);
+        samples.extend(vec![0; 1_000]);
+        samples.extend(&speech);
+        samples.extend(vec![0; 500]);
+        let (trimmed, removed) = trimmer.trim(&samples, 1_000, 1);
+        // 首尾各保留 150 ms 余量，停顿两侧各保留 200 ms
+        assert_eq!(removed, vec![(0, 350), (550, 600), (1_100, 350)]);
+        assert_eq!(trimmed.len(), 150 + 200 + 400 + 200 + 150);
+        assert_eq!(trimmed[150], 8_000);
+        assert_eq!(trimmed[750], 8_000);
+
+        let (trimmed, removed) = trimmer.trim(&[0; 300], 1_000, 1);
+        assert!(trimmed.is_empty());
+        assert_eq!(removed, vec![(0, 300)]);
+    }
+
+    #[test]
+    fn write_segments_skips_silence_and_maps_trimmed_time() {
+        let mut recording = crate::settings::Settings::default().recording;
+        recording.auto_gain = false;
+        recording.silence_trim.enabled = true;
+        // 第 1 秒与第 2 秒前半秒静音，之后半秒说话
+        let mut samples = vec![0i16; 16_000 * 3 / 2];
+        samples.extend(vec![8_000i16; 8_000]);
+        let audio = RecordedAudio {
+            samples,
+            sample_rate: 16_000,
+            channels: 1,
+            spill: None,
+            tracks: Vec::new(),
+        };
+        let dir =
+            std::env::temp_dir().join(format!("vtt-keyboard-test-{}", crate::util::timestamp_id()));
+        let segments = write_segments(
+            &audio,
+            1,
+            0,
+            SegmentFormat::Original,
+            &FilterChain::from_settings(&recording),
+            UploadFormat::Wav,
+            &dir,
+        )
+        .unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(segments[0].silent);
+        assert!(!segments[1].silent);
+        // 第 2 段开头裁掉 350 ms，写入音频中的 100 ms 对应该段原始录音的 450 ms
+        assert_eq!(segments[1].removed_ms, vec![(0, 350)]);
+        assert_eq!(segments[1].source_offset_ms(100), 450);
+        assert_eq!(segments[1].end_ms, 2_000);
+    }
+
+    #[test]
+    fn spectral_gate_attenuates_steady_noise_and_keeps_tone() {
+        let sample_rate = 16_000;
+        let mut seed = 12_345u32;
+        let mut noise = || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            f32::from((seed >> 16) as u16 as i16) / 32.0
+        };
+        // 前 1 秒只有噪声，后 1 秒叠加 440 Hz 正弦
+        let mut samples = (0..sample_rate * 2)
+            .map(|index| {
+                let tone = if index >= sample_rate {
+                    let phase = 2.0 * std::f32::consts::PI * 440.0 * index as f32;
+                    8_000.0 * (phase / sample_rate as f32).sin()
+                } else {
+                    0.0
+                };
+                (tone + noise()) as i16
+            })
+            .collect::<Vec<_>>();
+        let energy = |samples: &[i16]| {
+            samples
+                .iter()
+                .map(|sample| f64::from(*sample).powi(2))
+                .sum::<f64>()
+        };
+        let half = sample_rate as usize;
+        let (noise_before, tone_before) = (energy(&samples[..half]), energy(&samples[half..]));
+        SpectralGate::default().process(&mut samples, sample_rate, 1);
+        assert!(energy(&samples[..half]) < noise_before * 0.1);
+        assert!(energy(&samples[half..]) > tone_before * 0.8);
+    }
+
+    #[test]
+    fn filter_chain_normalizes_segment_peak_when_auto_gain_enabled() {
+        let mut recording = crate::settings::Settings::default().recording;
+        assert!(FilterChain::from_settings(&recording).is_empty());
+        recording.auto_gain = true;
+        recording.auto_gain_target_db = -6.0;
+        let mut samples = vec![0, 1_000, -2_000, 500];
+        FilterChain::from_settings(&recording).process(&mut samples, 16_000, 1);
+        assert_eq!(samples[2], -16_422);
+        assert_eq!(samples[1], 8_211);
+
+        recording.auto_gain = false;
+        assert!(FilterChain::from_settings(&recording).is_empty());
+    }
+
+    #[test]
+    fn speech_format_downmixes_and_downsamples() {
+        let stereo = [100i16, 300].repeat(48);
+        let (samples, sample_rate, channels) =
+            SegmentFormat::Speech.convert(Cow::Borrowed(&stereo), 48_000, 2);
+        assert_eq!((sample_rate, channels), (16_000, 1));
+        assert_eq!(samples.len(), 16);
+        assert!(samples.iter().all(|sample| *sample == 200));
+
+        let narrowband = vec![1i16; 8];
+        let (samples, sample_rate, _) =
+            SegmentFormat::Speech.convert(Cow::Borrowed(&narrowband), 8_000, 1);
+        assert_eq!((samples.len(), sample_rate), (8, 8_000));
+    }
+}
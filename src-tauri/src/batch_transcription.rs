@@ -0,0 +1,283 @@
+//! 批量转写：处理文件夹中的音频文件，在原文件旁写出同名 `.txt` 与 `.srt`
+//!
+//! 复用听写的分段转写流程（分段、重试、失败占位），并应用逆文本标准化与脱敏设置。
+//! 已存在同名转写结果的文件会被跳过，不覆盖用户已有的文本。
+
+use crate::audio_inspect::{self, AudioFormat};
+use crate::audio_processing;
+use crate::itn;
+use crate::processing;
+use crate::recorder::RecordedAudio;
+use crate::redaction;
+use crate::settings::Settings;
+use crate::transcription::{self, TranscriptSegment};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 可识别为音频的扩展名；WAV 直接读取，压缩格式先解码为 PCM，无法解码的格式（如 Opus）
+/// 在提供商接受压缩上传时直接上传原文件
+const AUDIO_EXTENSIONS: [&str; 7] = ["wav", "mp3", "m4a", "flac", "ogg", "opus", "webm"];
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchFileStatus {
+    Processing,
+    Done,
+    Failed,
+    Skipped,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchProgress {
+    pub total: usize,
+    /// 当前文件序号（从 1 开始）
+    pub index: usize,
+    pub path: String,
+    pub status: BatchFileStatus,
+    pub error_message: Option<String>,
+}
+
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+pub fn transcribe_folder(
+    settings: &Settings,
+    dir: &Path,
+    recursive: bool,
+    on_progress: impl Fn(BatchProgress),
+) -> Result<BatchSummary, String> {
+    if !dir.is_dir() {
+        return Err(format!("文件夹不存在: {}", dir.display()));
+    }
+    let mut files = Vec::new();
+    collect_audio_files(dir, recursive, &mut files)?;
+    files.sort();
+
+    let engine = transcription::create_backend(settings);
+    let mut summary = BatchSummary {
+        total: files.len(),
+        ..BatchSummary::default()
+    };
+    for (index, path) in files.iter().enumerate() {
+        let progress = |status, error_message| BatchProgress {
+            total: files.len(),
+            index: index + 1,
+            path: path.display().to_string(),
+            status,
+            error_message,
+        };
+        let (text_path, srt_path) = output_paths(path);
+        if text_path.exists() || srt_path.exists() {
+            summary.skipped += 1;
+            on_progress(progress(
+                BatchFileStatus::Skipped,
+                Some("已存在转写结果，如需重新转写请先删除同名 .txt 与 .srt 文件".to_string()),
+            ));
+            continue;
+        }
+        on_progress(progress(BatchFileStatus::Processing, None));
+        match transcribe_file(settings, engine.as_ref(), path) {
+            Ok(()) => {
+                summary.succeeded += 1;
+                on_progress(progress(BatchFileStatus::Done, None));
+            }
+            Err(err) => {
+                summary.failed += 1;
+                on_progress(progress(BatchFileStatus::Failed, Some(err)));
+            }
+        }
+    }
+    Ok(summary)
+}
+
+fn transcribe_file(
+    settings: &Settings,
+    engine: &dyn transcription::TranscriptionBackend,
+    path: &Path,
+) -> Result<(), String> {
+    let (segments, cues) = match read_audio(path) {
+        Ok(recording) => {
+            let transcript = processing::transcribe_segments(settings, engine, &recording, false)?;
+            (transcript.segments, transcript.cues)
+        }
+        // 无法解码的格式交给接受压缩上传的提供商自行解码
+        Err(_) if engine.capabilities().compressed_upload => upload_file(engine, path)?,
+        Err(err) => return Err(err),
+    };
+    let postprocess = |segments: Vec<TranscriptSegment>| {
+        segments
+            .into_iter()
+            .map(|segment| TranscriptSegment {
+                text: redaction::redact(
+                    &itn::normalize(segment.text.trim(), &settings.output.itn),
+                    &settings.redaction,
+                ),
+                ..segment
+            })
+            .filter(|segment| !segment.text.is_empty())
+            .collect::<Vec<_>>()
+    };
+    let segments = postprocess(segments);
+    let cues = postprocess(cues);
+
+    let text = segments
+        .iter()
+        .map(|segment| segment.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let (text_path, srt_path) = output_paths(path);
+    fs::write(text_path, text).map_err(|err| err.to_string())?;
+    fs::write(srt_path, format_srt(&cues)).map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+fn output_paths(path: &Path) -> (PathBuf, PathBuf) {
+    (path.with_extension("txt"), path.with_extension("srt"))
+}
+
+/// 读取音频为 PCM 录音数据：WAV 直接读取，其余格式解码
+fn read_audio(path: &Path) -> Result<RecordedAudio, String> {
+    let recording = if audio_inspect::detect_format(path) == Some(AudioFormat::Wav) {
+        audio_processing::read_wav_file(path)
+    } else {
+        audio_processing::decode_audio_file(path)
+    };
+    recording.map_err(|err| err.to_string())
+}
+
+/// 不分段直接上传原文件，返回（分段文本，字幕片段）；提供商未返回时间片段时字幕为空
+fn upload_file(
+    engine: &dyn transcription::TranscriptionBackend,
+    path: &Path,
+) -> Result<(Vec<TranscriptSegment>, Vec<TranscriptSegment>), String> {
+    let size = fs::metadata(path).map_err(|err| err.to_string())?.len();
+    if let Some(limit) = engine.options().max_upload_bytes {
+        if size > limit {
+            return Err(format!(
+                "文件大小 {} MB 超过提供商上限 {} MB，请先转换为 WAV 或 MP3",
+                size / (1024 * 1024),
+                limit / (1024 * 1024)
+            ));
+        }
+    }
+    let result = engine.transcribe(path).map_err(|err| err.to_string())?;
+    let text = TranscriptSegment {
+        start_ms: 0,
+        end_ms: result.segments.last().map_or(0, |segment| segment.end_ms),
+        text: result.text,
+    };
+    Ok((vec![text], result.segments))
+}
+
+fn collect_audio_files(
+    dir: &Path,
+    recursive: bool,
+    files: &mut Vec<PathBuf>,
+) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|err| err.to_string())?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if recursive {
+                collect_audio_files(&path, recursive, files)?;
+            }
+            continue;
+        }
+        let is_audio = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                AUDIO_EXTENSIONS
+                    .iter()
+                    .any(|known| known.eq_ignore_ascii_case(ext))
+            });
+        if is_audio {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn format_srt(segments: &[TranscriptSegment]) -> String {
+    segments
+        .iter()
+        .enumerate()
+        .map(|(index, segment)| {
+            format!(
+                "{}\n{} --> {}\n{}\n",
+                index + 1,
+                format_srt_timestamp(segment.start_ms),
+                format_srt_timestamp(segment.end_ms),
+                segment.text
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn format_srt_timestamp(ms: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_srt_numbers_cues_and_formats_timestamps() {
+        let segments = vec![
+            TranscriptSegment {
+                start_ms: 0,
+                end_ms: 60_000,
+                text: "第一段".to_string(),
+            },
+            TranscriptSegment {
+                start_ms: 60_000,
+                end_ms: 3_723_456,
+                text: "second".to_string(),
+            },
+        ];
+        assert_eq!(
+            format_srt(&segments),
+            "1\n00:00:00,000 --> 00:01:00,000\n第一段\n\n2\n00:01:00,000 --> 01:02:03,456\nsecond\n"
+        );
+    }
+
+    #[test]
+    fn transcribe_folder_skips_files_with_existing_outputs() {
+        let dir = std::env::temp_dir().join(format!(
+            "vtt-keyboard-batch-{}",
+            crate::util::timestamp_id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("meeting.mp3"), b"ID3").unwrap();
+        fs::write(dir.join("meeting.txt"), "已有文本").unwrap();
+
+        let summary = transcribe_folder(&Settings::default(), &dir, false, |progress| {
+            assert!(matches!(progress.status, BatchFileStatus::Skipped))
+        })
+        .unwrap();
+
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(
+            fs::read_to_string(dir.join("meeting.txt")).unwrap(),
+            "已有文本"
+        );
+        assert!(!dir.join("meeting.srt").exists());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
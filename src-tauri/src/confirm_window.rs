@@ -0,0 +1,108 @@
+//! 粘贴前确认窗口
+//!
+//! 开启后听写结果不直接粘贴，而是显示在置顶的小窗口中，编辑后再粘贴、复制或丢弃。
+//! 窗口与主界面共用前端入口，按窗口标签渲染；待确认文本保存在此处供窗口读取。
+
+use crate::corrections;
+use crate::events;
+use crate::paste::{self, PasteError};
+use crate::processing;
+use crate::AppState;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+
+pub const CONFIRM_WINDOW_LABEL: &str = "confirm";
+/// 窗口已打开时推送新的待确认文本
+pub const CONFIRM_TEXT_EVENT: &str = "confirm-text";
+/// 隐藏窗口后等待焦点回到原应用再模拟粘贴
+const FOCUS_RESTORE_DELAY: Duration = Duration::from_millis(150);
+
+static PENDING: Mutex<Option<PendingConfirmation>> = Mutex::new(None);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingConfirmation {
+    pub text: String,
+    #[serde(skip)]
+    paste_history_size: usize,
+}
+
+/// 保存待确认文本并显示确认窗口，窗口不存在时创建
+pub fn show(text: &str, paste_history_size: usize) -> Result<(), String> {
+    let app = events::app_handle().ok_or_else(|| "应用尚未初始化".to_string())?;
+    let pending = PendingConfirmation {
+        text: text.to_string(),
+        paste_history_size,
+    };
+    *PENDING.lock().unwrap_or_else(|e| e.into_inner()) = Some(pending.clone());
+
+    if let Some(window) = app.get_webview_window(CONFIRM_WINDOW_LABEL) {
+        let _ = app.emit_to(CONFIRM_WINDOW_LABEL, CONFIRM_TEXT_EVENT, pending);
+        window.show().map_err(|err| err.to_string())?;
+        window.set_focus().map_err(|err| err.to_string())?;
+        return Ok(());
+    }
+    WebviewWindowBuilder::new(app, CONFIRM_WINDOW_LABEL, WebviewUrl::default())
+        .title("vtt-keyboard")
+        .inner_size(420.0, 240.0)
+        .always_on_top(true)
+        .decorations(false)
+        .skip_taskbar(true)
+        .resizable(false)
+        .focused(true)
+        .build()
+        .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+pub fn pending() -> Option<PendingConfirmation> {
+    PENDING.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// 粘贴编辑后的文本：先隐藏窗口让焦点回到原应用
+pub fn paste(app: &AppHandle, text: &str) -> Result<(), PasteError> {
+    let pending = take_pending(app);
+    thread::sleep(FOCUS_RESTORE_DELAY);
+    paste::write_and_paste(text)?;
+    finish(app, pending, text);
+    processing::emit_status("completed");
+    Ok(())
+}
+
+pub fn copy(app: &AppHandle, text: &str) -> Result<(), PasteError> {
+    let pending = take_pending(app);
+    paste::write_text(text)?;
+    finish(app, pending, text);
+    Ok(())
+}
+
+pub fn discard(app: &AppHandle) {
+    take_pending(app);
+}
+
+/// 清空待确认文本并隐藏窗口
+fn take_pending(app: &AppHandle) -> Option<PendingConfirmation> {
+    if let Some(window) = app.get_webview_window(CONFIRM_WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+    PENDING.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// 记录粘贴结果；用户改动过文本时交给修改学习
+fn finish(app: &AppHandle, pending: Option<PendingConfirmation>, text: &str) {
+    let capacity = pending
+        .as_ref()
+        .map_or(0, |pending| pending.paste_history_size);
+    paste::remember_result(text, capacity);
+    let Some(pending) = pending.filter(|pending| pending.text != text) else {
+        return;
+    };
+    let state = app.state::<AppState>();
+    if let Err(_err) = corrections::report(&state.settings_store, &pending.text, text) {
+        #[cfg(debug_assertions)]
+        eprintln!("[confirm] 记录修改失败: {_err}");
+    }
+}
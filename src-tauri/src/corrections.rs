@@ -0,0 +1,273 @@
+//! 从用户修改中学习替换与热词
+//!
+//! 用户在确认窗口中编辑结果或主动上报修改时，逐词比对原文与修改后的文本，
+//! 记录被替换的片段。同一修改反复出现后自动应用到后续转写结果，并作为热词随请求发送。
+
+use crate::processing::{is_wide_char, is_word_char};
+use crate::settings::{CorrectionEntry, CorrectionSettings, Settings, SettingsStore};
+use std::cmp::Reverse;
+
+/// 最多保留的修改记录数，超出时淘汰出现次数最少的记录
+const MAX_ENTRIES: usize = 200;
+/// 参与比对的最大词元数，过长的文本视为整段改写不做学习
+const MAX_DIFF_TOKENS: usize = 400;
+/// 单处修改的最大字符数，更长的改动多为改写而非纠错
+const MAX_SPAN_CHARS: usize = 32;
+
+/// 比对原文与修改后的文本并累计修改记录，返回本次记录的修改数
+pub fn report(store: &SettingsStore, original: &str, corrected: &str) -> Result<usize, String> {
+    let pairs = extract_corrections(original, corrected);
+    if pairs.is_empty() {
+        return Ok(0);
+    }
+    let mut recorded = 0;
+    store
+        .update_corrections(|settings| {
+            if settings.enabled {
+                recorded = pairs.len();
+                record(settings, &pairs);
+            }
+        })
+        .map_err(|err| err.to_string())?;
+    Ok(recorded)
+}
+
+/// 将出现次数达到阈值的修改应用到文本
+pub fn apply(text: &str, settings: &CorrectionSettings) -> String {
+    let mut learned: Vec<&CorrectionEntry> = learned_entries(settings).collect();
+    // 同一原文有多种修改时取出现次数最多者；较长的原文优先替换
+    learned.sort_by(|a, b| {
+        b.original
+            .chars()
+            .count()
+            .cmp(&a.original.chars().count())
+            .then(b.count.cmp(&a.count))
+    });
+    let mut seen = Vec::new();
+    let mut output = text.to_string();
+    for entry in learned {
+        if seen.contains(&entry.original.as_str()) {
+            continue;
+        }
+        seen.push(entry.original.as_str());
+        output = replace_whole_words(&output, &entry.original, &entry.corrected);
+    }
+    output
+}
+
+/// 将学习到的修改后词语加入热词，提升专有名词的识别率
+pub fn with_learned_hotwords(mut settings: Settings) -> Settings {
+    let learned: Vec<String> = learned_entries(&settings.corrections)
+        .map(|entry| entry.corrected.clone())
+        .collect();
+    for word in learned {
+        if !settings.volcengine.hotwords.contains(&word) {
+            settings.volcengine.hotwords.push(word);
+        }
+    }
+    settings
+}
+
+fn learned_entries(settings: &CorrectionSettings) -> impl Iterator<Item = &CorrectionEntry> {
+    settings
+        .entries
+        .iter()
+        .filter(move |entry| settings.enabled && entry.count >= settings.min_occurrences)
+}
+
+fn record(settings: &mut CorrectionSettings, pairs: &[(String, String)]) {
+    for (original, corrected) in pairs {
+        match settings
+            .entries
+            .iter_mut()
+            .find(|entry| &entry.original == original && &entry.corrected == corrected)
+        {
+            Some(entry) => entry.count = entry.count.saturating_add(1),
+            None => settings.entries.push(CorrectionEntry {
+                original: original.clone(),
+                corrected: corrected.clone(),
+                count: 1,
+            }),
+        }
+    }
+    if settings.entries.len() > MAX_ENTRIES {
+        // 稳定排序保证同次数时保留较早的记录
+        settings.entries.sort_by_key(|entry| Reverse(entry.count));
+        settings.entries.truncate(MAX_ENTRIES);
+    }
+}
+
+/// 逐词比对两段文本，返回被替换的（原文，修改后）片段；纯插入或删除不计入
+fn extract_corrections(original: &str, corrected: &str) -> Vec<(String, String)> {
+    let old_tokens = tokenize(original);
+    let new_tokens = tokenize(corrected);
+    if old_tokens.len() > MAX_DIFF_TOKENS || new_tokens.len() > MAX_DIFF_TOKENS {
+        return Vec::new();
+    }
+    // 仅以空白隔开的相邻修改合并为一处，如 “vs code” → “VS Code”
+    let mut ranges: Vec<(Span, Span)> = Vec::new();
+    for (old_range, new_range) in changed_ranges(&old_tokens, &new_tokens) {
+        match ranges.last_mut() {
+            Some((prev_old, prev_new))
+                if old_tokens[prev_old.end..old_range.start]
+                    .iter()
+                    .all(|token| token.trim().is_empty()) =>
+            {
+                prev_old.end = old_range.end;
+                prev_new.end = new_range.end;
+            }
+            _ => ranges.push((old_range, new_range)),
+        }
+    }
+    let mut pairs = Vec::new();
+    for (old_range, new_range) in ranges {
+        let (old_range, new_range) = widen_single_char(&old_tokens, old_range, new_range);
+        let from = old_tokens[old_range].concat();
+        let to = new_tokens[new_range].concat();
+        let (from, to) = (from.trim(), to.trim());
+        if from.is_empty()
+            || to.is_empty()
+            || from == to
+            || from.chars().count() > MAX_SPAN_CHARS
+            || to.chars().count() > MAX_SPAN_CHARS
+        {
+            continue;
+        }
+        pairs.push((from.to_string(), to.to_string()));
+    }
+    pairs
+}
+
+type Span = std::ops::Range<usize>;
+
+/// 基于最长公共子序列找出两侧不一致的词元区间
+fn changed_ranges(old: &[&str], new: &[&str]) -> Vec<(Span, Span)> {
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0u16; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut ranges = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let (mut start_i, mut start_j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            if start_i < i || start_j < j {
+                ranges.push((start_i..i, start_j..j));
+            }
+            i += 1;
+            j += 1;
+            start_i = i;
+            start_j = j;
+        } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            j += 1;
+        } else {
+            i += 1;
+        }
+    }
+    if start_i < n || start_j < m {
+        ranges.push((start_i..n, start_j..m));
+    }
+    ranges
+}
+
+/// 单个汉字的替换向两侧扩展到相邻汉字，避免把常用字全局替换
+fn widen_single_char(old: &[&str], old_range: Span, new_range: Span) -> (Span, Span) {
+    let is_single_wide =
+        |tokens: &[&str]| tokens.len() == 1 && tokens[0].chars().next().is_some_and(is_wide_char);
+    if !is_single_wide(&old[old_range.clone()]) {
+        return (old_range, new_range);
+    }
+    let before = old_range.start > 0 && is_single_wide(&old[old_range.start - 1..old_range.start]);
+    let after = old_range.end < old.len() && is_single_wide(&old[old_range.end..old_range.end + 1]);
+    let (lead, trail) = (usize::from(before), usize::from(after));
+    (
+        old_range.start - lead..old_range.end + trail,
+        new_range.start - lead..new_range.end + trail,
+    )
+}
+
+/// 切分词元：连续的字母数字为一个词，汉字等宽字符与其余符号各自成词
+fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut word_start = None;
+    for (index, ch) in text.char_indices() {
+        if is_word_char(ch) {
+            word_start.get_or_insert(index);
+            continue;
+        }
+        if let Some(start) = word_start.take() {
+            tokens.push(&text[start..index]);
+        }
+        tokens.push(&text[index..index + ch.len_utf8()]);
+    }
+    if let Some(start) = word_start {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+/// 仅在词边界处替换，避免误改包含该片段的更长单词
+fn replace_whole_words(text: &str, from: &str, to: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+    for (index, _) in text.match_indices(from) {
+        let before = text[..index].chars().next_back();
+        let after = text[index + from.len()..].chars().next();
+        let starts_word = from.chars().next().is_some_and(is_word_char);
+        let ends_word = from.chars().next_back().is_some_and(is_word_char);
+        if (starts_word && before.is_some_and(is_word_char))
+            || (ends_word && after.is_some_and(is_word_char))
+        {
+            continue;
+        }
+        output.push_str(&text[last..index]);
+        output.push_str(to);
+        last = index + from.len();
+    }
+    output.push_str(&text[last..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_corrections_finds_replaced_words_and_widens_single_chinese_chars() {
+        assert_eq!(
+            extract_corrections("open the vs code window", "open the VS Code window"),
+            vec![("vs code".to_string(), "VS Code".to_string())]
+        );
+        assert_eq!(
+            extract_corrections("我们用泰瑞来打包", "我们用Tauri来打包"),
+            vec![("泰瑞".to_string(), "Tauri".to_string())]
+        );
+        assert_eq!(
+            extract_corrections("今天天汽不错", "今天天气不错"),
+            vec![("天汽不".to_string(), "天气不".to_string())]
+        );
+        // 纯插入不产生替换记录
+        assert!(extract_corrections("hello world", "hello big world").is_empty());
+    }
+
+    #[test]
+    fn apply_uses_entries_after_threshold_on_word_boundaries() {
+        let mut settings = CorrectionSettings::default();
+        let pairs = vec![("tari".to_string(), "Tauri".to_string())];
+        record(&mut settings, &pairs);
+        assert_eq!(apply("use tari today", &settings), "use tari today");
+        record(&mut settings, &pairs);
+        assert_eq!(settings.entries[0].count, 2);
+        assert_eq!(
+            apply("use tari, not tarifa", &settings),
+            "use Tauri, not tarifa"
+        );
+    }
+}
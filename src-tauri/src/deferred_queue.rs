@@ -0,0 +1,284 @@
+//! 离线待转写队列
+//!
+//! 离线或本地模型预热期间录下的音频以加密的 WAV 文件保存在应用数据目录，
+//! 待提供商可用后由转写调度线程自动补转写。补转写失败的录音保留在队列中，
+//! 按同名 JSON 文件记录的次数指数退避；无法读取的录音多次失败后移入隔离目录，不会被删除。
+
+use crate::audio_crypto;
+use crate::audio_processing;
+use crate::recorder::RecordedAudio;
+use crate::settings::{Settings, SettingsStore};
+use crate::system_state;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const DEFERRED_DIR_NAME: &str = "deferred";
+const DEFERRED_ID_PREFIX: &str = "deferred-";
+/// 无法读取的录音移入的子目录，留待用户手动处理
+const QUARANTINE_DIR_NAME: &str = "quarantine";
+/// 首次补转写失败后的等待时长，之后每次失败翻倍
+const INITIAL_BACKOFF: Duration = Duration::from_secs(30);
+/// 退避等待的上限
+const MAX_BACKOFF: Duration = Duration::from_secs(30 * 60);
+/// 连续读取失败达到该次数后移入隔离目录；密钥暂不可用等短暂故障在此之前可恢复
+const MAX_LOAD_FAILURES: u32 = 5;
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct RetryState {
+    attempts: u32,
+    load_failures: u32,
+    next_attempt_ms: u64,
+}
+
+pub struct DeferredRecording {
+    pub id: String,
+    pub path: PathBuf,
+    state: RetryState,
+}
+
+impl DeferredRecording {
+    pub fn is_due(&self, now_ms: u64) -> bool {
+        self.state.next_attempt_ms <= now_ms
+    }
+
+    fn state_path(&self) -> PathBuf {
+        self.path.with_extension("json")
+    }
+}
+
+/// 单条录音一次补转写的结果
+pub enum RetryOutcome {
+    Completed,
+    /// 解密或读取录音失败
+    LoadFailed,
+    /// 转写失败，录音完好
+    Failed,
+}
+
+/// 持久化录音并返回队列项 ID（同时用作历史记录 ID）
+/// 只保存主录音（麦克风），附加音轨（如系统音频）不入队，补转写时按单音轨处理
+pub fn enqueue(store: &SettingsStore, recording: &RecordedAudio) -> Result<String, String> {
+    let dir = queue_dir(store)?;
+    let id = format!("{DEFERRED_ID_PREFIX}{}", crate::util::timestamp_id());
+    let key = store.encryption_key().map_err(|err| err.to_string())?;
+    let source = audio_processing::wav_source(recording).map_err(|err| err.to_string())?;
+    audio_crypto::encrypt_to_file(source, &dir.join(format!("{id}.wav")), &key)?;
+    Ok(id)
+}
+
+/// 按入队顺序列出待转写的录音
+pub fn pending(store: &SettingsStore) -> Vec<DeferredRecording> {
+    queue_dir(store)
+        .map(|dir| pending_in(&dir))
+        .unwrap_or_default()
+}
+
+fn pending_in(dir: &Path) -> Vec<DeferredRecording> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let paths = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    // 音频已被存储配额删除的重试记录随之清理
+    for path in &paths {
+        if path.extension().is_some_and(|ext| ext == "json") && !path.with_extension("wav").exists()
+        {
+            let _ = fs::remove_file(path);
+        }
+    }
+    let mut items = paths
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wav"))
+        .filter_map(|path| {
+            let id = path.file_stem()?.to_str()?.to_string();
+            if !id.starts_with(DEFERRED_ID_PREFIX) {
+                return None;
+            }
+            let state = fs::read_to_string(path.with_extension("json"))
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .unwrap_or_default();
+            Some(DeferredRecording { id, path, state })
+        })
+        .collect::<Vec<_>>();
+    items.sort_by(|left, right| left.id.cmp(&right.id));
+    items
+}
+
+/// 按入队顺序补转写到期的录音：成功后移出队列；失败的录音保留并推迟，不阻塞后续录音
+pub fn retry_due(
+    items: Vec<DeferredRecording>,
+    now_ms: u64,
+    mut attempt: impl FnMut(&DeferredRecording) -> RetryOutcome,
+) {
+    for item in items.iter().filter(|item| item.is_due(now_ms)) {
+        match attempt(item) {
+            RetryOutcome::Completed => remove(item),
+            RetryOutcome::LoadFailed => mark_failed(item, now_ms, true),
+            RetryOutcome::Failed => mark_failed(item, now_ms, false),
+        }
+    }
+}
+
+fn mark_failed(item: &DeferredRecording, now_ms: u64, load_failed: bool) {
+    let load_failures = if load_failed {
+        item.state.load_failures.saturating_add(1)
+    } else {
+        0
+    };
+    if load_failures >= MAX_LOAD_FAILURES {
+        quarantine(item);
+        return;
+    }
+    let attempts = item.state.attempts.saturating_add(1);
+    let state = RetryState {
+        attempts,
+        load_failures,
+        next_attempt_ms: now_ms + backoff(attempts).as_millis() as u64,
+    };
+    if let Ok(content) = serde_json::to_string(&state) {
+        let _ = fs::write(item.state_path(), content);
+    }
+}
+
+/// 将无法读取的录音移入隔离目录，保留原文件名
+fn quarantine(item: &DeferredRecording) {
+    let Some(dir) = item.path.parent().map(|dir| dir.join(QUARANTINE_DIR_NAME)) else {
+        return;
+    };
+    let Some(name) = item.path.file_name() else {
+        return;
+    };
+    if fs::create_dir_all(&dir).is_ok() && fs::rename(&item.path, dir.join(name)).is_ok() {
+        let _ = fs::remove_file(item.state_path());
+    }
+}
+
+/// 第 `attempts` 次失败后的等待时长，按指数增长并封顶
+fn backoff(attempts: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(1u32 << attempts.saturating_sub(1).min(16))
+        .min(MAX_BACKOFF)
+}
+
+/// 解密到 `scratch_dir` 下的临时文件后读取，临时文件随即删除
+pub fn load(
+    store: &SettingsStore,
+    item: &DeferredRecording,
+    scratch_dir: &Path,
+) -> Result<RecordedAudio, String> {
+    let key = store.encryption_key().map_err(|err| err.to_string())?;
+    let decrypted = audio_crypto::decrypt_to_temp(&item.path, scratch_dir, &key)?;
+    audio_processing::read_wav_file(decrypted.path()).map_err(|err| err.to_string())
+}
+
+pub fn remove(item: &DeferredRecording) {
+    let _ = fs::remove_file(&item.path);
+    let _ = fs::remove_file(item.state_path());
+}
+
+/// 当前提供商是否可以立即转写：云端需要联网，本地模型需要已安装且未在预热
+pub fn provider_available(settings: &Settings) -> bool {
+    if settings.provider.is_cloud() {
+        return system_state::is_network_online();
+    }
+    settings.sensevoice.installed && !is_local_model_warming(settings)
+}
+
+pub fn is_local_model_warming(settings: &Settings) -> bool {
+    settings
+        .sensevoice
+        .download_state
+        .trim()
+        .eq_ignore_ascii_case("running")
+}
+
+pub fn queue_dir(store: &SettingsStore) -> Result<PathBuf, String> {
+    store
+        .recordings_dir(DEFERRED_DIR_NAME)
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue_with(name: &str, ids: &[&str]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "vtt-keyboard-deferred-{name}-{}",
+            crate::util::timestamp_id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        for id in ids {
+            fs::write(dir.join(format!("{DEFERRED_ID_PREFIX}{id}.wav")), b"audio").unwrap();
+        }
+        dir
+    }
+
+    fn ids(dir: &Path) -> Vec<String> {
+        pending_in(dir).into_iter().map(|item| item.id).collect()
+    }
+
+    #[test]
+    fn retry_due_removes_completed_recordings() {
+        let dir = queue_with("completed", &["1", "2"]);
+        retry_due(pending_in(&dir), 0, |_| RetryOutcome::Completed);
+        assert!(ids(&dir).is_empty());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn retry_due_keeps_failed_recording_and_continues_with_later_ones() {
+        let dir = queue_with("failed", &["1", "2"]);
+        let mut attempted = Vec::new();
+        retry_due(pending_in(&dir), 0, |item| {
+            attempted.push(item.id.clone());
+            if item.id.ends_with('1') {
+                RetryOutcome::Failed
+            } else {
+                RetryOutcome::Completed
+            }
+        });
+        assert_eq!(attempted.len(), 2);
+        assert_eq!(ids(&dir), vec![format!("{DEFERRED_ID_PREFIX}1")]);
+
+        // 退避期间不再尝试，到期后重试且等待时长翻倍
+        let items = pending_in(&dir);
+        assert!(!items[0].is_due(1_000));
+        assert!(items[0].is_due(backoff(1).as_millis() as u64));
+        let now_ms = backoff(1).as_millis() as u64;
+        retry_due(items, now_ms, |_| RetryOutcome::Failed);
+        let item = &pending_in(&dir)[0];
+        assert_eq!(item.state.attempts, 2);
+        assert_eq!(
+            item.state.next_attempt_ms,
+            now_ms + backoff(2).as_millis() as u64
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn retry_due_quarantines_unreadable_recording_instead_of_deleting() {
+        let dir = queue_with("unreadable", &["1"]);
+        for _ in 0..MAX_LOAD_FAILURES - 1 {
+            let items = pending_in(&dir);
+            let now_ms = items[0].state.next_attempt_ms;
+            retry_due(items, now_ms, |_| RetryOutcome::LoadFailed);
+            assert_eq!(ids(&dir).len(), 1);
+        }
+        let items = pending_in(&dir);
+        let now_ms = items[0].state.next_attempt_ms;
+        retry_due(items, now_ms, |_| RetryOutcome::LoadFailed);
+        assert!(ids(&dir).is_empty());
+        assert!(dir
+            .join(QUARANTINE_DIR_NAME)
+            .join(format!("{DEFERRED_ID_PREFIX}1.wav"))
+            .exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,156 @@
+//! 最近听写上下文
+//!
+//! 保留最近几次听写的转写文本，作为下一次转写的 `prompt` 与文本处理的参考上下文，
+//! 使连续听写中的术语与大小写保持一致。开启后录音开始时的前台窗口标题也一并作为参考，
+//! 例如在 “JIRA-1234: Fix GPU OOM” 中听写时偏向识别相关术语。
+
+use crate::active_window::{self, ActiveWindow};
+use crate::settings::ContextCarryoverSettings;
+use std::sync::Mutex;
+use std::thread;
+
+/// 窗口标题参与提示词的最大字符数
+const MAX_WINDOW_TITLE_CHARS: usize = 120;
+
+static RECENT_CONTEXT: Mutex<String> = Mutex::new(String::new());
+static RECORDING_WINDOW: Mutex<Option<ActiveWindow>> = Mutex::new(None);
+
+/// 追加一次听写结果，超出 `max_chars` 时从最早的内容开始丢弃
+pub fn remember(text: &str, settings: &ContextCarryoverSettings) {
+    let text = text.trim();
+    if !settings.enabled || text.is_empty() {
+        return;
+    }
+    let mut context = RECENT_CONTEXT.lock().unwrap_or_else(|e| e.into_inner());
+    if !context.is_empty() {
+        context.push(' ');
+    }
+    context.push_str(text);
+    let kept = keep_last_chars(&context, settings.max_chars).to_string();
+    *context = kept;
+}
+
+/// 当前上下文；未启用时返回空字符串
+pub fn recent(settings: &ContextCarryoverSettings) -> String {
+    if !settings.enabled {
+        return String::new();
+    }
+    let context = RECENT_CONTEXT.lock().unwrap_or_else(|e| e.into_inner());
+    keep_last_chars(&context, settings.max_chars).to_string()
+}
+
+/// 录音开始时在后台记录前台窗口，避免较慢的探测拖慢录音启动；未开启时清空上次记录
+pub fn capture_window(settings: &ContextCarryoverSettings) {
+    *RECORDING_WINDOW.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    if !settings.use_window_title {
+        return;
+    }
+    thread::spawn(|| {
+        let window = active_window::detect().filter(|window| !window.title.trim().is_empty());
+        *RECORDING_WINDOW.lock().unwrap_or_else(|e| e.into_inner()) = window;
+    });
+}
+
+/// 转写提示词中的上下文：录音时的窗口标题在前，最近听写内容在后
+pub fn prompt_context(settings: &ContextCarryoverSettings) -> String {
+    let title = recording_window(settings)
+        .map(|window| truncate_chars(window.title.trim(), MAX_WINDOW_TITLE_CHARS).to_string())
+        .unwrap_or_default();
+    [title, recent(settings)]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 供文本处理模型参考的上下文说明；未启用或没有上下文时返回空字符串
+pub fn llm_instructions(settings: &ContextCarryoverSettings) -> String {
+    if !settings.include_in_text_processing {
+        return String::new();
+    }
+    let mut sections = Vec::new();
+    if let Some(window) = recording_window(settings) {
+        let title = truncate_chars(window.title.trim(), MAX_WINDOW_TITLE_CHARS);
+        let source = if window.app_name.is_empty() {
+            title.to_string()
+        } else {
+            format!("{}: {title}", window.app_name)
+        };
+        sections.push(format!(
+            "用户正在以下窗口中听写，仅用于理解专有名词与术语，不要输出这部分内容：\n{source}"
+        ));
+    }
+    let context = recent(settings);
+    if !context.is_empty() {
+        sections.push(format!(
+            "以下是用户最近的听写内容，仅用于保持术语与大小写一致，不要输出或改写这部分内容：\n{context}"
+        ));
+    }
+    sections.join("\n\n")
+}
+
+fn recording_window(settings: &ContextCarryoverSettings) -> Option<ActiveWindow> {
+    if !settings.use_window_title {
+        return None;
+    }
+    RECORDING_WINDOW
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// 截取开头 `max_chars` 个字符
+fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    text.char_indices()
+        .nth(max_chars)
+        .map_or(text, |(index, _)| &text[..index])
+}
+
+/// 截取末尾 `max_chars` 个字符，并尽量从词边界开始
+fn keep_last_chars(text: &str, max_chars: usize) -> &str {
+    let total = text.chars().count();
+    if total <= max_chars {
+        return text;
+    }
+    let start = text
+        .char_indices()
+        .nth(total - max_chars)
+        .map(|(index, _)| index)
+        .unwrap_or(text.len());
+    let tail = &text[start..];
+    // 截断点落在英文单词中间时跳到下一个空白之后
+    let mid_word = text[..start]
+        .chars()
+        .next_back()
+        .is_some_and(|ch| ch.is_ascii_alphanumeric())
+        && tail
+            .chars()
+            .next()
+            .is_some_and(|ch| ch.is_ascii_alphanumeric());
+    if mid_word {
+        if let Some(space) = tail.find(char::is_whitespace) {
+            return tail[space..].trim_start();
+        }
+    }
+    tail.trim_start()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keep_last_chars_trims_from_front_at_word_boundary() {
+        assert_eq!(keep_last_chars("short", 10), "short");
+        assert_eq!(keep_last_chars("今天天气很好", 3), "气很好");
+        assert_eq!(keep_last_chars("deploy Kubernetes cluster", 10), "cluster");
+        assert_eq!(keep_last_chars("deploy Kubernetes cluster", 8), "cluster");
+    }
+
+    #[test]
+    fn truncate_chars_counts_characters_not_bytes() {
+        assert_eq!(truncate_chars("JIRA-1234: Fix GPU OOM", 9), "JIRA-1234");
+        assert_eq!(truncate_chars("修复显存溢出", 2), "修复");
+        assert_eq!(truncate_chars("short", 10), "short");
+    }
+}
@@ -0,0 +1,64 @@
+//! 说话结束自动停止录音
+//!
+//! 与语音唤醒按固定静音时长停止不同，这里要求累计说话达到最短时长后再出现足够长的
+//! 静音才判定结束，避免刚开口或句中思考停顿时录音被提前结束。
+
+use crate::settings::EndOfSpeechSettings;
+
+pub struct EndOfSpeechDetector {
+    threshold_db: f32,
+    trailing_silence_ms: u32,
+    min_speech_ms: u32,
+    speech_ms: u32,
+    silence_ms: u32,
+}
+
+impl EndOfSpeechDetector {
+    /// 未开启时返回 None
+    pub fn new(settings: &EndOfSpeechSettings) -> Option<Self> {
+        settings.enabled.then(|| Self {
+            threshold_db: settings.threshold_db,
+            trailing_silence_ms: settings.trailing_silence_ms,
+            min_speech_ms: settings.min_speech_ms,
+            speech_ms: 0,
+            silence_ms: 0,
+        })
+    }
+
+    /// 输入一段时长为 `duration_ms` 的电平，判定说话已结束时返回 true
+    pub fn push(&mut self, level_db: f32, duration_ms: u32) -> bool {
+        if level_db >= self.threshold_db {
+            self.speech_ms = self.speech_ms.saturating_add(duration_ms);
+            self.silence_ms = 0;
+        } else {
+            self.silence_ms = self.silence_ms.saturating_add(duration_ms);
+        }
+        self.speech_ms >= self.min_speech_ms && self.silence_ms >= self.trailing_silence_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detector_requires_min_speech_before_trailing_silence() {
+        let settings = EndOfSpeechSettings {
+            enabled: true,
+            threshold_db: -40.0,
+            trailing_silence_ms: 300,
+            min_speech_ms: 500,
+        };
+        let mut detector = EndOfSpeechDetector::new(&settings).unwrap();
+        // 开口前的静音与过短的发言都不结束录音
+        assert!(!detector.push(-60.0, 1000));
+        assert!(!detector.push(-20.0, 300));
+        assert!(!detector.push(-60.0, 400));
+        // 句中停顿短于静音时长时继续录音
+        assert!(!detector.push(-20.0, 300));
+        assert!(!detector.push(-60.0, 200));
+        assert!(detector.push(-60.0, 100));
+
+        assert!(EndOfSpeechDetector::new(&EndOfSpeechSettings::default()).is_none());
+    }
+}
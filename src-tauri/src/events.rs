@@ -0,0 +1,269 @@
+//! 全局事件推送
+//!
+//! 转写引擎等模块运行在后台线程且不持有 `AppHandle`，通过此处向前端推送事件。
+//! 应用启动时调用 [`init`] 注册句柄，未注册时推送静默忽略。
+
+use crate::jobs::{self, JobId, JobStatus};
+use crate::settings::Settings;
+use serde::Serialize;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Emitter};
+
+/// 流式识别中间结果事件
+pub const PARTIAL_TRANSCRIPT_EVENT: &str = "partial-transcript";
+/// 转写任务状态变化事件，负载为任务的完整状态
+pub const JOB_STATUS_EVENT: &str = "job-status";
+/// 录音输入设备与静音提示事件
+pub const RECORDING_SOURCE_EVENT: &str = "recording-source";
+/// 录音实际使用的输入设备发生变化事件
+pub const INPUT_DEVICE_CHANGED_EVENT: &str = "input-device-changed";
+/// 录音流出错（如设备断开）及自动重新打开结果事件
+pub const RECORDING_ERROR_EVENT: &str = "recording-error";
+/// 录音质量提示事件，如输入音量过高出现削波
+pub const RECORDING_WARNING_EVENT: &str = "recording-warning";
+/// 录音达到时长上限被自动结束事件
+pub const RECORDING_LIMIT_EVENT: &str = "recording-limit-reached";
+/// 录音实时电平事件，约每 100ms 一次
+pub const RECORDING_LEVEL_EVENT: &str = "recording-level";
+/// 录音分段边界与转写进度事件，供前端绘制录音时间轴
+pub const RECORDING_SEGMENT_EVENT: &str = "recording-segment";
+/// 设置在主窗口之外（如托盘快捷开关）被修改事件，负载为保存后的完整设置
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialTranscript {
+    /// 当前已识别的完整文本（非增量）
+    pub text: String,
+    /// 是否为该段音频的最终结果
+    pub is_final: bool,
+    /// 产生该结果时正在转写的任务；没有进行中的任务时为 None
+    pub job_id: Option<JobId>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingSource {
+    pub device_name: String,
+    /// 录音电平持续接近零，可能选错了输入设备
+    pub silent: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputDeviceChanged {
+    pub device_name: String,
+}
+
+/// 录音流出错后的恢复进展
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordingRecovery {
+    /// 录音流已失效，正在尝试重新打开输入设备
+    Reconnecting,
+    /// 已在 `device_name` 上继续录音
+    Recovered,
+    /// 多次尝试后仍无法重新打开，结束录音时只包含出错前的音频
+    Failed,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingError {
+    pub device_name: String,
+    pub message: String,
+    pub recovery: RecordingRecovery,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordingWarningKind {
+    /// 样本持续达到满幅，输入增益过高
+    Clipping,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingWarning {
+    pub device_name: String,
+    pub kind: RecordingWarningKind,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingLimitReached {
+    pub max_duration_seconds: u32,
+}
+
+/// 一个聚合窗口内的录音电平（dBFS，静音为 -100）
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingLevel {
+    pub rms_db: f32,
+    pub peak_db: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SegmentStatus {
+    /// 分段已写入临时目录，等待转写
+    Written,
+    Transcribing,
+    Completed,
+    Failed,
+}
+
+/// 录音分段在时间轴上的位置，帧序号与时间均相对于预处理后的录音开头
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingSegment {
+    /// 同一次分段的标识，区分并发的听写与批量转写
+    pub batch_id: String,
+    pub index: usize,
+    pub count: usize,
+    pub start_frame: u64,
+    pub end_frame: u64,
+    pub sample_rate: u32,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub status: SegmentStatus,
+}
+
+pub fn init(app: &AppHandle) {
+    let _ = APP_HANDLE.set(app.clone());
+}
+
+/// 供需要操作窗口的后台模块取得句柄，未注册时返回 None
+pub fn app_handle() -> Option<&'static AppHandle> {
+    APP_HANDLE.get()
+}
+
+pub fn emit_partial_transcript(text: &str, is_final: bool) {
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+    let payload = PartialTranscript {
+        text: text.to_string(),
+        is_final,
+        job_id: jobs::current(),
+    };
+    if let Err(_err) = app.emit(PARTIAL_TRANSCRIPT_EVENT, payload) {
+        #[cfg(debug_assertions)]
+        eprintln!("发送中间转写结果事件失败: {_err}");
+    }
+}
+
+pub fn emit_job_status(status: &JobStatus) {
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+    if let Err(_err) = app.emit(JOB_STATUS_EVENT, status) {
+        #[cfg(debug_assertions)]
+        eprintln!("发送转写任务状态事件失败: {_err}");
+    }
+}
+
+pub fn emit_recording_source(device_name: &str, silent: bool) {
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+    let payload = RecordingSource {
+        device_name: device_name.to_string(),
+        silent,
+    };
+    if let Err(_err) = app.emit(RECORDING_SOURCE_EVENT, payload) {
+        #[cfg(debug_assertions)]
+        eprintln!("发送录音设备事件失败: {_err}");
+    }
+}
+
+pub fn emit_input_device_changed(device_name: &str) {
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+    let payload = InputDeviceChanged {
+        device_name: device_name.to_string(),
+    };
+    if let Err(_err) = app.emit(INPUT_DEVICE_CHANGED_EVENT, payload) {
+        #[cfg(debug_assertions)]
+        eprintln!("发送输入设备变化事件失败: {_err}");
+    }
+}
+
+pub fn emit_recording_error(device_name: &str, message: &str, recovery: RecordingRecovery) {
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+    let payload = RecordingError {
+        device_name: device_name.to_string(),
+        message: message.to_string(),
+        recovery,
+    };
+    if let Err(_err) = app.emit(RECORDING_ERROR_EVENT, payload) {
+        #[cfg(debug_assertions)]
+        eprintln!("发送录音错误事件失败: {_err}");
+    }
+}
+
+pub fn emit_recording_warning(device_name: &str, kind: RecordingWarningKind) {
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+    let payload = RecordingWarning {
+        device_name: device_name.to_string(),
+        kind,
+    };
+    if let Err(_err) = app.emit(RECORDING_WARNING_EVENT, payload) {
+        #[cfg(debug_assertions)]
+        eprintln!("发送录音提示事件失败: {_err}");
+    }
+}
+
+pub fn emit_recording_limit_reached(max_duration_seconds: u32) {
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+    let payload = RecordingLimitReached {
+        max_duration_seconds,
+    };
+    if let Err(_err) = app.emit(RECORDING_LIMIT_EVENT, payload) {
+        #[cfg(debug_assertions)]
+        eprintln!("发送录音时长上限事件失败: {_err}");
+    }
+}
+
+pub fn emit_settings_changed(settings: &Settings) {
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+    if let Err(_err) = app.emit(SETTINGS_CHANGED_EVENT, settings) {
+        #[cfg(debug_assertions)]
+        eprintln!("发送设置变化事件失败: {_err}");
+    }
+}
+
+pub fn emit_recording_level(level: RecordingLevel) {
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+    if let Err(_err) = app.emit(RECORDING_LEVEL_EVENT, level) {
+        #[cfg(debug_assertions)]
+        eprintln!("发送录音电平事件失败: {_err}");
+    }
+}
+
+pub fn emit_recording_segment(segment: &RecordingSegment, status: SegmentStatus) {
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+    let payload = RecordingSegment {
+        status,
+        ..segment.clone()
+    };
+    if let Err(_err) = app.emit(RECORDING_SEGMENT_EVENT, payload) {
+        #[cfg(debug_assertions)]
+        eprintln!("发送录音分段事件失败: {_err}");
+    }
+}
@@ -0,0 +1,382 @@
+//! 逆文本标准化（ITN）：将口语化的数字表达转换为阿拉伯数字
+//!
+//! 本地模型（如 SenseVoice）输出未经标准化的原始文本，这里按语言分别处理：
+//! 中文数字（“一千零五” → “1005”、“百分之三十” → “30%”）与英文数词
+//! （“twenty three” → “23”）。规则偏保守，单个数字字词（“一个”、“one of”）保持原样。
+
+use crate::settings::ItnSettings;
+use regex::{Captures, Regex};
+use std::sync::OnceLock;
+
+/// 按设置对文本执行逆文本标准化
+pub fn normalize(text: &str, settings: &ItnSettings) -> String {
+    if !settings.enabled {
+        return text.to_string();
+    }
+    let mut output = text.to_string();
+    if settings.chinese_numerals {
+        output = zh::normalize(&output);
+    }
+    if settings.english_numbers {
+        output = en::normalize(&output);
+    }
+    output
+}
+
+mod zh {
+    const PERCENT_PREFIX: &str = "百分之";
+
+    pub fn normalize(text: &str) -> String {
+        let chars = text.chars().collect::<Vec<_>>();
+        let mut output = String::with_capacity(text.len());
+        let mut index = 0;
+        while index < chars.len() {
+            let percent = starts_with(&chars, index, PERCENT_PREFIX);
+            let start = if percent {
+                index + PERCENT_PREFIX.chars().count()
+            } else {
+                index
+            };
+            if let Some((value, end)) = convert_run(&chars, start, percent) {
+                output.push_str(&value);
+                if percent {
+                    output.push('%');
+                }
+                index = end;
+                continue;
+            }
+            // 无法转换时整段原样保留，避免只转换后半段（“三四百” → “三400”）
+            let end = run_end(&chars, index).max(index + 1);
+            output.extend(&chars[index..end]);
+            index = end;
+        }
+        output
+    }
+
+    fn run_end(chars: &[char], start: usize) -> usize {
+        let mut end = start;
+        while end < chars.len() && is_numeral(chars[end]) {
+            end += 1;
+        }
+        end
+    }
+
+    /// 尝试从 `start` 处转换一段数字，返回转换结果与结束位置
+    fn convert_run(chars: &[char], start: usize, force: bool) -> Option<(String, usize)> {
+        let first = *chars.get(start)?;
+        if digit_value(first).is_none() && first != '十' {
+            return None;
+        }
+        let end = run_end(chars, start);
+        let run = &chars[start..end];
+        let has_unit = run.iter().any(|ch| unit_value(*ch).is_some());
+        let mut value = parse_integer(run)?;
+
+        let mut fraction_end = end;
+        if chars.get(end) == Some(&'点') {
+            let mut cursor = end + 1;
+            let mut fraction = String::new();
+            while let Some(digit) = chars.get(cursor).and_then(|ch| digit_value(*ch)) {
+                fraction.push(char::from(b'0' + digit as u8));
+                cursor += 1;
+            }
+            if !fraction.is_empty() && !is_time_suffix(chars.get(cursor).copied()) {
+                value = format!("{value}.{fraction}");
+                fraction_end = cursor;
+            }
+        }
+
+        // 单个数字字（“一个”“十分”）多为固定用法，“七八个”“三五天”为约数，
+        // 除非带小数或百分号，均保持原样
+        let min_len = if has_unit { 2 } else { 3 };
+        if run.len() < min_len && fraction_end == end && !force {
+            return None;
+        }
+        Some((value, fraction_end))
+    }
+
+    /// 小数部分只由逐位读出的数字组成；其后紧跟单位或“分/刻/钟/半/点”时是钟点
+    /// （“十二点三十分”“三点一刻”）或叠词（“一点一点地”），不按小数处理
+    fn is_time_suffix(next: Option<char>) -> bool {
+        next.is_some_and(|ch| unit_value(ch).is_some() || "分刻钟半点".contains(ch))
+    }
+
+    fn parse_integer(run: &[char]) -> Option<String> {
+        if !run.iter().any(|ch| unit_value(*ch).is_some()) {
+            // 纯数字序列（年份、编号），逐位转换并保留前导零；“两”不用于逐位读数
+            if run.contains(&'两') {
+                return None;
+            }
+            return run
+                .iter()
+                .map(|ch| digit_value(*ch).map(|digit| char::from(b'0' + digit as u8)))
+                .collect();
+        }
+
+        let mut total = 0u64;
+        let mut section = 0u64;
+        let mut number = 0u64;
+        let mut pending_digit = false;
+        let mut last_unit = 1u64;
+        let mut saw_zero = false;
+        for ch in run {
+            if let Some(digit) = digit_value(*ch) {
+                // “三四百”之类的约数无法确定数值，保持原文
+                if pending_digit {
+                    return None;
+                }
+                if digit == 0 {
+                    saw_zero = true;
+                } else {
+                    number = digit;
+                    pending_digit = true;
+                }
+                continue;
+            }
+            let unit = unit_value(*ch)?;
+            match unit {
+                10 | 100 | 1000 => {
+                    let multiplier = if number == 0 && unit == 10 { 1 } else { number };
+                    section += multiplier * unit;
+                }
+                10_000 => {
+                    total = total.checked_add((section + number).checked_mul(unit)?)?;
+                    section = 0;
+                }
+                _ => {
+                    total = (total + section + number).checked_mul(unit)?;
+                    section = 0;
+                }
+            }
+            last_unit = unit;
+            number = 0;
+            pending_digit = false;
+            saw_zero = false;
+        }
+        // 口语省略末位单位：“二百五” = 250、“三万五” = 35000
+        if pending_digit && !saw_zero && last_unit >= 100 {
+            number *= last_unit / 10;
+        }
+        Some((total + section + number).to_string())
+    }
+
+    fn starts_with(chars: &[char], index: usize, prefix: &str) -> bool {
+        prefix
+            .chars()
+            .enumerate()
+            .all(|(offset, expected)| chars.get(index + offset) == Some(&expected))
+    }
+
+    fn is_numeral(ch: char) -> bool {
+        digit_value(ch).is_some() || unit_value(ch).is_some()
+    }
+
+    fn digit_value(ch: char) -> Option<u64> {
+        match ch {
+            '零' | '〇' => Some(0),
+            '一' => Some(1),
+            '二' | '两' => Some(2),
+            '三' => Some(3),
+            '四' => Some(4),
+            '五' => Some(5),
+            '六' => Some(6),
+            '七' => Some(7),
+            '八' => Some(8),
+            '九' => Some(9),
+            _ => None,
+        }
+    }
+
+    fn unit_value(ch: char) -> Option<u64> {
+        match ch {
+            '十' => Some(10),
+            '百' => Some(100),
+            '千' => Some(1000),
+            '万' => Some(10_000),
+            '亿' => Some(100_000_000),
+            _ => None,
+        }
+    }
+}
+
+mod en {
+    use super::{Captures, OnceLock, Regex};
+
+    const WORDS: [(&str, u64); 28] = [
+        ("zero", 0),
+        ("one", 1),
+        ("two", 2),
+        ("three", 3),
+        ("four", 4),
+        ("five", 5),
+        ("six", 6),
+        ("seven", 7),
+        ("eight", 8),
+        ("nine", 9),
+        ("ten", 10),
+        ("eleven", 11),
+        ("twelve", 12),
+        ("thirteen", 13),
+        ("fourteen", 14),
+        ("fifteen", 15),
+        ("sixteen", 16),
+        ("seventeen", 17),
+        ("eighteen", 18),
+        ("nineteen", 19),
+        ("twenty", 20),
+        ("thirty", 30),
+        ("forty", 40),
+        ("fifty", 50),
+        ("sixty", 60),
+        ("seventy", 70),
+        ("eighty", 80),
+        ("ninety", 90),
+    ];
+    const SCALES: [(&str, u64); 4] = [
+        ("hundred", 100),
+        ("thousand", 1_000),
+        ("million", 1_000_000),
+        ("billion", 1_000_000_000),
+    ];
+
+    fn number_pattern() -> &'static Regex {
+        static PATTERN: OnceLock<Regex> = OnceLock::new();
+        PATTERN.get_or_init(|| {
+            let word = WORDS
+                .iter()
+                .chain(SCALES.iter())
+                .map(|(word, _)| *word)
+                .collect::<Vec<_>>()
+                .join("|");
+            let pattern =
+                format!(r"(?i)\b(?:{word})\b(?:(?:\s+|-)(?:and\s+)?(?:{word})\b)*(\s+percent\b)?");
+            Regex::new(&pattern).expect("ITN number pattern")
+        })
+    }
+
+    pub fn normalize(text: &str) -> String {
+        number_pattern()
+            .replace_all(text, |captures: &Captures| {
+                let matched = &captures[0];
+                let percent = captures.get(1);
+                let phrase = match percent {
+                    Some(suffix) => &matched[..matched.len() - suffix.as_str().len()],
+                    None => matched,
+                };
+                match parse_phrase(phrase) {
+                    Some(value) if percent.is_some() => format!("{value}%"),
+                    Some(value) => value.to_string(),
+                    None => matched.to_string(),
+                }
+            })
+            .into_owned()
+    }
+
+    /// 解析数词短语；单个个位数词（“one of them”）或无法确定数值的序列返回 None
+    fn parse_phrase(phrase: &str) -> Option<u64> {
+        let words = phrase
+            .split(|ch: char| ch.is_whitespace() || ch == '-')
+            .filter(|word| !word.is_empty() && !word.eq_ignore_ascii_case("and"))
+            .map(|word| word.to_ascii_lowercase())
+            .collect::<Vec<_>>();
+        if words.len() == 1 && lookup(&WORDS, &words[0]).is_some_and(|value| value < 10) {
+            return None;
+        }
+
+        let mut total = 0u64;
+        let mut current = 0u64;
+        // 上一个词的数值，用于拒绝 “one two”“twenty thirty” 这类并列序列
+        let mut previous: Option<u64> = None;
+        for word in &words {
+            if let Some(value) = lookup(&WORDS, word) {
+                if let Some(previous) = previous {
+                    let tens_then_ones = previous >= 20 && previous % 10 == 0 && value < 10;
+                    if !tens_then_ones {
+                        return None;
+                    }
+                }
+                current += value;
+                previous = Some(value);
+                continue;
+            }
+            let scale = lookup(&SCALES, word)?;
+            if scale == 100 {
+                current = current.max(1).checked_mul(scale)?;
+            } else {
+                total = total.checked_add(current.max(1).checked_mul(scale)?)?;
+                current = 0;
+            }
+            previous = None;
+        }
+        Some(total + current)
+    }
+
+    fn lookup(table: &[(&str, u64)], word: &str) -> Option<u64> {
+        table
+            .iter()
+            .find(|(candidate, _)| *candidate == word)
+            .map(|(_, value)| *value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled() -> ItnSettings {
+        ItnSettings {
+            enabled: true,
+            chinese_numerals: true,
+            english_numbers: true,
+        }
+    }
+
+    #[test]
+    fn converts_chinese_numerals_with_units_decimals_and_percent() {
+        let settings = enabled();
+        assert_eq!(normalize("一共一千零五个", &settings), "一共1005个");
+        assert_eq!(normalize("二零二四年", &settings), "2024年");
+        assert_eq!(normalize("增长了百分之三十", &settings), "增长了30%");
+        assert_eq!(normalize("三点一四", &settings), "3.14");
+        assert_eq!(normalize("十二万三千", &settings), "123000");
+        assert_eq!(normalize("二百五", &settings), "250");
+    }
+
+    #[test]
+    fn does_not_read_clock_times_as_decimals() {
+        let settings = enabled();
+        assert_eq!(normalize("十二点三十分", &settings), "12点30分");
+        assert_eq!(normalize("三点一刻", &settings), "三点一刻");
+        assert_eq!(normalize("三点零五分", &settings), "三点零五分");
+        assert_eq!(normalize("一点一点地", &settings), "一点一点地");
+        assert_eq!(normalize("两点半", &settings), "两点半");
+        assert_eq!(normalize("零点五倍", &settings), "0.5倍");
+    }
+
+    #[test]
+    fn keeps_single_chinese_numerals_and_approximations() {
+        let settings = enabled();
+        assert_eq!(normalize("一个人十分开心", &settings), "一个人十分开心");
+        assert_eq!(normalize("三四百人", &settings), "三四百人");
+        assert_eq!(normalize("七八个", &settings), "七八个");
+    }
+
+    #[test]
+    fn converts_english_number_words() {
+        let settings = enabled();
+        assert_eq!(normalize("twenty three apples", &settings), "23 apples");
+        assert_eq!(
+            normalize("one hundred and five people", &settings),
+            "105 people"
+        );
+        assert_eq!(normalize("Fifty percent off", &settings), "50% off");
+        assert_eq!(normalize("one of them", &settings), "one of them");
+        assert_eq!(normalize("one two three", &settings), "one two three");
+    }
+
+    #[test]
+    fn disabled_settings_leave_text_unchanged() {
+        let settings = ItnSettings::default();
+        assert_eq!(normalize("二零二四 twenty", &settings), "二零二四 twenty");
+    }
+}
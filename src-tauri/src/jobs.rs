@@ -0,0 +1,199 @@
+//! 转写任务登记
+//!
+//! 录音结束送入转写时分配任务 ID。任务状态变化通过 `job-status` 事件推送，中间结果与
+//! 历史记录事件也携带任务 ID，前端据此关联长时间运行的任务，而不必从全局状态文案推断；
+//! 已结束的任务保留最近若干条供 `get_job_status` 查询。
+
+use crate::events;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard};
+
+/// 转写任务 ID，同一次运行内递增且不重复
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobId(pub(crate) u64);
+
+/// 保留的已结束任务数量
+const MAX_FINISHED_JOBS: usize = 50;
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry::new());
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobState {
+    /// 等待前面的任务转写完成
+    Queued,
+    Transcribing,
+    Completed,
+    Failed,
+}
+
+impl JobState {
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed)
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobStatus {
+    pub id: JobId,
+    pub state: JobState,
+    /// 最近一次状态标识，与状态浮窗一致，如 `offline-local`、`deferred`、`low-confidence`
+    pub status: String,
+    /// 写入的历史记录 ID；未开启历史记录或尚未完成时为 None
+    pub history_id: Option<String>,
+    pub error: Option<String>,
+}
+
+struct Registry {
+    next_id: u64,
+    jobs: VecDeque<JobStatus>,
+    /// 正在转写的任务，流式中间结果据此标注任务 ID
+    current: Option<JobId>,
+}
+
+impl Registry {
+    const fn new() -> Self {
+        Self {
+            next_id: 0,
+            jobs: VecDeque::new(),
+            current: None,
+        }
+    }
+
+    fn register(&mut self) -> JobStatus {
+        self.next_id += 1;
+        let status = JobStatus {
+            id: JobId(self.next_id),
+            state: JobState::Queued,
+            status: "queued".to_string(),
+            history_id: None,
+            error: None,
+        };
+        self.jobs.push_back(status.clone());
+        self.prune();
+        status
+    }
+
+    /// 修改任务并返回修改后的状态；任务已被淘汰时返回 None
+    fn update(&mut self, job: JobId, apply: impl FnOnce(&mut JobStatus)) -> Option<JobStatus> {
+        let status = self.jobs.iter_mut().find(|status| status.id == job)?;
+        apply(status);
+        let status = status.clone();
+        match status.state {
+            JobState::Transcribing => self.current = Some(job),
+            state if state.is_finished() && self.current == Some(job) => self.current = None,
+            _ => {}
+        }
+        Some(status)
+    }
+
+    /// 只淘汰已结束的任务，进行中的任务始终可查
+    fn prune(&mut self) {
+        let mut finished = self
+            .jobs
+            .iter()
+            .filter(|status| status.state.is_finished())
+            .count();
+        self.jobs.retain(|status| {
+            if finished > MAX_FINISHED_JOBS && status.state.is_finished() {
+                finished -= 1;
+                return false;
+            }
+            true
+        });
+    }
+}
+
+fn registry() -> MutexGuard<'static, Registry> {
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 登记新任务，状态为排队中
+pub fn register() -> JobId {
+    let status = registry().register();
+    events::emit_job_status(&status);
+    status.id
+}
+
+/// 更新任务状态与状态标识
+pub fn update(job: JobId, state: JobState, status: &str) {
+    let updated = registry().update(job, |current| {
+        current.state = state;
+        current.status = status.to_string();
+    });
+    if let Some(updated) = updated {
+        if updated.state.is_finished() {
+            registry().prune();
+        }
+        events::emit_job_status(&updated);
+    }
+}
+
+/// 记录任务失败的原因，随后由 [`update`] 推送失败状态
+pub fn set_error(job: JobId, error: &str) {
+    registry().update(job, |current| current.error = Some(error.to_string()));
+}
+
+pub fn set_history_id(job: JobId, history_id: &str) {
+    let updated = registry().update(job, |current| {
+        current.history_id = Some(history_id.to_string());
+    });
+    if let Some(updated) = updated {
+        events::emit_job_status(&updated);
+    }
+}
+
+pub fn status(job: JobId) -> Option<JobStatus> {
+    registry()
+        .jobs
+        .iter()
+        .find(|status| status.id == job)
+        .cloned()
+}
+
+/// 正在转写的任务
+pub fn current() -> Option<JobId> {
+    registry().current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_tracks_current_job_and_prunes_only_finished_jobs() {
+        let mut registry = Registry::new();
+        let first = registry.register().id;
+        let second = registry.register().id;
+        assert_ne!(first, second);
+
+        registry.update(first, |status| status.state = JobState::Transcribing);
+        assert_eq!(registry.current, Some(first));
+        let finished = registry
+            .update(first, |status| {
+                status.state = JobState::Completed;
+                status.status = "completed".to_string();
+            })
+            .unwrap();
+        assert_eq!(finished.status, "completed");
+        assert_eq!(registry.current, None);
+
+        for _ in 0..MAX_FINISHED_JOBS + 5 {
+            let job = registry.register().id;
+            registry.update(job, |status| status.state = JobState::Failed);
+            registry.prune();
+        }
+        assert_eq!(
+            registry
+                .jobs
+                .iter()
+                .filter(|status| status.state.is_finished())
+                .count(),
+            MAX_FINISHED_JOBS
+        );
+        assert!(registry.jobs.iter().any(|status| status.id == second));
+        assert!(registry.jobs.iter().all(|status| status.id != first));
+    }
+}
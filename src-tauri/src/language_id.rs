@@ -0,0 +1,81 @@
+//! 轻量语种识别：按文字书写系统统计字符占比判断转写文本的主要语种
+//!
+//! 仅区分中文、日文、韩文与英文（拉丁字母），用于按语种路由后续处理，不追求精细识别。
+
+/// `detect` 能返回的语种代码
+const DETECTABLE_LANGUAGES: [&str; 4] = ["zh", "ja", "ko", "en"];
+
+/// 识别文本主要语种，返回 `zh`/`ja`/`ko`/`en`；没有可识别字符时返回 None
+pub fn detect(text: &str) -> Option<&'static str> {
+    let mut han = 0usize;
+    let mut kana = 0usize;
+    let mut hangul = 0usize;
+    let mut latin = 0usize;
+    for ch in text.chars() {
+        match ch {
+            '\u{3040}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}' => kana += 1,
+            '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' => {
+                hangul += 1
+            }
+            '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' => han += 1,
+            ch if ch.is_ascii_alphabetic() => latin += 1,
+            _ => {}
+        }
+    }
+    // 日文通常混用汉字与假名，出现一定比例的假名即判定为日文
+    if kana > 0 && kana * 5 >= kana + han {
+        return Some("ja");
+    }
+    // 一个 CJK 字符信息量约等于数个拉丁字母，按 1:3 折算后比较
+    let cjk_weight = (han + kana) * 3;
+    let hangul_weight = hangul * 3;
+    if cjk_weight == 0 && hangul_weight == 0 && latin == 0 {
+        return None;
+    }
+    if hangul_weight >= cjk_weight && hangul_weight >= latin {
+        Some("ko")
+    } else if cjk_weight >= latin {
+        Some("zh")
+    } else {
+        Some("en")
+    }
+}
+
+/// 语种代码比较：忽略大小写与地区后缀（`zh-CN` 与 `zh` 视为相同）
+pub fn matches(configured: &str, detected: &str) -> bool {
+    let primary = configured
+        .trim()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default();
+    primary.eq_ignore_ascii_case(detected)
+}
+
+/// 配置的语种是否在可识别范围内，其余语种无法校验输出
+pub fn is_detectable(configured: &str) -> bool {
+    DETECTABLE_LANGUAGES
+        .iter()
+        .any(|language| matches(configured, language))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_identifies_dominant_script() {
+        assert_eq!(detect("今天的会议改到下午三点"), Some("zh"));
+        assert_eq!(detect("Please send the report by Friday"), Some("en"));
+        assert_eq!(detect("会议は午後三時に変更します"), Some("ja"));
+        assert_eq!(detect("회의는 오후 세 시로 변경됩니다"), Some("ko"));
+        assert_eq!(detect("用 Rust 重写这个 module"), Some("zh"));
+        assert_eq!(detect("123 !?"), None);
+    }
+
+    #[test]
+    fn matches_ignores_case_and_region() {
+        assert!(matches("zh-CN", "zh"));
+        assert!(matches("EN", "en"));
+        assert!(!matches("ja", "zh"));
+    }
+}
@@ -1,34 +1,89 @@
+mod active_window;
 mod aliyun_realtime;
+mod audio_crypto;
+mod audio_inspect;
+mod audio_pipeline;
 mod audio_processing;
+mod batch_transcription;
+mod confirm_window;
+mod corrections;
+mod deferred_queue;
+mod dictation_context;
+mod end_of_speech;
+mod events;
+mod itn;
+mod jobs;
+mod language_id;
+mod literal_mode;
+mod low_resource;
+mod ogg_opus;
+mod onboarding;
 mod openai;
+mod pangu;
 mod paste;
+mod presence;
 mod processing;
+mod prompt_presets;
+mod provider_compare;
+mod provider_error;
+mod punctuation;
+mod quick_settings;
 mod recorder;
+mod recording_archive;
+mod redaction;
+mod sample_spill;
+mod secure_input;
+mod segment_retry;
 mod sensevoice;
 mod settings;
+mod status_board;
 mod status_native;
+mod storage_quota;
+mod system_state;
+mod templates;
+mod text_backend;
+mod text_diff;
 mod transcription;
 mod transcription_dispatcher;
+mod trigger_pack;
 mod triggers;
+mod tts;
 mod updater;
 mod util;
+mod variable_picker;
 mod volcengine;
+mod wake_on_speech;
 
+use end_of_speech::EndOfSpeechDetector;
+use jobs::{JobId, JobStatus};
+use quick_settings::QuickToggle;
 use recorder::RecorderService;
 use sensevoice::model::{
-    resolve_vllm_model_id, spec_for_local_model, supports_sherpa_onnx_target, LocalRuntimeKind,
+    normalize_vllm_quantization, resolve_vllm_model_id, spec_for_local_model,
+    supports_sherpa_onnx_target, LocalRuntimeKind,
 };
 use sensevoice::{SenseVoiceManager, SenseVoiceStatus};
 use settings::{
-    SenseVoiceSettings, Settings, SettingsStore, TranscriptionHistoryItem, TranscriptionProvider,
+    DeviceProfile, DictationTemplate, RecordingSettings, RecordingTakeover, SenseVoiceSettings,
+    Settings, SettingsStore, TranscriptionHistoryItem, TranscriptionProvider,
 };
 use std::fs;
+use std::path::Path;
 use std::sync::Mutex;
-use tauri::menu::{MenuBuilder, MenuItem, MenuItemBuilder};
+use std::time::Duration;
+use tauri::menu::{
+    CheckMenuItem, CheckMenuItemBuilder, IsMenuItem, MenuBuilder, MenuItem, MenuItemBuilder,
+};
 use tauri::tray::{TrayIcon, TrayIconBuilder};
 use tauri::{AppHandle, Emitter, Manager, State, WindowEvent, Wry};
 use transcription_dispatcher::TranscriptionDispatcher;
 use updater::UpdateManager;
+use wake_on_speech::{WakeOnSpeechService, WakeOnSpeechState, WAKE_ON_SPEECH_EVENT};
+
+/// 设置页“测试听写”的固定录音时长
+const TEST_DICTATION_DURATION: Duration = Duration::from_secs(5);
+/// 检查录音是否需要自动结束的间隔，与录音电平窗口一致
+const AUTO_STOP_POLL_INTERVAL: Duration = Duration::from_millis(100);
 
 macro_rules! dev_eprintln {
     ($($arg:tt)*) => {
@@ -43,16 +98,47 @@ macro_rules! dev_eprintln {
 #[serde(rename_all = "camelCase")]
 struct TrayLabels {
     show_settings: String,
+    wake_on_speech: String,
+    auto_polish: String,
+    paste_after_transcribe: String,
+    use_local_model: String,
+    microphone: String,
     quit: String,
 }
 
+impl TrayLabels {
+    fn quick_toggle(&self, toggle: QuickToggle) -> &str {
+        match toggle {
+            QuickToggle::AutoPolish => &self.auto_polish,
+            QuickToggle::PasteAfterTranscribe => &self.paste_after_transcribe,
+            QuickToggle::UseLocalModel => &self.use_local_model,
+        }
+    }
+}
+
 #[derive(Default)]
 struct TrayState {
     tray: Option<TrayIcon<Wry>>,
     show_item: Option<MenuItem<Wry>>,
+    wake_item: Option<CheckMenuItem<Wry>>,
+    /// 快捷设置开关，勾选状态与设置保持一致
+    quick_items: Vec<(QuickToggle, CheckMenuItem<Wry>)>,
+    /// 只读菜单项，显示最近一次录音使用的麦克风
+    microphone_item: Option<MenuItem<Wry>>,
+    microphone_label: String,
+    microphone_name: Option<String>,
     quit_item: Option<MenuItem<Wry>>,
 }
 
+impl TrayState {
+    fn microphone_text(&self) -> String {
+        match self.microphone_name.as_deref() {
+            Some(name) => format!("{}: {name}", self.microphone_label),
+            None => self.microphone_label.clone(),
+        }
+    }
+}
+
 pub(crate) struct AppState {
     recorder: RecorderService,
     transcription_dispatcher: TranscriptionDispatcher,
@@ -60,6 +146,7 @@ pub(crate) struct AppState {
     sensevoice_manager: Mutex<SenseVoiceManager>,
     tray_state: Mutex<TrayState>,
     updater_manager: Mutex<UpdateManager>,
+    wake_on_speech: WakeOnSpeechService,
 }
 
 #[tauri::command]
@@ -77,23 +164,20 @@ fn update_settings(
         .settings_store
         .load()
         .map_err(|err| err.to_string())?;
-    let previous_local_model = previous.sensevoice.local_model.clone();
-    let previous_model_id = previous.sensevoice.model_id.clone();
-
     let persisted = state
         .settings_store
         .save_user_settings(&settings)
         .map_err(|err| err.to_string())?;
 
     updater::handle_settings_changed(app.clone(), state.settings_store.clone());
+    state.recorder.configure_idle_streams(&persisted.recording);
+    sync_quick_toggles(&app, &persisted);
 
     maybe_restart_local_runtime_if_switched(
         &app,
         &state,
-        &previous_local_model,
-        &previous_model_id,
-        &persisted.sensevoice.local_model,
-        &persisted.sensevoice.model_id,
+        &previous.sensevoice,
+        &persisted.sensevoice,
     )?;
 
     Ok(persisted)
@@ -117,38 +201,33 @@ fn update_sensevoice_settings(
         .settings_store
         .load_sensevoice()
         .map_err(|err| err.to_string())?;
-    let previous_local_model = previous.local_model.clone();
-    let previous_model_id = previous.model_id.clone();
-
     state
         .settings_store
         .save_sensevoice_editable(&sensevoice)
         .map_err(|err| err.to_string())?;
 
-    maybe_restart_local_runtime_if_switched(
-        &app,
-        &state,
-        &previous_local_model,
-        &previous_model_id,
-        &sensevoice.local_model,
-        &sensevoice.model_id,
-    )
+    maybe_restart_local_runtime_if_switched(&app, &state, &previous, &sensevoice)
 }
 
 fn maybe_restart_local_runtime_if_switched(
     app: &tauri::AppHandle,
     state: &State<AppState>,
-    previous_local_model: &str,
-    previous_model_id: &str,
-    next_local_model: &str,
-    next_model_id: &str,
+    previous: &SenseVoiceSettings,
+    next: &SenseVoiceSettings,
 ) -> Result<(), String> {
-    let previous = spec_for_local_model(previous_local_model);
-    let next = spec_for_local_model(next_local_model);
-    // 比较模型系列和具体变体 ID（解析后的完整 model ID）
-    let prev_resolved = resolve_vllm_model_id(previous_local_model, previous_model_id);
-    let next_resolved = resolve_vllm_model_id(next_local_model, next_model_id);
-    if previous.model_key == next.model_key && prev_resolved == next_resolved {
+    let previous_spec = spec_for_local_model(&previous.local_model);
+    let next_spec = spec_for_local_model(&next.local_model);
+    // 比较模型系列、具体变体 ID（解析后的完整 model ID）与量化选项
+    let prev_quantization =
+        normalize_vllm_quantization(&previous.local_model, &previous.quantization);
+    let next_quantization = normalize_vllm_quantization(&next.local_model, &next.quantization);
+    let prev_resolved =
+        resolve_vllm_model_id(&previous.local_model, &previous.model_id, prev_quantization);
+    let next_resolved = resolve_vllm_model_id(&next.local_model, &next.model_id, next_quantization);
+    if previous_spec.model_key == next_spec.model_key
+        && prev_resolved == next_resolved
+        && prev_quantization == next_quantization
+    {
         return Ok(());
     }
 
@@ -190,20 +269,452 @@ fn import_settings(state: State<AppState>, path: String) -> Result<Settings, Str
 }
 
 #[tauri::command]
-fn start_recording(state: State<AppState>) -> Result<(), String> {
-    state.recorder.start().map_err(|err| err.to_string())?;
-    processing::emit_status("recording");
+async fn install_trigger_pack(
+    app: AppHandle,
+    url: String,
+) -> Result<trigger_pack::InstalledTriggerPack, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        trigger_pack::install(&state.settings_store, &url).map_err(|err| err.to_string())
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+#[tauri::command]
+async fn detect_capabilities(app: AppHandle) -> Result<onboarding::Capabilities, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        onboarding::detect_capabilities(|progress| {
+            let _ = app.emit(onboarding::ONBOARDING_PROGRESS_EVENT, progress);
+        })
+    })
+    .await
+    .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+fn apply_recommended_profile(
+    app: AppHandle,
+    state: State<AppState>,
+    kind: onboarding::OnboardingProfile,
+) -> Result<Settings, String> {
+    let previous = state.settings_store.load().map_err(|err| err.to_string())?;
+    let mut settings = previous.clone();
+    onboarding::apply_profile(&mut settings, kind);
+    let persisted = state
+        .settings_store
+        .save_user_settings(&settings)
+        .map_err(|err| err.to_string())?;
+
+    maybe_restart_local_runtime_if_switched(
+        &app,
+        &state,
+        &previous.sensevoice,
+        &persisted.sensevoice,
+    )?;
+    let _ = app.emit(
+        onboarding::ONBOARDING_PROGRESS_EVENT,
+        onboarding::OnboardingProgress {
+            stage: "applied".to_string(),
+            message: "已应用推荐配置".to_string(),
+            percent: 100,
+        },
+    );
+    Ok(persisted)
+}
+
+/// 开始录音；`literal` 为 true 时本次录音按字面听写处理，`prompt_preset` 为本次录音使用的提示词预设
+#[tauri::command]
+async fn start_recording(
+    app: AppHandle,
+    literal: Option<bool>,
+    prompt_preset: Option<String>,
+) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let settings = state.settings_store.load().map_err(|err| err.to_string())?;
+        // 倒计时留出把光标移到目标位置的时间；已在录音时按接管策略立即处理
+        if settings.recording.countdown_ms > 0 && !state.recorder.is_recording() {
+            processing::run_countdown(settings.recording.countdown_ms);
+        }
+        let start = state
+            .recorder
+            .start(settings.recording.takeover, &settings.recording)
+            .map_err(|err| err.to_string())?;
+        // 按忽略策略沿用当前录音，状态浮窗与静音检测仍在运行
+        if !start.started {
+            return Ok(());
+        }
+        if let Some(previous) = start.previous {
+            state.transcription_dispatcher.enqueue(
+                previous,
+                literal_mode::take_recording(),
+                prompt_presets::take_recording(),
+            )?;
+        }
+        literal_mode::set_recording(literal.unwrap_or(false));
+        prompt_presets::set_recording(prompt_preset);
+        if start.device_changed {
+            sync_input_device(&app, &start.device_name);
+        }
+        dictation_context::capture_window(&settings.context_carryover);
+        processing::emit_recording_status(&start.device_name);
+        processing::spawn_level_watch(state.recorder.level_meter(), start.device_name);
+        spawn_auto_stop(&app, &settings.recording);
+        Ok(())
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+#[tauri::command]
+fn list_input_devices() -> Result<Vec<recorder::InputDeviceInfo>, String> {
+    recorder::list_input_devices().map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+fn pause_recording(state: State<AppState>) -> Result<(), String> {
+    state.recorder.pause().map_err(|err| err.to_string())?;
+    processing::emit_status("paused");
+    Ok(())
+}
+
+#[tauri::command]
+fn resume_recording(state: State<AppState>) -> Result<(), String> {
+    let device_name = state.recorder.resume().map_err(|err| err.to_string())?;
+    processing::emit_recording_status(&device_name);
     Ok(())
 }
 
 #[tauri::command]
-fn stop_recording(state: State<AppState>) -> Result<(), String> {
+fn save_device_profile(
+    state: State<AppState>,
+    profile: DeviceProfile,
+) -> Result<Vec<DeviceProfile>, String> {
+    state
+        .settings_store
+        .save_device_profile(&profile)
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+fn delete_device_profile(
+    state: State<AppState>,
+    device_name: String,
+) -> Result<Vec<DeviceProfile>, String> {
+    state
+        .settings_store
+        .delete_device_profile(&device_name)
+        .map_err(|err| err.to_string())
+}
+
+/// 结束录音并送入转写，返回的任务 ID 用于关联后续事件或查询任务状态
+#[tauri::command]
+fn stop_recording(state: State<AppState>) -> Result<JobId, String> {
     let audio = state.recorder.stop().map_err(|err| err.to_string())?;
-    processing::emit_status("transcribing");
-    state.transcription_dispatcher.enqueue(audio)?;
+    state.transcription_dispatcher.enqueue(
+        audio,
+        literal_mode::take_recording(),
+        prompt_presets::take_recording(),
+    )
+}
+
+#[tauri::command]
+fn get_job_status(id: JobId) -> Result<JobStatus, String> {
+    jobs::status(id).ok_or_else(|| "未找到转写任务".to_string())
+}
+
+/// 结束录音并直接丢弃音频，不送入转写，避免误触发的录音消耗接口额度
+#[tauri::command]
+fn cancel_recording(state: State<AppState>) -> Result<(), String> {
+    let audio = state.recorder.stop().map_err(|err| err.to_string())?;
+    literal_mode::take_recording();
+    prompt_presets::take_recording();
+    // 溢出到磁盘的临时文件随录音释放一并删除
+    drop(audio);
+    processing::emit_status("cancelled");
     Ok(())
 }
 
+/// 录音 5 秒后用当前提供商转写，返回文本与各阶段耗时，不粘贴、不写入历史
+#[tauri::command]
+async fn test_dictation(app: AppHandle) -> Result<processing::DictationTestResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let recording = state
+            .settings_store
+            .load()
+            .map(|settings| settings.recording)
+            .map_err(|err| err.to_string())?;
+        let start = state
+            .recorder
+            .start(RecordingTakeover::Ignore, &recording)
+            .map_err(|err| err.to_string())?;
+        // 不中断正在进行的听写录音
+        if !start.started {
+            return Err("已有录音正在进行".to_string());
+        }
+        if start.device_changed {
+            sync_input_device(&app, &start.device_name);
+        }
+        std::thread::sleep(TEST_DICTATION_DURATION);
+        let audio = state.recorder.stop().map_err(|err| err.to_string())?;
+        Ok(processing::test_dictation(&state.settings_store, audio))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+/// 用当前朗读设置试读一段文本
+#[tauri::command]
+async fn test_tts(app: AppHandle, text: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let settings = app
+            .state::<AppState>()
+            .settings_store
+            .load()
+            .map_err(|err| err.to_string())?;
+        tts::speak(&settings.tts, &settings.openai, &text).map_err(|err| err.to_string())
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+#[tauri::command]
+fn get_wake_on_speech_armed(state: State<AppState>) -> bool {
+    state.wake_on_speech.is_armed()
+}
+
+#[tauri::command]
+fn set_wake_on_speech_armed(app: AppHandle, armed: bool) -> bool {
+    set_wake_on_speech(&app, armed)
+}
+
+/// 语音唤醒的布防/撤防快捷键
+#[tauri::command]
+fn toggle_wake_on_speech(app: AppHandle, state: State<AppState>) -> bool {
+    set_wake_on_speech(&app, !state.wake_on_speech.is_armed())
+}
+
+fn set_wake_on_speech(app: &AppHandle, armed: bool) -> bool {
+    let armed = app.state::<AppState>().wake_on_speech.set_armed(app, armed);
+    sync_wake_on_speech(app, armed);
+    armed
+}
+
+/// 同步托盘勾选状态并通知前端；监听失败自动撤防时也会调用
+pub(crate) fn sync_wake_on_speech(app: &AppHandle, armed: bool) {
+    if let Ok(tray_state) = app.state::<AppState>().tray_state.lock() {
+        if let Some(wake_item) = tray_state.wake_item.as_ref() {
+            let _ = wake_item.set_checked(armed);
+        }
+    }
+    let _ = app.emit(WAKE_ON_SPEECH_EVENT, WakeOnSpeechState { armed });
+}
+
+/// 录音达到时长上限或检测到说话结束时自动结束并送入转写队列；达到上限时同时提醒前端
+pub(crate) fn spawn_auto_stop(app: &AppHandle, recording: &RecordingSettings) {
+    let app = app.clone();
+    let max_duration_seconds = recording.max_duration_seconds;
+    let mut end_of_speech = EndOfSpeechDetector::new(&recording.end_of_speech);
+    let meter = app.state::<AppState>().recorder.level_meter();
+    let session = meter.session();
+    let poll_ms = AUTO_STOP_POLL_INTERVAL.as_millis() as u32;
+    std::thread::spawn(move || {
+        while meter.is_active(session) {
+            std::thread::sleep(AUTO_STOP_POLL_INTERVAL);
+            let limit_reached = meter.limit_reached();
+            let speech_ended = !meter.is_paused()
+                && end_of_speech
+                    .as_mut()
+                    .is_some_and(|detector| detector.push(meter.rms_db(), poll_ms));
+            if !(limit_reached || speech_ended) || !meter.is_active(session) {
+                continue;
+            }
+            let state = app.state::<AppState>();
+            if let Ok(audio) = state.recorder.stop() {
+                let literal = literal_mode::take_recording();
+                let prompt_preset = prompt_presets::take_recording();
+                if let Err(_err) =
+                    state
+                        .transcription_dispatcher
+                        .enqueue(audio, literal, prompt_preset)
+                {
+                    dev_eprintln!("自动结束录音后送入转写失败: {_err}");
+                }
+                if limit_reached {
+                    events::emit_recording_limit_reached(max_duration_seconds);
+                }
+            }
+            return;
+        }
+    });
+}
+
+/// 托盘快捷开关：直接修改并保存设置，再通知主窗口刷新
+fn toggle_quick_setting(app: &AppHandle, toggle: QuickToggle) {
+    let state = app.state::<AppState>();
+    let result = state
+        .settings_store
+        .load()
+        .map_err(|err| err.to_string())
+        .and_then(|mut settings| {
+            toggle.toggle(&mut settings)?;
+            state
+                .settings_store
+                .save_user_settings(&settings)
+                .map_err(|err| err.to_string())
+        });
+    let settings = match result {
+        Ok(persisted) => {
+            events::emit_settings_changed(&persisted);
+            if toggle == QuickToggle::UseLocalModel && persisted.provider.is_local() {
+                spawn_local_runtime_start(app);
+            }
+            persisted
+        }
+        Err(_err) => {
+            dev_eprintln!("切换托盘快捷开关失败: {_err}");
+            match state.settings_store.load() {
+                Ok(settings) => settings,
+                Err(_) => return,
+            }
+        }
+    };
+    // 点击时菜单项已自行切换勾选，按实际设置回写，切换失败时恢复原状态
+    sync_quick_toggles(app, &settings);
+}
+
+/// 按设置更新托盘快捷开关的勾选状态
+fn sync_quick_toggles(app: &AppHandle, settings: &Settings) {
+    if let Ok(tray_state) = app.state::<AppState>().tray_state.lock() {
+        for (toggle, item) in &tray_state.quick_items {
+            let _ = item.set_checked(toggle.is_enabled(settings));
+        }
+    }
+}
+
+/// 从托盘切换到本地模型后在后台启动本地服务
+fn spawn_local_runtime_start(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let state = app.state::<AppState>();
+        let Ok(mut manager) = state.sensevoice_manager.lock() else {
+            return;
+        };
+        if manager.has_running_runtime() {
+            return;
+        }
+        if let Err(_err) = manager.start_service_async(&app, &state.settings_store) {
+            dev_eprintln!("启动本地模型服务失败: {_err}");
+        }
+    });
+}
+
+/// 录音设备变化（如插入耳机后系统默认设备切换）时更新托盘并通知前端
+pub(crate) fn sync_input_device(app: &AppHandle, device_name: &str) {
+    if let Ok(mut tray_state) = app.state::<AppState>().tray_state.lock() {
+        tray_state.microphone_name = Some(device_name.to_string());
+        if let Some(microphone_item) = tray_state.microphone_item.as_ref() {
+            let _ = microphone_item.set_text(tray_state.microphone_text());
+        }
+    }
+    events::emit_input_device_changed(device_name);
+}
+
+#[tauri::command]
+fn get_dictation_templates(state: State<AppState>) -> Result<Vec<DictationTemplate>, String> {
+    state
+        .settings_store
+        .load()
+        .map(|settings| settings.templates)
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+fn save_dictation_template(
+    state: State<AppState>,
+    template: DictationTemplate,
+) -> Result<Vec<DictationTemplate>, String> {
+    state
+        .settings_store
+        .save_dictation_template(&template)
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+fn delete_dictation_template(
+    state: State<AppState>,
+    id: String,
+) -> Result<Vec<DictationTemplate>, String> {
+    state
+        .settings_store
+        .delete_dictation_template(&id)
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+fn set_active_dictation_template(state: State<AppState>, id: Option<String>) -> Result<(), String> {
+    state
+        .settings_store
+        .set_active_dictation_template(id.as_deref())
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+fn get_recent_results() -> Vec<String> {
+    paste::recent_results()
+}
+
+#[tauri::command]
+fn paste_nth_result(n: usize) -> Result<(), String> {
+    paste::paste_nth_result(n).map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+fn get_pending_confirmation() -> Option<confirm_window::PendingConfirmation> {
+    confirm_window::pending()
+}
+
+#[tauri::command]
+async fn confirm_paste(app: AppHandle, text: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        confirm_window::paste(&app, &text).map_err(|err| err.to_string())
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+#[tauri::command]
+fn confirm_copy(app: AppHandle, text: String) -> Result<(), String> {
+    confirm_window::copy(&app, &text).map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+fn confirm_discard(app: AppHandle) {
+    confirm_window::discard(&app);
+}
+
+#[tauri::command]
+fn get_pending_variable_pick() -> Option<variable_picker::VariablePick> {
+    variable_picker::pending()
+}
+
+#[tauri::command]
+fn resolve_variable_pick(id: u64, index: Option<usize>) {
+    variable_picker::resolve(id, index);
+}
+
+/// 上报用户对粘贴结果的修改，返回本次记录的修改数
+#[tauri::command]
+fn report_correction(
+    state: State<AppState>,
+    original: String,
+    corrected: String,
+) -> Result<usize, String> {
+    corrections::report(&state.settings_store, &original, &corrected)
+}
+
 #[tauri::command]
 fn get_transcription_history(
     state: State<AppState>,
@@ -214,6 +725,21 @@ fn get_transcription_history(
         .map_err(|err| err.to_string())
 }
 
+/// 按 ID 读取完整历史记录，配合摘要形式的历史事件按需加载详情
+#[tauri::command]
+fn get_history_item(
+    state: State<AppState>,
+    id: String,
+) -> Result<TranscriptionHistoryItem, String> {
+    state
+        .settings_store
+        .load_transcription_history()
+        .map_err(|err| err.to_string())?
+        .into_iter()
+        .find(|item| item.id == id)
+        .ok_or_else(|| format!("未找到历史记录: {id}"))
+}
+
 #[tauri::command]
 fn clear_transcription_history(state: State<AppState>) -> Result<(), String> {
     state
@@ -222,6 +748,107 @@ fn clear_transcription_history(state: State<AppState>) -> Result<(), String> {
         .map_err(|err| err.to_string())
 }
 
+/// 解密读取历史记录存档的原始录音（WAV），供回放
+#[tauri::command]
+fn get_history_audio(state: State<AppState>, id: String) -> Result<tauri::ipc::Response, String> {
+    let store = &state.settings_store;
+    let settings = store.load().map_err(|err| err.to_string())?;
+    let audio_path = store
+        .load_transcription_history()
+        .map_err(|err| err.to_string())?
+        .into_iter()
+        .find(|item| item.id == id)
+        .and_then(|item| item.audio_path)
+        .ok_or_else(|| "该历史记录没有存档录音".to_string())?;
+    let scratch_dir = audio_processing::resolve_scratch_dir(&settings.recording.scratch_dir);
+    recording_archive::read_wav(store, Path::new(&audio_path), &scratch_dir)
+        .map(tauri::ipc::Response::new)
+}
+
+/// 用存档的原始录音重新转写，`provider` 为空时按当前设置选择提供商；返回新追加的历史记录
+#[tauri::command]
+async fn retranscribe_history_item(
+    app: AppHandle,
+    id: String,
+    provider: Option<TranscriptionProvider>,
+) -> Result<TranscriptionHistoryItem, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        transcription_dispatcher::retranscribe_history_item(
+            &app,
+            &state.settings_store,
+            &id,
+            provider,
+        )
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+#[tauri::command]
+async fn compare_providers(
+    state: State<'_, AppState>,
+    audio_path: String,
+    providers: Vec<TranscriptionProvider>,
+) -> Result<Vec<provider_compare::ProviderComparison>, String> {
+    let settings = state.settings_store.load().map_err(|err| err.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        provider_compare::compare_providers(&settings, Path::new(&audio_path), &providers)
+    })
+    .await
+    .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+fn get_storage_usage(state: State<AppState>) -> Result<storage_quota::StorageUsage, String> {
+    let settings = state.settings_store.load().map_err(|err| err.to_string())?;
+    Ok(storage_quota::usage(&state.settings_store, &settings))
+}
+
+/// `model` 用于设置界面中尚未保存的 OpenAI 模型
+#[tauri::command]
+fn get_provider_capabilities(
+    state: State<AppState>,
+    provider: TranscriptionProvider,
+    model: Option<String>,
+) -> Result<transcription::ProviderCapabilities, String> {
+    let mut settings = state.settings_store.load().map_err(|err| err.to_string())?;
+    if let Some(model) = model {
+        settings.openai.speech_to_text.model = model;
+    }
+    Ok(transcription::provider_capabilities(&settings, provider))
+}
+
+#[tauri::command]
+async fn transcribe_folder(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    recursive: bool,
+    provider: Option<TranscriptionProvider>,
+) -> Result<batch_transcription::BatchSummary, String> {
+    let mut settings = state.settings_store.load().map_err(|err| err.to_string())?;
+    if let Some(provider) = provider {
+        settings.provider = provider;
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        batch_transcription::transcribe_folder(&settings, Path::new(&path), recursive, |progress| {
+            let _ = app.emit("batch-transcription-progress", progress);
+        })
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+#[tauri::command]
+async fn get_runtime_metrics(
+    app: AppHandle,
+) -> Result<Option<sensevoice::metrics::RuntimeMetrics>, String> {
+    tauri::async_runtime::spawn_blocking(move || sensevoice::metrics::current(&app))
+        .await
+        .map_err(|err| err.to_string())?
+}
+
 #[tauri::command]
 fn get_sensevoice_status(state: State<AppState>) -> Result<SenseVoiceStatus, String> {
     let mut manager = state
@@ -247,6 +874,20 @@ fn prepare_sensevoice(
         .map_err(|err| err.to_string())
 }
 
+#[tauri::command]
+fn cancel_prepare(
+    app: tauri::AppHandle,
+    state: State<AppState>,
+) -> Result<SenseVoiceStatus, String> {
+    let mut manager = state
+        .sensevoice_manager
+        .lock()
+        .map_err(|_| "failed to lock SenseVoice manager".to_string())?;
+    manager
+        .cancel_prepare(&app, &state.settings_store)
+        .map_err(|err| err.to_string())
+}
+
 #[tauri::command]
 fn start_sensevoice_service(
     app: tauri::AppHandle,
@@ -301,14 +942,50 @@ fn set_tray_menu(
         .map_err(|_| "failed to lock tray state".to_string())?;
 
     if tray_state.tray.is_none() {
-        let show_item = MenuItemBuilder::with_id("show", labels.show_settings)
+        let show_item = MenuItemBuilder::with_id("show", &labels.show_settings)
+            .build(&app)
+            .map_err(|err| err.to_string())?;
+        let wake_item = CheckMenuItemBuilder::with_id("wake", &labels.wake_on_speech)
+            .checked(state.wake_on_speech.is_armed())
+            .build(&app)
+            .map_err(|err| err.to_string())?;
+        let settings = state.settings_store.load().ok();
+        let quick_items = QuickToggle::ALL
+            .into_iter()
+            .map(|toggle| {
+                CheckMenuItemBuilder::with_id(toggle.menu_id(), labels.quick_toggle(toggle))
+                    .checked(
+                        settings
+                            .as_ref()
+                            .is_some_and(|settings| toggle.is_enabled(settings)),
+                    )
+                    .build(&app)
+                    .map(|item| (toggle, item))
+                    .map_err(|err| err.to_string())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        tray_state.microphone_label = labels.microphone;
+        if tray_state.microphone_name.is_none() {
+            tray_state.microphone_name = settings.as_ref().and_then(|settings| {
+                recorder::resolved_input_device_name(&settings.recording.input_device)
+            });
+        }
+        let microphone_item = MenuItemBuilder::with_id("microphone", tray_state.microphone_text())
+            .enabled(false)
             .build(&app)
             .map_err(|err| err.to_string())?;
         let quit_item = MenuItemBuilder::with_id("quit", labels.quit)
             .build(&app)
             .map_err(|err| err.to_string())?;
+        let mut items: Vec<&dyn IsMenuItem<Wry>> = vec![&show_item, &wake_item];
+        items.extend(
+            quick_items
+                .iter()
+                .map(|(_, item)| item as &dyn IsMenuItem<Wry>),
+        );
+        items.extend([&microphone_item as &dyn IsMenuItem<Wry>, &quit_item]);
         let menu = MenuBuilder::new(&app)
-            .items(&[&show_item, &quit_item])
+            .items(&items)
             .build()
             .map_err(|err| err.to_string())?;
         let icon = app
@@ -325,8 +1002,13 @@ fn set_tray_menu(
                     if let Some(window) = app.get_webview_window("main") {
                         let _ = window.show();
                         let _ = window.set_focus();
+                        low_resource::set_window_hidden(app, false);
                     }
                 }
+                "wake" => {
+                    let armed = app.state::<AppState>().wake_on_speech.is_armed();
+                    set_wake_on_speech(app, !armed);
+                }
                 "quit" => {
                     let state = app.state::<AppState>();
                     if let Ok(mut manager) = state.sensevoice_manager.lock() {
@@ -337,20 +1019,42 @@ fn set_tray_menu(
                     }
                     app.exit(0);
                 }
-                _ => {}
+                id => {
+                    if let Some(toggle) = QuickToggle::from_menu_id(id) {
+                        toggle_quick_setting(app, toggle);
+                    }
+                }
             })
             .build(&app)
             .map_err(|err| err.to_string())?;
 
         tray_state.tray = Some(tray);
         tray_state.show_item = Some(show_item);
+        tray_state.wake_item = Some(wake_item);
+        tray_state.quick_items = quick_items;
+        tray_state.microphone_item = Some(microphone_item);
         tray_state.quit_item = Some(quit_item);
         return Ok(());
     }
 
     if let Some(show_item) = tray_state.show_item.as_ref() {
         show_item
-            .set_text(labels.show_settings)
+            .set_text(&labels.show_settings)
+            .map_err(|err| err.to_string())?;
+    }
+    if let Some(wake_item) = tray_state.wake_item.as_ref() {
+        wake_item
+            .set_text(&labels.wake_on_speech)
+            .map_err(|err| err.to_string())?;
+    }
+    for (toggle, item) in &tray_state.quick_items {
+        item.set_text(labels.quick_toggle(*toggle))
+            .map_err(|err| err.to_string())?;
+    }
+    tray_state.microphone_label = labels.microphone;
+    if let Some(microphone_item) = tray_state.microphone_item.as_ref() {
+        microphone_item
+            .set_text(tray_state.microphone_text())
             .map_err(|err| err.to_string())?;
     }
     if let Some(quit_item) = tray_state.quit_item.as_ref() {
@@ -368,11 +1072,8 @@ fn get_update_status(app: AppHandle) -> Result<updater::UpdateStatusPayload, Str
 }
 
 #[tauri::command]
-fn install_downloaded_update(app: AppHandle, state: State<AppState>) -> Result<(), String> {
-    if let Ok(mut manager) = state.sensevoice_manager.lock() {
-        manager.pause_runtime_for_exit(&app);
-    }
-    updater::install_downloaded_update(&app)
+fn install_update(app: AppHandle) -> Result<(), String> {
+    updater::install_update(&app)
 }
 
 #[tauri::command]
@@ -381,7 +1082,7 @@ fn dismiss_update_error(app: AppHandle) -> Result<(), String> {
 }
 
 #[tauri::command]
-fn retry_update_check(app: AppHandle, state: State<AppState>) -> Result<(), String> {
+fn check_for_updates(app: AppHandle, state: State<AppState>) -> Result<(), String> {
     updater::schedule_update_check(app, state.settings_store.clone(), true);
     Ok(())
 }
@@ -422,6 +1123,7 @@ pub fn run() {
             }
 
             let app_handle = app.handle();
+            events::init(app_handle);
             let store = SettingsStore::new(app_handle.clone());
             let startup_store = store.clone();
             let startup_app = app_handle.clone();
@@ -438,23 +1140,33 @@ pub fn run() {
                 sensevoice_manager: Mutex::new(SenseVoiceManager::new()),
                 tray_state: Mutex::new(TrayState::default()),
                 updater_manager: Mutex::new(UpdateManager::new(current_version)),
+                wake_on_speech: WakeOnSpeechService::new(),
             });
 
             if let Some(window) = app.get_webview_window("main") {
                 if is_autostart_launch {
                     let _ = window.hide();
+                    low_resource::set_window_hidden(app_handle, true);
                 }
                 let window_clone = window.clone();
-                window.on_window_event(move |event| {
-                    if let WindowEvent::CloseRequested { api, .. } = event {
+                let visibility_handle = app_handle.clone();
+                window.on_window_event(move |event| match event {
+                    WindowEvent::CloseRequested { api, .. } => {
                         api.prevent_close();
                         dev_eprintln!("close requested, hiding window to tray instead");
                         let _ = window_clone.hide();
+                        low_resource::set_window_hidden(&visibility_handle, true);
+                    }
+                    // 窗口也可能由系统（如 Dock 图标）重新显示，获得焦点即视为已显示
+                    WindowEvent::Focused(true) => {
+                        low_resource::set_window_hidden(&visibility_handle, false);
                     }
+                    _ => {}
                 });
             }
 
             updater::schedule_update_check(app_handle.clone(), startup_store.clone(), false);
+            sensevoice::metrics::spawn_monitor(app_handle.clone());
 
             std::thread::spawn(move || {
                 let settings = match startup_store.load() {
@@ -464,6 +1176,10 @@ pub fn run() {
                         return;
                     }
                 };
+                startup_app
+                    .state::<AppState>()
+                    .recorder
+                    .configure_idle_streams(&settings.recording);
                 if settings.provider != TranscriptionProvider::Sensevoice {
                     return;
                 }
@@ -479,6 +1195,8 @@ pub fn run() {
                 if !settings.sensevoice.installed {
                     return;
                 }
+                // 锁屏或长时间无操作时推迟自动启动，避免无人时占用 GPU
+                presence::wait_until_present(&settings.presence);
                 // Docker Desktop 在系统重启后可能需要一段时间才能就绪；若首次 start_service_async
                 // 因 Docker 尚未可用等原因失败，重试 10 次，每次间隔 6s，总计约 1 分钟。
                 // 原生模型不存在该问题，一次调用即可。
@@ -519,20 +1237,57 @@ pub fn run() {
             update_sensevoice_settings,
             export_settings,
             import_settings,
+            install_trigger_pack,
+            detect_capabilities,
+            apply_recommended_profile,
             start_recording,
+            list_input_devices,
+            pause_recording,
+            resume_recording,
+            save_device_profile,
+            delete_device_profile,
             stop_recording,
+            get_job_status,
+            cancel_recording,
+            test_dictation,
+            test_tts,
+            get_wake_on_speech_armed,
+            set_wake_on_speech_armed,
+            toggle_wake_on_speech,
+            get_dictation_templates,
+            save_dictation_template,
+            delete_dictation_template,
+            set_active_dictation_template,
+            get_recent_results,
+            paste_nth_result,
+            get_pending_confirmation,
+            confirm_paste,
+            confirm_copy,
+            confirm_discard,
+            get_pending_variable_pick,
+            resolve_variable_pick,
+            report_correction,
             get_transcription_history,
+            get_history_item,
             clear_transcription_history,
+            get_history_audio,
+            retranscribe_history_item,
+            compare_providers,
+            get_provider_capabilities,
+            get_storage_usage,
+            transcribe_folder,
             get_sensevoice_status,
+            get_runtime_metrics,
             prepare_sensevoice,
+            cancel_prepare,
             start_sensevoice_service,
             stop_sensevoice_service,
             update_sensevoice_runtime,
             set_tray_menu,
             get_update_status,
-            install_downloaded_update,
+            install_update,
             dismiss_update_error,
-            retry_update_check,
+            check_for_updates,
             get_app_info
         ])
         .run(tauri::generate_context!())
@@ -0,0 +1,270 @@
+//! 字面听写：用于听写标识符、数字与代码片段
+//!
+//! 通过字面听写快捷键开始的录音不按自然语言整理，而是按本地语法逐词转换：口述的数字、
+//! 符号与常见编程词汇（“underscore”、“open paren”、“下划线”、“左括号”）转换为对应字符，
+//! 其余单词转为小写后直接拼接，不插入空格，需要空格时口述 “space” 或 “空格”。
+//! “capital”/“大写” 使下一个单词首字母大写，“all caps”/“全大写” 使下一个单词全部大写。
+
+use crate::itn;
+use crate::settings::ItnSettings;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 当前录音是否为字面听写
+static RECORDING_LITERAL: AtomicBool = AtomicBool::new(false);
+
+/// 英文口述词与输出：符号、数字与大小写指令，按最长匹配
+const PHRASES: &[(&str, Word)] = &[
+    ("open paren", Word::Text("(")),
+    ("left paren", Word::Text("(")),
+    ("close paren", Word::Text(")")),
+    ("right paren", Word::Text(")")),
+    ("open bracket", Word::Text("[")),
+    ("left bracket", Word::Text("[")),
+    ("close bracket", Word::Text("]")),
+    ("right bracket", Word::Text("]")),
+    ("open brace", Word::Text("{")),
+    ("left brace", Word::Text("{")),
+    ("close brace", Word::Text("}")),
+    ("right brace", Word::Text("}")),
+    ("less than", Word::Text("<")),
+    ("greater than", Word::Text(">")),
+    ("single quote", Word::Text("'")),
+    ("double quote", Word::Text("\"")),
+    ("question mark", Word::Text("?")),
+    ("exclamation mark", Word::Text("!")),
+    ("forward slash", Word::Text("/")),
+    ("at sign", Word::Text("@")),
+    ("dollar sign", Word::Text("$")),
+    ("new line", Word::Text("\n")),
+    ("all caps", Word::AllCaps),
+    ("underscore", Word::Text("_")),
+    ("dash", Word::Text("-")),
+    ("hyphen", Word::Text("-")),
+    ("minus", Word::Text("-")),
+    ("plus", Word::Text("+")),
+    ("equals", Word::Text("=")),
+    ("dot", Word::Text(".")),
+    ("point", Word::Text(".")),
+    ("comma", Word::Text(",")),
+    ("colon", Word::Text(":")),
+    ("semicolon", Word::Text(";")),
+    ("slash", Word::Text("/")),
+    ("backslash", Word::Text("\\")),
+    ("star", Word::Text("*")),
+    ("asterisk", Word::Text("*")),
+    ("percent", Word::Text("%")),
+    ("hash", Word::Text("#")),
+    ("ampersand", Word::Text("&")),
+    ("pipe", Word::Text("|")),
+    ("caret", Word::Text("^")),
+    ("tilde", Word::Text("~")),
+    ("backtick", Word::Text("`")),
+    ("quote", Word::Text("\"")),
+    ("space", Word::Text(" ")),
+    ("newline", Word::Text("\n")),
+    ("tab", Word::Text("\t")),
+    ("capital", Word::Capital),
+    ("zero", Word::Text("0")),
+    ("one", Word::Text("1")),
+    ("two", Word::Text("2")),
+    ("three", Word::Text("3")),
+    ("four", Word::Text("4")),
+    ("five", Word::Text("5")),
+    ("six", Word::Text("6")),
+    ("seven", Word::Text("7")),
+    ("eight", Word::Text("8")),
+    ("nine", Word::Text("9")),
+];
+
+/// 中文口述词对应的英文口述词；中文转写不分词，在文本中按最长匹配替换
+const ZH_PHRASES: &[(&str, &str)] = &[
+    ("左方括号", "open bracket"),
+    ("右方括号", "close bracket"),
+    ("左花括号", "open brace"),
+    ("右花括号", "close brace"),
+    ("左大括号", "open brace"),
+    ("右大括号", "close brace"),
+    ("美元符号", "dollar sign"),
+    ("下划线", "underscore"),
+    ("左括号", "open paren"),
+    ("右括号", "close paren"),
+    ("反斜杠", "backslash"),
+    ("单引号", "single quote"),
+    ("小于号", "less than"),
+    ("大于号", "greater than"),
+    ("感叹号", "exclamation mark"),
+    ("百分号", "percent"),
+    ("全大写", "all caps"),
+    ("斜杠", "slash"),
+    ("横线", "dash"),
+    ("减号", "minus"),
+    ("加号", "plus"),
+    ("等于", "equals"),
+    ("逗号", "comma"),
+    ("冒号", "colon"),
+    ("分号", "semicolon"),
+    ("星号", "star"),
+    ("井号", "hash"),
+    ("艾特", "at sign"),
+    ("竖线", "pipe"),
+    ("引号", "quote"),
+    ("问号", "question mark"),
+    ("空格", "space"),
+    ("换行", "new line"),
+    ("大写", "capital"),
+    ("点", "dot"),
+    ("零", "zero"),
+    ("一", "one"),
+    ("二", "two"),
+    ("两", "two"),
+    ("三", "three"),
+    ("四", "four"),
+    ("五", "five"),
+    ("六", "six"),
+    ("七", "seven"),
+    ("八", "eight"),
+    ("九", "nine"),
+];
+
+#[derive(Clone, Copy)]
+enum Word {
+    Text(&'static str),
+    /// 下一个单词首字母大写
+    Capital,
+    /// 下一个单词全部大写
+    AllCaps,
+}
+
+/// 标记当前录音是否为字面听写，开始录音时调用
+pub fn set_recording(literal: bool) {
+    RECORDING_LITERAL.store(literal, Ordering::SeqCst);
+}
+
+/// 取出当前录音的字面听写标记；录音结束送入转写或被丢弃时调用
+pub fn take_recording() -> bool {
+    RECORDING_LITERAL.swap(false, Ordering::SeqCst)
+}
+
+/// 按字面听写语法转换转写结果
+pub fn convert(text: &str) -> String {
+    // 多位数先按逆文本标准化转为阿拉伯数字（“forty two” → “42”、“十二” → “12”）
+    let itn_settings = ItnSettings {
+        enabled: true,
+        chinese_numerals: true,
+        english_numbers: true,
+    };
+    let text = expand_chinese(&itn::normalize(text, &itn_settings));
+    let words = text
+        .split_whitespace()
+        .map(trim_sentence_punctuation)
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+
+    let mut output = String::with_capacity(text.len());
+    let mut pending = None;
+    let mut index = 0;
+    while index < words.len() {
+        if let Some((word, len)) = match_phrase(&words[index..]) {
+            index += len;
+            match word {
+                Word::Text(text) => output.push_str(text),
+                modifier => pending = Some(modifier),
+            }
+            continue;
+        }
+        let word = words[index];
+        index += 1;
+        match pending.take() {
+            Some(Word::AllCaps) => output.push_str(&word.to_uppercase()),
+            Some(Word::Capital) => {
+                let mut chars = word.chars();
+                if let Some(first) = chars.next() {
+                    output.extend(first.to_uppercase());
+                    output.push_str(&chars.as_str().to_lowercase());
+                }
+            }
+            _ => output.push_str(&word.to_lowercase()),
+        }
+    }
+    output
+}
+
+/// 把中文口述词替换为以空格隔开的英文口述词，其余文字保持原样
+fn expand_chinese(text: &str) -> String {
+    let mut output = String::with_capacity(text.len() * 2);
+    let mut rest = text;
+    while let Some(ch) = rest.chars().next() {
+        let matched = ZH_PHRASES
+            .iter()
+            .filter(|(phrase, _)| rest.starts_with(phrase))
+            .max_by_key(|(phrase, _)| phrase.len());
+        match matched {
+            Some((phrase, spoken)) => {
+                output.push(' ');
+                output.push_str(spoken);
+                output.push(' ');
+                rest = &rest[phrase.len()..];
+            }
+            None => {
+                output.push(ch);
+                rest = &rest[ch.len_utf8()..];
+            }
+        }
+    }
+    output
+}
+
+/// 去掉转写引擎在单词首尾添加的句读；口述的符号由语法转换，不依赖转写引擎的标点
+fn trim_sentence_punctuation(word: &str) -> &str {
+    word.trim_matches(|ch| matches!(ch, ',' | '.' | '?' | '!' | '，' | '。' | '？' | '！' | '、'))
+}
+
+/// 从开头匹配最长的口述词，返回对应输出与占用的单词数
+fn match_phrase(words: &[&str]) -> Option<(Word, usize)> {
+    PHRASES
+        .iter()
+        .filter_map(|(phrase, word)| {
+            let len = phrase.split(' ').count();
+            let candidate = words.get(..len)?;
+            let matches = phrase
+                .split(' ')
+                .zip(candidate)
+                .all(|(expected, actual)| actual.eq_ignore_ascii_case(expected));
+            matches.then_some((*word, len))
+        })
+        .max_by_key(|(_, len)| *len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_spoken_symbols_and_numbers() {
+        assert_eq!(
+            convert("Get underscore user underscore ID open paren close paren."),
+            "get_user_id()"
+        );
+        assert_eq!(
+            convert("capital max space equals space forty two"),
+            "Max = 42"
+        );
+        assert_eq!(
+            convert("all caps api dot v two slash users"),
+            "API.v2/users"
+        );
+    }
+
+    #[test]
+    fn converts_chinese_spoken_symbols() {
+        assert_eq!(convert("用户下划线名字等于三。"), "用户_名字=3");
+        assert_eq!(convert("大写config点十二"), "Config.12");
+    }
+
+    #[test]
+    fn recording_flag_is_taken_once() {
+        set_recording(true);
+        assert!(take_recording());
+        assert!(!take_recording());
+    }
+}
@@ -0,0 +1,62 @@
+//! 主窗口隐藏到托盘时的低资源模式
+//!
+//! 隐藏后跳过只为设置页服务的资源采集、放宽健康检查轮询间隔，并通知前端释放界面缓存
+//! （历史列表、设备列表、运行日志等，设置快照保留）。全局快捷键与事件监听运行在主窗口的
+//! webview 中，因此 webview 本身不能释放。
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// 主窗口显示/隐藏事件
+pub const WINDOW_VISIBILITY_EVENT: &str = "main-window-visibility";
+/// 低资源模式下轮询间隔的放大倍数
+const HIDDEN_POLL_MULTIPLIER: u32 = 4;
+/// 放大后的轮询间隔上限，避免启动检查长时间得不到结果
+const MAX_HIDDEN_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+static WINDOW_HIDDEN: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WindowVisibility {
+    hidden: bool,
+}
+
+/// 记录主窗口是否隐藏到托盘，并通知前端释放或重新加载缓存
+pub fn set_window_hidden(app: &AppHandle, hidden: bool) {
+    if WINDOW_HIDDEN.swap(hidden, Ordering::Relaxed) == hidden {
+        return;
+    }
+    let _ = app.emit(WINDOW_VISIBILITY_EVENT, WindowVisibility { hidden });
+}
+
+pub fn is_active() -> bool {
+    WINDOW_HIDDEN.load(Ordering::Relaxed)
+}
+
+/// 低资源模式下放大轮询间隔，已超过上限的间隔保持不变
+pub fn poll_interval(base: Duration) -> Duration {
+    if is_active() {
+        backoff(base)
+    } else {
+        base
+    }
+}
+
+fn backoff(base: Duration) -> Duration {
+    (base * HIDDEN_POLL_MULTIPLIER).min(MAX_HIDDEN_POLL_INTERVAL.max(base))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_is_capped_but_never_shortens() {
+        assert_eq!(backoff(Duration::from_millis(500)), Duration::from_secs(2));
+        assert_eq!(backoff(Duration::from_secs(5)), Duration::from_secs(10));
+        assert_eq!(backoff(Duration::from_secs(30)), Duration::from_secs(30));
+    }
+}
@@ -0,0 +1,293 @@
+//! Opus-in-Ogg 编码：上传前压缩录音分段
+//!
+//! 长录音的 WAV 分段体积大，上传慢。支持压缩格式的提供商（OpenAI、火山引擎文件识别）可改为
+//! 上传 Ogg 封装的 Opus，语音在每声道 24 kbps 下约为 16 kHz 单声道 WAV 的 1/10。
+//! Opus 只接受 8/12/16/24/48 kHz，其他采样率先重采样到不低于原采样率的最近一档，
+//! 超过两声道时混为单声道。封装按 RFC 7845 写入 OpusHead、OpusTags 与音频页。
+
+use crate::audio_pipeline;
+use audiopus::coder::Encoder;
+use audiopus::{Application, Bitrate, Channels, SampleRate};
+use std::borrow::Cow;
+
+/// Opus 支持的输入采样率
+const OPUS_RATES: [u32; 5] = [8_000, 12_000, 16_000, 24_000, 48_000];
+/// 每声道的编码码率
+const BITRATE_PER_CHANNEL: i32 = 24_000;
+/// 每个 Opus 包的时长
+const FRAME_MS: u32 = 20;
+/// 单个 Opus 包的最大字节数
+const MAX_PACKET_BYTES: usize = 4_000;
+/// Ogg 粒度位置固定按 48 kHz 计
+const GRANULE_RATE: u64 = 48_000;
+/// 单页最多的分段（lacing）数
+const MAX_PAGE_SEGMENTS: usize = 255;
+const STREAM_SERIAL: u32 = 0x5654_544b;
+const VENDOR: &str = "vtt-keyboard";
+
+const FLAG_BEGIN: u8 = 0x02;
+const FLAG_END: u8 = 0x04;
+
+static CRC_TABLE: [u32; 256] = crc_table();
+
+/// 将 16-bit PCM 编码为 Ogg Opus 字节流
+pub fn encode(samples: &[i16], sample_rate: u32, channels: u16) -> Result<Vec<u8>, String> {
+    let (samples, channels) = if channels > 2 {
+        let mono = audio_pipeline::downmix_samples(samples, usize::from(channels));
+        (Cow::Owned(mono), 1)
+    } else {
+        (Cow::Borrowed(samples), channels.max(1))
+    };
+    let rate = opus_rate(sample_rate);
+    let samples = if rate == sample_rate {
+        samples
+    } else {
+        Cow::Owned(audio_pipeline::resample_samples(
+            &samples,
+            usize::from(channels),
+            sample_rate,
+            rate,
+        ))
+    };
+
+    let opus_rate = SampleRate::try_from(rate as i32).map_err(|err| err.to_string())?;
+    let opus_channels = if channels == 2 {
+        Channels::Stereo
+    } else {
+        Channels::Mono
+    };
+    let mut encoder =
+        Encoder::new(opus_rate, opus_channels, Application::Voip).map_err(|err| err.to_string())?;
+    encoder
+        .set_bitrate(Bitrate::BitsPerSecond(
+            BITRATE_PER_CHANNEL * i32::from(channels),
+        ))
+        .map_err(|err| err.to_string())?;
+    let lookahead = encoder.lookahead().map_err(|err| err.to_string())? as usize;
+
+    let channels = usize::from(channels);
+    let frame_len = (rate * FRAME_MS / 1000) as usize;
+    let total_frames = samples.len() / channels;
+    // 编码器有 `lookahead` 帧延迟，尾部补静音使最后的有效样本也被编码
+    let packet_count = (total_frames + lookahead).div_ceil(frame_len).max(1);
+    let mut frame = vec![0i16; frame_len * channels];
+    let mut packet = vec![0u8; MAX_PACKET_BYTES];
+    let mut packets = Vec::with_capacity(packet_count);
+    for index in 0..packet_count {
+        let start = (index * frame_len * channels).min(samples.len());
+        let end = ((index + 1) * frame_len * channels).min(samples.len());
+        frame.fill(0);
+        frame[..end - start].copy_from_slice(&samples[start..end]);
+        let len = encoder
+            .encode(&frame, &mut packet)
+            .map_err(|err| err.to_string())?;
+        packets.push(packet[..len].to_vec());
+    }
+
+    let scale = |frames: usize| frames as u64 * GRANULE_RATE / u64::from(rate);
+    Ok(write_stream(
+        &packets,
+        channels as u8,
+        sample_rate,
+        scale(lookahead) as u16,
+        scale(frame_len),
+        scale(total_frames),
+    ))
+}
+
+/// 编码后每秒的平均字节数（不含 Ogg 封装开销），超过两声道时按混成的单声道计
+pub fn bytes_per_second(channels: u16) -> u64 {
+    let channels = if channels > 2 { 1 } else { channels.max(1) };
+    BITRATE_PER_CHANNEL as u64 * u64::from(channels) / 8
+}
+
+/// 不低于原采样率的最近一档 Opus 采样率，超过 48 kHz 时取 48 kHz
+fn opus_rate(sample_rate: u32) -> u32 {
+    OPUS_RATES
+        .into_iter()
+        .find(|rate| *rate >= sample_rate)
+        .unwrap_or(48_000)
+}
+
+/// 写出完整的 Ogg Opus 流；时长参数均以 48 kHz 样本数计，最后一页的粒度位置按实际时长裁掉补齐的静音
+fn write_stream(
+    packets: &[Vec<u8>],
+    channels: u8,
+    input_rate: u32,
+    pre_skip: u16,
+    packet_duration: u64,
+    total_duration: u64,
+) -> Vec<u8> {
+    let mut writer = OggWriter::new(STREAM_SERIAL);
+    writer.write_page(&[&opus_head(channels, input_rate, pre_skip)], 0, FLAG_BEGIN);
+    writer.write_page(&[&opus_tags()], 0, 0);
+
+    let mut page = Vec::new();
+    let mut segments = 0;
+    let mut granule = u64::from(pre_skip);
+    for (index, packet) in packets.iter().enumerate() {
+        let packet_segments = packet.len() / 255 + 1;
+        if segments + packet_segments > MAX_PAGE_SEGMENTS {
+            writer.write_page(&page, granule, 0);
+            page.clear();
+            segments = 0;
+        }
+        page.push(packet.as_slice());
+        segments += packet_segments;
+        granule += packet_duration;
+        if index + 1 == packets.len() {
+            let end = u64::from(pre_skip) + total_duration;
+            writer.write_page(&page, granule.min(end), FLAG_END);
+        }
+    }
+    writer.output
+}
+
+fn opus_head(channels: u8, input_rate: u32, pre_skip: u16) -> Vec<u8> {
+    let mut head = Vec::with_capacity(19);
+    head.extend_from_slice(b"OpusHead");
+    head.push(1);
+    head.push(channels);
+    head.extend_from_slice(&pre_skip.to_le_bytes());
+    head.extend_from_slice(&input_rate.to_le_bytes());
+    // 输出增益 0 dB，声道映射族 0（单声道或立体声）
+    head.extend_from_slice(&0i16.to_le_bytes());
+    head.push(0);
+    head
+}
+
+fn opus_tags() -> Vec<u8> {
+    let mut tags = Vec::with_capacity(16 + VENDOR.len());
+    tags.extend_from_slice(b"OpusTags");
+    tags.extend_from_slice(&(VENDOR.len() as u32).to_le_bytes());
+    tags.extend_from_slice(VENDOR.as_bytes());
+    tags.extend_from_slice(&0u32.to_le_bytes());
+    tags
+}
+
+struct OggWriter {
+    output: Vec<u8>,
+    serial: u32,
+    sequence: u32,
+}
+
+impl OggWriter {
+    fn new(serial: u32) -> Self {
+        Self {
+            output: Vec::new(),
+            serial,
+            sequence: 0,
+        }
+    }
+
+    /// 写出一页，包不跨页；调用方保证分段数不超过 255
+    fn write_page(&mut self, packets: &[&[u8]], granule: u64, flags: u8) {
+        let lacing = packets
+            .iter()
+            .flat_map(|packet| {
+                let full = packet.len() / 255;
+                std::iter::repeat_n(255u8, full).chain([(packet.len() % 255) as u8])
+            })
+            .collect::<Vec<_>>();
+        let start = self.output.len();
+        self.output.extend_from_slice(b"OggS");
+        self.output.push(0);
+        self.output.push(flags);
+        self.output.extend_from_slice(&granule.to_le_bytes());
+        self.output.extend_from_slice(&self.serial.to_le_bytes());
+        self.output.extend_from_slice(&self.sequence.to_le_bytes());
+        self.output.extend_from_slice(&[0; 4]);
+        self.output.push(lacing.len() as u8);
+        self.output.extend_from_slice(&lacing);
+        for packet in packets {
+            self.output.extend_from_slice(packet);
+        }
+        let crc = crc32(&self.output[start..]);
+        self.output[start + 22..start + 26].copy_from_slice(&crc.to_le_bytes());
+        self.sequence += 1;
+    }
+}
+
+/// Ogg 页校验：多项式 0x04c11db7，初值 0，不反转、不取反
+fn crc32(data: &[u8]) -> u32 {
+    data.iter().fold(0, |crc, byte| {
+        (crc << 8) ^ CRC_TABLE[usize::from((crc >> 24) as u8 ^ byte)]
+    })
+}
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut value = (index as u32) << 24;
+        let mut bit = 0;
+        while bit < 8 {
+            value = if value & 0x8000_0000 != 0 {
+                (value << 1) ^ 0x04c1_1db7
+            } else {
+                value << 1
+            };
+            bit += 1;
+        }
+        table[index] = value;
+        index += 1;
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opus_rate_rounds_up_to_supported_rate() {
+        assert_eq!(opus_rate(16_000), 16_000);
+        assert_eq!(opus_rate(22_050), 24_000);
+        assert_eq!(opus_rate(44_100), 48_000);
+        assert_eq!(opus_rate(96_000), 48_000);
+    }
+
+    #[test]
+    fn ogg_pages_carry_lacing_granule_and_checksum() {
+        assert_eq!(crc32(b"123456789"), 0x89a1_897f);
+
+        let packets = vec![vec![1u8; 300], vec![2u8; 10]];
+        let stream = write_stream(&packets, 1, 16_000, 312, 960, 1_500);
+        let pages = split_pages(&stream);
+        assert_eq!(pages.len(), 3);
+        assert_eq!(&pages[0][28..36], b"OpusHead");
+        assert_eq!(pages[0][5], FLAG_BEGIN);
+        assert_eq!(&pages[1][28..36], b"OpusTags");
+
+        let audio = &pages[2];
+        assert_eq!(audio[5], FLAG_END);
+        // 最后一页的粒度位置裁到实际时长：312 + 1500，而不是 312 + 2 × 960
+        assert_eq!(u64::from_le_bytes(audio[6..14].try_into().unwrap()), 1_812);
+        assert_eq!(u32::from_le_bytes(audio[18..22].try_into().unwrap()), 2);
+        assert_eq!(&audio[26..30], &[3, 255, 45, 10]);
+        for page in pages {
+            let mut zeroed = page.to_vec();
+            zeroed[22..26].fill(0);
+            assert_eq!(
+                crc32(&zeroed).to_le_bytes(),
+                <[u8; 4]>::try_from(&page[22..26]).unwrap()
+            );
+        }
+    }
+
+    fn split_pages(stream: &[u8]) -> Vec<&[u8]> {
+        let mut pages = Vec::new();
+        let mut rest = stream;
+        while !rest.is_empty() {
+            let segments = usize::from(rest[26]);
+            let body = rest[27..27 + segments]
+                .iter()
+                .map(|len| usize::from(*len))
+                .sum::<usize>();
+            let (page, tail) = rest.split_at(27 + segments + body);
+            pages.push(page);
+            rest = tail;
+        }
+        pages
+    }
+}
@@ -0,0 +1,156 @@
+//! 首次运行向导：探测本机能力并写入推荐的设置预设
+//!
+//! 探测麦克风、Docker、GPU 与网络，按结果推荐云端或本地配置，
+//! 让新用户无需手动编辑设置即可完成可用的初始配置。
+
+use crate::recorder;
+use crate::sensevoice::docker_utils::{docker_daemon_running, query_gpu_memory};
+use crate::sensevoice::model::{
+    DEFAULT_SENSEVOICE_MODEL_ID, DEFAULT_SHERPA_ONNX_SENSEVOICE_MODEL_ID, LOCAL_MODEL_SENSEVOICE,
+    LOCAL_MODEL_SHERPA_ONNX_SENSEVOICE,
+};
+use crate::settings::{OfflineFallback, Settings, TranscriptionProvider};
+use crate::system_state;
+use serde::{Deserialize, Serialize};
+
+pub const ONBOARDING_PROGRESS_EVENT: &str = "onboarding-progress";
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingProgress {
+    pub stage: String,
+    pub message: String,
+    pub percent: u8,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    pub microphone: bool,
+    pub microphone_name: Option<String>,
+    pub docker: bool,
+    pub gpu: bool,
+    /// 首块 GPU 的显存（MiB），未检测到 GPU 时为 None
+    pub gpu_memory_mib: Option<u64>,
+    pub network: bool,
+    pub recommended_profile: OnboardingProfile,
+}
+
+/// 向导可写入的设置预设
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum OnboardingProfile {
+    /// 云端转写（OpenAI），离线时暂存录音待补转写
+    #[default]
+    Cloud,
+    /// 原生 Sherpa-ONNX SenseVoice，无需 Docker 与 GPU
+    LocalCpu,
+    /// Docker SenseVoice 使用 CUDA 加速
+    LocalGpu,
+}
+
+/// 依次探测各项能力，每完成一项通过 `on_progress` 汇报
+pub fn detect_capabilities(on_progress: impl Fn(OnboardingProgress)) -> Capabilities {
+    let progress = |stage: &str, message: &str, percent: u8| {
+        on_progress(OnboardingProgress {
+            stage: stage.to_string(),
+            message: message.to_string(),
+            percent,
+        })
+    };
+    let mut capabilities = Capabilities::default();
+
+    progress("microphone", "正在检测麦克风", 0);
+    capabilities.microphone_name = recorder::default_input_device_name();
+    capabilities.microphone = capabilities.microphone_name.is_some();
+
+    progress("docker", "正在检测 Docker", 25);
+    capabilities.docker = docker_daemon_running();
+
+    progress("gpu", "正在检测 GPU", 50);
+    capabilities.gpu_memory_mib = query_gpu_memory()
+        .and_then(|gpus| gpus.first().map(|gpu| gpu.total_mib))
+        .filter(|total_mib| *total_mib > 0);
+    capabilities.gpu = capabilities.gpu_memory_mib.is_some();
+
+    progress("network", "正在检测网络", 75);
+    capabilities.network = system_state::is_network_online();
+
+    capabilities.recommended_profile = recommend_profile(&capabilities);
+    progress("done", "检测完成", 100);
+    capabilities
+}
+
+/// 有 Docker 与 GPU 时推荐本地 GPU；离线时推荐本地 CPU；其余推荐云端
+pub fn recommend_profile(capabilities: &Capabilities) -> OnboardingProfile {
+    if capabilities.docker && capabilities.gpu {
+        OnboardingProfile::LocalGpu
+    } else if !capabilities.network {
+        OnboardingProfile::LocalCpu
+    } else {
+        OnboardingProfile::Cloud
+    }
+}
+
+/// 将预设写入设置；本地模型的下载与启动由向导随后触发
+pub fn apply_profile(settings: &mut Settings, profile: OnboardingProfile) {
+    match profile {
+        OnboardingProfile::Cloud => {
+            settings.provider = TranscriptionProvider::Openai;
+            settings.offline.fallback = OfflineFallback::Queue;
+        }
+        OnboardingProfile::LocalCpu => {
+            settings.provider = TranscriptionProvider::Sensevoice;
+            settings.sensevoice.local_model = LOCAL_MODEL_SHERPA_ONNX_SENSEVOICE.to_string();
+            settings.sensevoice.model_id = DEFAULT_SHERPA_ONNX_SENSEVOICE_MODEL_ID.to_string();
+            settings.sensevoice.device = "cpu".to_string();
+        }
+        OnboardingProfile::LocalGpu => {
+            settings.provider = TranscriptionProvider::Sensevoice;
+            settings.sensevoice.local_model = LOCAL_MODEL_SENSEVOICE.to_string();
+            settings.sensevoice.model_id = DEFAULT_SENSEVOICE_MODEL_ID.to_string();
+            settings.sensevoice.device = "cuda".to_string();
+            settings.sensevoice.auto_start_docker = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recommend_profile_prefers_local_gpu_then_offline_cpu() {
+        let mut capabilities = Capabilities {
+            network: true,
+            ..Capabilities::default()
+        };
+        assert_eq!(recommend_profile(&capabilities), OnboardingProfile::Cloud);
+        capabilities.network = false;
+        assert_eq!(
+            recommend_profile(&capabilities),
+            OnboardingProfile::LocalCpu
+        );
+        capabilities.docker = true;
+        capabilities.gpu = true;
+        assert_eq!(
+            recommend_profile(&capabilities),
+            OnboardingProfile::LocalGpu
+        );
+    }
+
+    #[test]
+    fn apply_profile_switches_provider_and_local_model() {
+        let mut settings = Settings::default();
+        apply_profile(&mut settings, OnboardingProfile::LocalCpu);
+        assert!(settings.provider == TranscriptionProvider::Sensevoice);
+        assert_eq!(
+            settings.sensevoice.local_model,
+            LOCAL_MODEL_SHERPA_ONNX_SENSEVOICE
+        );
+
+        apply_profile(&mut settings, OnboardingProfile::Cloud);
+        assert!(settings.provider == TranscriptionProvider::Openai);
+        assert_eq!(settings.offline.fallback, OfflineFallback::Queue);
+    }
+}
@@ -1,4 +1,8 @@
-use crate::settings::{OpenAiSettings, Settings, TextSettings};
+use crate::audio_inspect;
+use crate::dictation_context;
+use crate::provider_error::ProviderFailure;
+use crate::settings::{OpenAiSettings, Settings, TextSettings, TranscriptionAlignment};
+use crate::transcription::{confidence_from_logprobs, TranscriptSegment};
 use reqwest::blocking::{multipart, Client};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -16,6 +20,8 @@ pub enum OpenAiError {
     Config(String),
     #[error("无法读取音频: {0}")]
     Io(String),
+    #[error("OpenAI 拒绝请求: {0}")]
+    Rejected(#[from] ProviderFailure),
 }
 
 #[derive(Serialize)]
@@ -32,19 +38,82 @@ struct ResponseRequest<'a> {
     top_p: Option<f32>,
 }
 
+/// 兼容 `json` 与 `verbose_json`：后者额外包含语种、句级片段与逐词时间戳
 #[derive(Deserialize)]
 struct TranscriptionResponse {
     text: String,
+    #[serde(default)]
+    logprobs: Vec<TokenLogprob>,
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    segments: Vec<VerboseSegment>,
+    #[serde(default)]
+    words: Vec<VerboseWord>,
 }
 
-pub fn transcribe_audio(settings: &Settings, audio_path: &Path) -> Result<String, OpenAiError> {
+#[derive(Deserialize)]
+struct TokenLogprob {
+    logprob: f64,
+}
+
+/// `verbose_json` 的句级片段，时间单位为秒
+#[derive(Deserialize)]
+struct VerboseSegment {
+    start: f64,
+    end: f64,
+    text: String,
+    #[serde(default)]
+    avg_logprob: Option<f64>,
+}
+
+/// `verbose_json` 的逐词时间戳（需请求 `timestamp_granularities[]=word`）
+#[derive(Deserialize)]
+struct VerboseWord {
+    word: String,
+    start: f64,
+    end: f64,
+}
+
+pub struct OpenAiTranscription {
+    pub text: String,
+    pub confidence: Option<f32>,
+    pub alignment: Option<TranscriptionAlignment>,
+    pub segments: Vec<TranscriptSegment>,
+    pub language: Option<String>,
+}
+
+impl From<String> for OpenAiTranscription {
+    fn from(text: String) -> Self {
+        Self {
+            text,
+            confidence: None,
+            alignment: None,
+            segments: Vec::new(),
+            language: None,
+        }
+    }
+}
+
+pub fn transcribe_audio(
+    settings: &Settings,
+    audio_path: &Path,
+) -> Result<OpenAiTranscription, OpenAiError> {
     ensure_auth(&settings.openai)?;
     let file_bytes = fs::read(audio_path).map_err(|err| OpenAiError::Io(err.to_string()))?;
-    let file_name = audio_path
-        .file_name()
-        .and_then(|name| name.to_str())
-        .unwrap_or("recording.wav");
-    let form = build_transcription_form(&settings.openai, file_name, file_bytes)?;
+    let file_name = audio_inspect::upload_file_name(audio_path);
+    let prompt = transcription_prompt(
+        &settings.openai.speech_to_text.prompt,
+        &dictation_context::prompt_context(&settings.context_carryover),
+    );
+    let confidence_check = settings.output.confidence_threshold > 0.0;
+    let form = build_transcription_form(
+        &settings.openai,
+        &prompt,
+        &file_name,
+        file_bytes,
+        confidence_check,
+    )?;
     let client = Client::new();
     let url = format!(
         "{}/audio/transcriptions",
@@ -59,6 +128,9 @@ pub fn transcribe_audio(settings: &Settings, audio_path: &Path) -> Result<String
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().unwrap_or_default();
+        if let Some(failure) = ProviderFailure::classify(status.as_u16(), &body) {
+            return Err(failure.into());
+        }
         return Err(OpenAiError::Request(format!("{status}: {body}")));
     }
     let body = response
@@ -67,15 +139,64 @@ pub fn transcribe_audio(settings: &Settings, audio_path: &Path) -> Result<String
     if settings.openai.speech_to_text.stream {
         let streamed = parse_streamed_text(&body)?;
         if !streamed.is_empty() {
-            return Ok(streamed);
+            return Ok(streamed.into());
         }
     }
     if settings.openai.speech_to_text.response_format == "text" {
-        return Ok(body.trim().to_string());
+        return Ok(body.trim().to_string().into());
     }
+    parse_transcription_response(&body)
+}
+
+fn parse_transcription_response(body: &str) -> Result<OpenAiTranscription, OpenAiError> {
     let data: TranscriptionResponse =
-        serde_json::from_str(&body).map_err(|err| OpenAiError::Parse(err.to_string()))?;
-    Ok(data.text)
+        serde_json::from_str(body).map_err(|err| OpenAiError::Parse(err.to_string()))?;
+    // 优先使用逐 token 对数概率，verbose_json 则退而使用各片段的平均对数概率
+    let logprobs = if data.logprobs.is_empty() {
+        data.segments
+            .iter()
+            .filter_map(|segment| segment.avg_logprob)
+            .collect::<Vec<_>>()
+    } else {
+        data.logprobs
+            .iter()
+            .map(|token| token.logprob)
+            .collect::<Vec<_>>()
+    };
+    let alignment = (!data.words.is_empty()).then(|| TranscriptionAlignment {
+        tokens: data.words.iter().map(|word| word.word.clone()).collect(),
+        timestamps_ms: data
+            .words
+            .iter()
+            .map(|word| seconds_to_ms(word.start))
+            .collect(),
+        durations_ms: data
+            .words
+            .iter()
+            .map(|word| seconds_to_ms(word.end).saturating_sub(seconds_to_ms(word.start)))
+            .collect(),
+    });
+    let segments = data
+        .segments
+        .into_iter()
+        .map(|segment| TranscriptSegment {
+            start_ms: seconds_to_ms(segment.start),
+            end_ms: seconds_to_ms(segment.end),
+            text: segment.text.trim().to_string(),
+        })
+        .filter(|segment| !segment.text.is_empty())
+        .collect();
+    Ok(OpenAiTranscription {
+        text: data.text,
+        confidence: confidence_from_logprobs(&logprobs),
+        alignment,
+        segments,
+        language: data.language.filter(|language| !language.trim().is_empty()),
+    })
+}
+
+fn seconds_to_ms(seconds: f64) -> u64 {
+    (seconds.max(0.0) * 1000.0).round() as u64
 }
 
 pub fn generate_text(
@@ -110,6 +231,9 @@ pub fn generate_text(
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().unwrap_or_default();
+        if let Some(failure) = ProviderFailure::classify(status.as_u16(), &body) {
+            return Err(failure.into());
+        }
         return Err(OpenAiError::Request(format!("{status}: {body}")));
     }
     let value: Value = response
@@ -118,7 +242,7 @@ pub fn generate_text(
     extract_output_text(&value)
 }
 
-fn ensure_auth(settings: &OpenAiSettings) -> Result<(), OpenAiError> {
+pub fn ensure_auth(settings: &OpenAiSettings) -> Result<(), OpenAiError> {
     if settings.api_key.trim().is_empty() {
         return Err(OpenAiError::Config("API Key 不能为空".to_string()));
     }
@@ -132,10 +256,21 @@ fn ensure_text_auth(settings: &TextSettings) -> Result<(), OpenAiError> {
     Ok(())
 }
 
+/// 固定提示词在前，最近听写上下文在后（模型更关注提示词末尾的内容）
+fn transcription_prompt(configured: &str, context: &str) -> String {
+    [configured.trim(), context.trim()]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 fn build_transcription_form(
     settings: &OpenAiSettings,
+    prompt: &str,
     filename: &str,
     bytes: Vec<u8>,
+    confidence_check: bool,
 ) -> Result<multipart::Form, OpenAiError> {
     let mut form = multipart::Form::new()
         .text("model", settings.speech_to_text.model.clone())
@@ -147,8 +282,8 @@ fn build_transcription_form(
     if !settings.speech_to_text.language.trim().is_empty() {
         form = form.text("language", settings.speech_to_text.language.clone());
     }
-    if !settings.speech_to_text.prompt.trim().is_empty() {
-        form = form.text("prompt", settings.speech_to_text.prompt.clone());
+    if !prompt.is_empty() {
+        form = form.text("prompt", prompt.to_string());
     }
     if !settings.speech_to_text.response_format.trim().is_empty() {
         form = form.text(
@@ -170,8 +305,8 @@ fn build_transcription_form(
             settings.speech_to_text.chunking_strategy.clone(),
         );
     }
-    for value in &settings.speech_to_text.include {
-        form = form.text("include[]", value.clone());
+    for value in transcription_includes(settings, confidence_check) {
+        form = form.text("include[]", value);
     }
     for granularity in &settings.speech_to_text.timestamp_granularities {
         form = form.text("timestamp_granularities[]", granularity.clone());
@@ -186,6 +321,25 @@ fn build_transcription_form(
     Ok(form)
 }
 
+/// 开启置信度检查时自动请求逐 token 对数概率，否则低置信度提示永远不会触发
+fn transcription_includes(settings: &OpenAiSettings, confidence_check: bool) -> Vec<String> {
+    let mut include = settings.speech_to_text.include.clone();
+    if confidence_check
+        && supports_logprobs(settings)
+        && !include.iter().any(|value| value == "logprobs")
+    {
+        include.push("logprobs".to_string());
+    }
+    include
+}
+
+/// 仅 gpt-4o 系列转写模型在 JSON 响应格式下返回 logprobs；
+/// whisper-1 的 verbose_json 自带分段 avg_logprob，无需额外请求
+fn supports_logprobs(settings: &OpenAiSettings) -> bool {
+    let format = settings.speech_to_text.response_format.trim();
+    settings.speech_to_text.model.starts_with("gpt-4o") && (format.is_empty() || format == "json")
+}
+
 fn extract_output_text(value: &Value) -> Result<String, OpenAiError> {
     if let Some(text) = value
         .pointer("/output/0/content/0/text")
@@ -220,3 +374,65 @@ fn parse_streamed_text(body: &str) -> Result<String, OpenAiError> {
     }
     Ok(output.trim().to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_verbose_json_extracts_segments_words_and_language() {
+        let body = r#"{
+            "task": "transcribe",
+            "language": "english",
+            "duration": 2.5,
+            "text": "Hello world. Bye.",
+            "segments": [
+                {"id": 0, "start": 0.0, "end": 1.2, "text": " Hello world.", "avg_logprob": -0.1},
+                {"id": 1, "start": 1.2, "end": 2.5, "text": " Bye.", "avg_logprob": -0.3}
+            ],
+            "words": [
+                {"word": "Hello", "start": 0.0, "end": 0.5},
+                {"word": "world", "start": 0.5, "end": 1.2}
+            ]
+        }"#;
+        let result = parse_transcription_response(body).unwrap();
+        assert_eq!(result.text, "Hello world. Bye.");
+        assert_eq!(result.language.as_deref(), Some("english"));
+        assert_eq!(result.segments.len(), 2);
+        assert_eq!(result.segments[1].start_ms, 1200);
+        assert_eq!(result.segments[1].end_ms, 2500);
+        assert_eq!(result.segments[0].text, "Hello world.");
+        let alignment = result.alignment.unwrap();
+        assert_eq!(alignment.tokens, vec!["Hello", "world"]);
+        assert_eq!(alignment.timestamps_ms, vec![0, 500]);
+        assert_eq!(alignment.durations_ms, vec![500, 700]);
+        let expected = ((-0.1f64).exp() + (-0.3f64).exp()) / 2.0;
+        assert!((f64::from(result.confidence.unwrap()) - expected).abs() < 1e-4);
+    }
+
+    #[test]
+    fn confidence_check_requests_logprobs_from_supported_models() {
+        let mut settings = Settings::default().openai;
+        settings.speech_to_text.model = "gpt-4o-transcribe".to_string();
+        settings.speech_to_text.response_format = "json".to_string();
+        assert!(transcription_includes(&settings, false).is_empty());
+        assert_eq!(transcription_includes(&settings, true), vec!["logprobs"]);
+
+        settings.speech_to_text.include = vec!["logprobs".to_string()];
+        assert_eq!(transcription_includes(&settings, true), vec!["logprobs"]);
+
+        settings.speech_to_text.include.clear();
+        settings.speech_to_text.model = "whisper-1".to_string();
+        settings.speech_to_text.response_format = "verbose_json".to_string();
+        assert!(transcription_includes(&settings, true).is_empty());
+    }
+
+    #[test]
+    fn parse_plain_json_has_no_timing() {
+        let result = parse_transcription_response(r#"{"text": "你好"}"#).unwrap();
+        assert_eq!(result.text, "你好");
+        assert!(result.segments.is_empty());
+        assert!(result.alignment.is_none());
+        assert!(result.confidence.is_none());
+    }
+}
@@ -0,0 +1,85 @@
+//! 中英文间距（“盘古之白”）：在中文与英文、数字及常见符号之间插入空格
+//!
+//! 技术类中文听写常夹杂英文术语与数字，整理时需要逐处补空格。这里只在中文与另一侧相邻处
+//! 插入一个空格，不改动已有空格，网址、代码等纯英文片段内部保持原样，重复执行结果不变。
+
+/// 在中文与英文、数字之间插入空格
+pub fn space(text: &str) -> String {
+    let mut output = String::with_capacity(text.len() + text.len() / 8);
+    let mut prev = None;
+    for ch in text.chars() {
+        if let Some(prev) = prev {
+            if needs_space(prev, ch) {
+                output.push(' ');
+            }
+        }
+        output.push(ch);
+        prev = Some(ch);
+    }
+    output
+}
+
+fn needs_space(left: char, right: char) -> bool {
+    (is_cjk(left) && is_latin_start(right)) || (is_latin_end(left) && is_cjk(right))
+}
+
+/// 紧跟中文之后、需要与中文隔开的字符：“使用 @user”、“花了 $5”、“说 (注)”
+fn is_latin_start(ch: char) -> bool {
+    is_latin(ch)
+        || matches!(
+            ch,
+            '@' | '#' | '$' | '&' | '+' | '=' | '`' | '~' | '(' | '['
+        )
+}
+
+/// 位于中文之前、需要与中文隔开的字符：“50% 的”、“C++ 和”、“(注) 后”
+fn is_latin_end(ch: char) -> bool {
+    is_latin(ch)
+        || matches!(
+            ch,
+            '%' | '+' | '#' | '`' | ')' | ']' | '!' | '?' | ',' | ';' | ':'
+        )
+}
+
+/// 中日韩文字（不含全角标点）
+pub(crate) fn is_cjk(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{3040}'..='\u{30ff}'
+            | '\u{3400}'..='\u{4dbf}'
+            | '\u{4e00}'..='\u{9fff}'
+            | '\u{ac00}'..='\u{d7af}'
+            | '\u{f900}'..='\u{faff}'
+    )
+}
+
+/// 英文字母与数字
+pub(crate) fn is_latin(ch: char) -> bool {
+    ch.is_alphanumeric() && !is_cjk(ch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spaces_chinese_around_latin_numbers_and_symbols() {
+        assert_eq!(
+            space("用Rust重写了3个CLI，性能提升50%以上"),
+            "用 Rust 重写了 3 个 CLI，性能提升 50% 以上"
+        );
+        assert_eq!(
+            space("学习C++和C#，联系@alice"),
+            "学习 C++ 和 C#，联系 @alice"
+        );
+        assert_eq!(space("参见(附录)说明"), "参见 (附录) 说明");
+    }
+
+    #[test]
+    fn keeps_existing_spacing_and_latin_text_unchanged() {
+        let spaced = "部署到 https://example.com/a_b 上";
+        assert_eq!(space(spaced), spaced);
+        assert_eq!(space(&space("版本v2.1发布")), "版本 v2.1 发布");
+        assert_eq!(space("Hello, world."), "Hello, world.");
+    }
+}
@@ -1,13 +1,26 @@
 use arboard::Clipboard;
 use enigo::{Enigo, Key, KeyboardControllable};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
+/// 自动粘贴频率限制的统计窗口
+const PASTE_RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// 最近粘贴结果的内部记录（最新在前），系统剪贴板被覆盖后仍可重新粘贴
+static RECENT_RESULTS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static PASTE_GUARD: Mutex<PasteGuard> = Mutex::new(PasteGuard::new());
+
 #[derive(Debug, Error)]
 pub enum PasteError {
     #[error("剪贴板写入失败: {0}")]
     Clipboard(String),
     #[error("模拟粘贴失败: {0}")]
     Paste(String),
+    #[error("没有第 {0} 条粘贴记录")]
+    NotFound(usize),
 }
 
 pub fn write_text(text: &str) -> Result<(), PasteError> {
@@ -24,6 +37,136 @@ pub fn write_and_paste(text: &str) -> Result<(), PasteError> {
     Ok(())
 }
 
+/// 自动粘贴的结果
+#[derive(Debug, PartialEq, Eq)]
+pub enum AutoPaste {
+    /// 已立即粘贴
+    Pasted,
+    /// 超出频率限制，文本已暂存，稍后与其他输出合并粘贴
+    Coalesced,
+}
+
+/// 自动粘贴：每秒最多粘贴 `max_per_second` 次（0 表示不限）；超出时暂存文本，
+/// 窗口空出后与期间的其他输出合并为一次粘贴，防止失控的队列持续向前台应用发送按键
+pub fn auto_paste(text: &str, max_per_second: u32) -> Result<AutoPaste, PasteError> {
+    let mut guard = PASTE_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+    let now = Instant::now();
+    let wait = guard.wait_time(now, max_per_second);
+    // 已有暂存文本时同样排在其后，保持输出顺序
+    if wait.is_some() || !guard.pending.is_empty() {
+        guard.coalesce(text);
+        if !guard.flush_scheduled {
+            guard.flush_scheduled = true;
+            spawn_flush(wait.unwrap_or_default(), max_per_second);
+        }
+        return Ok(AutoPaste::Coalesced);
+    }
+    guard.record(now);
+    drop(guard);
+    write_and_paste(text)?;
+    Ok(AutoPaste::Pasted)
+}
+
+/// 等待频率窗口空出后粘贴合并的暂存文本
+fn spawn_flush(initial_wait: Duration, max_per_second: u32) {
+    thread::spawn(move || {
+        let mut wait = initial_wait;
+        loop {
+            thread::sleep(wait);
+            let mut guard = PASTE_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            if let Some(next) = guard.wait_time(now, max_per_second) {
+                wait = next;
+                continue;
+            }
+            guard.record(now);
+            guard.flush_scheduled = false;
+            let text = std::mem::take(&mut guard.pending);
+            drop(guard);
+            if let Err(_err) = write_and_paste(&text) {
+                #[cfg(debug_assertions)]
+                eprintln!("合并粘贴失败: {_err}");
+            }
+            return;
+        }
+    });
+}
+
+struct PasteGuard {
+    /// 统计窗口内各次粘贴的时间
+    recent: VecDeque<Instant>,
+    /// 超出频率限制后暂存、待合并粘贴的文本
+    pending: String,
+    flush_scheduled: bool,
+}
+
+impl PasteGuard {
+    const fn new() -> Self {
+        Self {
+            recent: VecDeque::new(),
+            pending: String::new(),
+            flush_scheduled: false,
+        }
+    }
+
+    /// 距离下一次允许粘贴还需等待的时长，`None` 表示可以立即粘贴
+    fn wait_time(&mut self, now: Instant, max_per_second: u32) -> Option<Duration> {
+        while self
+            .recent
+            .front()
+            .is_some_and(|time| now.duration_since(*time) >= PASTE_RATE_WINDOW)
+        {
+            self.recent.pop_front();
+        }
+        if max_per_second == 0 || self.recent.len() < max_per_second as usize {
+            return None;
+        }
+        let oldest = *self.recent.front()?;
+        Some(PASTE_RATE_WINDOW.saturating_sub(now.duration_since(oldest)))
+    }
+
+    fn record(&mut self, now: Instant) {
+        self.recent.push_back(now);
+    }
+
+    fn coalesce(&mut self, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        if crate::processing::needs_separator(&self.pending, text) {
+            self.pending.push(' ');
+        }
+        self.pending.push_str(text);
+    }
+}
+
+/// 记录一次听写结果，超出容量时丢弃最早的记录；容量为 0 时不记录
+pub fn remember_result(text: &str, capacity: usize) {
+    if text.trim().is_empty() {
+        return;
+    }
+    let mut results = RECENT_RESULTS.lock().unwrap_or_else(|e| e.into_inner());
+    results.push_front(text.to_string());
+    results.truncate(capacity);
+}
+
+pub fn recent_results() -> Vec<String> {
+    let results = RECENT_RESULTS.lock().unwrap_or_else(|e| e.into_inner());
+    results.iter().cloned().collect()
+}
+
+/// 重新粘贴第 n 条最近结果（1 为最近一条），不改变记录顺序
+pub fn paste_nth_result(n: usize) -> Result<(), PasteError> {
+    let text = {
+        let results = RECENT_RESULTS.lock().unwrap_or_else(|e| e.into_inner());
+        n.checked_sub(1)
+            .and_then(|index| results.get(index).cloned())
+            .ok_or(PasteError::NotFound(n))?
+    };
+    write_and_paste(&text)
+}
+
 fn send_paste_shortcut() -> Result<(), String> {
     let mut enigo = Enigo::new();
     #[cfg(target_os = "macos")]
@@ -40,3 +183,27 @@ fn send_paste_shortcut() -> Result<(), String> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paste_guard_limits_pastes_per_window_and_coalesces() {
+        let mut guard = PasteGuard::new();
+        let start = Instant::now();
+        assert_eq!(guard.wait_time(start, 2), None);
+        guard.record(start);
+        guard.record(start + Duration::from_millis(100));
+        let now = start + Duration::from_millis(400);
+        assert_eq!(guard.wait_time(now, 2), Some(Duration::from_millis(600)));
+        assert_eq!(guard.wait_time(now, 0), None);
+        let later = start + Duration::from_millis(1000);
+        assert_eq!(guard.wait_time(later, 2), None);
+
+        guard.coalesce("hello");
+        guard.coalesce("world");
+        guard.coalesce("你好");
+        assert_eq!(guard.pending, "hello world你好");
+    }
+}
@@ -0,0 +1,196 @@
+//! 用户在场探测：锁屏与键鼠空闲时长
+//!
+//! 离开电脑时暂停语音唤醒监听、本地服务的自动启动与预热，避免无人时录音或占用 GPU。
+//! 各平台实现保持轻量、无额外依赖，探测失败时视为在场。
+
+use crate::settings::PresenceSettings;
+use std::thread;
+use std::time::Duration;
+
+/// 等待用户回来时的探测间隔
+const AWAY_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Presence {
+    Active,
+    Idle,
+    Locked,
+}
+
+/// 探测当前会话状态，无输入超过 `idle_after` 视为空闲
+pub fn detect(idle_after: Duration) -> Presence {
+    let (idle, locked) = session_state();
+    if locked {
+        Presence::Locked
+    } else if idle.is_some_and(|idle| idle >= idle_after) {
+        Presence::Idle
+    } else {
+        Presence::Active
+    }
+}
+
+/// 按设置判断用户是否离开；未开启暂停时始终返回 false
+pub fn is_away(settings: &PresenceSettings) -> bool {
+    if !settings.pause_when_away {
+        return false;
+    }
+    let idle_after = Duration::from_secs(u64::from(settings.idle_minutes) * 60);
+    detect(idle_after) != Presence::Active
+}
+
+/// 阻塞直到用户回到电脑前，供后台启动任务推迟执行
+pub fn wait_until_present(settings: &PresenceSettings) {
+    while is_away(settings) {
+        thread::sleep(AWAY_POLL_INTERVAL);
+    }
+}
+
+/// 返回（键鼠空闲时长，是否锁屏）
+#[cfg(target_os = "windows")]
+fn session_state() -> (Option<Duration>, bool) {
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    struct LastInputInfo {
+        cb_size: u32,
+        time: u32,
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetLastInputInfo(info: *mut LastInputInfo) -> i32;
+        fn OpenInputDesktop(flags: u32, inherit: i32, access: u32) -> *mut c_void;
+        fn SwitchDesktop(desktop: *mut c_void) -> i32;
+        fn CloseDesktop(desktop: *mut c_void) -> i32;
+    }
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetTickCount() -> u32;
+    }
+    const DESKTOP_SWITCHDESKTOP: u32 = 0x0100;
+
+    let mut info = LastInputInfo {
+        cb_size: std::mem::size_of::<LastInputInfo>() as u32,
+        time: 0,
+    };
+    let idle = (unsafe { GetLastInputInfo(&mut info) } != 0).then(|| {
+        let now = unsafe { GetTickCount() };
+        Duration::from_millis(u64::from(now.wrapping_sub(info.time)))
+    });
+
+    // 锁屏时输入桌面切换为 Winlogon，当前会话无法打开或切换到它
+    let desktop = unsafe { OpenInputDesktop(0, 0, DESKTOP_SWITCHDESKTOP) };
+    let locked = if desktop.is_null() {
+        true
+    } else {
+        let switched = unsafe { SwitchDesktop(desktop) } != 0;
+        unsafe { CloseDesktop(desktop) };
+        !switched
+    };
+    (idle, locked)
+}
+
+#[cfg(target_os = "macos")]
+fn session_state() -> (Option<Duration>, bool) {
+    let Ok(output) = std::process::Command::new("ioreg")
+        .args(["-c", "IOHIDSystem", "-d", "4"])
+        .output()
+    else {
+        return (None, false);
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // 锁屏需私有框架才能可靠探测，锁屏后通常无输入，由空闲时长覆盖
+    (parse_hid_idle_time(&stdout), false)
+}
+
+#[cfg(target_os = "linux")]
+fn session_state() -> (Option<Duration>, bool) {
+    let Ok(session_id) = std::env::var("XDG_SESSION_ID") else {
+        return (None, false);
+    };
+    let Ok(output) = std::process::Command::new("loginctl")
+        .args([
+            "show-session",
+            &session_id,
+            "-p",
+            "IdleHint",
+            "-p",
+            "IdleSinceHint",
+            "-p",
+            "LockedHint",
+        ])
+        .output()
+    else {
+        return (None, false);
+    };
+    let now_us = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_micros() as u64)
+        .unwrap_or_default();
+    parse_loginctl_session(&String::from_utf8_lossy(&output.stdout), now_us)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn session_state() -> (Option<Duration>, bool) {
+    (None, false)
+}
+
+/// 解析 `ioreg` 输出中的 `"HIDIdleTime" = <纳秒>`
+#[cfg_attr(not(any(target_os = "macos", test)), allow(dead_code))]
+fn parse_hid_idle_time(output: &str) -> Option<Duration> {
+    output
+        .lines()
+        .find_map(|line| line.split_once("\"HIDIdleTime\" = "))
+        .and_then(|(_, value)| value.trim().parse::<u64>().ok())
+        .map(Duration::from_nanos)
+}
+
+/// 解析 `loginctl show-session` 的 `IdleHint`/`IdleSinceHint`（微秒时间戳）/`LockedHint`
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn parse_loginctl_session(output: &str, now_us: u64) -> (Option<Duration>, bool) {
+    let mut idle_hint = false;
+    let mut idle_since_us = None;
+    let mut locked = false;
+    for line in output.lines() {
+        match line.trim().split_once('=') {
+            Some(("IdleHint", value)) => idle_hint = value == "yes",
+            Some(("IdleSinceHint", value)) => idle_since_us = value.parse::<u64>().ok(),
+            Some(("LockedHint", value)) => locked = value == "yes",
+            _ => {}
+        }
+    }
+    let idle = if idle_hint {
+        idle_since_us
+            .filter(|since| *since > 0)
+            .map(|since| Duration::from_micros(now_us.saturating_sub(since)))
+    } else {
+        Some(Duration::ZERO)
+    };
+    (idle, locked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_loginctl_session_reads_idle_and_lock_hints() {
+        let output = "IdleHint=yes\nIdleSinceHint=1000000\nLockedHint=no\n";
+        assert_eq!(
+            parse_loginctl_session(output, 61_000_000),
+            (Some(Duration::from_secs(60)), false)
+        );
+        let output = "IdleHint=no\nIdleSinceHint=0\nLockedHint=yes\n";
+        assert_eq!(
+            parse_loginctl_session(output, 61_000_000),
+            (Some(Duration::ZERO), true)
+        );
+    }
+
+    #[test]
+    fn parse_hid_idle_time_reads_nanoseconds() {
+        let output = "    | |   \"HIDIdleTime\" = 5000000000\n";
+        assert_eq!(parse_hid_idle_time(output), Some(Duration::from_secs(5)));
+        assert_eq!(parse_hid_idle_time("no idle key"), None);
+    }
+}
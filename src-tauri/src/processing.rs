@@ -1,12 +1,40 @@
+use crate::audio_pipeline::{self, PipelineOutput};
 use crate::audio_processing;
+use crate::confirm_window;
+use crate::corrections;
+use crate::deferred_queue;
+use crate::dictation_context;
+use crate::events::{self, RecordingWarningKind, SegmentStatus};
+use crate::itn;
+use crate::jobs::{self, JobId, JobState};
+use crate::literal_mode;
 use crate::paste;
-use crate::recorder::RecordedAudio;
-use crate::settings::{SettingsStore, TranscriptionAlignment, TriggerMatch};
+use crate::prompt_presets;
+use crate::provider_error::ProviderFailure;
+use crate::punctuation;
+use crate::recorder::{LevelMeter, RecordedAudio, SYSTEM_TRACK};
+use crate::redaction;
+use crate::secure_input;
+use crate::settings::{
+    NetworkCondition, OfflineFallback, PowerCondition, ProviderRule, Settings, SettingsStore,
+    SpeechMetadata, StageLatency, TranscriptionAlignment, TranscriptionProvider, TriggerMatch,
+    UploadFormat,
+};
+use crate::status_board;
 use crate::status_native::{self, StatusType};
-use crate::transcription;
+use crate::system_state::{self, PowerSource, SystemState};
+use crate::transcription::{
+    self, TranscriptSegment, TranscriptionBackend, TranscriptionError, TranscriptionResult,
+};
+use crate::templates;
+use crate::text_backend;
 use crate::triggers;
+use crate::tts;
+use serde::Serialize;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
 
@@ -14,6 +42,46 @@ use std::time::{Duration, Instant};
 /// when hiding the status window after a delay.
 static STATUS_COUNTER: AtomicU64 = AtomicU64::new(0);
 
+/// 单段转写重试的退避基数，第 n 次重试等待 n 倍该时长
+const SEGMENT_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+/// 录音静音检测的电平采样间隔
+const LEVEL_POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// 峰值低于该值（约 -50 dBFS）视为没有声音输入
+const SILENT_PEAK_THRESHOLD: u16 = 100;
+/// 持续静音超过该时长后提示检查输入设备
+const SILENT_WARNING_AFTER: Duration = Duration::from_secs(4);
+/// 一次录音中削波累计达到该次数后提示调低输入增益
+const CLIP_WARNING_RUNS: u32 = 8;
+
+/// 分段音频重叠时，在上一段结尾与本段开头比较的最多词数
+const MAX_OVERLAP_WORDS: usize = 40;
+/// 本段开头可跳过的词数：重叠音频的起点可能切在词中间，识别出的第一个词常与上一段不一致
+const MAX_OVERLAP_LEAD_WORDS: usize = 2;
+
+/// 录音处理模式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProcessingMode {
+    /// 实时听写：转写后写入剪贴板并模拟粘贴，进度显示在该任务的状态浮窗条目上
+    Dictation(JobId),
+    /// 字面听写：与实时听写相同，但转写结果按本地语法转换为字面文本，跳过文本整理、模板与触发词
+    Literal(JobId),
+    /// 后台处理（如离线队列补转写）：仅生成结果，不触碰剪贴板与状态浮窗
+    Background,
+}
+
+impl ProcessingMode {
+    fn is_dictation(self) -> bool {
+        self.job().is_some()
+    }
+
+    pub fn job(self) -> Option<JobId> {
+        match self {
+            Self::Dictation(job) | Self::Literal(job) => Some(job),
+            Self::Background => None,
+        }
+    }
+}
+
 fn dev_log(_message: &str) {
     #[cfg(debug_assertions)]
     {
@@ -34,6 +102,19 @@ pub struct ProcessingOutcome {
     pub trigger_matches: Vec<TriggerMatch>,
     pub alignment: Option<TranscriptionAlignment>,
     pub error_message: Option<String>,
+    pub deferred_id: Option<String>,
+    pub confidence: Option<f32>,
+    pub metadata: SpeechMetadata,
+    pub provider: Option<TranscriptionProvider>,
+    pub model: String,
+    pub segment_count: usize,
+    pub stage_latency: StageLatency,
+    /// 保留音频待重试队列补转写的失败分段
+    pub failed_segments: Vec<FailedSegment>,
+    /// 转写失败、以占位符代替的分段数，包括未保留音频的分段
+    pub failed_segment_count: usize,
+    /// 提供商拒绝请求的归类，状态浮窗据此显示处理建议
+    pub failure: Option<ProviderFailure>,
 }
 
 impl ProcessingOutcome {
@@ -44,6 +125,10 @@ impl ProcessingOutcome {
     pub fn is_success(&self) -> bool {
         self.error_message.is_none()
     }
+
+    pub fn is_deferred(&self) -> bool {
+        self.deferred_id.is_some()
+    }
 }
 
 #[derive(Default)]
@@ -58,6 +143,16 @@ struct ProcessingOutcomeBuilder {
     triggered_by_keyword: bool,
     trigger_matches: Vec<TriggerMatch>,
     alignment: Option<TranscriptionAlignment>,
+    deferred_id: Option<String>,
+    confidence: Option<f32>,
+    metadata: SpeechMetadata,
+    provider: Option<TranscriptionProvider>,
+    model: String,
+    segment_count: usize,
+    stage_latency: StageLatency,
+    failed_segments: Vec<FailedSegment>,
+    failed_segment_count: usize,
+    failure: Option<ProviderFailure>,
 }
 
 impl ProcessingOutcomeBuilder {
@@ -71,6 +166,17 @@ impl ProcessingOutcomeBuilder {
     fn triggered_by_keyword(mut self, v: bool) -> Self { self.triggered_by_keyword = v; self }
     fn trigger_matches(mut self, v: Vec<TriggerMatch>) -> Self { self.trigger_matches = v; self }
     fn alignment(mut self, v: Option<TranscriptionAlignment>) -> Self { self.alignment = v; self }
+    fn deferred_id(mut self, v: String) -> Self { self.deferred_id = Some(v); self }
+    fn confidence(mut self, v: Option<f32>) -> Self { self.confidence = v; self }
+    fn metadata(mut self, v: SpeechMetadata) -> Self { self.metadata = v; self }
+    fn provider(mut self, v: TranscriptionProvider) -> Self { self.provider = Some(v); self }
+    fn model(mut self, v: String) -> Self { self.model = v; self }
+    fn segment_count(mut self, v: usize) -> Self { self.segment_count = v; self }
+    fn stage_latency(mut self, v: StageLatency) -> Self { self.stage_latency = v; self }
+    fn output_elapsed_ms(mut self, v: u64) -> Self { self.stage_latency.output_ms = v; self }
+    fn failed_segments(mut self, v: Vec<FailedSegment>) -> Self { self.failed_segments = v; self }
+    fn failed_segment_count(mut self, v: usize) -> Self { self.failed_segment_count = v; self }
+    fn failure(mut self, v: Option<ProviderFailure>) -> Self { self.failure = v; self }
 
     fn build(self) -> ProcessingOutcome {
         ProcessingOutcome {
@@ -85,6 +191,16 @@ impl ProcessingOutcomeBuilder {
             trigger_matches: self.trigger_matches,
             alignment: self.alignment,
             error_message: None,
+            deferred_id: self.deferred_id,
+            confidence: self.confidence,
+            metadata: self.metadata,
+            provider: self.provider,
+            model: self.model,
+            segment_count: self.segment_count,
+            stage_latency: self.stage_latency,
+            failed_segments: self.failed_segments,
+            failed_segment_count: self.failed_segment_count,
+            failure: None,
         }
     }
 
@@ -101,11 +217,38 @@ impl ProcessingOutcomeBuilder {
             trigger_matches: self.trigger_matches,
             alignment: self.alignment,
             error_message: Some(msg.into()),
+            deferred_id: self.deferred_id,
+            confidence: self.confidence,
+            metadata: self.metadata,
+            provider: self.provider,
+            model: self.model,
+            segment_count: self.segment_count,
+            stage_latency: self.stage_latency,
+            failed_segments: self.failed_segments,
+            failed_segment_count: self.failed_segment_count,
+            failure: self.failure,
         }
     }
 }
 
-pub fn handle_recording(store: &SettingsStore, recording: RecordedAudio) -> ProcessingOutcome {
+/// `prompt_preset` 为录音指定的提示词预设，为 None 时使用设置中启用的预设
+pub fn handle_recording(
+    store: &SettingsStore,
+    recording: RecordedAudio,
+    mode: ProcessingMode,
+    prompt_preset: Option<&str>,
+) -> ProcessingOutcome {
+    handle_recording_with_provider(store, recording, mode, prompt_preset, None)
+}
+
+/// 与 [`handle_recording`] 相同；`provider` 指定时直接使用该提供商，不再应用按时长路由与切换规则
+pub fn handle_recording_with_provider(
+    store: &SettingsStore,
+    recording: RecordedAudio,
+    mode: ProcessingMode,
+    prompt_preset: Option<&str>,
+    provider: Option<TranscriptionProvider>,
+) -> ProcessingOutcome {
     let settings = match store.load() {
         Ok(value) => value,
         Err(err) => {
@@ -113,10 +256,33 @@ pub fn handle_recording(store: &SettingsStore, recording: RecordedAudio) -> Proc
                 .build_error(format!("设置读取失败: {err}"));
         }
     };
+    let settings = match provider {
+        Some(provider) => Settings {
+            provider,
+            ..settings
+        },
+        None => apply_provider_rules(apply_duration_routing(settings, &recording)),
+    };
+    let settings = corrections::with_learned_hotwords(settings);
+    let settings = prompt_presets::apply(settings, prompt_preset);
+    let (settings, status) = match apply_offline_fallback(settings, mode) {
+        OfflineDecision::Proceed(value) => (value, "transcribing"),
+        OfflineDecision::Local(value) => (value, "offline-local"),
+        OfflineDecision::Defer(settings) => {
+            return defer_recording(store, &settings, &recording, mode);
+        }
+        OfflineDecision::Fail(settings, message) => {
+            return early_outcome(&settings, &recording).build_error(message);
+        }
+    };
+    // 确定实际使用的提供商后再更新状态，避免通用的转写提示覆盖离线回退提示
+    emit_mode_status(mode, status);
     let history_enabled = settings.history.enabled;
     let remove_newlines = settings.output.remove_newlines;
-    let engine = transcription::create_engine(&settings);
+    let engine = transcription::create_backend(&settings);
     let model_group = engine.model_group();
+    let model = engine.model_name();
+    let provider = settings.provider;
     let recording_duration_ms = calculate_recording_duration_ms(&recording);
 
     // Common builder with shared context
@@ -124,78 +290,57 @@ pub fn handle_recording(store: &SettingsStore, recording: RecordedAudio) -> Proc
         ProcessingOutcome::builder()
             .history_enabled(history_enabled)
             .model_group(model_group.clone())
+            .model(model.clone())
+            .provider(provider)
             .recording_duration_ms(recording_duration_ms)
     };
 
-    if recording.samples.is_empty() {
+    if recording.is_empty() {
         dev_log("录音为空，跳过转写");
-        emit_status("completed");
+        emit_mode_status(mode, "completed");
         return base().build();
     }
     let transcription_started = Instant::now();
-    let segment_seconds = settings.recording.segment_seconds.max(1);
-    dev_log(&format!(
-        "开始转写，采样 {}，分段秒数 {}",
-        recording.samples.len(),
-        segment_seconds
-    ));
-    let paths = match audio_processing::write_segments(&recording, segment_seconds) {
+    // 仅实时听写保留失败分段：后台补转写失败时整段录音仍留在离线队列中
+    let retain_failed = mode.is_dictation() && settings.recording.segment_retry_queue;
+    let transcribed = if recording.tracks.is_empty() {
+        transcribe_segments(&settings, engine.as_ref(), &recording, retain_failed)
+    } else {
+        transcribe_tracks(&settings, engine.as_ref(), &recording)
+    };
+    let transcript = match transcribed {
         Ok(value) => value,
         Err(err) => {
             return base()
                 .transcription_elapsed_ms(elapsed_since_ms(transcription_started))
-                .build_error(format!("录音分段失败: {err}"));
+                .failure(err.failure)
+                .build_error(err.message);
         }
     };
-    dev_log(&format!("生成 {} 段录音", paths.len()));
 
-    let mut transcripts = Vec::new();
-    let mut alignment_tokens = Vec::new();
-    let mut alignment_timestamps_ms = Vec::new();
-    let mut alignment_durations_ms = Vec::new();
-    for (index, path) in paths.iter().enumerate() {
-        dev_log(&format!("开始请求转写段落 {}", index + 1));
-        let transcription = match engine.transcribe(path) {
-            Ok(value) => value,
-            Err(err) => {
-                cleanup_files(&paths);
-                let partial = normalize_text_for_output(&transcripts.join(" "), remove_newlines);
-                return base()
-                    .transcription_text(partial.clone())
-                    .final_text(partial)
-                    .transcription_elapsed_ms(elapsed_since_ms(transcription_started))
-                    .build_error(err.to_string());
-            }
-        };
-        let text = transcription.text;
-        if let Some(alignment) = transcription.alignment {
-            let segment_offset_ms = index as u64 * segment_seconds * 1000;
-            alignment_tokens.extend(alignment.tokens);
-            alignment_timestamps_ms.extend(
-                alignment
-                    .timestamps_ms
-                    .into_iter()
-                    .map(|timestamp_ms| timestamp_ms + segment_offset_ms),
-            );
-            alignment_durations_ms.extend(alignment.durations_ms);
-        }
-        dev_log(&format!("转写结果 {}: {}", index + 1, text));
-        transcripts.push(text);
-    }
-
-    cleanup_files(&paths);
-
-    let combined = normalize_text_for_output(&transcripts.join(" "), remove_newlines);
-    let alignment = if alignment_tokens.is_empty() {
+    let combined = normalize_text_for_output(&transcript.text(), remove_newlines);
+    let literal = matches!(mode, ProcessingMode::Literal(_));
+    let normalized = if literal {
+        literal_mode::convert(&combined)
+    } else {
+        let normalized = itn::normalize(&combined, &settings.output.itn);
+        let normalized = corrections::apply(&normalized, &settings.corrections);
+        punctuation::format(&normalized, &settings.output.punctuation)
+    };
+    let combined = redaction::redact(&normalized, &settings.redaction);
+    // 脱敏改动了文本时丢弃逐字对齐信息，避免原文经由对齐 token 写入历史
+    let alignment = if combined != normalized {
         None
     } else {
-        Some(TranscriptionAlignment {
-            tokens: alignment_tokens,
-            timestamps_ms: alignment_timestamps_ms,
-            durations_ms: alignment_durations_ms,
-        })
+        transcript.alignment
     };
+    let confidence = transcript.confidence;
+    let metadata = transcript.metadata;
+    let segment_count = transcript.segment_count;
+    let failed_segments = transcript.failed_segments;
+    let failed_segment_count = transcript.failed_count;
     let transcription_elapsed_ms = elapsed_since_ms(transcription_started);
+    let text_processing_started = Instant::now();
     dev_log(&format!("合并转写结果: {}", combined));
 
     // After transcription, build the post-transcription base
@@ -204,16 +349,53 @@ pub fn handle_recording(store: &SettingsStore, recording: RecordedAudio) -> Proc
             .transcription_text(combined.clone())
             .transcription_elapsed_ms(transcription_elapsed_ms)
             .alignment(alignment.clone())
+            .confidence(confidence)
+            .metadata(metadata.clone())
+            .segment_count(segment_count)
+            .failed_segments(failed_segments.clone())
+            .failed_segment_count(failed_segment_count)
+            .stage_latency(StageLatency {
+                transcription_ms: transcription_elapsed_ms,
+                ..StageLatency::default()
+            })
     };
 
     let logger = |message: &str| dev_log(message);
-    let result = match triggers::apply_triggers(&settings, &combined, &logger) {
-        Ok(value) => value,
-        Err(err) => {
-            return post()
-                .final_text(combined.clone())
-                .build_error(format!("触发词处理失败: {err}"));
+    // 启用听写模板时以模板填充代替触发词处理；字面听写的结果原样输出
+    let result = match templates::active_template(&settings) {
+        _ if literal => triggers::TriggerResult {
+            output: combined.clone(),
+            triggered: false,
+            triggered_by_keyword: false,
+            trigger_matches: Vec::new(),
+        },
+        Some(template) => {
+            dev_log(&format!("应用听写模板: {}", template.title));
+            let filled = text_backend::resolve(&settings.text_processing, "").and_then(|backend| {
+                templates::fill_template(backend.as_ref(), template, &combined)
+            });
+            match filled {
+                Ok(output) => triggers::TriggerResult {
+                    output,
+                    triggered: false,
+                    triggered_by_keyword: false,
+                    trigger_matches: Vec::new(),
+                },
+                Err(err) => {
+                    return post()
+                        .final_text(combined.clone())
+                        .build_error(format!("模板填充失败: {err}"));
+                }
+            }
         }
+        None => match triggers::apply_triggers(&settings, &combined, &logger) {
+            Ok(value) => value,
+            Err(err) => {
+                return post()
+                    .final_text(combined.clone())
+                    .build_error(format!("触发词处理失败: {err}"));
+            }
+        },
     };
     dev_log(&format!(
         "触发词处理: {}",
@@ -224,16 +406,66 @@ pub fn handle_recording(store: &SettingsStore, recording: RecordedAudio) -> Proc
         }
     ));
     dev_log(&format!("触发词输出: {}", result.output));
-    let final_output = normalize_text_for_output(&result.output, remove_newlines);
+    let final_output = if literal {
+        result.output.clone()
+    } else {
+        normalize_text_for_output(&result.output, remove_newlines)
+    };
+    // 触发词与模板的输出未经过标点处理，按同一设置补上中英文间空格
+    let final_output = if literal {
+        final_output
+    } else {
+        punctuation::apply_cjk_latin_spacing(
+            &final_output,
+            settings.output.punctuation.cjk_latin_spacing,
+        )
+    };
+    let final_output = redaction::redact(&final_output, &settings.redaction);
+    let text_processing_ms = elapsed_since_ms(text_processing_started);
 
     let post_trigger = || {
         post()
+            .stage_latency(StageLatency {
+                transcription_ms: transcription_elapsed_ms,
+                text_processing_ms,
+                output_ms: 0,
+            })
             .final_text(final_output.clone())
             .triggered(result.triggered)
             .triggered_by_keyword(result.triggered_by_keyword)
             .trigger_matches(result.trigger_matches.clone())
     };
 
+    // 后台补转写的是较早的录音，不计入连续听写上下文
+    if mode == ProcessingMode::Background {
+        return post_trigger().build();
+    }
+    dictation_context::remember(&combined, &settings.context_carryover);
+
+    let low_confidence = is_low_confidence(confidence, settings.output.confidence_threshold);
+    let output_started = Instant::now();
+    if low_confidence && settings.output.confirm_low_confidence {
+        dev_log("置信度低于阈值，仅写入剪贴板等待用户确认");
+        if let Err(err) = paste::write_text(&final_output) {
+            return post_trigger()
+                .build_error(format!("写入剪贴板失败: {err}"));
+        }
+        paste::remember_result(&final_output, settings.output.paste_history_size);
+        emit_mode_status(mode, "low-confidence-confirm");
+        return post_trigger()
+            .output_elapsed_ms(elapsed_since_ms(output_started))
+            .build();
+    }
+
+    if settings.output.confirm_before_paste {
+        dev_log("显示确认窗口，等待用户编辑后粘贴");
+        if let Err(err) = confirm_window::show(&final_output, settings.output.paste_history_size) {
+            return post_trigger().build_error(format!("显示确认窗口失败: {err}"));
+        }
+        emit_mode_status(mode, "awaiting-confirm");
+        return post_trigger().build();
+    }
+
     if result.triggered {
         dev_log("复制原文到剪贴板");
         if let Err(err) = paste::write_text(&combined) {
@@ -241,34 +473,682 @@ pub fn handle_recording(store: &SettingsStore, recording: RecordedAudio) -> Proc
                 .build_error(format!("写入剪贴板失败: {err}"));
         }
     }
-    dev_log("写入并粘贴处理后的文本");
-    if let Err(err) = paste::write_and_paste(&final_output) {
-        return post_trigger()
-            .build_error(format!("写入剪贴板失败: {err}"));
+    let secure_field = settings.output.paste_after_transcribe
+        && settings.output.guard_secure_fields
+        && secure_input::is_focused();
+    if !settings.output.paste_after_transcribe || secure_field {
+        dev_log(if secure_field {
+            "焦点在密码输入框，不自动粘贴，仅写入剪贴板"
+        } else {
+            "已关闭自动粘贴，仅写入剪贴板"
+        });
+        if let Err(err) = paste::write_text(&final_output) {
+            return post_trigger().build_error(format!("写入剪贴板失败: {err}"));
+        }
+    } else {
+        dev_log("写入并粘贴处理后的文本");
+        match paste::auto_paste(&final_output, settings.output.max_pastes_per_second) {
+            Ok(paste::AutoPaste::Pasted) => {}
+            Ok(paste::AutoPaste::Coalesced) => dev_log("粘贴过于频繁，已与后续输出合并"),
+            Err(err) => {
+                return post_trigger().build_error(format!("写入剪贴板失败: {err}"));
+            }
+        }
     }
-    emit_status("completed");
-    post_trigger().build()
+    paste::remember_result(&final_output, settings.output.paste_history_size);
+    let output_elapsed_ms = elapsed_since_ms(output_started);
+    tts::speak_in_background(&settings.tts, &settings.openai, &final_output);
+    emit_mode_status(
+        mode,
+        if secure_field {
+            "secure-field"
+        } else if low_confidence {
+            "low-confidence"
+        } else {
+            "completed"
+        },
+    );
+    post_trigger().output_elapsed_ms(output_elapsed_ms).build()
 }
 
-/// Show status overlay with native window.
-/// For "completed" and "error" status, auto-hide after 2 seconds.
-pub fn emit_status(status: &str) {
-    let (status_type, text) = match status {
+/// 设置页“测试听写”的结果：文本与各阶段耗时，不写入剪贴板
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DictationTestResult {
+    pub transcription_text: String,
+    pub final_text: String,
+    pub model_group: String,
+    pub recording_ms: u64,
+    pub transcription_ms: u64,
+    /// 转写之后的 ITN、纠错、标点与脱敏处理耗时
+    pub post_processing_ms: u64,
+    pub error_message: Option<String>,
+}
+
+/// 用当前提供商转写录音，之后只做 ITN、纠错、标点与脱敏这些确定性处理；
+/// 不应用按时长路由、切换规则与提示词预设，不执行触发词与模板，不粘贴、不更新状态浮窗
+pub fn test_dictation(store: &SettingsStore, recording: RecordedAudio) -> DictationTestResult {
+    let recording_ms = calculate_recording_duration_ms(&recording);
+    let mut result = DictationTestResult {
+        transcription_text: String::new(),
+        final_text: String::new(),
+        model_group: String::new(),
+        recording_ms,
+        transcription_ms: 0,
+        post_processing_ms: 0,
+        error_message: None,
+    };
+    let settings = match store.load() {
+        Ok(value) => corrections::with_learned_hotwords(value),
+        Err(err) => {
+            result.error_message = Some(format!("设置读取失败: {err}"));
+            return result;
+        }
+    };
+    let engine = transcription::create_backend(&settings);
+    result.model_group = engine.model_group();
+    if recording.is_empty() {
+        return result;
+    }
+    let transcription_started = Instant::now();
+    let transcribed = if recording.tracks.is_empty() {
+        transcribe_segments(&settings, engine.as_ref(), &recording, false)
+    } else {
+        transcribe_tracks(&settings, engine.as_ref(), &recording)
+    };
+    result.transcription_ms = elapsed_since_ms(transcription_started);
+    let transcript = match transcribed {
+        Ok(value) => value,
+        Err(err) => {
+            result.error_message = Some(err.message);
+            return result;
+        }
+    };
+    let post_processing_started = Instant::now();
+    let text = transcript.text();
+    // 原始转写同样脱敏后再显示，与写入历史的转写文本一致
+    result.transcription_text = redaction::redact(
+        &normalize_text_for_output(&text, settings.output.remove_newlines),
+        &settings.redaction,
+    );
+    result.final_text = clean_transcript(&text, &settings);
+    result.post_processing_ms = elapsed_since_ms(post_processing_started);
+    result
+}
+
+pub struct SegmentedTranscript {
+    /// 每个录音分段的转写文本
+    pub segments: Vec<TranscriptSegment>,
+    /// 录音切分出的分段数，包括转写失败的分段
+    pub segment_count: usize,
+    /// 字幕粒度的片段：提供商返回句级时间戳时按其拆分，否则与录音分段一致
+    pub cues: Vec<TranscriptSegment>,
+    pub metadata: SpeechMetadata,
+    pub alignment: Option<TranscriptionAlignment>,
+    pub confidence: Option<f32>,
+    /// 转写失败且保留了音频文件的分段，由调用方负责移交重试队列或删除
+    pub failed_segments: Vec<FailedSegment>,
+    /// 转写失败的分段数，包括未保留音频的分段
+    pub failed_count: usize,
+    /// 多音轨合并的结果：每个分段是一位说话方的连续发言，拼接时各占一行
+    pub speaker_turns: bool,
+}
+
+/// 转写失败的录音分段及其保留的 WAV 文件
+#[derive(Clone, Debug)]
+pub struct FailedSegment {
+    pub index: usize,
+    pub path: PathBuf,
+}
+
+impl SegmentedTranscript {
+    pub fn text(&self) -> String {
+        if self.speaker_turns {
+            return self
+                .segments
+                .iter()
+                .map(|segment| segment.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
+        }
+        join_segment_texts(self.segments.iter().map(|segment| segment.text.as_str()))
+    }
+}
+
+/// 拼接各分段文本：中日韩文字两侧不加空格，拉丁文字之间补一个空格。
+/// 分段音频重叠时重复转写的词已在转写各分段时由 `strip_overlap_words` 去掉，这里不再去重
+fn join_segment_texts<'a>(parts: impl IntoIterator<Item = &'a str>) -> String {
+    let mut joined = String::new();
+    for part in parts {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+        if needs_separator(&joined, part) {
+            joined.push(' ');
+        }
+        joined.push_str(part);
+    }
+    joined
+}
+
+/// 分段音频重叠时去掉本段开头重复转写的词：在本段开头（允许跳过切断的前几个词）寻找与
+/// 上一段结尾相同的最长词序列，忽略大小写与标点，至少两个词才视为重复
+fn strip_overlap_words<'a>(prev: &str, next: &'a str) -> &'a str {
+    let prev_words = overlap_words(prev);
+    let tail = &prev_words[prev_words.len().saturating_sub(MAX_OVERLAP_WORDS)..];
+    let head = overlap_words(next);
+    let mut best: Option<(usize, usize)> = None;
+    for skip in 0..=MAX_OVERLAP_LEAD_WORDS.min(head.len()) {
+        let max_len = tail.len().min(head.len() - skip).min(MAX_OVERLAP_WORDS);
+        let matched = (2..=max_len).rev().find(|&len| {
+            tail[tail.len() - len..]
+                .iter()
+                .zip(&head[skip..skip + len])
+                .all(|(left, right)| left.1 == right.1)
+        });
+        if let Some(len) = matched {
+            if best.is_none_or(|(best_len, _)| len > best_len) {
+                best = Some((len, skip + len));
+            }
+        }
+    }
+    match best {
+        Some((_, end)) => {
+            next[head[end - 1].0..].trim_start_matches(|ch: char| !ch.is_alphanumeric())
+        }
+        None => next,
+    }
+}
+
+/// 按词切分：拉丁文字以连续字母数字为一个词，中日韩文字每字为一个词，标点与空白不计；
+/// 返回每个词的结束位置与小写形式
+fn overlap_words(text: &str) -> Vec<(usize, String)> {
+    let mut words: Vec<(usize, String)> = Vec::new();
+    let mut in_word = false;
+    for (index, ch) in text.char_indices() {
+        let end = index + ch.len_utf8();
+        if is_word_char(ch) {
+            match words.last_mut() {
+                Some((word_end, word)) if in_word => {
+                    *word_end = end;
+                    word.extend(ch.to_lowercase());
+                }
+                _ => words.push((end, ch.to_lowercase().collect())),
+            }
+            in_word = true;
+        } else {
+            if ch.is_alphanumeric() {
+                words.push((end, ch.to_string()));
+            }
+            in_word = false;
+        }
+    }
+    words
+}
+
+pub(crate) fn needs_separator(prev: &str, next: &str) -> bool {
+    let (Some(last), Some(first)) = (prev.chars().next_back(), next.chars().next()) else {
+        return false;
+    };
+    !(is_wide_char(last)
+        || is_wide_char(first)
+        || matches!(first, ',' | '.' | '!' | '?' | ';' | ':' | ')' | ']' | '%'))
+}
+
+/// 中日文字与全角标点（U+2E80 起），书写时不以空格分词；
+/// 韩文按词以空格分隔，半角形式按半角字符处理，均不在此列
+pub(crate) fn is_wide_char(ch: char) -> bool {
+    matches!(u32::from(ch), 0x2E80..=0xFFEF)
+        && !matches!(u32::from(ch), 0x3130..=0x318F | 0xAC00..=0xD7AF | 0xFF61..=0xFFEF)
+}
+
+pub(crate) fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() && !is_wide_char(ch)
+}
+
+/// 分段转写失败的原因；提供商拒绝请求时附带归类
+#[derive(Debug)]
+pub struct SegmentsError {
+    pub message: String,
+    pub failure: Option<ProviderFailure>,
+}
+
+impl From<String> for SegmentsError {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            failure: None,
+        }
+    }
+}
+
+impl From<SegmentsError> for String {
+    fn from(err: SegmentsError) -> Self {
+        err.message
+    }
+}
+
+/// 按设置将录音分段写入临时目录并逐段转写（含重试与失败占位），供听写与批量转写共用
+pub fn transcribe_segments(
+    settings: &Settings,
+    engine: &dyn TranscriptionBackend,
+    recording: &RecordedAudio,
+    retain_failed: bool,
+) -> Result<SegmentedTranscript, SegmentsError> {
+    let PipelineOutput {
+        audio: recording,
+        trimmed_start_ms,
+        tempo,
+    } = audio_pipeline::apply(recording, &settings.audio_pipeline);
+    // 时间戳按原始录音计算：按变速比还原时长，并加回预处理裁掉的开头
+    let to_source_ms = |ms: u64| trimmed_start_ms + (ms as f64 * tempo).round() as u64;
+    let scratch_dir = audio_processing::resolve_scratch_dir(&settings.recording.scratch_dir);
+    let format = audio_processing::SegmentFormat::from_settings(&settings.recording);
+    let filters = audio_processing::FilterChain::from_settings(&settings.recording);
+    let upload_format = if engine.capabilities().compressed_upload {
+        settings.recording.upload_format
+    } else {
+        UploadFormat::Wav
+    };
+    // 分段超过提供商的时长或文件大小上限时自动缩短，避免整段被拒绝
+    let options = engine.options();
+    let max_segment_seconds = audio_processing::max_segment_seconds(
+        &recording,
+        format,
+        upload_format,
+        settings.recording.segment_overlap_ms,
+        options.max_audio_seconds,
+        options.max_upload_bytes,
+    );
+    let segment_seconds = settings.recording.segment_seconds.max(1);
+    let segment_seconds = match max_segment_seconds {
+        Some(limit) if limit < segment_seconds => {
+            dev_log(&format!(
+                "分段 {segment_seconds} 秒超过提供商上限，缩短为 {limit} 秒"
+            ));
+            limit
+        }
+        _ => segment_seconds,
+    };
+    dev_log(&format!(
+        "开始转写，采样 {}，分段秒数 {}",
+        recording.sample_count(),
+        segment_seconds
+    ));
+    let segment_files = audio_processing::write_segments(
+        &recording,
+        segment_seconds,
+        settings.recording.segment_overlap_ms,
+        format,
+        &filters,
+        upload_format,
+        &scratch_dir,
+    )
+    .map_err(|err| format!("录音分段失败: {err}"))?;
+    dev_log(&format!("生成 {} 段录音", segment_files.len()));
+    let paths = segment_files
+        .iter()
+        .map(|segment| segment.path.clone())
+        .collect::<Vec<_>>();
+
+    let mut segments = Vec::new();
+    let mut cues = Vec::new();
+    let mut metadata = SpeechMetadata::default();
+    let mut alignment_tokens = Vec::new();
+    let mut alignment_timestamps_ms = Vec::new();
+    let mut alignment_durations_ms = Vec::new();
+    let retry_attempts = settings.recording.segment_retry_attempts;
+    let placeholder = settings.recording.failed_segment_placeholder.trim().to_string();
+    let mut segment_confidences = Vec::new();
+    let mut failed_segments = 0usize;
+    let mut skipped_silent = 0usize;
+    let mut retained = Vec::new();
+    let mut last_error = None;
+    // 上一段成功转写的文本，用于去掉重叠音频中重复转写的词
+    let mut previous_text: Option<String> = None;
+    for (index, segment_file) in segment_files.iter().enumerate() {
+        dev_log(&format!("开始请求转写段落 {}", index + 1));
+        let path = &segment_file.path;
+        let start_ms = to_source_ms(segment_file.start_ms);
+        let end_ms = to_source_ms(segment_file.end_ms);
+        // 写入的音频从分段起点之前开始，提供商返回的时间相对于音频开头
+        let audio_start_ms = segment_file.start_ms - segment_file.overlap_ms;
+        let previous = previous_text.take();
+        if segment_file.silent {
+            // 整段静音不上传，避免提供商对静音臆造文字
+            dev_log(&format!("段落 {} 全部为静音，跳过转写", index + 1));
+            events::emit_recording_segment(&segment_file.timeline, SegmentStatus::Completed);
+            skipped_silent += 1;
+            continue;
+        }
+        events::emit_recording_segment(&segment_file.timeline, SegmentStatus::Transcribing);
+        let transcription = match transcribe_segment_with_retry(engine, path, retry_attempts) {
+            Ok(value) => value,
+            Err(err) => {
+                // 单段持续失败时以占位符代替，避免整段听写被截断
+                dev_log(&format!("段落 {} 转写失败: {err}", index + 1));
+                events::emit_recording_segment(&segment_file.timeline, SegmentStatus::Failed);
+                failed_segments += 1;
+                last_error = Some(err);
+                if retain_failed {
+                    retained.push(FailedSegment {
+                        index,
+                        path: path.clone(),
+                    });
+                }
+                if !placeholder.is_empty() {
+                    let segment = TranscriptSegment {
+                        start_ms,
+                        end_ms,
+                        text: placeholder.clone(),
+                    };
+                    cues.push(segment.clone());
+                    segments.push(segment);
+                }
+                continue;
+            }
+        };
+        let text = match previous {
+            Some(previous) if segment_file.overlap_ms > 0 => {
+                strip_overlap_words(&previous, &transcription.text).to_string()
+            }
+            _ => transcription.text,
+        };
+        metadata.merge(transcription.metadata);
+        if transcription.segments.is_empty() {
+            cues.push(TranscriptSegment {
+                start_ms,
+                end_ms,
+                text: text.clone(),
+            });
+        } else {
+            // 完全落在重叠部分的句子已由上一段给出
+            cues.extend(
+                transcription
+                    .segments
+                    .into_iter()
+                    .map(|cue| TranscriptSegment {
+                        start_ms: to_source_ms(
+                            audio_start_ms + segment_file.source_offset_ms(cue.start_ms),
+                        )
+                        .max(start_ms),
+                        end_ms: to_source_ms(
+                            audio_start_ms + segment_file.source_offset_ms(cue.end_ms),
+                        )
+                        .min(end_ms),
+                        text: cue.text,
+                    })
+                    .filter(|cue| segment_file.overlap_ms == 0 || cue.end_ms > start_ms),
+            );
+        }
+        if let Some(confidence) = transcription.confidence {
+            segment_confidences.push(confidence);
+        }
+        if let Some(alignment) = transcription.alignment {
+            let timestamps_ms = alignment
+                .timestamps_ms
+                .into_iter()
+                .map(|timestamp_ms| {
+                    to_source_ms(audio_start_ms + segment_file.source_offset_ms(timestamp_ms))
+                })
+                .collect::<Vec<_>>();
+            // 重叠部分的逐字时间戳与上一段重复
+            let overlapped = timestamps_ms
+                .iter()
+                .take_while(|timestamp_ms| **timestamp_ms < start_ms)
+                .count();
+            alignment_tokens.extend(alignment.tokens.into_iter().skip(overlapped));
+            alignment_timestamps_ms.extend(timestamps_ms.into_iter().skip(overlapped));
+            alignment_durations_ms.extend(
+                alignment
+                    .durations_ms
+                    .into_iter()
+                    .skip(overlapped)
+                    .map(|duration_ms| (duration_ms as f64 * tempo).round() as u64),
+            );
+        }
+        dev_log(&format!("转写结果 {}: {}", index + 1, text));
+        events::emit_recording_segment(&segment_file.timeline, SegmentStatus::Completed);
+        previous_text = Some(text.clone());
+        segments.push(TranscriptSegment {
+            start_ms,
+            end_ms,
+            text,
+        });
+    }
+
+    let retained_paths = retained
+        .iter()
+        .map(|segment| segment.path.clone())
+        .collect::<Vec<_>>();
+    let transcribed_paths = paths
+        .iter()
+        .filter(|path| !retained_paths.contains(path))
+        .cloned()
+        .collect::<Vec<_>>();
+    cleanup_files(&transcribed_paths);
+
+    // 跳过的静音分段不算成功：送去转写的分段全部失败时结果只剩占位符
+    let sent_segments = paths.len() - skipped_silent;
+    if sent_segments > 0 && failed_segments == sent_segments {
+        if let Some(err) = last_error {
+            // 全部分段失败时没有可回填的历史文本，不保留音频
+            cleanup_files(&retained_paths);
+            return Err(SegmentsError {
+                message: err.to_string(),
+                failure: err.failure(),
+            });
+        }
+    }
+
+    let alignment = if alignment_tokens.is_empty() {
+        None
+    } else {
+        Some(TranscriptionAlignment {
+            tokens: alignment_tokens,
+            timestamps_ms: alignment_timestamps_ms,
+            durations_ms: alignment_durations_ms,
+        })
+    };
+    Ok(SegmentedTranscript {
+        segments,
+        segment_count: paths.len(),
+        cues,
+        metadata,
+        alignment,
+        confidence: average_confidence(&segment_confidences),
+        failed_segments: retained,
+        failed_count: failed_segments,
+        speaker_turns: false,
+    })
+}
+
+/// 多音轨录音（如麦克风与系统音频）：各音轨分别写入分段并转写，再按时间合并并标注说话方。
+/// 失败分段的重试按分段序号回填文本，不适用于合并后的结果，因此不保留失败分段
+fn transcribe_tracks(
+    settings: &Settings,
+    engine: &dyn TranscriptionBackend,
+    recording: &RecordedAudio,
+) -> Result<SegmentedTranscript, SegmentsError> {
+    let mut labeled = vec![(
+        settings.recording.mic_track_label.trim().to_string(),
+        transcribe_segments(settings, engine, recording, false)?,
+    )];
+    for track in &recording.tracks {
+        dev_log(&format!("开始转写音轨 {}", track.name));
+        let label = match track.name.as_str() {
+            SYSTEM_TRACK => settings.recording.system_track_label.trim().to_string(),
+            name => name.to_string(),
+        };
+        labeled.push((
+            label,
+            transcribe_segments(settings, engine, &track.audio, false)?,
+        ));
+    }
+    Ok(merge_track_transcripts(labeled))
+}
+
+/// 按开始时间交错合并各音轨的字幕片段，同一说话方连续的片段合并为一段发言
+fn merge_track_transcripts(labeled: Vec<(String, SegmentedTranscript)>) -> SegmentedTranscript {
+    let mut metadata = SpeechMetadata::default();
+    let mut confidences = Vec::new();
+    let mut segment_count = 0;
+    let mut failed_count = 0;
+    let mut cues = Vec::new();
+    for (track, (_, transcript)) in labeled.iter().enumerate() {
+        metadata.merge(transcript.metadata.clone());
+        confidences.extend(transcript.confidence);
+        segment_count += transcript.segment_count;
+        failed_count += transcript.failed_count;
+        cues.extend(
+            transcript
+                .cues
+                .iter()
+                .filter(|cue| !cue.text.trim().is_empty())
+                .map(|cue| (track, cue.clone())),
+        );
+    }
+    cues.sort_by_key(|(_, cue)| cue.start_ms);
+
+    let mut turns: Vec<(usize, TranscriptSegment)> = Vec::new();
+    for (track, cue) in &cues {
+        match turns.last_mut() {
+            Some((last_track, turn)) if *last_track == *track => {
+                turn.end_ms = turn.end_ms.max(cue.end_ms);
+                turn.text = join_segment_texts([turn.text.as_str(), cue.text.as_str()]);
+            }
+            _ => turns.push((*track, cue.clone())),
+        }
+    }
+    let tag = |(track, segment): &(usize, TranscriptSegment)| TranscriptSegment {
+        text: format!("{}：{}", labeled[*track].0, segment.text.trim()),
+        ..segment.clone()
+    };
+    SegmentedTranscript {
+        segments: turns.iter().map(tag).collect(),
+        segment_count,
+        cues: cues.iter().map(tag).collect(),
+        metadata,
+        // 逐字时间戳来自不同音轨，合并后不再对应输出文本
+        alignment: None,
+        confidence: average_confidence(&confidences),
+        failed_segments: Vec::new(),
+        failed_count,
+        speaker_turns: true,
+    }
+}
+
+/// 重新转写重试队列中的单个分段，按听写流程做换行、ITN、纠错、标点与脱敏处理
+pub fn transcribe_retained_segment(settings: &Settings, path: &Path) -> Result<String, String> {
+    let settings = corrections::with_learned_hotwords(settings.clone());
+    let engine = transcription::create_backend(&settings);
+    let text = engine.transcribe(path).map_err(|err| err.to_string())?.text;
+    Ok(clean_transcript(&text, &settings))
+}
+
+/// 对转写文本做换行、ITN、纠错、标点与脱敏处理，不调用文本模型
+fn clean_transcript(text: &str, settings: &Settings) -> String {
+    let text = normalize_text_for_output(text, settings.output.remove_newlines);
+    let text = itn::normalize(&text, &settings.output.itn);
+    let text = corrections::apply(&text, &settings.corrections);
+    let text = punctuation::format(&text, &settings.output.punctuation);
+    redaction::redact(&text, &settings.redaction)
+}
+
+/// 状态标识对应的浮窗类型与文案
+fn status_display(status: &str) -> Option<(StatusType, &'static str)> {
+    let display = match status {
         "recording" => (StatusType::Recording, "正在录音"),
         "transcribing" => (StatusType::Transcribing, "正在转写"),
         "completed" => (StatusType::Completed, "已完成"),
         "error" => (StatusType::Error, "已中断"),
-        _ => return,
+        "paused" => (StatusType::Paused, "录音已暂停"),
+        "cancelled" => (StatusType::Completed, "已取消，录音未转写"),
+        "offline-local" => (StatusType::Transcribing, "离线，使用本地模型转写"),
+        "deferred" => (StatusType::Completed, "离线，已保存待稍后转写"),
+        "low-confidence" => (StatusType::Completed, "已完成，识别置信度较低"),
+        "low-confidence-confirm" => (StatusType::Completed, "置信度较低，已复制待确认"),
+        "awaiting-confirm" => (StatusType::Completed, "已完成，等待确认"),
+        "secure-field" => (StatusType::Error, "焦点在密码框，已仅复制到剪贴板"),
+        "error-invalid-key" => (StatusType::Error, "API Key 无效，请在设置中检查"),
+        "error-quota" => (StatusType::Error, "账户额度不足，请充值或更换 Key"),
+        "error-audio-too-large" => (StatusType::Error, "音频过大，请缩短分段时长"),
+        "error-rate-limited" => (StatusType::Error, "请求过于频繁，请稍后再试"),
+        _ => return None,
     };
+    Some(display)
+}
 
+fn is_final_status(status_type: StatusType) -> bool {
+    status_type == StatusType::Completed || status_type == StatusType::Error
+}
+
+/// Show status overlay with native window for statuses not tied to a transcription job
+/// (recording, pause, cancel, confirm window results).
+pub fn emit_status(status: &str) {
+    let Some((status_type, text)) = status_display(status) else {
+        return;
+    };
+    match status {
+        "recording" | "paused" => status_board::set_recording(status_type, text),
+        "cancelled" => status_board::clear_recording(),
+        _ => {}
+    }
+    show_status(status_type, text);
+}
+
+/// 录音结束送入转写时登记任务，浮窗显示待完成的转写数
+pub fn begin_job() -> JobId {
+    let job = jobs::register();
+    status_board::begin_job(job);
+    show_status(StatusType::Transcribing, "正在转写");
+    job
+}
+
+/// Show the status of one transcription job. Completed and error statuses remove the job;
+/// the overlay keeps showing the recording or other pending jobs if there are any.
+pub fn emit_job_status(job: JobId, status: &str) {
+    let Some((status_type, text)) = status_display(status) else {
+        return;
+    };
+    let state = if status == "error" || status.starts_with("error-") {
+        JobState::Failed
+    } else if is_final_status(status_type) {
+        JobState::Completed
+    } else {
+        JobState::Transcribing
+    };
+    jobs::update(job, state, status);
+    if state.is_finished() {
+        status_board::finish_job(job);
+    } else {
+        status_board::update_job(job, text);
+    }
+    show_status(status_type, text);
+}
+
+fn emit_mode_status(mode: ProcessingMode, status: &str) {
+    if let Some(job) = mode.job() {
+        emit_job_status(job, status);
+    }
+}
+
+/// For "completed" and "error" status (including "cancelled"), auto-hide after 2 seconds.
+fn show_status(status_type: StatusType, text: &str) {
     // Increment counter to invalidate any pending hide operations
     let current_count = STATUS_COUNTER.fetch_add(1, Ordering::SeqCst) + 1;
 
+    // 仍在录音或有转写未完成时显示合成状态，单个任务的结束提示不覆盖它们
+    if let Some((busy_type, busy_text)) = status_board::overlay() {
+        status_native::show(busy_type, &busy_text);
+        return;
+    }
     status_native::show(status_type, text);
 
     // Auto-hide after 2 seconds for completed/error states
     // Only hide if no new status was shown during the delay
-    if status_type == StatusType::Completed || status_type == StatusType::Error {
+    if is_final_status(status_type) {
         thread::spawn(move || {
             thread::sleep(Duration::from_secs(2));
             // Only hide if the counter hasn't changed (no new status was shown)
@@ -279,6 +1159,274 @@ pub fn emit_status(status: &str) {
     }
 }
 
+/// 录音前倒计时：浮窗按整秒显示剩余时间，结束后返回
+pub fn run_countdown(countdown_ms: u32) {
+    for (seconds, wait) in countdown_ticks(countdown_ms) {
+        show_recording(StatusType::Recording, &format!("即将开始录音 · {seconds}"));
+        thread::sleep(wait);
+    }
+}
+
+/// 倒计时的每一跳：（显示的剩余秒数，停留时长），使显示在整秒处切换
+fn countdown_ticks(countdown_ms: u32) -> Vec<(u32, Duration)> {
+    let mut remaining = countdown_ms;
+    let mut ticks = Vec::new();
+    while remaining > 0 {
+        let seconds = remaining.div_ceil(1000);
+        let wait = remaining - (seconds - 1) * 1000;
+        ticks.push((seconds, Duration::from_millis(u64::from(wait))));
+        remaining -= wait;
+    }
+    ticks
+}
+
+/// 显示带输入设备名称的录音状态，并通知前端当前录音来源
+pub fn emit_recording_status(device_name: &str) {
+    if device_name.is_empty() {
+        show_recording(StatusType::Recording, "正在录音");
+    } else {
+        show_recording(StatusType::Recording, &format!("正在录音 · {device_name}"));
+    }
+    events::emit_recording_source(device_name, false);
+}
+
+fn show_recording(status_type: StatusType, text: &str) {
+    status_board::set_recording(status_type, text);
+    show_status(status_type, text);
+}
+
+/// 录音期间监测电平，每次录音各提示一次：
+/// - 电平持续接近零时在浮窗提示，常见于默认输入设备为虚拟声卡或“立体声混音”
+/// - 反复削波时提示调低输入增益，以免白白等待一次转写
+pub fn spawn_level_watch(meter: Arc<LevelMeter>, device_name: String) {
+    let session = meter.session();
+    thread::spawn(move || {
+        let mut silent_for = Duration::ZERO;
+        let mut silence_warned = false;
+        let mut clip_runs = 0;
+        while meter.is_active(session) && !(silence_warned && clip_runs >= CLIP_WARNING_RUNS) {
+            thread::sleep(LEVEL_POLL_INTERVAL);
+            if clip_runs < CLIP_WARNING_RUNS {
+                clip_runs += meter.take_clip_runs();
+                if clip_runs >= CLIP_WARNING_RUNS && meter.is_active(session) {
+                    show_recording(
+                        StatusType::Error,
+                        &format!("输入音量过高，请调低麦克风增益: {device_name}"),
+                    );
+                    events::emit_recording_warning(&device_name, RecordingWarningKind::Clipping);
+                }
+            }
+            if silence_warned {
+                continue;
+            }
+            // 暂停期间没有样本，不计入静音时长
+            if meter.take_peak() > SILENT_PEAK_THRESHOLD || meter.is_paused() {
+                silent_for = Duration::ZERO;
+                continue;
+            }
+            silent_for += LEVEL_POLL_INTERVAL;
+            if silent_for >= SILENT_WARNING_AFTER {
+                silence_warned = true;
+                if meter.is_active(session) {
+                    show_recording(
+                        StatusType::Error,
+                        &format!("未检测到声音，请确认输入设备: {device_name}"),
+                    );
+                    events::emit_recording_source(&device_name, true);
+                }
+            }
+        }
+    });
+}
+
+/// 按提供商切换规则（电源/网络状态）覆盖本次转写使用的提供商
+fn apply_provider_rules(mut settings: Settings) -> Settings {
+    if !settings.provider_rules.enabled || settings.provider_rules.rules.is_empty() {
+        return settings;
+    }
+    // 仅在规则用到网络条件时才探测网络，避免每次转写前多等一次连接
+    let probe_network = settings
+        .provider_rules
+        .rules
+        .iter()
+        .any(|rule| rule.network != NetworkCondition::Any);
+    let state = system_state::detect(probe_network);
+    let Some(provider) = select_rule_provider(&settings, &state) else {
+        return settings;
+    };
+    dev_log(&format!(
+        "提供商切换规则命中: power={:?}, online={}",
+        state.power, state.online
+    ));
+    settings.provider = provider;
+    settings
+}
+
+/// 按录音时长切换提供商；随后的电源/网络规则与离线回退仍可覆盖此结果
+fn apply_duration_routing(mut settings: Settings, recording: &RecordedAudio) -> Settings {
+    if !settings.duration_routing.enabled {
+        return settings;
+    }
+    let duration_ms = calculate_recording_duration_ms(recording);
+    let Some(provider) = select_duration_provider(&settings, duration_ms) else {
+        return settings;
+    };
+    dev_log(&format!("按录音时长切换提供商: {duration_ms} ms"));
+    settings.provider = provider;
+    settings
+}
+
+fn select_duration_provider(
+    settings: &Settings,
+    duration_ms: u64,
+) -> Option<TranscriptionProvider> {
+    let routing = &settings.duration_routing;
+    let seconds = duration_ms as f32 / 1000.0;
+    let provider = if seconds <= routing.short_max_seconds {
+        routing.short_provider.clone()
+    } else if seconds >= routing.long_min_seconds {
+        routing.long_provider.clone()
+    } else {
+        None
+    }?;
+    // 本地模型未安装时保持当前提供商
+    (provider.is_cloud() || settings.sensevoice.installed).then_some(provider)
+}
+
+fn average_confidence(values: &[f32]) -> Option<f32> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f32>() / values.len() as f32)
+}
+
+/// 阈值为 0 时关闭低置信度处理；提供商未返回置信度时不视为低置信度
+fn is_low_confidence(confidence: Option<f32>, threshold: f32) -> bool {
+    threshold > 0.0 && confidence.is_some_and(|value| value < threshold)
+}
+
+fn transcribe_segment_with_retry(
+    engine: &dyn TranscriptionBackend,
+    path: &Path,
+    retry_attempts: u32,
+) -> Result<TranscriptionResult, TranscriptionError> {
+    let mut attempt = 0;
+    loop {
+        match engine.transcribe(path) {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt < retry_attempts && err.is_retryable() => {
+                attempt += 1;
+                dev_log(&format!(
+                    "段落转写失败，{}/{} 次重试: {err}",
+                    attempt, retry_attempts
+                ));
+                thread::sleep(SEGMENT_RETRY_BACKOFF * attempt);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+enum OfflineDecision {
+    Proceed(Settings),
+    /// 已回退到本地模型
+    Local(Settings),
+    Defer(Settings),
+    Fail(Settings, String),
+}
+
+fn apply_offline_fallback(settings: Settings, mode: ProcessingMode) -> OfflineDecision {
+    decide_offline_fallback(settings, mode, system_state::is_network_online)
+}
+
+/// 云端提供商离线（或本地模型预热中）时按设置回退到本地模型、加入待转写队列，
+/// 或给出明确的离线错误；`is_online` 仅在需要时调用
+fn decide_offline_fallback(
+    mut settings: Settings,
+    mode: ProcessingMode,
+    is_online: impl FnOnce() -> bool,
+) -> OfflineDecision {
+    let fallback = settings.offline.fallback;
+    if fallback == OfflineFallback::Disabled {
+        return OfflineDecision::Proceed(settings);
+    }
+    // 后台补转写本身来自队列，失败时保留原文件即可，不再重复入队
+    let can_defer = fallback == OfflineFallback::Queue && mode.is_dictation();
+    if !settings.provider.is_cloud() {
+        if can_defer && deferred_queue::is_local_model_warming(&settings) {
+            return OfflineDecision::Defer(settings);
+        }
+        return OfflineDecision::Proceed(settings);
+    }
+    if is_online() {
+        return OfflineDecision::Proceed(settings);
+    }
+    dev_log("网络不可用，云端转写无法进行");
+    if fallback == OfflineFallback::Local {
+        if let Some(provider) = transcription::offline_fallback_provider(&settings) {
+            settings.provider = provider;
+            return OfflineDecision::Local(settings);
+        }
+    }
+    if can_defer {
+        return OfflineDecision::Defer(settings);
+    }
+    OfflineDecision::Fail(
+        settings,
+        "网络不可用，无法调用云端转写服务；可在设置中启用离线时回退到本地模型".to_string(),
+    )
+}
+
+fn defer_recording(
+    store: &SettingsStore,
+    settings: &Settings,
+    recording: &RecordedAudio,
+    mode: ProcessingMode,
+) -> ProcessingOutcome {
+    match deferred_queue::enqueue(store, recording) {
+        Ok(id) => {
+            dev_log(&format!("录音已加入待转写队列: {id}"));
+            emit_mode_status(mode, "deferred");
+            early_outcome(settings, recording).deferred_id(id).build()
+        }
+        Err(err) => early_outcome(settings, recording)
+            .build_error(format!("保存待转写录音失败: {err}")),
+    }
+}
+
+/// 尚未开始转写时的结果构建器
+fn early_outcome(settings: &Settings, recording: &RecordedAudio) -> ProcessingOutcomeBuilder {
+    ProcessingOutcome::builder()
+        .history_enabled(settings.history.enabled)
+        .model_group(transcription::create_backend(settings).model_group())
+        .recording_duration_ms(calculate_recording_duration_ms(recording))
+}
+
+fn select_rule_provider(settings: &Settings, state: &SystemState) -> Option<TranscriptionProvider> {
+    settings
+        .provider_rules
+        .rules
+        .iter()
+        .filter(|rule| rule_matches(rule, state))
+        // 本地模型未安装时跳过该规则，避免切换到不可用的提供商
+        .find(|rule| rule.provider.is_cloud() || settings.sensevoice.installed)
+        .map(|rule| rule.provider.clone())
+}
+
+fn rule_matches(rule: &ProviderRule, state: &SystemState) -> bool {
+    let power_matches = match rule.power {
+        PowerCondition::Any => true,
+        PowerCondition::Ac => state.power == PowerSource::Ac,
+        PowerCondition::Battery => state.power == PowerSource::Battery,
+    };
+    let network_matches = match rule.network {
+        NetworkCondition::Any => true,
+        NetworkCondition::Online => state.online,
+        NetworkCondition::Offline => !state.online,
+    };
+    power_matches && network_matches
+}
+
 fn cleanup_files(paths: &[std::path::PathBuf]) {
     for path in paths {
         let _ = fs::remove_file(path);
@@ -305,7 +1453,7 @@ fn calculate_recording_duration_ms(recording: &RecordedAudio) -> u64 {
     if samples_per_second == 0 {
         return 0;
     }
-    let sample_count = recording.samples.len() as u128;
+    let sample_count = recording.sample_count() as u128;
     let duration_ms = sample_count.saturating_mul(1000) / u128::from(samples_per_second);
     duration_ms as u64
 }
@@ -316,8 +1464,61 @@ fn elapsed_since_ms(started: Instant) -> u64 {
 
 #[cfg(test)]
 mod tests {
-    use super::{calculate_recording_duration_ms, remove_line_breaks};
+    use super::{
+        calculate_recording_duration_ms, decide_offline_fallback, is_low_confidence,
+        join_segment_texts, merge_track_transcripts, remove_line_breaks, select_rule_provider,
+        strip_overlap_words, transcribe_segment_with_retry, transcribe_segments, OfflineDecision,
+        ProcessingMode, SegmentedTranscript,
+    };
+    use crate::openai::OpenAiError;
     use crate::recorder::RecordedAudio;
+    use crate::settings::{
+        NetworkCondition, OfflineFallback, PowerCondition, ProviderRule, Settings, SpeechMetadata,
+        TranscriptionProvider,
+    };
+    use crate::system_state::{PowerSource, SystemState};
+    use crate::transcription::{
+        BackendCapabilities, EngineEnvironment, TranscriptSegment, TranscriptionBackend,
+        TranscriptionError, TranscriptionResult,
+    };
+    use std::cell::Cell;
+    use std::path::Path;
+
+    struct FlakyEngine {
+        failures_left: Cell<u32>,
+    }
+
+    impl TranscriptionBackend for FlakyEngine {
+        fn transcribe(&self, _audio_path: &Path) -> Result<TranscriptionResult, TranscriptionError> {
+            if self.failures_left.get() > 0 {
+                self.failures_left.set(self.failures_left.get() - 1);
+                return Err(OpenAiError::Request("timeout".to_string()).into());
+            }
+            Ok(TranscriptionResult {
+                text: "ok".to_string(),
+                alignment: None,
+                confidence: None,
+                segments: Vec::new(),
+                metadata: SpeechMetadata::default(),
+            })
+        }
+
+        fn capabilities(&self) -> BackendCapabilities {
+            BackendCapabilities::default()
+        }
+
+        fn validate(&self) -> Result<(), TranscriptionError> {
+            Ok(())
+        }
+
+        fn model_group(&self) -> String {
+            "Test".to_string()
+        }
+
+        fn environment(&self) -> EngineEnvironment {
+            EngineEnvironment::Cloud
+        }
+    }
 
     #[test]
     fn remove_line_breaks_removes_crlf_lf_and_cr() {
@@ -346,6 +1547,8 @@ mod tests {
             samples: vec![0; 16_000],
             sample_rate: 16_000,
             channels: 1,
+            spill: None,
+            tracks: Vec::new(),
         };
         assert_eq!(calculate_recording_duration_ms(&recording), 1000);
 
@@ -353,6 +1556,8 @@ mod tests {
             samples: vec![0; 100],
             sample_rate: 0,
             channels: 1,
+            spill: None,
+            tracks: Vec::new(),
         };
         assert_eq!(calculate_recording_duration_ms(&zero_rate), 0);
 
@@ -360,7 +1565,295 @@ mod tests {
             samples: vec![0; 100],
             sample_rate: 16_000,
             channels: 0,
+            spill: None,
+            tracks: Vec::new(),
         };
         assert_eq!(calculate_recording_duration_ms(&zero_channels), 0);
     }
+
+    #[test]
+    fn transcribe_segments_fails_when_every_spoken_segment_fails() {
+        let dir = std::env::temp_dir().join(format!(
+            "vtt-keyboard-segments-{}",
+            crate::util::timestamp_id()
+        ));
+        let mut settings = Settings::default();
+        settings.recording.scratch_dir = dir.to_string_lossy().into_owned();
+        settings.recording.segment_seconds = 1;
+        settings.recording.segment_overlap_ms = 0;
+        settings.recording.segment_retry_attempts = 0;
+        settings.recording.silence_trim.enabled = true;
+        // 第 1 段全部静音被跳过，第 2 段有声音但转写失败
+        let mut samples = vec![0i16; 16_000 * 3 / 2];
+        samples.extend(vec![8_000i16; 8_000]);
+        let recording = RecordedAudio {
+            samples,
+            sample_rate: 16_000,
+            channels: 1,
+            spill: None,
+            tracks: Vec::new(),
+        };
+        let engine = FlakyEngine {
+            failures_left: Cell::new(u32::MAX),
+        };
+        let result = transcribe_segments(&settings, &engine, &recording, false);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn select_rule_provider_uses_first_matching_rule() {
+        let mut settings = Settings::default();
+        settings.sensevoice.installed = true;
+        settings.provider_rules.enabled = true;
+        settings.provider_rules.rules = vec![
+            ProviderRule {
+                id: "battery-or-offline".to_string(),
+                power: PowerCondition::Battery,
+                network: NetworkCondition::Any,
+                provider: TranscriptionProvider::Sensevoice,
+            },
+            ProviderRule {
+                id: "ac-online".to_string(),
+                power: PowerCondition::Ac,
+                network: NetworkCondition::Online,
+                provider: TranscriptionProvider::Openai,
+            },
+        ];
+
+        let on_battery = SystemState {
+            power: PowerSource::Battery,
+            online: true,
+        };
+        assert!(
+            select_rule_provider(&settings, &on_battery) == Some(TranscriptionProvider::Sensevoice)
+        );
+
+        let on_ac = SystemState {
+            power: PowerSource::Ac,
+            online: true,
+        };
+        assert!(
+            select_rule_provider(&settings, &on_ac) == Some(TranscriptionProvider::Openai)
+        );
+
+        let offline_on_ac = SystemState {
+            power: PowerSource::Ac,
+            online: false,
+        };
+        assert!(select_rule_provider(&settings, &offline_on_ac).is_none());
+    }
+
+    #[test]
+    fn select_rule_provider_skips_uninstalled_local_model() {
+        let mut settings = Settings::default();
+        settings.sensevoice.installed = false;
+        settings.provider_rules.rules = vec![ProviderRule {
+            id: "offline".to_string(),
+            power: PowerCondition::Any,
+            network: NetworkCondition::Offline,
+            provider: TranscriptionProvider::Sensevoice,
+        }];
+        let state = SystemState {
+            power: PowerSource::Unknown,
+            online: false,
+        };
+        assert!(select_rule_provider(&settings, &state).is_none());
+    }
+
+    fn offline_fallback_settings() -> Settings {
+        let mut settings = Settings::default();
+        settings.provider = TranscriptionProvider::Openai;
+        settings.offline.fallback = OfflineFallback::Local;
+        settings
+    }
+
+    #[test]
+    fn offline_fallback_keeps_cloud_provider_when_online() {
+        let mut settings = offline_fallback_settings();
+        settings.sensevoice.enabled = true;
+        settings.sensevoice.installed = true;
+        let OfflineDecision::Proceed(settings) =
+            decide_offline_fallback(settings, ProcessingMode::Background, || true)
+        else {
+            panic!("在线时应直接使用云端提供商");
+        };
+        assert!(settings.provider == TranscriptionProvider::Openai);
+    }
+
+    #[test]
+    fn offline_fallback_switches_to_ready_local_model() {
+        let mut settings = offline_fallback_settings();
+        settings.sensevoice.enabled = true;
+        settings.sensevoice.installed = true;
+        let OfflineDecision::Local(settings) =
+            decide_offline_fallback(settings, ProcessingMode::Background, || false)
+        else {
+            panic!("离线且本地模型可用时应回退到本地模型");
+        };
+        assert!(settings.provider == TranscriptionProvider::Sensevoice);
+    }
+
+    #[test]
+    fn offline_fallback_fails_without_local_model() {
+        let settings = offline_fallback_settings();
+        let decision = decide_offline_fallback(settings, ProcessingMode::Background, || false);
+        assert!(
+            matches!(decision, OfflineDecision::Fail(_, message) if message.contains("网络不可用"))
+        );
+    }
+
+    #[test]
+    fn select_duration_provider_routes_short_and_long_clips() {
+        let mut settings = Settings::default();
+        settings.sensevoice.installed = true;
+        settings.duration_routing.enabled = true;
+        settings.duration_routing.short_provider = Some(TranscriptionProvider::Sensevoice);
+        settings.duration_routing.long_provider = Some(TranscriptionProvider::Volcengine);
+
+        assert!(
+            select_duration_provider(&settings, 2_000) == Some(TranscriptionProvider::Sensevoice)
+        );
+        assert!(select_duration_provider(&settings, 30_000).is_none());
+        assert!(
+            select_duration_provider(&settings, 90_000) == Some(TranscriptionProvider::Volcengine)
+        );
+
+        settings.sensevoice.installed = false;
+        assert!(select_duration_provider(&settings, 2_000).is_none());
+    }
+
+    #[test]
+    fn transcribe_segment_with_retry_recovers_within_attempts() {
+        let engine = FlakyEngine {
+            failures_left: Cell::new(1),
+        };
+        let result = transcribe_segment_with_retry(&engine, Path::new("segment.wav"), 1);
+        assert_eq!(result.map(|value| value.text).ok().as_deref(), Some("ok"));
+    }
+
+    #[test]
+    fn transcribe_segment_with_retry_gives_up_after_attempts() {
+        let engine = FlakyEngine {
+            failures_left: Cell::new(2),
+        };
+        let result = transcribe_segment_with_retry(&engine, Path::new("segment.wav"), 0);
+        assert!(result.is_err());
+        assert_eq!(engine.failures_left.get(), 1);
+    }
+
+    #[test]
+    fn countdown_ticks_switch_on_whole_seconds() {
+        assert!(countdown_ticks(0).is_empty());
+        assert_eq!(
+            countdown_ticks(1500),
+            vec![
+                (2, Duration::from_millis(500)),
+                (1, Duration::from_millis(1000)),
+            ]
+        );
+        assert_eq!(countdown_ticks(800), vec![(1, Duration::from_millis(800))]);
+    }
+
+    #[test]
+    fn is_low_confidence_respects_threshold_and_missing_values() {
+        assert!(!is_low_confidence(Some(0.2), 0.0));
+        assert!(!is_low_confidence(None, 0.8));
+        assert!(is_low_confidence(Some(0.5), 0.8));
+        assert!(!is_low_confidence(Some(0.9), 0.8));
+    }
+
+    #[test]
+    fn join_segment_texts_uses_language_aware_separators() {
+        assert_eq!(join_segment_texts(["今天天气", "很好。"]), "今天天气很好。");
+        assert_eq!(join_segment_texts(["hello", " world "]), "hello world");
+        assert_eq!(join_segment_texts(["Hi", ", there", ""]), "Hi, there");
+        assert_eq!(
+            join_segment_texts(["안녕하세요", "감사합니다"]),
+            "안녕하세요 감사합니다"
+        );
+        assert_eq!(
+            join_segment_texts(["我们用", "React 开发"]),
+            "我们用React 开发"
+        );
+    }
+
+    #[test]
+    fn join_segment_texts_keeps_repeated_boundary_text() {
+        // 没有音频重叠时，边界处的重复是真实说出的内容
+        assert_eq!(
+            join_segment_texts(["我们明天上午开会", "上午开会讨论方案"]),
+            "我们明天上午开会上午开会讨论方案"
+        );
+        assert_eq!(
+            join_segment_texts(["see you next week", "Next week we ship"]),
+            "see you next week Next week we ship"
+        );
+    }
+
+    #[test]
+    fn strip_overlap_words_skips_cut_words_and_ignores_punctuation() {
+        assert_eq!(
+            strip_overlap_words(
+                "We should ship the release on Friday.",
+                "ip the release, on Friday. Then we rest"
+            ),
+            "Then we rest"
+        );
+        assert_eq!(
+            strip_overlap_words("我们明天上午开会。", "天上午开会，讨论方案"),
+            "讨论方案"
+        );
+        // 单个词的巧合重复不去重
+        assert_eq!(
+            strip_overlap_words("I said yes", "yes we can"),
+            "yes we can"
+        );
+        assert_eq!(strip_overlap_words("", "hello world"), "hello world");
+    }
+
+    fn cue(start_ms: u64, end_ms: u64, text: &str) -> TranscriptSegment {
+        TranscriptSegment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+        }
+    }
+
+    fn track_transcript(cues: Vec<TranscriptSegment>) -> SegmentedTranscript {
+        SegmentedTranscript {
+            segments: cues.clone(),
+            segment_count: 1,
+            cues,
+            metadata: SpeechMetadata::default(),
+            alignment: None,
+            confidence: None,
+            failed_segments: Vec::new(),
+            failed_count: 0,
+            speaker_turns: false,
+        }
+    }
+
+    #[test]
+    fn merge_track_transcripts_interleaves_speakers_by_time() {
+        let merged = merge_track_transcripts(vec![
+            (
+                "我".to_string(),
+                track_transcript(vec![
+                    cue(0, 1000, "你好"),
+                    cue(1200, 2000, "最近怎么样"),
+                    cue(5000, 6000, "好的"),
+                ]),
+            ),
+            (
+                "对方".to_string(),
+                track_transcript(vec![cue(2500, 4000, "还不错"), cue(7000, 7500, " ")]),
+            ),
+        ]);
+        assert!(merged.speaker_turns);
+        assert_eq!(merged.segment_count, 2);
+        assert_eq!(merged.cues.len(), 4);
+        assert_eq!(merged.text(), "我：你好最近怎么样\n对方：还不错\n我：好的");
+        assert_eq!(merged.segments[0].end_ms, 2000);
+    }
 }
@@ -0,0 +1,86 @@
+//! 转写提示词预设：按用途（医学、法律、编程、日常）切换转写提示词与热词
+//!
+//! 默认使用设置中启用的预设；通过预设快捷键开始的录音使用该快捷键对应的预设。
+//! 预设的提示词加在转写设置的提示词之前，热词并入火山引擎热词，其他提供商不受影响。
+
+use crate::settings::{PromptPreset, Settings};
+use std::sync::Mutex;
+
+/// 当前录音通过预设快捷键指定的预设 ID
+static RECORDING_PRESET: Mutex<Option<String>> = Mutex::new(None);
+
+pub fn set_recording(preset_id: Option<String>) {
+    *RECORDING_PRESET.lock().unwrap_or_else(|e| e.into_inner()) =
+        preset_id.filter(|id| !id.trim().is_empty());
+}
+
+/// 取出并清除当前录音指定的预设 ID
+pub fn take_recording() -> Option<String> {
+    RECORDING_PRESET
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+}
+
+/// 本次转写使用的预设：优先使用录音指定的预设，否则使用设置中启用的预设
+fn resolve<'a>(settings: &'a Settings, preset_id: Option<&str>) -> Option<&'a PromptPreset> {
+    let id = preset_id
+        .unwrap_or(&settings.active_prompt_preset_id)
+        .trim();
+    if id.is_empty() {
+        return None;
+    }
+    settings
+        .prompt_presets
+        .iter()
+        .find(|preset| preset.id == id)
+}
+
+/// 将预设的提示词与热词合并到本次转写使用的设置
+pub fn apply(mut settings: Settings, preset_id: Option<&str>) -> Settings {
+    let Some(preset) = resolve(&settings, preset_id).cloned() else {
+        return settings;
+    };
+    let speech_to_text = &mut settings.openai.speech_to_text;
+    speech_to_text.prompt = [preset.prompt.trim(), speech_to_text.prompt.trim()]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    for word in preset.hotwords {
+        let word = word.trim();
+        if !word.is_empty() && !settings.volcengine.hotwords.iter().any(|item| item == word) {
+            settings.volcengine.hotwords.push(word.to_string());
+        }
+    }
+    settings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_prefers_recording_preset_over_active_preset() {
+        let mut settings = Settings::default();
+        settings.openai.speech_to_text.prompt = "VTT Keyboard".to_string();
+        settings.volcengine.hotwords = vec!["API".to_string()];
+        settings.active_prompt_preset_id = "casual".to_string();
+
+        let applied = apply(settings.clone(), Some("programming"));
+        let prompt = &applied.openai.speech_to_text.prompt;
+        assert!(prompt.starts_with("Programming discussion"));
+        assert!(prompt.ends_with("\nVTT Keyboard"));
+        assert_eq!(
+            applied.volcengine.hotwords,
+            vec!["API", "JSON", "TypeScript", "Rust", "GitHub"]
+        );
+
+        let applied = apply(settings.clone(), None);
+        assert!(applied.openai.speech_to_text.prompt.starts_with("Hey"));
+
+        settings.active_prompt_preset_id.clear();
+        let applied = apply(settings, None);
+        assert_eq!(applied.openai.speech_to_text.prompt, "VTT Keyboard");
+    }
+}
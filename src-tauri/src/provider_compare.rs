@@ -0,0 +1,105 @@
+//! 提供商对比：同一段音频依次交给多个提供商转写，汇总文本、耗时与费用估算
+
+use crate::settings::{Settings, TranscriptionProvider};
+use crate::transcription::{self, BackendCapabilities};
+use hound::WavReader;
+use serde::Serialize;
+use std::path::Path;
+use std::time::Instant;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderComparison {
+    pub provider: TranscriptionProvider,
+    pub model_group: String,
+    pub capabilities: BackendCapabilities,
+    pub text: String,
+    pub latency_ms: u64,
+    pub audio_duration_ms: Option<u64>,
+    /// 按公开标价估算的费用（美元），未知价格时为空
+    pub estimated_cost_usd: Option<f64>,
+    pub error_message: Option<String>,
+}
+
+pub fn compare_providers(
+    settings: &Settings,
+    audio_path: &Path,
+    providers: &[TranscriptionProvider],
+) -> Vec<ProviderComparison> {
+    let audio_duration_ms = wav_duration_ms(audio_path);
+    providers
+        .iter()
+        .map(|provider| {
+            let mut provider_settings = settings.clone();
+            provider_settings.provider = provider.clone();
+            let engine = transcription::create_backend(&provider_settings);
+            let started = Instant::now();
+            // 配置不完整时直接报告，不发起请求
+            let result = engine
+                .validate()
+                .and_then(|()| engine.transcribe(audio_path));
+            let latency_ms = started.elapsed().as_millis() as u64;
+            let estimated_cost_usd = audio_duration_ms.and_then(|duration_ms| {
+                cost_per_minute_usd(&provider_settings)
+                    .map(|per_minute| per_minute * duration_ms as f64 / 60_000.0)
+            });
+            let (text, error_message) = match result {
+                Ok(value) => (value.text, None),
+                Err(err) => (String::new(), Some(err.to_string())),
+            };
+            ProviderComparison {
+                provider: provider.clone(),
+                model_group: engine.model_group(),
+                capabilities: engine.capabilities(),
+                text,
+                latency_ms,
+                audio_duration_ms,
+                estimated_cost_usd,
+                error_message,
+            }
+        })
+        .collect()
+}
+
+/// 公开标价（美元/分钟），仅用于粗略对比；本地模型不产生 API 费用
+fn cost_per_minute_usd(settings: &Settings) -> Option<f64> {
+    if settings.provider.is_local() {
+        return Some(0.0);
+    }
+    if settings.provider != TranscriptionProvider::Openai {
+        return None;
+    }
+    match settings.openai.speech_to_text.model.trim() {
+        "gpt-4o-transcribe" | "whisper-1" => Some(0.006),
+        "gpt-4o-mini-transcribe" => Some(0.003),
+        _ => None,
+    }
+}
+
+fn wav_duration_ms(path: &Path) -> Option<u64> {
+    let reader = WavReader::open(path).ok()?;
+    let sample_rate = u64::from(reader.spec().sample_rate);
+    if sample_rate == 0 {
+        return None;
+    }
+    Some(u64::from(reader.duration()) * 1000 / sample_rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cost_per_minute_is_zero_for_local_and_unknown_for_unpriced_cloud() {
+        let mut settings = Settings::default();
+        settings.provider = TranscriptionProvider::Sensevoice;
+        assert_eq!(cost_per_minute_usd(&settings), Some(0.0));
+
+        settings.provider = TranscriptionProvider::Volcengine;
+        assert_eq!(cost_per_minute_usd(&settings), None);
+
+        settings.provider = TranscriptionProvider::Openai;
+        settings.openai.speech_to_text.model = "gpt-4o-mini-transcribe".to_string();
+        assert_eq!(cost_per_minute_usd(&settings), Some(0.003));
+    }
+}
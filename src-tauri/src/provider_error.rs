@@ -0,0 +1,73 @@
+//! 提供商请求失败分类
+//!
+//! 将提供商返回的常见 HTTP 失败状态归类为带处理建议的错误，状态浮窗与历史记录据此提示
+//! “API Key 无效”等原因，而不是显示原始响应正文。
+
+use thiserror::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Error)]
+pub enum ProviderFailure {
+    #[error("API Key 无效或无权访问，请在设置中检查密钥")]
+    InvalidKey,
+    #[error("账户余额或额度不足，请充值或更换 API Key")]
+    QuotaExceeded,
+    #[error("音频超过提供商的大小限制，请缩短分段时长或改用压缩上传格式")]
+    AudioTooLarge,
+    #[error("请求过于频繁，已触发速率限制，请稍后再试")]
+    RateLimited,
+}
+
+impl ProviderFailure {
+    /// 按 HTTP 状态码归类；OpenAI 额度用尽时同样返回 429，以响应正文中的错误码区分
+    pub fn classify(status: u16, body: &str) -> Option<Self> {
+        match status {
+            401 | 403 => Some(Self::InvalidKey),
+            402 => Some(Self::QuotaExceeded),
+            413 => Some(Self::AudioTooLarge),
+            429 if body.contains("insufficient_quota") => Some(Self::QuotaExceeded),
+            429 => Some(Self::RateLimited),
+            _ => None,
+        }
+    }
+
+    /// 稍后重试可能成功；其余失败需要用户处理，重试只会重复失败
+    pub fn is_retryable(self) -> bool {
+        self == Self::RateLimited
+    }
+
+    /// 状态浮窗使用的状态标识
+    pub fn status(self) -> &'static str {
+        match self {
+            Self::InvalidKey => "error-invalid-key",
+            Self::QuotaExceeded => "error-quota",
+            Self::AudioTooLarge => "error-audio-too-large",
+            Self::RateLimited => "error-rate-limited",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_maps_common_statuses() {
+        assert_eq!(
+            ProviderFailure::classify(401, ""),
+            Some(ProviderFailure::InvalidKey)
+        );
+        assert_eq!(
+            ProviderFailure::classify(413, ""),
+            Some(ProviderFailure::AudioTooLarge)
+        );
+        assert_eq!(
+            ProviderFailure::classify(429, r#"{"error":{"code":"rate_limit_exceeded"}}"#),
+            Some(ProviderFailure::RateLimited)
+        );
+        assert_eq!(
+            ProviderFailure::classify(429, r#"{"error":{"code":"insufficient_quota"}}"#),
+            Some(ProviderFailure::QuotaExceeded)
+        );
+        assert_eq!(ProviderFailure::classify(500, ""), None);
+    }
+}
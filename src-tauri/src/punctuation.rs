@@ -0,0 +1,353 @@
+//! 标点风格格式化：统一中英混排转写结果的标点
+//!
+//! 转写引擎对中英混排文本的标点处理并不一致（中文句子夹半角逗号、英文单词后跟全角句号等）。
+//! 这里在转写后按设置做确定性调整：标点宽度（全角、半角或随上下文）、英文列表的牛津逗号，
+//! 以及中文与英文、数字之间的空格。数字、缩写与网址中的半角标点（“3.14”、“12:30”）保持原样。
+
+use crate::pangu::{self, is_cjk, is_latin};
+use crate::settings::{CjkLatinSpacing, PunctuationSettings, PunctuationWidth, SerialComma};
+
+/// 半角与全角标点对照
+const PAIRS: &[(char, char)] = &[
+    (',', '，'),
+    ('.', '。'),
+    ('!', '！'),
+    ('?', '？'),
+    (';', '；'),
+    (':', '：'),
+    ('(', '（'),
+    (')', '）'),
+];
+/// 判断英文列表时每项允许的最多单词数，更长的分句视为复句而非列表项
+const MAX_LIST_ITEM_WORDS: usize = 3;
+
+/// 按设置统一文本的标点风格；中英文间空格不受 `enabled` 影响
+pub fn format(text: &str, settings: &PunctuationSettings) -> String {
+    if !settings.enabled {
+        return apply_cjk_latin_spacing(text, settings.cjk_latin_spacing);
+    }
+    let output = convert_width(text, settings.width);
+    let output = apply_serial_comma(&output, settings.serial_comma);
+    apply_cjk_latin_spacing(&output, settings.cjk_latin_spacing)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Width {
+    Full,
+    Half,
+}
+
+fn convert_width(text: &str, style: PunctuationWidth) -> String {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut output = String::with_capacity(text.len());
+    let mut quote_open = false;
+    let mut index = 0;
+    while index < chars.len() {
+        let ch = chars[index];
+        index += 1;
+        let is_quote = matches!(ch, '"' | '“' | '”');
+        if !is_quote && to_full(ch).is_none() && to_half(ch).is_none() {
+            output.push(ch);
+            continue;
+        }
+        if is_embedded(&chars, index - 1) {
+            output.push(ch);
+            continue;
+        }
+        let opening = if is_quote {
+            quote_open = !quote_open;
+            quote_open
+        } else {
+            matches!(ch, '(' | '（')
+        };
+        let Some(width) = target_width(&chars, index - 1, opening, style) else {
+            output.push(ch);
+            continue;
+        };
+        if !opening {
+            trim_trailing_spaces(&mut output);
+        }
+        match width {
+            Width::Full => {
+                output.push(match (is_quote, opening) {
+                    (true, true) => '“',
+                    (true, false) => '”',
+                    _ => to_full(ch).unwrap_or(ch),
+                });
+                // 全角标点自带间距，去掉其后多余的空格
+                while chars.get(index) == Some(&' ') {
+                    index += 1;
+                }
+            }
+            Width::Half => {
+                // 半角开标点与前文之间留一个空格：“world (note)”
+                let after_text = output
+                    .chars()
+                    .next_back()
+                    .is_some_and(|prev| !prev.is_whitespace() && !matches!(prev, '(' | '"'));
+                if opening && after_text {
+                    output.push(' ');
+                }
+                output.push(if is_quote {
+                    '"'
+                } else {
+                    to_half(ch).unwrap_or(ch)
+                });
+                // 半角句读后补一个空格，紧跟其他标点时除外
+                let followed_by_text = chars
+                    .get(index)
+                    .is_some_and(|next| next.is_alphanumeric() || is_cjk(*next));
+                if !opening && followed_by_text {
+                    output.push(' ');
+                }
+            }
+        }
+    }
+    output
+}
+
+/// 目标宽度；随上下文时开标点看后文、其余标点看前文，无法判断时返回 None 保持原样
+fn target_width(
+    chars: &[char],
+    index: usize,
+    opening: bool,
+    style: PunctuationWidth,
+) -> Option<Width> {
+    match style {
+        PunctuationWidth::FullWidth => Some(Width::Full),
+        PunctuationWidth::HalfWidth => Some(Width::Half),
+        PunctuationWidth::Auto => {
+            // 数字在中英文中通用，跳过数字找最近的文字
+            let is_word = |ch: &&char| is_cjk(**ch) || ch.is_alphabetic();
+            let neighbour = if opening {
+                chars[index + 1..].iter().find(is_word)
+            } else {
+                chars[..index].iter().rev().find(is_word)
+            }?;
+            Some(if is_cjk(*neighbour) {
+                Width::Full
+            } else {
+                Width::Half
+            })
+        }
+    }
+}
+
+/// 数字、缩写、网址与省略号中的半角标点
+fn is_embedded(chars: &[char], index: usize) -> bool {
+    let ch = chars[index];
+    if !ch.is_ascii_punctuation() {
+        return false;
+    }
+    let prev = index.checked_sub(1).map(|prev| chars[prev]);
+    let next = chars.get(index + 1).copied();
+    if ch == '.' && (prev == Some('.') || next == Some('.')) {
+        return true;
+    }
+    prev.is_some_and(|prev| prev.is_ascii_alphanumeric())
+        && next.is_some_and(|next| next.is_ascii_alphanumeric())
+}
+
+fn trim_trailing_spaces(output: &mut String) {
+    let trimmed = output.trim_end_matches(' ').len();
+    output.truncate(trimmed);
+}
+
+fn to_full(ch: char) -> Option<char> {
+    PAIRS
+        .iter()
+        .find(|(half, _)| *half == ch)
+        .map(|(_, full)| *full)
+}
+
+fn to_half(ch: char) -> Option<char> {
+    if ch == '、' {
+        return Some(',');
+    }
+    PAIRS
+        .iter()
+        .find(|(_, full)| *full == ch)
+        .map(|(half, _)| *half)
+}
+
+fn apply_serial_comma(text: &str, style: SerialComma) -> String {
+    if style == SerialComma::Keep {
+        return text.to_string();
+    }
+    let mut output = String::with_capacity(text.len());
+    let mut clause_start = 0;
+    for (index, ch) in text.char_indices() {
+        if matches!(ch, '.' | '!' | '?' | ';' | '\n' | '。' | '！' | '？' | '；') {
+            output.push_str(&serial_comma_clause(&text[clause_start..index], style));
+            output.push(ch);
+            clause_start = index + ch.len_utf8();
+        }
+    }
+    output.push_str(&serial_comma_clause(&text[clause_start..], style));
+    output
+}
+
+/// 在单个分句内添加或去掉列表末项前的逗号（“a, b and c” ↔ “a, b, and c”）
+fn serial_comma_clause(clause: &str, style: SerialComma) -> String {
+    let parts = clause.split(", ").collect::<Vec<_>>();
+    let Some((last, items)) = parts.split_last() else {
+        return clause.to_string();
+    };
+    if items.is_empty() {
+        return clause.to_string();
+    }
+    match style {
+        SerialComma::Keep => clause.to_string(),
+        SerialComma::Add => {
+            let Some((head, conjunction, tail)) = split_conjunction(last) else {
+                return clause.to_string();
+            };
+            if !is_list(items.iter().copied().chain([head, tail])) {
+                return clause.to_string();
+            }
+            format!("{}, {head}, {conjunction} {tail}", items.join(", "))
+        }
+        SerialComma::Remove => {
+            let Some(tail) = last
+                .strip_prefix("and ")
+                .or_else(|| last.strip_prefix("or "))
+            else {
+                return clause.to_string();
+            };
+            // 至少三项才是列表，“a, and b” 多为复句
+            if items.len() < 2 || !is_list(items.iter().copied().chain([tail])) {
+                return clause.to_string();
+            }
+            let (front, previous) = items.split_at(items.len() - 1);
+            format!("{}, {} {last}", front.join(", "), previous[0])
+        }
+    }
+}
+
+fn split_conjunction(part: &str) -> Option<(&str, &str, &str)> {
+    ["and", "or"].into_iter().find_map(|conjunction| {
+        let pattern = format!(" {conjunction} ");
+        let index = part.find(&pattern)?;
+        Some((&part[..index], conjunction, &part[index + pattern.len()..]))
+    })
+}
+
+fn is_list<'a>(mut items: impl Iterator<Item = &'a str>) -> bool {
+    items.all(|item| (1..=MAX_LIST_ITEM_WORDS).contains(&item.split_whitespace().count()))
+}
+
+/// 按设置在中文与英文、数字之间添加或去掉空格，也用于触发词与模板的输出
+pub fn apply_cjk_latin_spacing(text: &str, style: CjkLatinSpacing) -> String {
+    match style {
+        CjkLatinSpacing::Keep => text.to_string(),
+        CjkLatinSpacing::Add => pangu::space(text),
+        CjkLatinSpacing::Remove => remove_cjk_latin_spacing(text),
+    }
+}
+
+/// 去掉中文与英文、数字之间的空格，连续空格整段去掉
+fn remove_cjk_latin_spacing(text: &str) -> String {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut output = String::with_capacity(text.len());
+    for (index, ch) in chars.iter().copied().enumerate() {
+        if ch == ' ' {
+            let prev = output.chars().next_back();
+            let next = chars[index + 1..].iter().find(|next| **next != ' ');
+            if let (Some(prev), Some(next)) = (prev, next) {
+                if is_script_boundary(prev, *next) {
+                    continue;
+                }
+            }
+        }
+        output.push(ch);
+    }
+    output
+}
+
+fn is_script_boundary(left: char, right: char) -> bool {
+    (is_cjk(left) && is_latin(right)) || (is_latin(left) && is_cjk(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(width: PunctuationWidth) -> PunctuationSettings {
+        PunctuationSettings {
+            enabled: true,
+            width,
+            serial_comma: SerialComma::Keep,
+            cjk_latin_spacing: CjkLatinSpacing::Keep,
+        }
+    }
+
+    #[test]
+    fn auto_width_follows_surrounding_script() {
+        let settings = settings(PunctuationWidth::Auto);
+        assert_eq!(
+            format("今天开会,讨论 API 设计. Then we ship。", &settings),
+            "今天开会，讨论 API 设计。Then we ship."
+        );
+        assert_eq!(
+            format("版本 3.14 在 12:30 发布.", &settings),
+            "版本 3.14 在 12:30 发布。"
+        );
+        assert_eq!(format("他说\"好的\"", &settings), "他说“好的”");
+    }
+
+    #[test]
+    fn fixed_width_converts_every_mark() {
+        assert_eq!(
+            format(
+                "你好，world！（注）",
+                &settings(PunctuationWidth::HalfWidth)
+            ),
+            "你好, world! (注)"
+        );
+        assert_eq!(
+            format("Hello , world. 好", &settings(PunctuationWidth::FullWidth)),
+            "Hello，world。好"
+        );
+    }
+
+    #[test]
+    fn serial_comma_is_added_or_removed_in_short_lists() {
+        let mut settings = settings(PunctuationWidth::Auto);
+        settings.serial_comma = SerialComma::Add;
+        assert_eq!(
+            format(
+                "Buy milk, eggs and bread. I stayed home and slept.",
+                &settings
+            ),
+            "Buy milk, eggs, and bread. I stayed home and slept."
+        );
+        settings.serial_comma = SerialComma::Remove;
+        assert_eq!(
+            format("Red, green, or blue. I tried, and it failed.", &settings),
+            "Red, green or blue. I tried, and it failed."
+        );
+    }
+
+    #[test]
+    fn cjk_latin_spacing_is_added_or_removed() {
+        let mut settings = settings(PunctuationWidth::Auto);
+        settings.cjk_latin_spacing = CjkLatinSpacing::Add;
+        assert_eq!(
+            format("使用Rust写了3个CLI工具", &settings),
+            "使用 Rust 写了 3 个 CLI 工具"
+        );
+        settings.cjk_latin_spacing = CjkLatinSpacing::Remove;
+        assert_eq!(
+            format("使用 Rust  写了 3 个工具", &settings),
+            "使用Rust写了3个工具"
+        );
+    }
+
+    #[test]
+    fn disabled_settings_leave_text_unchanged() {
+        let mut settings = PunctuationSettings::default();
+        assert_eq!(format("你好,world .", &settings), "你好,world .");
+        settings.cjk_latin_spacing = CjkLatinSpacing::Add;
+        assert_eq!(format("你好,world .", &settings), "你好, world .");
+    }
+}
@@ -0,0 +1,121 @@
+//! 托盘快捷开关
+//!
+//! 托盘菜单中的勾选项直接修改对应设置，不需要打开主窗口。
+
+use crate::settings::{Settings, TranscriptionProvider, TriggerCard};
+
+/// 自动应用后即为“自动润色”的内置触发卡片
+const POLISH_TRIGGER_ID: &str = "polish";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuickToggle {
+    /// 转写后自动应用润色触发卡片
+    AutoPolish,
+    /// 转写后自动粘贴，关闭时只写入剪贴板
+    PasteAfterTranscribe,
+    /// 使用本地模型转写
+    UseLocalModel,
+}
+
+impl QuickToggle {
+    pub const ALL: [Self; 3] = [
+        Self::AutoPolish,
+        Self::PasteAfterTranscribe,
+        Self::UseLocalModel,
+    ];
+
+    /// 托盘菜单项 ID
+    pub const fn menu_id(self) -> &'static str {
+        match self {
+            Self::AutoPolish => "quick-auto-polish",
+            Self::PasteAfterTranscribe => "quick-paste-after-transcribe",
+            Self::UseLocalModel => "quick-use-local-model",
+        }
+    }
+
+    pub fn from_menu_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|toggle| toggle.menu_id() == id)
+    }
+
+    pub fn is_enabled(self, settings: &Settings) -> bool {
+        match self {
+            Self::AutoPolish => {
+                polish_trigger(settings).is_some_and(|card| card.enabled && card.auto_apply)
+            }
+            Self::PasteAfterTranscribe => settings.output.paste_after_transcribe,
+            Self::UseLocalModel => settings.provider.is_local(),
+        }
+    }
+
+    /// 切换开关，返回切换后的状态；无法切换时返回错误且不修改设置
+    pub fn toggle(self, settings: &mut Settings) -> Result<bool, String> {
+        let enabled = !self.is_enabled(settings);
+        match self {
+            Self::AutoPolish => {
+                let card = settings
+                    .triggers
+                    .iter_mut()
+                    .find(|card| card.id == POLISH_TRIGGER_ID)
+                    .ok_or_else(|| "未找到润色触发卡片".to_string())?;
+                card.auto_apply = enabled;
+                if enabled {
+                    card.enabled = true;
+                }
+            }
+            Self::PasteAfterTranscribe => settings.output.paste_after_transcribe = enabled,
+            Self::UseLocalModel if enabled => {
+                if !settings.sensevoice.installed {
+                    return Err("本地模型尚未安装".to_string());
+                }
+                settings.last_cloud_provider = settings.provider.clone();
+                settings.provider = TranscriptionProvider::Sensevoice;
+            }
+            Self::UseLocalModel => {
+                settings.provider = settings.last_cloud_provider.clone();
+            }
+        }
+        Ok(enabled)
+    }
+}
+
+fn polish_trigger(settings: &Settings) -> Option<&TriggerCard> {
+    settings
+        .triggers
+        .iter()
+        .find(|card| card.id == POLISH_TRIGGER_ID)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggles_flip_settings_and_restore_cloud_provider() {
+        let mut settings = Settings::default();
+        assert!(!QuickToggle::AutoPolish.is_enabled(&settings));
+        assert_eq!(QuickToggle::AutoPolish.toggle(&mut settings), Ok(true));
+        assert!(QuickToggle::AutoPolish.is_enabled(&settings));
+
+        assert_eq!(
+            QuickToggle::PasteAfterTranscribe.toggle(&mut settings),
+            Ok(false)
+        );
+        assert!(!settings.output.paste_after_transcribe);
+
+        settings.provider = TranscriptionProvider::Volcengine;
+        assert!(QuickToggle::UseLocalModel.toggle(&mut settings).is_err());
+        settings.sensevoice.installed = true;
+        assert_eq!(QuickToggle::UseLocalModel.toggle(&mut settings), Ok(true));
+        assert!(settings.provider.is_local());
+        assert_eq!(QuickToggle::UseLocalModel.toggle(&mut settings), Ok(false));
+        assert!(settings.provider == TranscriptionProvider::Volcengine);
+    }
+
+    #[test]
+    fn menu_ids_round_trip() {
+        for toggle in QuickToggle::ALL {
+            assert_eq!(QuickToggle::from_menu_id(toggle.menu_id()), Some(toggle));
+        }
+        assert_eq!(QuickToggle::from_menu_id("quit"), None);
+    }
+}
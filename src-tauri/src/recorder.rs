@@ -1,8 +1,64 @@
+use crate::audio_processing;
+use crate::events::{self, RecordingLevel, RecordingRecovery};
+use crate::sample_spill::{SampleBuffer, SpillFile};
+use crate::settings::{RecordingSettings, RecordingTakeover};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{FromSample, Sample, SampleFormat, Stream, StreamConfig};
+use cpal::{
+    BufferSize, Device, FromSample, Sample, SampleFormat, SampleRate, Stream, StreamConfig,
+    SupportedBufferSize, SupportedStreamConfig, SupportedStreamConfigRange,
+};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU16, AtomicU32, AtomicU64, Ordering};
 use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 use thiserror::Error;
 
+/// 实时电平事件的聚合窗口，兼顾电平表流畅度与前端事件负载
+const LEVEL_WINDOW_MS: usize = 100;
+/// 静音或无样本时的电平下限（dBFS）
+const SILENCE_DB: f32 = -100.0;
+/// 连续达到满幅的样本数达到该值视为一次削波，偶发的单个满幅样本不计入
+const CLIP_RUN_SAMPLES: usize = 4;
+/// 设备偏好开启降噪时噪声门的阈值（dBFS）
+const PROFILE_DENOISE_THRESHOLD_DB: f32 = -45.0;
+/// 协商采集格式时优先使用的采样率，与上传的语音格式一致
+const SPEECH_SAMPLE_RATE: u32 = 16_000;
+/// 录音流出错后重新打开输入设备的尝试次数；系统切换默认设备需要一点时间
+const RECOVER_ATTEMPTS: u32 = 6;
+const RECOVER_RETRY_DELAY: Duration = Duration::from_millis(500);
+/// 系统音频（回环录制）音轨的名称
+pub const SYSTEM_TRACK: &str = "system";
+/// 录音监听最多积压的时长，输出跟不上时丢弃最旧的样本，延迟不会逐渐累积
+const MONITOR_MAX_LATENCY_MS: u32 = 80;
+/// 录音监听缓冲积累到该时长才开始播放，避免输入与输出回调错相时反复欠载产生爆音
+const MONITOR_PREFILL_MS: u32 = 20;
+/// 混入的其他麦克风最多积压的时长；各设备的时钟存在偏差，积压超过上限时丢弃最旧的样本以保持对齐
+const MIX_MAX_LATENCY_MS: u32 = 120;
+/// 混入的其他麦克风缓冲积累到该时长才开始混音，吸收设备间回调时机的差异
+const MIX_PREFILL_MS: u32 = 40;
+
+/// 按采样格式展开 `$build!(样本类型)`，回调中统一转换为 i16；不支持的格式返回 `$unsupported`
+macro_rules! match_sample_format {
+    ($format:expr, $build:ident, $unsupported:expr) => {
+        match $format {
+            cpal::SampleFormat::I8 => $build!(i8),
+            cpal::SampleFormat::I16 => $build!(i16),
+            cpal::SampleFormat::I32 => $build!(i32),
+            cpal::SampleFormat::I64 => $build!(i64),
+            cpal::SampleFormat::U8 => $build!(u8),
+            cpal::SampleFormat::U16 => $build!(u16),
+            cpal::SampleFormat::U32 => $build!(u32),
+            cpal::SampleFormat::U64 => $build!(u64),
+            cpal::SampleFormat::F32 => $build!(f32),
+            cpal::SampleFormat::F64 => $build!(f64),
+            _ => return $unsupported,
+        }
+    };
+}
+pub(crate) use match_sample_format;
+
 #[derive(Debug, Error)]
 pub enum RecorderError {
     #[error("无法获取默认输入设备")]
@@ -15,53 +71,514 @@ pub enum RecorderError {
     NotRecording,
     #[error("录音状态锁异常")]
     LockPoisoned,
+    #[error("录音缓存写入失败: {0}")]
+    Spill(String),
+}
+
+/// 默认输入设备名称；没有可用麦克风时返回 None
+pub fn default_input_device_name() -> Option<String> {
+    cpal::default_host().default_input_device()?.name().ok()
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputDeviceInfo {
+    pub name: String,
+    pub is_default: bool,
+}
+
+/// 列出当前可用的输入设备
+pub fn list_input_devices() -> Result<Vec<InputDeviceInfo>, RecorderError> {
+    let default_name = default_input_device_name();
+    let devices = cpal::default_host()
+        .input_devices()
+        .map_err(|err| RecorderError::Config(err.to_string()))?;
+    Ok(devices
+        .filter_map(|device| device.name().ok())
+        .map(|name| InputDeviceInfo {
+            is_default: default_name.as_deref() == Some(name.as_str()),
+            name,
+        })
+        .collect())
+}
+
+/// 按名称查找输入设备；名称为空或设备已断开时回退到默认输入设备
+pub fn resolve_input_device(name: &str) -> Result<Device, RecorderError> {
+    let name = name.trim();
+    if !name.is_empty() {
+        if let Some(device) = find_input_device(name) {
+            return Ok(device);
+        }
+        #[cfg(debug_assertions)]
+        eprintln!("输入设备 {name} 不可用，改用默认输入设备");
+    }
+    cpal::default_host()
+        .default_input_device()
+        .ok_or(RecorderError::DeviceUnavailable)
+}
+
+/// 按名称查找当前连接的输入设备
+fn find_input_device(name: &str) -> Option<Device> {
+    cpal::default_host()
+        .input_devices()
+        .ok()?
+        .find(|device| device.name().is_ok_and(|device_name| device_name == name))
+}
+
+/// 按设置录音时实际会使用的设备名称；没有可用麦克风时返回 None
+pub fn resolved_input_device_name(name: &str) -> Option<String> {
+    resolve_input_device(name).ok()?.name().ok()
+}
+
+/// 将设置的缓冲区帧数限制在设备支持的范围内，0 表示使用驱动默认值。
+/// WASAPI 等不报告范围的后端直接按设置请求，由驱动换算为对应的缓冲时长
+fn buffer_size(supported: &SupportedBufferSize, buffer_frames: u32) -> BufferSize {
+    match supported {
+        _ if buffer_frames == 0 => BufferSize::Default,
+        SupportedBufferSize::Range { min, max } => {
+            BufferSize::Fixed(buffer_frames.clamp(*min, (*max).max(*min)))
+        }
+        SupportedBufferSize::Unknown => BufferSize::Fixed(buffer_frames),
+    }
+}
+
+/// 协商采集配置：默认优先 16 kHz 单声道 i16，保留原始格式时优先设备默认的采样率与声道数，
+/// 设备偏好指定的采样率优先于两者；设备只提供其他样本格式时选用可转换的格式
+fn input_config(
+    device: &Device,
+    recording: &RecordingSettings,
+    preferred_sample_rate: u32,
+) -> Result<SupportedStreamConfig, RecorderError> {
+    let default_config = device.default_input_config().ok();
+    let target = capture_target(
+        default_config.as_ref(),
+        recording.keep_original_format,
+        preferred_sample_rate,
+    );
+    let ranges = device
+        .supported_input_configs()
+        .map(|ranges| ranges.collect::<Vec<_>>())
+        .unwrap_or_default();
+    if let Some(config) = negotiate_config(&ranges, target) {
+        return Ok(config);
+    }
+    match default_config {
+        Some(config) if is_supported_format(config.sample_format()) => Ok(config),
+        Some(_) => Err(RecorderError::Config("不支持的采样格式".to_string())),
+        None => Err(RecorderError::Config("设备没有可用的输入配置".to_string())),
+    }
+}
+
+/// 期望的采样率与声道数
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct CaptureTarget {
+    sample_rate: u32,
+    channels: u16,
+}
+
+fn capture_target(
+    default_config: Option<&SupportedStreamConfig>,
+    keep_original_format: bool,
+    preferred_sample_rate: u32,
+) -> CaptureTarget {
+    let mut target = match default_config {
+        Some(config) if keep_original_format => CaptureTarget {
+            sample_rate: config.sample_rate().0,
+            channels: config.channels(),
+        },
+        _ => CaptureTarget {
+            sample_rate: SPEECH_SAMPLE_RATE,
+            channels: 1,
+        },
+    };
+    if preferred_sample_rate != 0 {
+        target.sample_rate = preferred_sample_rate;
+    }
+    target
+}
+
+/// 在设备支持的配置中按采样率、声道数、样本格式的优先级选出最接近目标的配置；
+/// 采样率不在范围内时取范围内最接近的值
+fn negotiate_config(
+    ranges: &[SupportedStreamConfigRange],
+    target: CaptureTarget,
+) -> Option<SupportedStreamConfig> {
+    let range = ranges
+        .iter()
+        .filter(|range| is_supported_format(range.sample_format()))
+        .min_by_key(|range| {
+            let rate_range = range.min_sample_rate().0..=range.max_sample_rate().0;
+            (
+                !rate_range.contains(&target.sample_rate),
+                range.channels().abs_diff(target.channels),
+                format_rank(range.sample_format()),
+                clamp_rate(range, target.sample_rate).abs_diff(target.sample_rate),
+            )
+        })?;
+    let sample_rate = clamp_rate(range, target.sample_rate);
+    Some(range.clone().with_sample_rate(SampleRate(sample_rate)))
+}
+
+fn clamp_rate(range: &SupportedStreamConfigRange, sample_rate: u32) -> u32 {
+    let min = range.min_sample_rate().0;
+    sample_rate.clamp(min, range.max_sample_rate().0.max(min))
+}
+
+/// 样本格式的偏好次序，越小越优先：i16 无需转换，其余按精度与常见程度排列
+fn format_rank(format: SampleFormat) -> u8 {
+    match format {
+        SampleFormat::I16 => 0,
+        SampleFormat::F32 => 1,
+        SampleFormat::I32 => 2,
+        SampleFormat::U16 => 3,
+        SampleFormat::F64 => 4,
+        SampleFormat::I64 | SampleFormat::U32 | SampleFormat::U64 => 5,
+        _ => 6,
+    }
+}
+
+fn is_supported_format(format: SampleFormat) -> bool {
+    matches!(
+        format,
+        SampleFormat::I8
+            | SampleFormat::I16
+            | SampleFormat::I32
+            | SampleFormat::I64
+            | SampleFormat::U8
+            | SampleFormat::U16
+            | SampleFormat::U32
+            | SampleFormat::U64
+            | SampleFormat::F32
+            | SampleFormat::F64
+    )
+}
+
+/// 录音电平统计，供静音检测在录音线程之外读取
+#[derive(Default)]
+pub struct LevelMeter {
+    /// 上次读取以来的采样峰值（绝对值）
+    peak: AtomicU16,
+    /// 每次开始录音递增，用于区分不同的录音会话
+    session: AtomicU64,
+    active: AtomicBool,
+    /// 暂停期间采集到的样本直接丢弃
+    paused: AtomicBool,
+    /// 录音已达到时长上限，之后的样本不再写入
+    limit_reached: AtomicBool,
+    /// 最近一个电平窗口的 RMS 电平（dBFS，按 f32 位存储）
+    rms_db: AtomicU32,
+    /// 上次读取以来的削波次数
+    clip_runs: AtomicU32,
+}
+
+impl LevelMeter {
+    /// 读取并清零上次读取以来的峰值
+    pub fn take_peak(&self) -> u16 {
+        self.peak.swap(0, Ordering::Relaxed)
+    }
+
+    /// 读取并清零上次读取以来的削波次数
+    pub fn take_clip_runs(&self) -> u32 {
+        self.clip_runs.swap(0, Ordering::Relaxed)
+    }
+
+    pub fn session(&self) -> u64 {
+        self.session.load(Ordering::SeqCst)
+    }
+
+    /// 指定会话是否仍在录音
+    pub fn is_active(&self, session: u64) -> bool {
+        self.active.load(Ordering::SeqCst) && self.session() == session
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    pub fn limit_reached(&self) -> bool {
+        self.limit_reached.load(Ordering::SeqCst)
+    }
+
+    /// 最近一个电平窗口的 RMS 电平（dBFS）
+    pub fn rms_db(&self) -> f32 {
+        f32::from_bits(self.rms_db.load(Ordering::Relaxed))
+    }
+
+    fn begin(&self) {
+        self.peak.store(0, Ordering::Relaxed);
+        self.clip_runs.store(0, Ordering::Relaxed);
+        self.session.fetch_add(1, Ordering::SeqCst);
+        self.paused.store(false, Ordering::SeqCst);
+        self.limit_reached.store(false, Ordering::SeqCst);
+        self.set_rms_db(SILENCE_DB);
+        self.active.store(true, Ordering::SeqCst);
+    }
+
+    fn end(&self) {
+        self.active.store(false, Ordering::SeqCst);
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    fn set_rms_db(&self, rms_db: f32) {
+        self.rms_db.store(rms_db.to_bits(), Ordering::Relaxed);
+    }
+
+    fn record(&self, samples: &[i16]) {
+        let peak = samples
+            .iter()
+            .map(|sample| sample.unsigned_abs())
+            .max()
+            .unwrap_or(0);
+        self.peak.fetch_max(peak, Ordering::Relaxed);
+        let clip_runs = count_clip_runs(samples);
+        if clip_runs > 0 {
+            self.clip_runs.fetch_add(clip_runs, Ordering::Relaxed);
+        }
+    }
+}
+
+/// 统计一个回调块中连续满幅样本段的数量；多声道按交错样本整体统计，削波时各声道通常同时满幅
+fn count_clip_runs(samples: &[i16]) -> u32 {
+    let mut runs = 0;
+    let mut run = 0;
+    for sample in samples {
+        if sample.unsigned_abs() >= i16::MAX as u16 {
+            run += 1;
+            if run == CLIP_RUN_SAMPLES {
+                runs += 1;
+            }
+        } else {
+            run = 0;
+        }
+    }
+    runs
 }
 
 #[derive(Clone)]
 pub struct Recorder {
     inner: Arc<Mutex<RecorderInner>>,
+    meter: Arc<LevelMeter>,
+    /// 录音线程的命令通道，录音流的错误回调经此交回录音线程处理
+    commands: mpsc::Sender<RecorderCommand>,
 }
 
 struct RecorderInner {
+    /// 录音流；出错失效后为 None，此时录音仍在进行（`config` 非空）并等待重新打开
     stream: Option<Stream>,
-    buffer: Arc<Mutex<Vec<i16>>>,
+    /// 当前录音流的编号，用于忽略已关闭流迟到的错误
+    stream_id: u64,
+    /// 录音流最近一次的错误，重新打开成功后清除
+    stream_error: Option<String>,
+    /// 录音流回调写入的目标，未在录音时为 None
+    sink: Option<CaptureSink>,
     config: Option<StreamConfig>,
+    /// 本次录音的设置，录音流出错后据此重新打开设备
+    recording: Option<RecordingSettings>,
+    device_name: String,
+    /// 与麦克风同步的系统音频采集，未开启或当前平台不支持时为 None
+    system_capture: Option<SystemCapture>,
+    /// 录音监听的回放流，未开启或打开输出设备失败时为 None
+    monitor: Option<Monitor>,
+    /// 混入本次录音的其他麦克风
+    mix_inputs: Vec<MixInput>,
+    /// 未录音时保持的预录输入流
+    pre_roll: Option<PreRoll>,
+    /// 开启预录时的录音设置，结束录音后据此重新打开预录
+    pre_roll_settings: Option<RecordingSettings>,
+    /// 未录音时保持预热的录音流
+    warm: Option<CaptureStream>,
+    /// 开启预热时的录音设置；开启后由预热流的空闲回调代替预录流
+    warm_settings: Option<RecordingSettings>,
+}
+
+/// 预录：未录音时保持一条只写内存环形缓冲的输入流，开始录音时将其中最近的音频拼接到录音开头。
+/// 缓冲中的音频不写入磁盘、不上传，关闭预录或超出时长即丢弃
+struct PreRoll {
+    _stream: Stream,
+    ring: Arc<Mutex<PreRollRing>>,
+    device_name: String,
+    sample_rate: u32,
+    channels: u16,
+}
+
+impl PreRoll {
+    /// 取出缓冲中的音频；与新录音的设备或格式不一致时丢弃
+    fn take_samples(&self, device_name: &str, config: &StreamConfig) -> Vec<i16> {
+        if self.device_name != device_name
+            || self.sample_rate != config.sample_rate.0
+            || self.channels != config.channels
+        {
+            return Vec::new();
+        }
+        match self.ring.lock() {
+            Ok(mut ring) => ring.take(usize::from(self.channels)),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+/// 只保留最近 `capacity` 个样本的环形缓冲
+struct PreRollRing {
+    samples: VecDeque<i16>,
+    capacity: usize,
+}
+
+impl PreRollRing {
+    fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// 按流格式保留最近 `duration_ms` 的音频
+    fn for_duration(config: &StreamConfig, duration_ms: u32) -> Self {
+        let frames = config.sample_rate.0 as usize * duration_ms as usize / 1000;
+        Self::new(frames * usize::from(config.channels))
+    }
+
+    fn push(&mut self, samples: impl IntoIterator<Item = i16>) {
+        for sample in samples {
+            if self.samples.len() >= self.capacity {
+                self.samples.pop_front();
+            }
+            self.samples.push_back(sample);
+        }
+    }
+
+    /// 取出全部样本，丢弃开头不完整的帧
+    fn take(&mut self, channels: usize) -> Vec<i16> {
+        let partial = self.samples.len() % channels.max(1);
+        self.samples.drain(..).skip(partial).collect()
+    }
+}
+
+/// 按设置解析出的输入设备与采集参数
+struct InputSetup {
+    device: Device,
+    device_name: String,
+    sample_format: SampleFormat,
+    config: StreamConfig,
+    gain: f32,
+    denoise: bool,
+}
+
+impl InputSetup {
+    fn resolve(recording: &RecordingSettings) -> Result<Self, RecorderError> {
+        let device = resolve_input_device(&recording.input_device)?;
+        let device_name = device.name().unwrap_or_default();
+        let profile = recording.device_profile(&device_name).cloned();
+        let input_config = input_config(
+            &device,
+            recording,
+            profile.as_ref().map_or(0, |profile| profile.sample_rate),
+        )?;
+        let mut config: StreamConfig = input_config.clone().into();
+        config.buffer_size = buffer_size(input_config.buffer_size(), recording.buffer_frames);
+        Ok(Self {
+            device,
+            device_name,
+            sample_format: input_config.sample_format(),
+            config,
+            gain: profile
+                .as_ref()
+                .map_or(1.0, |profile| 10f32.powf(profile.gain_db / 20.0)),
+            denoise: profile.as_ref().is_some_and(|profile| profile.denoise),
+        })
+    }
 }
 
 pub struct RecorderService {
     sender: mpsc::Sender<RecorderCommand>,
+    meter: Arc<LevelMeter>,
+}
+
+/// 开始录音的结果
+pub struct RecordingStart {
+    /// 实际使用的输入设备名称
+    pub device_name: String,
+    /// 是否开始了新的录音；按忽略策略沿用当前录音时为 false
+    pub started: bool,
+    /// 按排队策略结束的上一段录音，由调用方送入转写队列
+    pub previous: Option<RecordedAudio>,
+    /// 与上次录音所用设备不同（含首次录音），如系统默认设备在空闲时发生了切换
+    pub device_changed: bool,
 }
 
 enum RecorderCommand {
-    Start(mpsc::Sender<Result<(), RecorderError>>),
+    Start(
+        RecordingTakeover,
+        RecordingSettings,
+        mpsc::Sender<Result<RecordingStart, RecorderError>>,
+    ),
     Stop(mpsc::Sender<Result<RecordedAudio, RecorderError>>),
+    Pause(mpsc::Sender<Result<String, RecorderError>>),
+    Resume(mpsc::Sender<Result<String, RecorderError>>),
+    ConfigureIdleStreams(RecordingSettings),
+    /// 录音流错误回调上报的错误：（流编号，错误信息）
+    StreamError(u64, String),
+    /// 重新打开出错的录音流：（出错的流编号，第几次尝试）
+    Recover(u64, u32),
 }
 
 impl RecorderService {
     pub fn new() -> Self {
         let (sender, receiver) = mpsc::channel::<RecorderCommand>();
+        let meter = Arc::new(LevelMeter::default());
+        let recorder_meter = meter.clone();
+        let commands = sender.clone();
         std::thread::spawn(move || {
-            let recorder = Recorder::new();
+            let recorder = Recorder::new(recorder_meter, commands);
             loop {
                 match receiver.recv() {
-                    Ok(RecorderCommand::Start(reply)) => {
-                        let result = recorder.start();
+                    Ok(RecorderCommand::Start(takeover, recording, reply)) => {
+                        let result = recorder.start(takeover, &recording);
                         let _ = reply.send(result);
                     }
                     Ok(RecorderCommand::Stop(reply)) => {
                         let result = recorder.stop();
                         let _ = reply.send(result);
                     }
+                    Ok(RecorderCommand::Pause(reply)) => {
+                        let result = recorder.set_paused(true);
+                        let _ = reply.send(result);
+                    }
+                    Ok(RecorderCommand::Resume(reply)) => {
+                        let result = recorder.set_paused(false);
+                        let _ = reply.send(result);
+                    }
+                    Ok(RecorderCommand::ConfigureIdleStreams(recording)) => {
+                        if let Err(_err) = recorder.configure_idle_streams(&recording) {
+                            #[cfg(debug_assertions)]
+                            eprintln!("打开空闲输入流失败: {_err}");
+                        }
+                    }
+                    Ok(RecorderCommand::StreamError(stream_id, message)) => {
+                        recorder.handle_stream_error(stream_id, message);
+                    }
+                    Ok(RecorderCommand::Recover(stream_id, attempt)) => {
+                        recorder.recover(stream_id, attempt);
+                    }
                     Err(_) => break,
                 }
             }
         });
-        Self { sender }
+        Self { sender, meter }
     }
 
-    pub fn start(&self) -> Result<(), RecorderError> {
+    /// 从设置的输入设备（为空时用默认设备）开始录音并应用该设备的偏好；
+    /// 已在录音时按 `takeover` 策略处理当前录音
+    pub fn start(
+        &self,
+        takeover: RecordingTakeover,
+        recording: &RecordingSettings,
+    ) -> Result<RecordingStart, RecorderError> {
         let (reply_tx, reply_rx) = mpsc::channel();
-        let _ = self.sender.send(RecorderCommand::Start(reply_tx));
+        let _ = self.sender.send(RecorderCommand::Start(
+            takeover,
+            recording.clone(),
+            reply_tx,
+        ));
         reply_rx.recv().unwrap_or(Err(RecorderError::NotRecording))
     }
 
@@ -70,106 +587,1480 @@ impl RecorderService {
         let _ = self.sender.send(RecorderCommand::Stop(reply_tx));
         reply_rx.recv().unwrap_or(Err(RecorderError::NotRecording))
     }
+
+    /// 暂停当前录音，返回录音设备名称
+    pub fn pause(&self) -> Result<String, RecorderError> {
+        let (reply_tx, reply_rx) = mpsc::channel();
+        let _ = self.sender.send(RecorderCommand::Pause(reply_tx));
+        reply_rx.recv().unwrap_or(Err(RecorderError::NotRecording))
+    }
+
+    /// 继续已暂停的录音，返回录音设备名称
+    pub fn resume(&self) -> Result<String, RecorderError> {
+        let (reply_tx, reply_rx) = mpsc::channel();
+        let _ = self.sender.send(RecorderCommand::Resume(reply_tx));
+        reply_rx.recv().unwrap_or(Err(RecorderError::NotRecording))
+    }
+
+    /// 按设置开启或关闭预录与预热录音流；设备或时长变化时重新打开
+    pub fn configure_idle_streams(&self, recording: &RecordingSettings) {
+        let _ = self
+            .sender
+            .send(RecorderCommand::ConfigureIdleStreams(recording.clone()));
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.meter.active.load(Ordering::SeqCst)
+    }
+
+    pub fn level_meter(&self) -> Arc<LevelMeter> {
+        self.meter.clone()
+    }
 }
 
 impl Recorder {
-    pub fn new() -> Self {
+    fn new(meter: Arc<LevelMeter>, commands: mpsc::Sender<RecorderCommand>) -> Self {
         Self {
             inner: Arc::new(Mutex::new(RecorderInner {
                 stream: None,
-                buffer: Arc::new(Mutex::new(Vec::new())),
+                stream_id: 0,
+                stream_error: None,
+                sink: None,
                 config: None,
+                recording: None,
+                device_name: String::new(),
+                system_capture: None,
+                monitor: None,
+                mix_inputs: Vec::new(),
+                pre_roll: None,
+                pre_roll_settings: None,
+                warm: None,
+                warm_settings: None,
             })),
+            meter,
+            commands,
         }
     }
 
-    pub fn start(&self) -> Result<(), RecorderError> {
+    pub fn start(
+        &self,
+        takeover: RecordingTakeover,
+        recording: &RecordingSettings,
+    ) -> Result<RecordingStart, RecorderError> {
         let inner = self.inner.lock().map_err(|_| RecorderError::LockPoisoned)?;
-        if inner.stream.is_some() {
-            return Ok(());
-        }
+        let active_device = inner.config.is_some().then(|| inner.device_name.clone());
+        let last_device = inner.device_name.clone();
         drop(inner);
 
-        let host = cpal::default_host();
-        let device = host
-            .default_input_device()
-            .ok_or(RecorderError::DeviceUnavailable)?;
-        let input_config = device
-            .default_input_config()
-            .map_err(|err| RecorderError::Config(err.to_string()))?;
-        let config: StreamConfig = input_config.clone().into();
+        let mut previous = None;
+        if let Some(device_name) = active_device {
+            match takeover {
+                RecordingTakeover::Ignore => {
+                    return Ok(RecordingStart {
+                        device_name,
+                        started: false,
+                        previous: None,
+                        device_changed: false,
+                    });
+                }
+                RecordingTakeover::Restart => {
+                    self.finish()?;
+                }
+                RecordingTakeover::Queue => previous = Some(self.finish()?),
+            }
+        }
+        // 每次开始录音都重新解析设备，不沿用上次录音的设备；失败时恢复空闲输入流
+        let device_name = self.open_stream(recording).inspect_err(|_| {
+            let _ = self.open_idle_streams();
+        })?;
+        Ok(RecordingStart {
+            device_changed: device_name != last_device,
+            device_name,
+            started: true,
+            previous,
+        })
+    }
 
-        let buffer = Arc::new(Mutex::new(Vec::new()));
-        let buffer_clone = buffer.clone();
-        let err_fn = |_err| {
-            #[cfg(debug_assertions)]
-            eprintln!("录音流错误: {_err}");
+    /// 打开输入设备并开始采集，返回实际使用的设备名称
+    fn open_stream(&self, recording: &RecordingSettings) -> Result<String, RecorderError> {
+        if let Some(device_name) = self.start_warm(recording)? {
+            return Ok(device_name);
+        }
+        let setup = InputSetup::resolve(recording)?;
+        let mut pre_roll = self
+            .inner
+            .lock()
+            .map_err(|_| RecorderError::LockPoisoned)?
+            .pre_roll
+            .take();
+        let mut pre_roll_samples = Vec::new();
+        let capture = match self.open_capture(&setup, recording, true) {
+            Ok(capture) => capture,
+            // 独占访问的设备不能同时打开两条输入流，先关闭预录再重试
+            Err(_) if pre_roll.is_some() => {
+                if let Some(pre_roll) = pre_roll.take() {
+                    pre_roll_samples = pre_roll.take_samples(&setup.device_name, &setup.config);
+                }
+                self.open_capture(&setup, recording, true)?
+            }
+            Err(err) => return Err(err),
         };
+        // 预录流在新录音开始采集后才关闭，两者之间只有很短的间隙
+        if let Some(pre_roll) = pre_roll.as_ref() {
+            pre_roll_samples = pre_roll.take_samples(&capture.device_name, &capture.config);
+        }
+        self.begin_capture(capture, recording, pre_roll_samples, || drop(pre_roll))
+    }
 
-        macro_rules! build_stream {
-            ($sample_type:ty) => {
-                device
-                    .build_input_stream(
-                        &config,
-                        move |data: &[$sample_type], _| push_samples(data, &buffer_clone),
-                        err_fn,
-                        None,
-                    )
-                    .map_err(|err| RecorderError::Stream(err.to_string()))?
-            };
+    /// 预热的录音流与本次录音设置一致时直接切换到新缓冲区开始写入，返回设备名称；
+    /// 不一致时关闭预热流并返回 None，由调用方重新打开
+    fn start_warm(&self, recording: &RecordingSettings) -> Result<Option<String>, RecorderError> {
+        let Some(warm) = self
+            .inner
+            .lock()
+            .map_err(|_| RecorderError::LockPoisoned)?
+            .warm
+            .take()
+        else {
+            return Ok(None);
+        };
+        // 跟随系统默认设备时，空闲期间默认设备可能已经切换
+        let default_changed = recording.input_device.trim().is_empty()
+            && default_input_device_name().as_deref() != Some(warm.device_name.as_str());
+        if default_changed || !same_stream_settings(&warm.recording, recording) {
+            return Ok(None);
         }
+        let channels = usize::from(warm.config.channels.max(1));
+        let pre_roll_samples = match warm.sink.idle_ring.as_deref().map(Mutex::lock) {
+            Some(Ok(mut ring)) => ring.take(channels),
+            _ => Vec::new(),
+        };
+        let denoise = recording
+            .device_profile(&warm.device_name)
+            .is_some_and(|profile| profile.denoise);
+        *warm
+            .sink
+            .buffer
+            .lock()
+            .map_err(|_| RecorderError::LockPoisoned)? =
+            new_buffer(&warm.config, recording, denoise);
+        self.begin_capture(warm, recording, pre_roll_samples, || {})
+            .map(Some)
+    }
 
-        let stream = match input_config.sample_format() {
-            SampleFormat::I16 => build_stream!(i16),
-            SampleFormat::U16 => build_stream!(u16),
-            SampleFormat::F32 => build_stream!(f32),
-            _ => return Err(RecorderError::Config("不支持的采样格式".to_string())),
+    /// 打开录音流但不开始播放；`armed` 为 false 时为预热的空闲流，回调只写入预录缓冲
+    fn open_capture(
+        &self,
+        setup: &InputSetup,
+        recording: &RecordingSettings,
+        armed: bool,
+    ) -> Result<CaptureStream, RecorderError> {
+        let mut config = setup.config.clone();
+        // 系统音频回环录制的就是默认输出设备，监听的回放会混入对方音轨，两者不同时开启
+        let monitor = (recording.monitor && !recording.capture_system_audio)
+            .then(|| {
+                Monitor::open(&config, recording.monitor_volume as f32 / 100.0)
+                    .inspect_err(|_err| {
+                        #[cfg(debug_assertions)]
+                        eprintln!("打开录音监听失败: {_err}");
+                    })
+                    .ok()
+            })
+            .flatten();
+        let mix_inputs = open_mix_inputs(recording, &setup.device_name, &config);
+        let sink = CaptureSink {
+            buffer: Arc::new(Mutex::new(new_buffer(&config, recording, setup.denoise))),
+            gain: setup.gain,
+            max_samples: max_samples(&config, recording),
+            channels: usize::from(config.channels.max(1)),
+            monitor: monitor.as_ref().map(|monitor| monitor.feed.clone()),
+            mix: mix_inputs.iter().map(|input| input.feed.clone()).collect(),
+            armed: Arc::new(AtomicBool::new(armed)),
+            idle_ring: (recording.keep_stream_warm
+                && recording.pre_roll
+                && recording.pre_roll_ms > 0)
+                .then(|| {
+                    Arc::new(Mutex::new(PreRollRing::for_duration(
+                        &config,
+                        recording.pre_roll_ms,
+                    )))
+                }),
         };
+        let stream_id = self.next_stream_id()?;
+        let stream = self.build_stream(
+            &setup.device,
+            setup.sample_format,
+            &mut config,
+            &sink,
+            stream_id,
+        )?;
+        Ok(CaptureStream {
+            stream,
+            stream_id,
+            sink,
+            config,
+            device_name: setup.device_name.clone(),
+            monitor,
+            mix_inputs,
+            recording: recording.clone(),
+        })
+    }
 
+    /// 把 `lead` 拼接到录音开头后开始写入；`release` 在录音流开始采集后调用，用于关闭预录流
+    fn begin_capture(
+        &self,
+        capture: CaptureStream,
+        recording: &RecordingSettings,
+        lead: Vec<i16>,
+        release: impl FnOnce(),
+    ) -> Result<String, RecorderError> {
+        let CaptureStream {
+            stream,
+            stream_id,
+            sink,
+            config,
+            device_name,
+            monitor,
+            mix_inputs,
+            ..
+        } = capture;
+        let lead_in_frames = lead.len() / usize::from(config.channels.max(1));
+        if let Ok(mut buffer) = sink.buffer.lock() {
+            buffer.extend(lead.into_iter().take(sink.max_samples));
+        }
+        sink.armed.store(true, Ordering::SeqCst);
         stream
             .play()
             .map_err(|err| RecorderError::Stream(err.to_string()))?;
+        release();
+        let system_capture = recording.capture_system_audio.then(|| {
+            let lead_in = Duration::from_millis(
+                lead_in_frames as u64 * 1000 / u64::from(config.sample_rate.0.max(1)),
+            );
+            SystemCapture::open(recording, &self.meter, lead_in)
+                .inspect_err(|_err| {
+                    #[cfg(debug_assertions)]
+                    eprintln!("打开系统音频采集失败，仅录制麦克风: {_err}");
+                })
+                .ok()
+        });
 
         let mut inner = self.inner.lock().map_err(|_| RecorderError::LockPoisoned)?;
         inner.stream = Some(stream);
-        inner.buffer = buffer;
+        inner.stream_id = stream_id;
+        inner.stream_error = None;
+        inner.sink = Some(sink);
         inner.config = Some(config);
-        Ok(())
+        inner.recording = Some(recording.clone());
+        inner.device_name = device_name.clone();
+        inner.system_capture = system_capture.flatten();
+        inner.monitor = monitor;
+        inner.mix_inputs = mix_inputs;
+        self.meter.begin();
+        Ok(device_name)
     }
 
-    pub fn stop(&self) -> Result<RecordedAudio, RecorderError> {
-        let mut inner = self.inner.lock().map_err(|_| RecorderError::LockPoisoned)?;
-        let Some(config) = inner.config.clone() else {
-            return Err(RecorderError::NotRecording);
+    /// 新录音流的编号；仅在流打开成功后写回
+    fn next_stream_id(&self) -> Result<u64, RecorderError> {
+        let inner = self.inner.lock().map_err(|_| RecorderError::LockPoisoned)?;
+        Ok(inner.stream_id + 1)
+    }
+
+    /// 打开采集流；驱动不接受设置的缓冲区时回退到默认值，不因低延迟设置导致无法录音
+    fn build_stream(
+        &self,
+        device: &Device,
+        sample_format: SampleFormat,
+        config: &mut StreamConfig,
+        sink: &CaptureSink,
+        stream_id: u64,
+    ) -> Result<Stream, RecorderError> {
+        let build = |config: &StreamConfig| {
+            let err_fn = self.stream_error_callback(stream_id);
+            build_capture_stream(device, sample_format, config, sink, &self.meter, err_fn)
         };
-        let buffer = inner
-            .buffer
+        match build(config) {
+            Ok(stream) => Ok(stream),
+            Err(_) if config.buffer_size != BufferSize::Default => {
+                config.buffer_size = BufferSize::Default;
+                build(config)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// 录音流的错误回调：每条流只上报一次，交回录音线程处理
+    fn stream_error_callback(
+        &self,
+        stream_id: u64,
+    ) -> impl FnMut(cpal::StreamError) + Send + 'static {
+        let commands = self.commands.clone();
+        let mut reported = false;
+        move |err| {
+            #[cfg(debug_assertions)]
+            eprintln!("录音流错误: {err}");
+            if !reported {
+                reported = true;
+                let _ = commands.send(RecorderCommand::StreamError(stream_id, err.to_string()));
+            }
+        }
+    }
+
+    /// 录音流出错（如蓝牙耳机断开）：保留已录到的音频并通知前端，随后尝试在当前可用设备上继续录音
+    fn handle_stream_error(&self, stream_id: u64, message: String) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        // 预热的空闲流失效时直接关闭，下次录音重新打开
+        if inner
+            .warm
+            .as_ref()
+            .is_some_and(|warm| warm.stream_id == stream_id)
+        {
+            inner.warm = None;
+            return;
+        }
+        if inner.stream_id != stream_id || inner.stream.is_none() {
+            return;
+        }
+        // 失效的流不再产生数据，重新打开的流继续写入同一缓冲区
+        inner.stream = None;
+        inner.stream_error = Some(message.clone());
+        let device_name = inner.device_name.clone();
+        drop(inner);
+        events::emit_recording_error(&device_name, &message, RecordingRecovery::Reconnecting);
+        self.recover(stream_id, 1);
+    }
+
+    /// 重新打开出错的录音流；失败时稍后重试，录音已结束或已恢复时放弃
+    fn recover(&self, stream_id: u64, attempt: u32) {
+        let Ok(inner) = self.inner.lock() else {
+            return;
+        };
+        if inner.stream_id != stream_id || inner.stream.is_some() {
+            return;
+        }
+        let (Some(recording), Some(config)) = (inner.recording.clone(), inner.config.clone())
+        else {
+            return;
+        };
+        drop(inner);
+        match self.reopen_stream(&recording, &config) {
+            Ok(device_name) => {
+                events::emit_recording_error(&device_name, "", RecordingRecovery::Recovered);
+                events::emit_recording_source(&device_name, false);
+                events::emit_input_device_changed(&device_name);
+            }
+            Err(_) if attempt < RECOVER_ATTEMPTS => {
+                let commands = self.commands.clone();
+                thread::spawn(move || {
+                    thread::sleep(RECOVER_RETRY_DELAY);
+                    let _ = commands.send(RecorderCommand::Recover(stream_id, attempt + 1));
+                });
+            }
+            Err(err) => {
+                let Ok(inner) = self.inner.lock() else {
+                    return;
+                };
+                let message = match inner.stream_error.as_deref() {
+                    Some(cause) => format!("{cause}；{err}"),
+                    None => err.to_string(),
+                };
+                events::emit_recording_error(
+                    &inner.device_name,
+                    &message,
+                    RecordingRecovery::Failed,
+                );
+            }
+        }
+    }
+
+    /// 在当前可用的设备上重新打开录音流，沿用本次录音的采样率、声道数与缓冲区
+    fn reopen_stream(
+        &self,
+        recording: &RecordingSettings,
+        format: &StreamConfig,
+    ) -> Result<String, RecorderError> {
+        let device = resolve_input_device(&recording.input_device)?;
+        let device_name = device.name().unwrap_or_default();
+        let target = CaptureTarget {
+            sample_rate: format.sample_rate.0,
+            channels: format.channels,
+        };
+        let ranges = device
+            .supported_input_configs()
+            .map(|ranges| ranges.collect::<Vec<_>>())
+            .unwrap_or_default();
+        let input_config = negotiate_config(&ranges, target)
+            .filter(|config| {
+                config.sample_rate().0 == target.sample_rate && config.channels() == target.channels
+            })
+            .ok_or_else(|| {
+                RecorderError::Config(format!("{device_name} 不支持本次录音的采样率与声道数"))
+            })?;
+        let mut config: StreamConfig = input_config.clone().into();
+        config.buffer_size = buffer_size(input_config.buffer_size(), recording.buffer_frames);
+        let stream_id = self.next_stream_id()?;
+        let sink = self
+            .inner
             .lock()
             .map_err(|_| RecorderError::LockPoisoned)?
-            .clone();
-        inner.stream.take();
-        inner.config = None;
-        Ok(RecordedAudio {
-            samples: buffer,
+            .sink
+            .clone()
+            .ok_or(RecorderError::NotRecording)?;
+        let sink = CaptureSink {
+            gain: recording
+                .device_profile(&device_name)
+                .map_or(1.0, |profile| 10f32.powf(profile.gain_db / 20.0)),
+            max_samples: max_samples(format, recording),
+            ..sink
+        };
+        let stream = self.build_stream(
+            &device,
+            input_config.sample_format(),
+            &mut config,
+            &sink,
+            stream_id,
+        )?;
+        stream
+            .play()
+            .map_err(|err| RecorderError::Stream(err.to_string()))?;
+
+        let mut inner = self.inner.lock().map_err(|_| RecorderError::LockPoisoned)?;
+        // 等待重试期间录音可能已结束
+        if inner.config.is_none() {
+            return Err(RecorderError::NotRecording);
+        }
+        inner.stream = Some(stream);
+        inner.stream_id = stream_id;
+        inner.stream_error = None;
+        inner.device_name = device_name.clone();
+        Ok(device_name)
+    }
+
+    /// 暂停或继续采集；流保持打开，暂停期间的样本不写入录音
+    pub fn set_paused(&self, paused: bool) -> Result<String, RecorderError> {
+        let inner = self.inner.lock().map_err(|_| RecorderError::LockPoisoned)?;
+        if inner.config.is_none() {
+            return Err(RecorderError::NotRecording);
+        }
+        self.meter.paused.store(paused, Ordering::SeqCst);
+        Ok(inner.device_name.clone())
+    }
+
+    /// 结束录音并恢复空闲输入流
+    pub fn stop(&self) -> Result<RecordedAudio, RecorderError> {
+        let audio = self.finish()?;
+        if let Err(_err) = self.open_idle_streams() {
+            #[cfg(debug_assertions)]
+            eprintln!("打开空闲输入流失败: {_err}");
+        }
+        Ok(audio)
+    }
+
+    /// 记录预录与预热设置；未在录音时立即按新设置重新打开或关闭空闲输入流，录音中则在结束后生效
+    pub fn configure_idle_streams(
+        &self,
+        recording: &RecordingSettings,
+    ) -> Result<(), RecorderError> {
+        let mut inner = self.inner.lock().map_err(|_| RecorderError::LockPoisoned)?;
+        inner.pre_roll_settings =
+            (recording.pre_roll && recording.pre_roll_ms > 0).then(|| recording.clone());
+        inner.warm_settings = recording.keep_stream_warm.then(|| recording.clone());
+        inner.pre_roll = None;
+        inner.warm = None;
+        if inner.config.is_some() {
+            return Ok(());
+        }
+        drop(inner);
+        self.open_idle_streams()
+    }
+
+    /// 按记录的设置打开未录音时保持的输入流：开启预热时保持录音流，否则按需打开预录流
+    fn open_idle_streams(&self) -> Result<(), RecorderError> {
+        let inner = self.inner.lock().map_err(|_| RecorderError::LockPoisoned)?;
+        if inner.config.is_some() || inner.warm.is_some() {
+            return Ok(());
+        }
+        let warm_settings = inner.warm_settings.clone();
+        drop(inner);
+        let Some(recording) = warm_settings else {
+            return self.open_pre_roll();
+        };
+        let capture = self.open_capture(&InputSetup::resolve(&recording)?, &recording, false)?;
+        capture
+            .stream
+            .play()
+            .map_err(|err| RecorderError::Stream(err.to_string()))?;
+        let mut inner = self.inner.lock().map_err(|_| RecorderError::LockPoisoned)?;
+        // 打开期间可能已开始录音
+        if inner.config.is_none() {
+            inner.stream_id = capture.stream_id;
+            inner.warm = Some(capture);
+        }
+        Ok(())
+    }
+
+    /// 按记录的设置打开预录流；未开启预录或已在录音时不做处理
+    fn open_pre_roll(&self) -> Result<(), RecorderError> {
+        let inner = self.inner.lock().map_err(|_| RecorderError::LockPoisoned)?;
+        let Some(recording) = inner.pre_roll_settings.clone() else {
+            return Ok(());
+        };
+        if inner.config.is_some() || inner.pre_roll.is_some() {
+            return Ok(());
+        }
+        drop(inner);
+
+        let InputSetup {
+            device,
+            device_name,
+            sample_format,
+            mut config,
+            gain,
+            ..
+        } = InputSetup::resolve(&recording)?;
+        // 预录只需低负载地持续写入内存，使用驱动默认缓冲区
+        config.buffer_size = BufferSize::Default;
+        let ring = Arc::new(Mutex::new(PreRollRing::for_duration(
+            &config,
+            recording.pre_roll_ms,
+        )));
+        let err_fn = |_err| {
+            #[cfg(debug_assertions)]
+            eprintln!("预录输入流错误: {_err}");
+        };
+
+        macro_rules! build_stream {
+            ($sample_type:ty) => {{
+                let ring = ring.clone();
+                device.build_input_stream(
+                    &config,
+                    move |data: &[$sample_type], _| {
+                        if let Ok(mut ring) = ring.lock() {
+                            ring.push(
+                                data.iter()
+                                    .map(|sample| apply_gain(i16::from_sample(*sample), gain)),
+                            );
+                        }
+                    },
+                    err_fn,
+                    None,
+                )
+            }};
+        }
+
+        let stream = match_sample_format!(
+            sample_format,
+            build_stream,
+            Err(RecorderError::Config("不支持的采样格式".to_string()))
+        )
+        .map_err(|err| RecorderError::Stream(err.to_string()))?;
+        stream
+            .play()
+            .map_err(|err| RecorderError::Stream(err.to_string()))?;
+
+        let mut inner = self.inner.lock().map_err(|_| RecorderError::LockPoisoned)?;
+        inner.pre_roll = Some(PreRoll {
+            _stream: stream,
+            ring,
+            device_name,
             sample_rate: config.sample_rate.0,
             channels: config.channels,
+        });
+        Ok(())
+    }
+
+    /// 关闭录音流（开启预热时保留为空闲流）并取出录音
+    fn finish(&self) -> Result<RecordedAudio, RecorderError> {
+        let mut inner = self.inner.lock().map_err(|_| RecorderError::LockPoisoned)?;
+        let (Some(config), Some(sink)) = (inner.config.take(), inner.sink.take()) else {
+            return Err(RecorderError::NotRecording);
+        };
+        // 先停止写入，确保回调不再写入缓冲区：预热的流只撤下写入标志，其余的流直接关闭
+        sink.armed.store(false, Ordering::SeqCst);
+        let stream = inner.stream.take();
+        let monitor = inner.monitor.take();
+        let mix_inputs = std::mem::take(&mut inner.mix_inputs);
+        let recording = inner.recording.take();
+        let keep_warm = recording.as_ref().is_some_and(|recording| {
+            inner
+                .warm_settings
+                .as_ref()
+                .is_some_and(|warm| same_stream_settings(warm, recording))
+        });
+        inner.warm = match (stream, recording) {
+            (Some(stream), Some(recording)) if keep_warm => Some(CaptureStream {
+                stream,
+                stream_id: inner.stream_id,
+                sink: sink.clone(),
+                config,
+                device_name: inner.device_name.clone(),
+                monitor,
+                mix_inputs,
+                recording,
+            }),
+            _ => None,
+        };
+        inner.stream_error = None;
+        let system_capture = inner.system_capture.take();
+        self.meter.end();
+        let buffer = std::mem::take(
+            &mut *sink
+                .buffer
+                .lock()
+                .map_err(|_| RecorderError::LockPoisoned)?,
+        );
+        let mut audio = buffer.finish().map_err(RecorderError::Spill)?;
+        // 系统音频只是附加音轨，取出失败时保留麦克风录音
+        match system_capture.map(SystemCapture::finish) {
+            Some(Ok(track)) if !track.is_empty() => audio.tracks.push(AudioTrack {
+                name: SYSTEM_TRACK.to_string(),
+                audio: track,
+            }),
+            Some(Err(_err)) => {
+                #[cfg(debug_assertions)]
+                eprintln!("取出系统音频音轨失败: {_err}");
+            }
+            _ => {}
+        }
+        Ok(audio)
+    }
+}
+
+/// 与麦克风同步采集的系统音频（回环录制），用于在会议转写中区分“我”与“对方”
+struct SystemCapture {
+    stream: Stream,
+    buffer: Arc<Mutex<SampleBuffer>>,
+}
+
+impl SystemCapture {
+    /// 在默认输出设备上打开回环采集；目前只有 WASAPI（Windows）支持在输出设备上建立输入流。
+    /// `lead_in` 为麦克风录音开头拼接的预录时长，以静音补齐使两条音轨时间对齐
+    fn open(
+        recording: &RecordingSettings,
+        meter: &Arc<LevelMeter>,
+        lead_in: Duration,
+    ) -> Result<Self, RecorderError> {
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or(RecorderError::DeviceUnavailable)?;
+        let supported = device
+            .default_output_config()
+            .map_err(|err| RecorderError::Config(err.to_string()))?;
+        let sample_format = supported.sample_format();
+        let config: StreamConfig = supported.into();
+        let max_samples = max_samples(&config, recording);
+        let mut buffer = SampleBuffer::new(
+            config.sample_rate.0,
+            config.channels,
+            audio_processing::resolve_scratch_dir(&recording.scratch_dir),
+            None,
+        );
+        let lead_in_samples = (lead_in.as_millis() as usize * config.sample_rate.0 as usize / 1000)
+            * usize::from(config.channels);
+        buffer.extend(std::iter::repeat(0).take(lead_in_samples.min(max_samples)));
+        let buffer = Arc::new(Mutex::new(buffer));
+        let err_fn = |_err| {
+            #[cfg(debug_assertions)]
+            eprintln!("系统音频采集流错误: {_err}");
+        };
+
+        macro_rules! build_stream {
+            ($sample_type:ty) => {{
+                let buffer = buffer.clone();
+                let meter = meter.clone();
+                device.build_input_stream(
+                    &config,
+                    move |data: &[$sample_type], _| {
+                        push_track_samples(data, &buffer, &meter, max_samples)
+                    },
+                    err_fn,
+                    None,
+                )
+            }};
+        }
+
+        let stream = match_sample_format!(
+            sample_format,
+            build_stream,
+            Err(RecorderError::Config("不支持的采样格式".to_string()))
+        )
+        .map_err(|err| RecorderError::Stream(err.to_string()))?;
+        stream
+            .play()
+            .map_err(|err| RecorderError::Stream(err.to_string()))?;
+        Ok(Self { stream, buffer })
+    }
+
+    fn finish(self) -> Result<RecordedAudio, RecorderError> {
+        let Self { stream, buffer } = self;
+        drop(stream);
+        let buffer = std::mem::take(&mut *buffer.lock().map_err(|_| RecorderError::LockPoisoned)?);
+        buffer.finish().map_err(RecorderError::Spill)
+    }
+}
+
+/// 写入附加音轨的样本：与麦克风录音同步暂停，达到时长上限后截断
+fn push_track_samples<T>(
+    data: &[T],
+    buffer: &Mutex<SampleBuffer>,
+    meter: &LevelMeter,
+    max_samples: usize,
+) where
+    T: Sample,
+    i16: FromSample<T>,
+{
+    if meter.is_paused() {
+        return;
+    }
+    if let Ok(mut guard) = buffer.lock() {
+        let room = max_samples.saturating_sub(guard.sample_count());
+        guard.extend(
+            data.iter()
+                .take(room)
+                .map(|sample| i16::from_sample(*sample)),
+        );
+        guard.spill_if_needed();
+    }
+}
+
+/// 录音监听（侧音）：以低音量把采集到的麦克风音频实时回放到默认输出设备，用于确认正在录音的是哪只麦克风。
+/// 外放时麦克风会再次收录回放的声音，建议配合耳机使用
+struct Monitor {
+    _stream: Stream,
+    feed: Arc<Mutex<ResampleFeed>>,
+}
+
+impl Monitor {
+    /// 在默认输出设备上打开回放流；`input` 为录音流的格式，`volume` 为线性音量（0~1）
+    fn open(input: &StreamConfig, volume: f32) -> Result<Self, RecorderError> {
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or(RecorderError::DeviceUnavailable)?;
+        let supported = device
+            .default_output_config()
+            .map_err(|err| RecorderError::Config(err.to_string()))?;
+        let sample_format = supported.sample_format();
+        let config: StreamConfig = supported.into();
+        let feed = Arc::new(Mutex::new(ResampleFeed::new(
+            input,
+            config.sample_rate.0,
+            volume.clamp(0.0, 1.0),
+            MONITOR_MAX_LATENCY_MS,
+            MONITOR_PREFILL_MS,
+        )));
+        let channels = usize::from(config.channels.max(1));
+        let err_fn = |_err| {
+            #[cfg(debug_assertions)]
+            eprintln!("录音监听输出流错误: {_err}");
+        };
+
+        macro_rules! build_stream {
+            ($sample_type:ty) => {{
+                let feed = feed.clone();
+                device.build_output_stream(
+                    &config,
+                    move |data: &mut [$sample_type], _| fill_monitor_output(data, channels, &feed),
+                    err_fn,
+                    None,
+                )
+            }};
+        }
+
+        let stream = match_sample_format!(
+            sample_format,
+            build_stream,
+            Err(RecorderError::Config("不支持的采样格式".to_string()))
+        )
+        .map_err(|err| RecorderError::Stream(err.to_string()))?;
+        stream
+            .play()
+            .map_err(|err| RecorderError::Stream(err.to_string()))?;
+        Ok(Self {
+            _stream: stream,
+            feed,
         })
     }
 }
 
+/// 混入录音的其他麦克风：独立采集，按设备偏好的增益重采样到主麦克风的采样率后逐帧叠加
+struct MixInput {
+    _stream: Stream,
+    feed: Arc<Mutex<ResampleFeed>>,
+}
+
+impl MixInput {
+    /// 打开名为 `device_name` 的输入设备；`target` 为主麦克风录音流的格式
+    fn open(
+        device_name: &str,
+        recording: &RecordingSettings,
+        target: &StreamConfig,
+    ) -> Result<Self, RecorderError> {
+        let device = find_input_device(device_name).ok_or(RecorderError::DeviceUnavailable)?;
+        let supported = device
+            .default_input_config()
+            .map_err(|err| RecorderError::Config(err.to_string()))?;
+        let sample_format = supported.sample_format();
+        let config: StreamConfig = supported.into();
+        let gain = recording
+            .device_profile(device_name)
+            .map_or(1.0, |profile| 10f32.powf(profile.gain_db / 20.0));
+        let feed = Arc::new(Mutex::new(ResampleFeed::new(
+            &config,
+            target.sample_rate.0,
+            gain,
+            MIX_MAX_LATENCY_MS,
+            MIX_PREFILL_MS,
+        )));
+        let err_fn = |_err| {
+            #[cfg(debug_assertions)]
+            eprintln!("混音输入流错误: {_err}");
+        };
+
+        macro_rules! build_stream {
+            ($sample_type:ty) => {{
+                let feed = feed.clone();
+                device.build_input_stream(
+                    &config,
+                    move |data: &[$sample_type], _| {
+                        let samples = data
+                            .iter()
+                            .map(|sample| i16::from_sample(*sample))
+                            .collect::<Vec<_>>();
+                        if let Ok(mut feed) = feed.lock() {
+                            feed.push(&samples);
+                        }
+                    },
+                    err_fn,
+                    None,
+                )
+            }};
+        }
+
+        let stream = match_sample_format!(
+            sample_format,
+            build_stream,
+            Err(RecorderError::Config("不支持的采样格式".to_string()))
+        )
+        .map_err(|err| RecorderError::Stream(err.to_string()))?;
+        stream
+            .play()
+            .map_err(|err| RecorderError::Stream(err.to_string()))?;
+        Ok(Self {
+            _stream: stream,
+            feed,
+        })
+    }
+}
+
+/// 打开设置中要混入的其他麦克风；跳过主麦克风本身与当前未连接的设备
+fn open_mix_inputs(
+    recording: &RecordingSettings,
+    primary: &str,
+    target: &StreamConfig,
+) -> Vec<MixInput> {
+    let mut opened = Vec::<&str>::new();
+    let mut inputs = Vec::new();
+    for name in &recording.mix_input_devices {
+        let name = name.trim();
+        if name.is_empty() || name == primary || opened.contains(&name) {
+            continue;
+        }
+        match MixInput::open(name, recording, target) {
+            Ok(input) => {
+                opened.push(name);
+                inputs.push(input);
+            }
+            Err(_err) => {
+                #[cfg(debug_assertions)]
+                eprintln!("打开混音输入设备 {name} 失败: {_err}");
+            }
+        }
+    }
+    inputs
+}
+
+/// 把其他麦克风的音频逐帧叠加到主麦克风的样本上，超出范围时削波；某个输入暂时欠载时该部分不叠加
+fn mix_into(samples: &mut [i16], channels: usize, inputs: &[Arc<Mutex<ResampleFeed>>]) {
+    for input in inputs {
+        let Ok(mut input) = input.lock() else {
+            continue;
+        };
+        for frame in samples.chunks_mut(channels.max(1)) {
+            let Some(value) = input.ring.pop() else {
+                break;
+            };
+            let value = value * f32::from(i16::MAX);
+            for sample in frame {
+                *sample = (f32::from(*sample) + value)
+                    .round()
+                    .clamp(f32::from(i16::MIN), f32::from(i16::MAX))
+                    as i16;
+            }
+        }
+    }
+}
+
+/// 用监听缓冲中的音频填充一个输出块，缓冲不足时输出静音
+fn fill_monitor_output<T>(data: &mut [T], channels: usize, feed: &Mutex<ResampleFeed>)
+where
+    T: Sample + FromSample<f32>,
+{
+    let mut feed = feed.lock().ok();
+    for frame in data.chunks_mut(channels) {
+        let value = feed
+            .as_mut()
+            .and_then(|feed| feed.ring.pop())
+            .unwrap_or(0.0);
+        frame.fill(T::from_sample(value));
+    }
+}
+
+/// 供录音监听与混音使用的音频：混为单声道、按音量缩放并线性插值重采样到目标采样率
+struct ResampleFeed {
+    channels: usize,
+    volume: f32,
+    /// 每个输出样本对应的输入帧数
+    step: f64,
+    /// 下一个输出样本在上一帧与当前帧之间的位置
+    position: f64,
+    previous: f32,
+    ring: ResampleRing,
+}
+
+impl ResampleFeed {
+    fn new(
+        input: &StreamConfig,
+        output_rate: u32,
+        volume: f32,
+        max_latency_ms: u32,
+        prefill_ms: u32,
+    ) -> Self {
+        let samples = |ms: u32| (output_rate as usize * ms as usize / 1000).max(1);
+        Self {
+            channels: usize::from(input.channels.max(1)),
+            volume,
+            step: f64::from(input.sample_rate.0) / f64::from(output_rate.max(1)),
+            position: 0.0,
+            previous: 0.0,
+            ring: ResampleRing::new(samples(max_latency_ms), samples(prefill_ms)),
+        }
+    }
+
+    fn push(&mut self, samples: &[i16]) {
+        for frame in samples.chunks_exact(self.channels) {
+            let sum = frame.iter().map(|sample| f32::from(*sample)).sum::<f32>();
+            let current = sum / self.channels as f32 / f32::from(i16::MAX) * self.volume;
+            while self.position < 1.0 {
+                let value = self.previous + (current - self.previous) * self.position as f32;
+                self.ring.push(value);
+                self.position += self.step;
+            }
+            self.position -= 1.0;
+            self.previous = current;
+        }
+    }
+}
+
+/// 重采样后的有界缓冲：超出容量时丢弃最旧的样本；欠载后重新积累到 `prefill` 个样本才继续输出
+struct ResampleRing {
+    samples: VecDeque<f32>,
+    capacity: usize,
+    prefill: usize,
+    playing: bool,
+}
+
+impl ResampleRing {
+    fn new(capacity: usize, prefill: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+            prefill: prefill.min(capacity),
+            playing: false,
+        }
+    }
+
+    fn push(&mut self, sample: f32) {
+        if self.samples.len() >= self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    fn pop(&mut self) -> Option<f32> {
+        if !self.playing && self.samples.len() < self.prefill {
+            return None;
+        }
+        let sample = self.samples.pop_front();
+        self.playing = sample.is_some();
+        sample
+    }
+}
+
 #[derive(Clone)]
 pub struct RecordedAudio {
     pub samples: Vec<i16>,
     pub sample_rate: u32,
     pub channels: u16,
+    /// 长录音溢出到磁盘时的临时文件，此时 `samples` 为空
+    pub spill: Option<Arc<SpillFile>>,
+    /// 与本录音（麦克风）同步采集的其他音轨，如系统音频；各音轨分别分段转写后按时间合并
+    pub tracks: Vec<AudioTrack>,
 }
 
-fn push_samples<T>(data: &[T], buffer: &Arc<Mutex<Vec<i16>>>)
-where
+/// 带名称的附加音轨，开头与主录音对齐
+#[derive(Clone)]
+pub struct AudioTrack {
+    pub name: String,
+    pub audio: RecordedAudio,
+}
+
+impl RecordedAudio {
+    /// 样本总数，包括溢出到磁盘的部分
+    pub fn sample_count(&self) -> usize {
+        self.spill
+            .as_ref()
+            .map_or(self.samples.len(), |spill| spill.sample_count())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sample_count() == 0
+    }
+}
+
+/// 录音流回调写入的目标；录音流出错重新打开后沿用同一缓冲区与监听
+#[derive(Clone)]
+struct CaptureSink {
+    buffer: Arc<Mutex<SampleBuffer>>,
+    gain: f32,
+    max_samples: usize,
+    /// 录音流的声道数
+    channels: usize,
+    monitor: Option<Arc<Mutex<ResampleFeed>>>,
+    /// 混入的其他麦克风
+    mix: Vec<Arc<Mutex<ResampleFeed>>>,
+    /// 是否写入录音；预热的空闲流在录音之间为 false
+    armed: Arc<AtomicBool>,
+    /// 预热的空闲流在录音之间写入的预录缓冲，未开启预录时为 None
+    idle_ring: Option<Arc<Mutex<PreRollRing>>>,
+}
+
+/// 打开的录音流；开启预热时录音结束后保留为空闲流
+struct CaptureStream {
+    stream: Stream,
+    stream_id: u64,
+    sink: CaptureSink,
+    config: StreamConfig,
+    device_name: String,
+    monitor: Option<Monitor>,
+    mix_inputs: Vec<MixInput>,
+    /// 打开该流时的录音设置
+    recording: RecordingSettings,
+}
+
+/// 两份录音设置打开的录音流是否一致，决定能否沿用预热的录音流
+fn same_stream_settings(left: &RecordingSettings, right: &RecordingSettings) -> bool {
+    left.input_device == right.input_device
+        && left.device_profiles == right.device_profiles
+        && left.buffer_frames == right.buffer_frames
+        && left.keep_original_format == right.keep_original_format
+        && left.max_duration_seconds == right.max_duration_seconds
+        && left.pre_roll == right.pre_roll
+        && left.pre_roll_ms == right.pre_roll_ms
+        && left.monitor == right.monitor
+        && left.monitor_volume == right.monitor_volume
+        && left.capture_system_audio == right.capture_system_audio
+        && left.mix_input_devices == right.mix_input_devices
+}
+
+/// 新录音的缓冲区：较长的录音超过内存阈值后转存到分段临时目录，开启降噪时逐块处理
+fn new_buffer(config: &StreamConfig, recording: &RecordingSettings, denoise: bool) -> SampleBuffer {
+    SampleBuffer::new(
+        config.sample_rate.0,
+        config.channels,
+        audio_processing::resolve_scratch_dir(&recording.scratch_dir),
+        denoise.then_some(PROFILE_DENOISE_THRESHOLD_DB),
+    )
+}
+
+/// 录音时长上限对应的样本数
+fn max_samples(config: &StreamConfig, recording: &RecordingSettings) -> usize {
+    config.sample_rate.0 as usize
+        * usize::from(config.channels)
+        * recording.max_duration_seconds as usize
+}
+
+fn build_capture_stream(
+    device: &Device,
+    sample_format: SampleFormat,
+    config: &StreamConfig,
+    sink: &CaptureSink,
+    meter: &Arc<LevelMeter>,
+    err_fn: impl FnMut(cpal::StreamError) + Send + 'static,
+) -> Result<Stream, RecorderError> {
+    let sink = sink.clone();
+    let meter = meter.clone();
+    let mut level_window = LevelWindow::new(config);
+
+    macro_rules! build_stream {
+        ($sample_type:ty) => {
+            device.build_input_stream(
+                config,
+                move |data: &[$sample_type], _| {
+                    push_samples(data, &sink, &meter, &mut level_window)
+                },
+                err_fn,
+                None,
+            )
+        };
+    }
+
+    match_sample_format!(
+        sample_format,
+        build_stream,
+        Err(RecorderError::Config("不支持的采样格式".to_string()))
+    )
+    .map_err(|err| RecorderError::Stream(err.to_string()))
+}
+
+/// 写入一个回调块的样本并送入监听；缓冲区达到 `max_samples` 后截断并标记达到上限
+fn push_samples<T>(
+    data: &[T],
+    sink: &CaptureSink,
+    meter: &LevelMeter,
+    level_window: &mut LevelWindow,
+) where
     T: Sample,
     i16: FromSample<T>,
 {
-    if let Ok(mut guard) = buffer.lock() {
-        guard.extend(data.iter().map(|sample| i16::from_sample(*sample)));
+    let gain = sink.gain;
+    let mut samples = data
+        .iter()
+        .map(|sample| apply_gain(i16::from_sample(*sample), gain))
+        .collect::<Vec<_>>();
+    // 混音输入在暂停与空闲期间同样取出，开始写入时与主麦克风保持对齐
+    mix_into(&mut samples, sink.channels, &sink.mix);
+    // 预热的空闲流在录音之间只写入预录缓冲
+    if !sink.armed.load(Ordering::SeqCst) {
+        if let Some(Ok(mut ring)) = sink.idle_ring.as_deref().map(Mutex::lock) {
+            ring.push(samples);
+        }
+        return;
+    }
+    if meter.is_paused() || meter.limit_reached() {
+        return;
+    }
+    let level = match sink.buffer.lock() {
+        Ok(mut guard) => {
+            let room = sink.max_samples.saturating_sub(guard.sample_count());
+            if samples.len() >= room {
+                meter.limit_reached.store(true, Ordering::SeqCst);
+            }
+            let added = guard.extend(samples.into_iter().take(room));
+            meter.record(added);
+            if let Some(Ok(mut monitor)) = sink.monitor.as_deref().map(Mutex::lock) {
+                monitor.push(added);
+            }
+            let level = level_window.push(added);
+            guard.spill_if_needed();
+            level
+        }
+        Err(_) => None,
+    };
+    // 在释放缓冲区锁之后再推送事件，避免阻塞录音回调
+    if let Some(level) = level {
+        meter.set_rms_db(level.rms_db);
+        events::emit_recording_level(level);
+    }
+}
+
+/// 按线性倍数调整样本幅值，超出范围时削波
+fn apply_gain(sample: i16, gain: f32) -> i16 {
+    if gain == 1.0 {
+        return sample;
+    }
+    (f32::from(sample) * gain).clamp(f32::from(i16::MIN), f32::from(i16::MAX)) as i16
+}
+
+/// 按固定时长窗口聚合电平，窗口填满时产出一次结果，以此限制事件频率
+struct LevelWindow {
+    window_samples: usize,
+    count: usize,
+    sum_squares: f64,
+    peak: u16,
+}
+
+impl LevelWindow {
+    fn new(config: &StreamConfig) -> Self {
+        let samples_per_second = config.sample_rate.0 as usize * usize::from(config.channels);
+        Self::with_window_samples(samples_per_second * LEVEL_WINDOW_MS / 1000)
+    }
+
+    fn with_window_samples(window_samples: usize) -> Self {
+        Self {
+            window_samples: window_samples.max(1),
+            count: 0,
+            sum_squares: 0.0,
+            peak: 0,
+        }
+    }
+
+    /// 累计样本；一次回调跨越多个窗口时只返回最后一个完整窗口的电平
+    fn push(&mut self, samples: &[i16]) -> Option<RecordingLevel> {
+        let mut level = None;
+        for sample in samples {
+            self.sum_squares += f64::from(*sample).powi(2);
+            self.peak = self.peak.max(sample.unsigned_abs());
+            self.count += 1;
+            if self.count >= self.window_samples {
+                let rms = (self.sum_squares / self.count as f64).sqrt();
+                level = Some(RecordingLevel {
+                    rms_db: to_dbfs(rms),
+                    peak_db: to_dbfs(f64::from(self.peak)),
+                });
+                self.count = 0;
+                self.sum_squares = 0.0;
+                self.peak = 0;
+            }
+        }
+        level
+    }
+}
+
+/// 将 i16 幅值换算为 dBFS
+fn to_dbfs(amplitude: f64) -> f32 {
+    if amplitude <= 0.0 {
+        return SILENCE_DB;
+    }
+    ((20.0 * (amplitude / f64::from(i16::MAX)).log10()) as f32).max(SILENCE_DB)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_meter_tracks_peak_per_session() {
+        let meter = LevelMeter::default();
+        meter.begin();
+        let session = meter.session();
+        meter.record(&[12, -300, 40]);
+        meter.record(&[i16::MIN]);
+        assert_eq!(meter.take_peak(), 32768);
+        assert_eq!(meter.take_peak(), 0);
+        assert!(meter.is_active(session));
+
+        meter.end();
+        meter.begin();
+        assert!(!meter.is_active(session));
+    }
+
+    #[test]
+    fn clip_runs_count_only_sustained_full_scale_samples() {
+        let max = i16::MAX;
+        let min = i16::MIN;
+        assert_eq!(count_clip_runs(&[max, 0, min, 100, max, max, max]), 0);
+        assert_eq!(
+            count_clip_runs(&[max, max, min, min, max, 0, min, min, min, min]),
+            2
+        );
+
+        let meter = LevelMeter::default();
+        meter.begin();
+        meter.record(&[max; 8]);
+        assert_eq!(meter.take_clip_runs(), 1);
+        assert_eq!(meter.take_clip_runs(), 0);
+    }
+
+    #[test]
+    fn buffer_size_clamps_to_supported_range() {
+        let range = SupportedBufferSize::Range { min: 64, max: 4096 };
+        assert_eq!(buffer_size(&range, 0), BufferSize::Default);
+        assert_eq!(buffer_size(&range, 128), BufferSize::Fixed(128));
+        assert_eq!(buffer_size(&range, 16), BufferSize::Fixed(64));
+        assert_eq!(buffer_size(&range, 8192), BufferSize::Fixed(4096));
+        assert_eq!(
+            buffer_size(&SupportedBufferSize::Unknown, 128),
+            BufferSize::Fixed(128)
+        );
+    }
+
+    #[test]
+    fn apply_gain_scales_and_clips_samples() {
+        assert_eq!(apply_gain(1000, 1.0), 1000);
+        assert_eq!(apply_gain(1000, 2.0), 2000);
+        assert_eq!(apply_gain(-1000, 0.5), -500);
+        assert_eq!(apply_gain(30_000, 2.0), i16::MAX);
+        assert_eq!(apply_gain(-30_000, 2.0), i16::MIN);
+    }
+
+    #[test]
+    fn level_meter_clears_pause_on_new_session() {
+        let meter = LevelMeter::default();
+        meter.begin();
+        meter.paused.store(true, Ordering::SeqCst);
+        assert!(meter.is_paused());
+        meter.end();
+        meter.begin();
+        assert!(!meter.is_paused());
+    }
+
+    #[test]
+    fn push_samples_stops_at_max_samples() {
+        let meter = LevelMeter::default();
+        meter.begin();
+        let sink = test_sink(true, None);
+        let mut window = LevelWindow::with_window_samples(1000);
+        push_samples(&[1i16, 2, 3], &sink, &meter, &mut window);
+        assert!(!meter.limit_reached());
+        push_samples(&[4i16, 5, 6], &sink, &meter, &mut window);
+        assert!(meter.limit_reached());
+        push_samples(&[7i16], &sink, &meter, &mut window);
+        let audio = std::mem::take(&mut *sink.buffer.lock().unwrap())
+            .finish()
+            .unwrap();
+        assert_eq!(audio.samples, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn disarmed_sink_only_fills_idle_ring() {
+        let meter = LevelMeter::default();
+        meter.begin();
+        let ring = Arc::new(Mutex::new(PreRollRing::new(2)));
+        let sink = test_sink(false, Some(ring.clone()));
+        let mut window = LevelWindow::with_window_samples(1000);
+        push_samples(&[1i16, 2, 3], &sink, &meter, &mut window);
+        assert_eq!(sink.buffer.lock().unwrap().sample_count(), 0);
+        assert_eq!(ring.lock().unwrap().take(1), vec![2, 3]);
+
+        sink.armed.store(true, Ordering::SeqCst);
+        push_samples(&[4i16], &sink, &meter, &mut window);
+        assert_eq!(sink.buffer.lock().unwrap().sample_count(), 1);
+        assert!(ring.lock().unwrap().take(1).is_empty());
+    }
+
+    fn test_sink(armed: bool, idle_ring: Option<Arc<Mutex<PreRollRing>>>) -> CaptureSink {
+        CaptureSink {
+            buffer: Arc::new(Mutex::new(SampleBuffer::default())),
+            gain: 1.0,
+            max_samples: 5,
+            channels: 1,
+            monitor: None,
+            mix: Vec::new(),
+            armed: Arc::new(AtomicBool::new(armed)),
+            idle_ring,
+        }
+    }
+
+    #[test]
+    fn level_window_reports_once_per_full_window() {
+        let mut window = LevelWindow::with_window_samples(4);
+        assert_eq!(window.push(&[0, 0, 0]), None);
+        let level = window.push(&[0, 0, 0]).unwrap();
+        assert_eq!(level.rms_db, SILENCE_DB);
+        assert_eq!(level.peak_db, SILENCE_DB);
+
+        let level = window.push(&[0, i16::MAX, -i16::MAX, i16::MAX, 0]).unwrap();
+        assert!(level.peak_db.abs() < 0.01);
+        assert!(level.rms_db < level.peak_db);
+    }
+
+    #[test]
+    fn pre_roll_ring_keeps_latest_whole_frames() {
+        let mut ring = PreRollRing::new(4);
+        ring.push([1, 2, 3, 4, 5, 6]);
+        assert_eq!(ring.take(2), vec![3, 4, 5, 6]);
+        assert!(ring.take(2).is_empty());
+
+        let mut ring = PreRollRing::new(5);
+        ring.push([1, 2, 3, 4, 5, 6]);
+        assert_eq!(ring.take(2), vec![3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn mix_into_adds_each_input_frame_to_all_channels() {
+        let input = StreamConfig {
+            channels: 1,
+            sample_rate: SampleRate(16_000),
+            buffer_size: BufferSize::Default,
+        };
+        let feed = Arc::new(Mutex::new(ResampleFeed::new(&input, 16_000, 1.0, 10, 0)));
+        // 线性插值从上一帧开始，第一个输出样本为初始的 0
+        feed.lock().unwrap().push(&[1000, -1000, i16::MAX]);
+        let mut samples = vec![100, 100, 100, 100, 100, 100, 30_000, -100];
+        mix_into(&mut samples, 2, &[feed.clone()]);
+        assert_eq!(
+            samples,
+            vec![100, 100, 1100, 1100, -900, -900, 30_000, -100]
+        );
+
+        feed.lock().unwrap().push(&[0]);
+        let mut samples = vec![30_000, 0];
+        mix_into(&mut samples, 2, &[feed]);
+        assert_eq!(samples, vec![i16::MAX, i16::MAX]);
+    }
+
+    #[test]
+    fn monitor_feed_resamples_and_bounds_latency() {
+        let input = StreamConfig {
+            channels: 2,
+            sample_rate: SampleRate(16_000),
+            buffer_size: BufferSize::Default,
+        };
+        let mut feed = ResampleFeed::new(
+            &input,
+            48_000,
+            0.5,
+            MONITOR_MAX_LATENCY_MS,
+            MONITOR_PREFILL_MS,
+        );
+        // 20 ms 预填充之前不输出
+        feed.push(&[i16::MAX; 2 * 100]);
+        assert_eq!(feed.ring.samples.len(), 300);
+        assert_eq!(feed.ring.pop(), None);
+        feed.push(&[i16::MAX; 2 * 300]);
+        assert_eq!(feed.ring.pop(), Some(0.0));
+        assert!((feed.ring.samples.back().unwrap() - 0.5).abs() < 1e-6);
+
+        // 积压超过上限时只保留最近 80 ms
+        feed.push(&[0; 2 * 16_000]);
+        assert_eq!(feed.ring.samples.len(), 48_000 * 80 / 1000);
+        while feed.ring.pop().is_some() {}
+        assert_eq!(feed.ring.pop(), None);
+        feed.push(&[0; 2]);
+        assert_eq!(feed.ring.pop(), None);
+    }
+
+    #[test]
+    fn negotiate_config_prefers_speech_format_and_converts_others() {
+        let range = |channels, min, max, format| {
+            SupportedStreamConfigRange::new(
+                channels,
+                SampleRate(min),
+                SampleRate(max),
+                SupportedBufferSize::Unknown,
+                format,
+            )
+        };
+        let speech = CaptureTarget {
+            sample_rate: 16_000,
+            channels: 1,
+        };
+        let ranges = [
+            range(2, 48_000, 48_000, SampleFormat::F32),
+            range(1, 8_000, 48_000, SampleFormat::F32),
+            range(1, 8_000, 48_000, SampleFormat::I16),
+        ];
+        let config = negotiate_config(&ranges, speech).unwrap();
+        assert_eq!(config.sample_format(), SampleFormat::I16);
+        assert_eq!((config.sample_rate().0, config.channels()), (16_000, 1));
+
+        let ranges = [range(2, 44_100, 44_100, SampleFormat::I32)];
+        let config = negotiate_config(&ranges, speech).unwrap();
+        assert_eq!(config.sample_format(), SampleFormat::I32);
+        assert_eq!((config.sample_rate().0, config.channels()), (44_100, 2));
+        assert!(negotiate_config(&[], speech).is_none());
+        assert_eq!(capture_target(None, true, 0), speech);
     }
 }
@@ -0,0 +1,120 @@
+//! 原始录音存档
+//!
+//! 开启“保留原始录音”时，每次听写的完整录音（仅主音轨）以加密的 WAV 文件保存在应用数据目录的
+//! `recordings/` 中，存档路径记录在对应的历史记录里，供回放与重新转写。
+//! 每次存档时删除超过保留天数的录音；存档同样计入录音存储配额。
+
+use crate::audio_crypto;
+use crate::audio_processing;
+use crate::recorder::RecordedAudio;
+use crate::settings::SettingsStore;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const ARCHIVE_DIR_NAME: &str = "recordings";
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// 按设置存档录音并清理过期存档，返回存档路径；未开启存档时返回 None
+pub fn save(store: &SettingsStore, recording: &RecordedAudio) -> Result<Option<PathBuf>, String> {
+    let settings = store.load().map_err(|err| err.to_string())?;
+    if !settings.recording.keep_raw_audio {
+        return Ok(None);
+    }
+    let dir = archive_dir(store)?;
+    prune(
+        &dir,
+        settings.recording.raw_audio_retention_days,
+        SystemTime::now(),
+    );
+    let path = dir.join(format!("{}.wav", crate::util::timestamp_id()));
+    let key = store.encryption_key().map_err(|err| err.to_string())?;
+    let source = audio_processing::wav_source(recording).map_err(|err| err.to_string())?;
+    audio_crypto::encrypt_to_file(source, &path, &key)?;
+    Ok(Some(path))
+}
+
+/// 解密读取存档的 WAV 内容，供回放
+pub fn read_wav(store: &SettingsStore, path: &Path, scratch_dir: &Path) -> Result<Vec<u8>, String> {
+    let decrypted = decrypt(store, path, scratch_dir)?;
+    fs::read(decrypted.path()).map_err(|err| err.to_string())
+}
+
+/// 解密读取存档录音，供重新转写
+pub fn load(
+    store: &SettingsStore,
+    path: &Path,
+    scratch_dir: &Path,
+) -> Result<RecordedAudio, String> {
+    let decrypted = decrypt(store, path, scratch_dir)?;
+    audio_processing::read_wav_file(decrypted.path()).map_err(|err| err.to_string())
+}
+
+fn decrypt(
+    store: &SettingsStore,
+    path: &Path,
+    scratch_dir: &Path,
+) -> Result<audio_crypto::DecryptedFile, String> {
+    // 按文件名在当前存档目录中查找：切换漫游设置后存档会移动，
+    // 且历史记录中的路径不应成为读取任意文件的入口
+    let name = path.file_name().ok_or_else(|| "录音路径无效".to_string())?;
+    let path = archive_dir(store)?.join(name);
+    if !path.exists() {
+        return Err("录音已按保留策略或存储配额删除".to_string());
+    }
+    let key = store.encryption_key().map_err(|err| err.to_string())?;
+    audio_crypto::decrypt_to_temp(&path, scratch_dir, &key)
+}
+
+pub fn remove(path: &Path) {
+    let _ = fs::remove_file(path);
+}
+
+/// 删除修改时间早于保留天数的存档，0 表示永久保留
+fn prune(dir: &Path, retention_days: u32, now: SystemTime) {
+    let Some(cutoff) = retention_cutoff(retention_days, now) else {
+        return;
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let expired = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| modified < cutoff);
+        if expired {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+fn retention_cutoff(retention_days: u32, now: SystemTime) -> Option<SystemTime> {
+    if retention_days == 0 {
+        return None;
+    }
+    now.checked_sub(Duration::from_secs(
+        u64::from(retention_days) * SECONDS_PER_DAY,
+    ))
+}
+
+pub fn archive_dir(store: &SettingsStore) -> Result<PathBuf, String> {
+    store
+        .recordings_dir(ARCHIVE_DIR_NAME)
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retention_cutoff_keeps_forever_at_zero_days() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100 * SECONDS_PER_DAY);
+        assert_eq!(retention_cutoff(0, now), None);
+        assert_eq!(
+            retention_cutoff(30, now),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(70 * SECONDS_PER_DAY))
+        );
+    }
+}
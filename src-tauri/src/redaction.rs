@@ -0,0 +1,239 @@
+//! 本地脱敏：在粘贴与写入历史之前屏蔽敏感词与个人信息
+//!
+//! 支持自定义词表，以及邮箱、电话号码、银行卡号（Luhn 校验）的正则识别，全部在本地完成。
+
+use crate::settings::RedactionSettings;
+use regex::{Captures, NoExpand, Regex};
+use std::sync::OnceLock;
+
+fn email_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"[A-Za-z0-9._%+\-]+@[A-Za-z0-9.\-]+\.[A-Za-z]{2,}").expect("email pattern")
+    })
+}
+
+fn card_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\d(?:[ \-]?\d){12,18}").expect("card pattern"))
+}
+
+/// 仅识别有明显电话特征的数字：`+` 国际区号、`1[3-9]` 开头的手机号、括号区号、
+/// `0` 开头的座机号与 `xxx-xxx-xxxx` 分组，避免把日期、金额与数字列表当作电话号码
+fn phone_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        let alternatives = [
+            r"\+\d{1,3}[ \-]?(?:\(\d{1,4}\)[ \-]?)?\d{1,4}(?:[ \-]?\d{2,4}){1,4}",
+            r"1[3-9]\d(?:[ \-]?\d{4}){2}",
+            r"\(\d{2,4}\)[ \-]?\d{3,4}[ \-]?\d{4}",
+            r"0\d{2,3}-\d{7,8}",
+            r"\d{3}-\d{3}-\d{4}",
+        ];
+        Regex::new(&alternatives.join("|")).expect("phone pattern")
+    })
+}
+
+/// 匹配前后紧邻 ASCII 数字时属于更长的数字串，不按电话号码或卡号处理；
+/// 不使用 `\b`，因为中文与数字之间不构成单词边界
+fn is_standalone_number(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    !before.is_some_and(|ch| ch.is_ascii_digit()) && !after.is_some_and(|ch| ch.is_ascii_digit())
+}
+
+/// 按设置屏蔽文本中的敏感内容
+pub fn redact(text: &str, settings: &RedactionSettings) -> String {
+    if !settings.enabled {
+        return text.to_string();
+    }
+    let replacement = settings.replacement.as_str();
+    let mut output = text.to_string();
+    if let Some(pattern) = wordlist_pattern(&settings.wordlist) {
+        let text = output;
+        output = pattern
+            .replace_all(&text, |captures: &Captures| {
+                let found = captures.get(0).expect("whole match");
+                if is_whole_word(&text, found.start(), found.end()) {
+                    replacement.to_string()
+                } else {
+                    captures[0].to_string()
+                }
+            })
+            .into_owned();
+    }
+    if settings.emails {
+        output = email_pattern()
+            .replace_all(&output, NoExpand(replacement))
+            .into_owned();
+    }
+    // 银行卡号先于电话号码处理，避免长数字串被拆成电话号码部分屏蔽
+    if settings.credit_cards {
+        let text = output;
+        output = card_pattern()
+            .replace_all(&text, |captures: &Captures| {
+                let found = captures.get(0).expect("whole match");
+                if passes_luhn(found.as_str())
+                    && is_standalone_number(&text, found.start(), found.end())
+                {
+                    replacement.to_string()
+                } else {
+                    captures[0].to_string()
+                }
+            })
+            .into_owned();
+    }
+    if settings.phone_numbers {
+        let text = output;
+        output = phone_pattern()
+            .replace_all(&text, |captures: &Captures| {
+                let found = captures.get(0).expect("whole match");
+                let digits = found.as_str().chars().filter(char::is_ascii_digit).count();
+                if (7..=15).contains(&digits)
+                    && is_standalone_number(&text, found.start(), found.end())
+                {
+                    replacement.to_string()
+                } else {
+                    captures[0].to_string()
+                }
+            })
+            .into_owned();
+    }
+    output
+}
+
+/// 英文词首尾不能紧邻其他 ASCII 字母或数字，即按整词匹配；中文词按子串匹配。
+/// 同样不使用 `\b`，否则紧跟在中文后的英文词不会被屏蔽
+fn is_whole_word(text: &str, start: usize, end: usize) -> bool {
+    let found = &text[start..end];
+    let is_word_char = |ch: char| ch.is_ascii_alphanumeric();
+    let before_ok = !found.chars().next().is_some_and(is_word_char)
+        || !text[..start].chars().next_back().is_some_and(is_word_char);
+    let after_ok = !found.chars().next_back().is_some_and(is_word_char)
+        || !text[end..].chars().next().is_some_and(is_word_char);
+    before_ok && after_ok
+}
+
+/// 词表匹配忽略大小写
+fn wordlist_pattern(wordlist: &[String]) -> Option<Regex> {
+    let alternatives = wordlist
+        .iter()
+        .map(|word| word.trim())
+        .filter(|word| !word.is_empty())
+        .map(regex::escape)
+        .collect::<Vec<_>>();
+    if alternatives.is_empty() {
+        return None;
+    }
+    Regex::new(&format!("(?i)(?:{})", alternatives.join("|"))).ok()
+}
+
+fn passes_luhn(candidate: &str) -> bool {
+    let digits = candidate
+        .chars()
+        .filter_map(|ch| ch.to_digit(10))
+        .collect::<Vec<_>>();
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, digit)| {
+            if index % 2 == 1 {
+                let doubled = digit * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                *digit
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled() -> RedactionSettings {
+        RedactionSettings {
+            enabled: true,
+            wordlist: vec!["机密".to_string(), "darn".to_string()],
+            ..RedactionSettings::default()
+        }
+    }
+
+    #[test]
+    fn redacts_wordlist_entries_case_insensitively() {
+        let settings = enabled();
+        assert_eq!(
+            redact("这是机密文件, Darn it, darned", &settings),
+            "这是***文件, *** it, darned"
+        );
+    }
+
+    #[test]
+    fn redacts_emails_phone_numbers_and_valid_cards() {
+        let settings = enabled();
+        assert_eq!(
+            redact("联系 alice@example.com 或 138 0013 8000", &settings),
+            "联系 *** 或 ***"
+        );
+        assert_eq!(redact("卡号 4111 1111 1111 1111", &settings), "卡号 ***");
+        assert_eq!(redact("共 2024 年", &settings), "共 2024 年");
+    }
+
+    #[test]
+    fn redacts_phone_numbers_adjacent_to_chinese_text() {
+        let settings = enabled();
+        assert_eq!(redact("联系13800138000", &settings), "联系***");
+        assert_eq!(redact("座机010-12345678转分机", &settings), "座机***转分机");
+        assert_eq!(
+            redact("call +1 (555) 123-4567 now", &settings),
+            "call *** now"
+        );
+        assert_eq!(redact("编号213800138000", &settings), "编号213800138000");
+    }
+
+    #[test]
+    fn redacts_cards_and_words_adjacent_to_chinese_text() {
+        let settings = enabled();
+        assert_eq!(redact("卡号4111111111111111", &settings), "卡号***");
+        assert_eq!(redact("这是darn", &settings), "这是***");
+        assert_eq!(redact("darn,机密", &settings), "***,***");
+        assert_eq!(redact("undarned", &settings), "undarned");
+        assert_eq!(
+            redact("编号94111111111111111", &settings),
+            "编号94111111111111111"
+        );
+    }
+
+    #[test]
+    fn leaves_dates_amounts_and_number_lists_unchanged() {
+        let settings = enabled();
+        for text in [
+            "会议定在 2024-10-17 上午",
+            "日期 2024/10/17",
+            "预算 1000 2000 之间",
+            "金额 12,345,678.90 元",
+            "季度销量 1200 3400 5600 7800",
+            "编号 1 2 3 4 5 6 7 8",
+        ] {
+            assert_eq!(redact(text, &settings), text);
+        }
+    }
+
+    #[test]
+    fn luhn_rejects_invalid_numbers() {
+        assert!(passes_luhn("4111111111111111"));
+        assert!(!passes_luhn("4111111111111112"));
+    }
+
+    #[test]
+    fn disabled_settings_leave_text_unchanged() {
+        let settings = RedactionSettings::default();
+        assert_eq!(redact("alice@example.com", &settings), "alice@example.com");
+    }
+}
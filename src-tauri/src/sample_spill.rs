@@ -0,0 +1,251 @@
+//! 长录音的磁盘溢出缓冲
+//!
+//! 录音样本先缓存在内存中，超过阈值后转存到临时 WAV 文件，之后按块交给写入线程追加。
+//! 录音回调只做内存拷贝与通道发送，不直接进行磁盘 I/O；分段时再按需从文件读取。
+
+use crate::audio_pipeline;
+use crate::recorder::RecordedAudio;
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+/// 内存中最多缓存的样本数（约 32 MB），超过后开始写入临时文件
+const SPILL_THRESHOLD_SAMPLES: usize = 16 * 1024 * 1024;
+/// 溢出后每次交给写入线程的样本数
+const SPILL_CHUNK_SAMPLES: usize = 256 * 1024;
+
+/// 录音过程中的样本缓冲
+pub struct SampleBuffer {
+    memory: Vec<i16>,
+    spilled: usize,
+    writer: Option<SpillWriter>,
+    sample_rate: u32,
+    channels: u16,
+    dir: PathBuf,
+    /// 设备偏好开启降噪时的噪声门阈值（dBFS）
+    denoise_threshold_db: Option<f32>,
+    spill_threshold: usize,
+}
+
+impl SampleBuffer {
+    /// `dir` 为溢出文件所在的临时目录
+    pub fn new(
+        sample_rate: u32,
+        channels: u16,
+        dir: PathBuf,
+        denoise_threshold_db: Option<f32>,
+    ) -> Self {
+        Self {
+            memory: Vec::new(),
+            spilled: 0,
+            writer: None,
+            sample_rate,
+            channels,
+            dir,
+            denoise_threshold_db,
+            spill_threshold: SPILL_THRESHOLD_SAMPLES,
+        }
+    }
+
+    /// 已录制的样本总数，包括已写入文件的部分
+    pub fn sample_count(&self) -> usize {
+        self.spilled + self.memory.len()
+    }
+
+    /// 追加样本并返回新写入的部分，供电平统计使用
+    pub fn extend(&mut self, samples: impl IntoIterator<Item = i16>) -> &[i16] {
+        let start = self.memory.len();
+        self.memory.extend(samples);
+        &self.memory[start..]
+    }
+
+    /// 内存缓存超过阈值时移交写入线程；首次溢出时启动写入线程并创建临时文件
+    pub fn spill_if_needed(&mut self) {
+        let threshold = if self.writer.is_some() {
+            SPILL_CHUNK_SAMPLES
+        } else {
+            self.spill_threshold
+        };
+        if self.memory.len() < threshold {
+            return;
+        }
+        let writer = self.writer.get_or_insert_with(|| {
+            let path = self
+                .dir
+                .join(format!("spill-{}.wav", crate::util::timestamp_id()));
+            SpillWriter::spawn(
+                path,
+                self.sample_rate,
+                self.channels,
+                self.denoise_threshold_db,
+            )
+        });
+        let chunk = std::mem::replace(&mut self.memory, Vec::with_capacity(SPILL_CHUNK_SAMPLES));
+        self.spilled += chunk.len();
+        // 写入线程已退出时错误在结束录音时返回
+        let _ = writer.sender.send(chunk);
+    }
+
+    /// 结束录音：未溢出时返回内存中的样本，否则写完剩余样本并返回溢出文件
+    pub fn finish(self) -> Result<RecordedAudio, String> {
+        let Some(writer) = self.writer else {
+            let mut audio = RecordedAudio {
+                samples: self.memory,
+                sample_rate: self.sample_rate,
+                channels: self.channels,
+                spill: None,
+                tracks: Vec::new(),
+            };
+            if let Some(threshold_db) = self.denoise_threshold_db {
+                audio_pipeline::noise_gate(&mut audio, threshold_db);
+            }
+            return Ok(audio);
+        };
+        let sample_count = self.spilled + self.memory.len();
+        let _ = writer.sender.send(self.memory);
+        drop(writer.sender);
+        let path = writer
+            .worker
+            .join()
+            .map_err(|_| "录音缓存写入线程异常退出".to_string())??;
+        Ok(RecordedAudio {
+            samples: Vec::new(),
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            spill: Some(Arc::new(SpillFile { path, sample_count })),
+            tracks: Vec::new(),
+        })
+    }
+}
+
+impl Default for SampleBuffer {
+    fn default() -> Self {
+        Self::new(0, 0, PathBuf::new(), None)
+    }
+}
+
+struct SpillWriter {
+    sender: mpsc::Sender<Vec<i16>>,
+    worker: thread::JoinHandle<Result<PathBuf, String>>,
+}
+
+impl SpillWriter {
+    fn spawn(
+        path: PathBuf,
+        sample_rate: u32,
+        channels: u16,
+        denoise_threshold_db: Option<f32>,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel::<Vec<i16>>();
+        let worker = thread::spawn(move || {
+            let result = write_chunks(&path, sample_rate, channels, denoise_threshold_db, receiver);
+            if result.is_err() {
+                let _ = fs::remove_file(&path);
+            }
+            result.map(|_| path)
+        });
+        Self { sender, worker }
+    }
+}
+
+fn write_chunks(
+    path: &Path,
+    sample_rate: u32,
+    channels: u16,
+    denoise_threshold_db: Option<f32>,
+    receiver: mpsc::Receiver<Vec<i16>>,
+) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let spec = WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(path, spec).map_err(|err| err.to_string())?;
+    for samples in receiver {
+        let mut chunk = RecordedAudio {
+            samples,
+            sample_rate,
+            channels,
+            spill: None,
+            tracks: Vec::new(),
+        };
+        if let Some(threshold_db) = denoise_threshold_db {
+            audio_pipeline::noise_gate(&mut chunk, threshold_db);
+        }
+        for sample in chunk.samples {
+            writer.write_sample(sample).map_err(|err| err.to_string())?;
+        }
+    }
+    writer.finalize().map_err(|err| err.to_string())
+}
+
+/// 已溢出到磁盘的录音；最后一个引用释放时删除临时文件
+pub struct SpillFile {
+    path: PathBuf,
+    sample_count: usize,
+}
+
+impl SpillFile {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.sample_count
+    }
+
+    /// 读取 `[start, end)` 帧范围内的交错样本
+    pub fn read_frames(&self, start: usize, end: usize) -> Result<Vec<i16>, String> {
+        let mut reader = WavReader::open(&self.path).map_err(|err| err.to_string())?;
+        let channels = usize::from(reader.spec().channels.max(1));
+        reader.seek(start as u32).map_err(|err| err.to_string())?;
+        reader
+            .samples::<i16>()
+            .take(end.saturating_sub(start) * channels)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_buffer_spills_past_threshold_and_reads_back_frames() {
+        let dir =
+            std::env::temp_dir().join(format!("vtt-keyboard-test-{}", crate::util::timestamp_id()));
+        let mut buffer = SampleBuffer::new(16_000, 2, dir.clone(), None);
+        buffer.spill_threshold = 4;
+        buffer.extend([1, 2, 3]);
+        buffer.spill_if_needed();
+        assert!(buffer.writer.is_none());
+        assert_eq!(buffer.extend([4, 5]), &[4, 5]);
+        buffer.spill_if_needed();
+        buffer.extend([6]);
+        assert_eq!(buffer.sample_count(), 6);
+
+        let audio = buffer.finish().unwrap();
+        assert!(audio.samples.is_empty());
+        let spill = audio.spill.clone().unwrap();
+        assert_eq!(audio.sample_count(), 6);
+        assert_eq!(spill.read_frames(1, 3).unwrap(), vec![3, 4, 5, 6]);
+        let path = spill.path().to_path_buf();
+        drop(spill);
+        drop(audio);
+        assert!(!path.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
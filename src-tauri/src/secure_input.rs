@@ -0,0 +1,84 @@
+//! 密码输入框探测
+//!
+//! 自动粘贴前通过辅助功能接口检查前台焦点是否为密码输入框：密码框中粘贴的听写文本可能以明文
+//! 保留，或被系统的安全输入模式静默拦截。Windows 检查焦点控件是否为带 `ES_PASSWORD` 样式的
+//! 编辑框，macOS 通过 System Events 读取焦点元素的子角色（需要辅助功能权限），
+//! Linux 没有通用接口。探测失败时视为普通输入框。
+
+#[cfg(target_os = "windows")]
+pub fn is_focused() -> bool {
+    use std::ffi::c_void;
+
+    const GWL_STYLE: i32 = -16;
+    const ES_PASSWORD: i32 = 0x0020;
+
+    #[repr(C)]
+    struct Rect {
+        left: i32,
+        top: i32,
+        right: i32,
+        bottom: i32,
+    }
+
+    #[repr(C)]
+    struct GuiThreadInfo {
+        cb_size: u32,
+        flags: u32,
+        hwnd_active: *mut c_void,
+        hwnd_focus: *mut c_void,
+        hwnd_capture: *mut c_void,
+        hwnd_menu_owner: *mut c_void,
+        hwnd_move_size: *mut c_void,
+        hwnd_caret: *mut c_void,
+        rc_caret: Rect,
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetGUIThreadInfo(thread_id: u32, info: *mut GuiThreadInfo) -> i32;
+        fn GetWindowLongW(hwnd: *mut c_void, index: i32) -> i32;
+        fn GetClassNameW(hwnd: *mut c_void, name: *mut u16, max_count: i32) -> i32;
+    }
+
+    // 线程 ID 为 0 时查询前台线程
+    let mut info: GuiThreadInfo = unsafe { std::mem::zeroed() };
+    info.cb_size = std::mem::size_of::<GuiThreadInfo>() as u32;
+    if unsafe { GetGUIThreadInfo(0, &mut info) } == 0 || info.hwnd_focus.is_null() {
+        return false;
+    }
+    let style = unsafe { GetWindowLongW(info.hwnd_focus, GWL_STYLE) };
+    if style & ES_PASSWORD == 0 {
+        return false;
+    }
+    // ES_PASSWORD 只对编辑框有意义，其他控件的同一样式位另有含义
+    let mut class = vec![0u16; 256];
+    let len = unsafe { GetClassNameW(info.hwnd_focus, class.as_mut_ptr(), class.len() as i32) };
+    String::from_utf16_lossy(&class[..len.max(0) as usize])
+        .to_ascii_lowercase()
+        .contains("edit")
+}
+
+#[cfg(target_os = "macos")]
+pub fn is_focused() -> bool {
+    const SCRIPT: &str = r#"tell application "System Events"
+    set frontApp to first application process whose frontmost is true
+    try
+        set focusedElement to value of attribute "AXFocusedUIElement" of frontApp
+        return value of attribute "AXSubrole" of focusedElement
+    end try
+end tell
+return """#;
+
+    std::process::Command::new("osascript")
+        .arg("-e")
+        .arg(SCRIPT)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .is_some_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "AXSecureTextField")
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn is_focused() -> bool {
+    false
+}
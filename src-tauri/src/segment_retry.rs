@@ -0,0 +1,204 @@
+//! 失败分段的持久重试队列
+//!
+//! 听写中单段转写重试仍失败（如短暂断网）时，分段音频加密后连同所属历史记录 ID 保存在应用数据目录，
+//! 由转写调度线程按指数退避补转写，成功后以转写文本替换历史记录中的占位符。
+
+use crate::audio_crypto::{self, DecryptedFile};
+use crate::processing::FailedSegment;
+use crate::settings::SettingsStore;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const RETRY_DIR_NAME: &str = "segment-retry";
+/// 首次补转写前的等待时长，之后每次失败翻倍
+const INITIAL_BACKOFF: Duration = Duration::from_secs(30);
+/// 退避等待的上限
+const MAX_BACKOFF: Duration = Duration::from_secs(30 * 60);
+/// 补转写失败超过该次数后放弃并删除音频
+const MAX_ATTEMPTS: u32 = 12;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RetryEntry {
+    history_id: String,
+    segment_index: usize,
+    attempts: u32,
+    next_attempt_ms: u64,
+}
+
+pub struct PendingSegment {
+    pub history_id: String,
+    pub segment_index: usize,
+    pub attempts: u32,
+    pub next_attempt_ms: u64,
+    pub audio_path: PathBuf,
+    entry_path: PathBuf,
+}
+
+impl PendingSegment {
+    pub fn is_due(&self, now_ms: u64) -> bool {
+        self.next_attempt_ms <= now_ms
+    }
+}
+
+/// 将失败分段的音频加密存入队列目录并记录所属历史记录，随后删除临时目录中的明文分段
+pub fn enqueue(
+    store: &SettingsStore,
+    history_id: &str,
+    segments: &[FailedSegment],
+    now_ms: u64,
+) -> Result<(), String> {
+    let dir = queue_dir(store)?;
+    let key = store.encryption_key().map_err(|err| err.to_string())?;
+    for segment in segments {
+        let name = format!("{history_id}-{:04}", segment.index);
+        let audio_path = dir.join(format!("{name}.wav"));
+        let source = fs::File::open(&segment.path).map_err(|err| err.to_string())?;
+        audio_crypto::encrypt_to_file(source, &audio_path, &key)?;
+        let _ = fs::remove_file(&segment.path);
+        let entry = RetryEntry {
+            history_id: history_id.to_string(),
+            segment_index: segment.index,
+            attempts: 0,
+            next_attempt_ms: now_ms + backoff(0).as_millis() as u64,
+        };
+        write_entry(&dir.join(format!("{name}.json")), &entry)?;
+    }
+    Ok(())
+}
+
+/// 删除未入队的失败分段音频（如未开启历史记录时）
+pub fn discard(segments: &[FailedSegment]) {
+    for segment in segments {
+        let _ = fs::remove_file(&segment.path);
+    }
+}
+
+/// 按历史记录与分段顺序列出队列中的分段
+pub fn pending(store: &SettingsStore) -> Vec<PendingSegment> {
+    let Ok(dir) = queue_dir(store) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut items = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry_path| {
+            let entry: RetryEntry =
+                serde_json::from_str(&fs::read_to_string(&entry_path).ok()?).ok()?;
+            let audio_path = entry_path.with_extension("wav");
+            if !audio_path.exists() {
+                let _ = fs::remove_file(&entry_path);
+                return None;
+            }
+            Some(PendingSegment {
+                history_id: entry.history_id,
+                segment_index: entry.segment_index,
+                attempts: entry.attempts,
+                next_attempt_ms: entry.next_attempt_ms,
+                audio_path,
+                entry_path,
+            })
+        })
+        .collect::<Vec<_>>();
+    items.sort_by(|left, right| {
+        left.history_id
+            .cmp(&right.history_id)
+            .then(left.segment_index.cmp(&right.segment_index))
+    });
+    items
+}
+
+/// 记录一次补转写失败并推迟下次重试；超过重试上限时移出队列，返回是否仍在队列中
+pub fn mark_failed(item: &PendingSegment, now_ms: u64) -> bool {
+    let attempts = item.attempts.saturating_add(1);
+    if attempts >= MAX_ATTEMPTS {
+        remove(item);
+        return false;
+    }
+    let entry = RetryEntry {
+        history_id: item.history_id.clone(),
+        segment_index: item.segment_index,
+        attempts,
+        next_attempt_ms: now_ms + backoff(attempts).as_millis() as u64,
+    };
+    write_entry(&item.entry_path, &entry).is_ok()
+}
+
+pub fn remove(item: &PendingSegment) {
+    let _ = fs::remove_file(&item.audio_path);
+    let _ = fs::remove_file(&item.entry_path);
+}
+
+/// 第 `attempts` 次失败后的等待时长，按指数增长并封顶
+fn backoff(attempts: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(1u32 << attempts.min(16))
+        .min(MAX_BACKOFF)
+}
+
+/// 以补转写的文本替换第一个占位符；占位符为空或已被后续处理改写时追加到末尾
+pub fn fill_placeholder(text: &str, placeholder: &str, recovered: &str) -> String {
+    let placeholder = placeholder.trim();
+    if !placeholder.is_empty() {
+        if let Some(index) = text.find(placeholder) {
+            let mut output = String::with_capacity(text.len() + recovered.len());
+            output.push_str(&text[..index]);
+            output.push_str(recovered);
+            output.push_str(&text[index + placeholder.len()..]);
+            return output;
+        }
+    }
+    if text.trim().is_empty() {
+        return recovered.to_string();
+    }
+    format!("{} {recovered}", text.trim_end())
+}
+
+fn write_entry(path: &Path, entry: &RetryEntry) -> Result<(), String> {
+    let content = serde_json::to_string(entry).map_err(|err| err.to_string())?;
+    fs::write(path, content).map_err(|err| err.to_string())
+}
+
+/// 将分段解密到 `scratch_dir` 下的临时文件，供补转写按路径读取
+pub fn decrypt(
+    store: &SettingsStore,
+    item: &PendingSegment,
+    scratch_dir: &Path,
+) -> Result<DecryptedFile, String> {
+    let key = store.encryption_key().map_err(|err| err.to_string())?;
+    audio_crypto::decrypt_to_temp(&item.audio_path, scratch_dir, &key)
+}
+
+pub fn queue_dir(store: &SettingsStore) -> Result<PathBuf, String> {
+    store
+        .recordings_dir(RETRY_DIR_NAME)
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        assert_eq!(backoff(0), Duration::from_secs(30));
+        assert_eq!(backoff(2), Duration::from_secs(120));
+        assert_eq!(backoff(20), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn fill_placeholder_replaces_first_occurrence_or_appends() {
+        assert_eq!(
+            fill_placeholder("hello […] and […]", "[…]", "world"),
+            "hello world and […]"
+        );
+        assert_eq!(fill_placeholder("hello", "[…]", "world"), "hello world");
+        assert_eq!(fill_placeholder("", "", "world"), "world");
+    }
+}
@@ -2,7 +2,12 @@ use super::{
     model::{normalize_local_model, resolve_vllm_model_id, spec_for_local_model, LocalRuntimeKind},
     native_runtime, SenseVoiceError,
 };
-use crate::settings::{Settings, TranscriptionAlignment};
+use crate::audio_inspect::{self, AudioFormat};
+use crate::audio_processing;
+use crate::provider_error::ProviderFailure;
+use crate::recorder::RecordedAudio;
+use crate::settings::{Settings, SpeechMetadata, TranscriptionAlignment};
+use crate::transcription::confidence_from_logprobs;
 use reqwest::blocking::{multipart, Client};
 use serde::Deserialize;
 use std::fs;
@@ -16,21 +21,47 @@ const REQUEST_TIMEOUT_SECS: u64 = 300;
 const CONNECT_TIMEOUT_SECS: u64 = 10;
 /// 网络错误最大重试次数
 const MAX_RETRIES: u32 = 2;
+/// 预热音频采样率
+const WARMUP_SAMPLE_RATE: u32 = 16_000;
 
 #[derive(Deserialize)]
 struct SenseVoiceResponse {
     text: String,
+    /// vLLM OpenAI 兼容接口在请求 `include[]=logprobs` 时返回
+    #[serde(default)]
+    logprobs: Vec<TokenLogprob>,
 }
 
+#[derive(Deserialize)]
+struct TokenLogprob {
+    logprob: f64,
+}
+
+/// SenseVoiceSmall 的情绪标签
+const EMOTION_TAGS: [&str; 7] = [
+    "HAPPY",
+    "SAD",
+    "ANGRY",
+    "NEUTRAL",
+    "FEARFUL",
+    "DISGUSTED",
+    "SURPRISED",
+];
+
+/// SenseVoiceSmall 的声学事件标签（`Speech` 为默认值，不单独记录）
+const EVENT_TAGS: [&str; 8] = [
+    "BGM", "Applause", "Laughter", "Cry", "Sneeze", "Breath", "Cough", "Speech",
+];
+
 pub struct SenseVoiceTranscription {
     pub text: String,
     pub alignment: Option<TranscriptionAlignment>,
+    pub confidence: Option<f32>,
+    pub metadata: SpeechMetadata,
 }
 
-pub fn transcribe_audio(
-    settings: &Settings,
-    audio_path: &Path,
-) -> Result<SenseVoiceTranscription, SenseVoiceError> {
+/// 校验本地模型已启用、已安装且不在预热中，不发起请求
+pub fn ensure_ready(settings: &Settings) -> Result<(), SenseVoiceError> {
     if !settings.sensevoice.enabled {
         return Err(SenseVoiceError::Config(
             "SenseVoice 尚未启用，请先下载并启用".to_string(),
@@ -51,15 +82,26 @@ pub fn transcribe_audio(
             "SenseVoice 模型正在预热中，请稍后重试".to_string(),
         ));
     }
+    Ok(())
+}
+
+pub fn transcribe_audio(
+    settings: &Settings,
+    audio_path: &Path,
+) -> Result<SenseVoiceTranscription, SenseVoiceError> {
+    ensure_ready(settings)?;
 
     let local_model = normalize_local_model(&settings.sensevoice.local_model);
     let local_model_spec = spec_for_local_model(local_model);
     if local_model_spec.runtime_kind == LocalRuntimeKind::Native {
         let result =
             native_runtime::transcribe_wav(local_model, &settings.sensevoice.language, audio_path)?;
+        let (text, metadata) = parse_rich_tags(&result.text);
         return Ok(SenseVoiceTranscription {
-            text: result.text,
+            text,
             alignment: result.alignment,
+            confidence: None,
+            metadata,
         });
     }
 
@@ -71,10 +113,10 @@ pub fn transcribe_audio(
     }
 
     let file_bytes = fs::read(audio_path).map_err(|err| SenseVoiceError::Io(err.to_string()))?;
-    let file_name = audio_path
-        .file_name()
-        .and_then(|name| name.to_str())
-        .unwrap_or("recording.wav");
+    let file_name = audio_inspect::upload_file_name(audio_path);
+    let mime = audio_inspect::detect_format(audio_path)
+        .unwrap_or(AudioFormat::Wav)
+        .mime();
 
     let client = Client::builder()
         .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
@@ -87,26 +129,31 @@ pub fn transcribe_audio(
         let mut form = multipart::Form::new().part(
             "file",
             multipart::Part::bytes(file_bytes.clone())
-                .file_name(file_name.to_string())
-                .mime_str("audio/wav")
+                .file_name(file_name.clone())
+                .mime_str(mime)
                 .unwrap(),
         );
         let endpoint = if local_model == "sensevoice" {
             form = form.text("language", "auto".to_string());
             "/api/v1/asr"
         } else {
-            let model_id = resolve_vllm_model_id(local_model, &settings.sensevoice.model_id);
+            let model_id = resolve_vllm_model_id(
+                local_model,
+                &settings.sensevoice.model_id,
+                &settings.sensevoice.quantization,
+            );
             form = form
                 .text("model", model_id)
-                .text("response_format", "json".to_string());
+                .text("response_format", "json".to_string())
+                .text("include[]", "logprobs".to_string());
             "/v1/audio/transcriptions"
         };
 
-        let response = match client
-            .post(format!("{service_url}{endpoint}"))
-            .multipart(form)
-            .send()
-        {
+        let mut request = client.post(format!("{service_url}{endpoint}"));
+        if local_model == "sensevoice" && !settings.sensevoice.api_token.is_empty() {
+            request = request.bearer_auth(&settings.sensevoice.api_token);
+        }
+        let response = match request.multipart(form).send() {
             Ok(resp) => resp,
             Err(err) => {
                 last_error = format_reqwest_error(&err);
@@ -127,9 +174,17 @@ pub fn transcribe_audio(
             let data: SenseVoiceResponse = response
                 .json()
                 .map_err(|err| SenseVoiceError::Parse(err.to_string()))?;
+            let logprobs = data
+                .logprobs
+                .iter()
+                .map(|token| token.logprob)
+                .collect::<Vec<_>>();
+            let (text, metadata) = parse_rich_tags(&data.text);
             return Ok(SenseVoiceTranscription {
-                text: data.text,
+                text,
                 alignment: None,
+                confidence: confidence_from_logprobs(&logprobs),
+                metadata,
             });
         }
 
@@ -139,6 +194,9 @@ pub fn transcribe_audio(
             thread::sleep(Duration::from_secs(2));
             continue;
         }
+        if let Some(failure) = ProviderFailure::classify(status.as_u16(), &body) {
+            return Err(failure.into());
+        }
         return Err(SenseVoiceError::Request(format!("{status}: {body}")));
     }
 
@@ -151,6 +209,70 @@ pub fn transcribe_audio(
     ))
 }
 
+/// 发送 1 秒静音音频触发模型编译与显存分配，使首次真实听写不再承担预热开销
+pub fn warm_up(settings: &Settings) -> Result<(), SenseVoiceError> {
+    let silence = RecordedAudio {
+        samples: vec![0; WARMUP_SAMPLE_RATE as usize],
+        sample_rate: WARMUP_SAMPLE_RATE,
+        channels: 1,
+        spill: None,
+        tracks: Vec::new(),
+    };
+    let path = std::env::temp_dir()
+        .join("vtt-keyboard")
+        .join("sensevoice-warmup.wav");
+    audio_processing::write_wav_file(&path, &silence)
+        .map_err(|err| SenseVoiceError::Io(err.to_string()))?;
+    let result = transcribe_audio(settings, &path);
+    let _ = fs::remove_file(&path);
+    result.map(|_| ())
+}
+
+/// 去除 SenseVoice 富文本标签（如 `<|zh|><|HAPPY|><|Laughter|><|withitn|>`），
+/// 并将语种、情绪与声学事件提取为结构化信息
+pub fn parse_rich_tags(raw: &str) -> (String, SpeechMetadata) {
+    let mut metadata = SpeechMetadata::default();
+    let mut text = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(start) = rest.find("<|") {
+        let Some(len) = rest[start + 2..].find("|>") else {
+            break;
+        };
+        text.push_str(&rest[..start]);
+        classify_tag(&rest[start + 2..start + 2 + len], &mut metadata);
+        rest = &rest[start + 2 + len + 2..];
+    }
+    text.push_str(rest);
+    (text.trim().to_string(), metadata)
+}
+
+fn classify_tag(tag: &str, metadata: &mut SpeechMetadata) {
+    if let Some(emotion) = EMOTION_TAGS
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(tag))
+    {
+        metadata
+            .emotion
+            .get_or_insert_with(|| emotion.to_ascii_lowercase());
+        return;
+    }
+    if let Some(event) = EVENT_TAGS
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(tag))
+    {
+        let event = event.to_ascii_lowercase();
+        if event != "speech" && !metadata.events.contains(&event) {
+            metadata.events.push(event);
+        }
+        return;
+    }
+    // 其余短的纯字母标签视为语种（zh/en/yue/ja/ko），ITN 标记与未知标签直接丢弃
+    let is_language = (2..=3).contains(&tag.len()) && tag.chars().all(|ch| ch.is_ascii_lowercase());
+    if is_language && metadata.language.is_none() {
+        metadata.language = Some(tag.to_string());
+    }
+}
+
 /// 展开 reqwest 错误链，便于诊断
 fn format_reqwest_error(err: &reqwest::Error) -> String {
     let mut msg = err.to_string();
@@ -169,3 +291,27 @@ fn is_warming_up_error(body: &str) -> bool {
         || lowered.contains("retry")
         || lowered.contains("预热")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rich_tags_extracts_metadata_and_strips_tags() {
+        let (text, metadata) =
+            parse_rich_tags("<|zh|><|HAPPY|><|Laughter|><|withitn|>今天天气真好。");
+        assert_eq!(text, "今天天气真好。");
+        assert_eq!(metadata.language.as_deref(), Some("zh"));
+        assert_eq!(metadata.emotion.as_deref(), Some("happy"));
+        assert_eq!(metadata.events, vec!["laughter"]);
+
+        let (text, metadata) = parse_rich_tags("<|en|><|EMO_UNKNOWN|><|Speech|><|woitn|>hello");
+        assert_eq!(text, "hello");
+        assert_eq!(metadata.emotion, None);
+        assert!(metadata.events.is_empty());
+
+        let (text, metadata) = parse_rich_tags("plain text");
+        assert_eq!(text, "plain text");
+        assert_eq!(metadata, SpeechMetadata::default());
+    }
+}
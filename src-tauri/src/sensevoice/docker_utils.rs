@@ -253,6 +253,143 @@ pub(super) fn read_selected_hub(state_file: &Path) -> Option<String> {
         .map(str::to_string)
 }
 
+/// `docker info` 成功即表示 daemon 已就绪
+pub(crate) fn docker_daemon_running() -> bool {
+    let mut info = docker_command();
+    info.arg("info").stdout(Stdio::null()).stderr(Stdio::null());
+    hide_window(&mut info);
+    info.status().is_ok_and(|status| status.success())
+}
+
+/// 按平台启动 Docker Desktop，只负责拉起进程，不等待 daemon 就绪
+pub(super) fn launch_docker_desktop() -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let program_files =
+            std::env::var("ProgramFiles").unwrap_or_else(|_| "C:\\Program Files".to_string());
+        let executable = Path::new(&program_files)
+            .join("Docker")
+            .join("Docker")
+            .join("Docker Desktop.exe");
+        if !executable.exists() {
+            return Err(format!("未找到 Docker Desktop: {}", executable.display()));
+        }
+        Command::new(executable)
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = Command::new("open");
+        command.arg("-a").arg("Docker");
+        command
+    };
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = {
+        let mut command = Command::new("systemctl");
+        command.arg("--user").arg("start").arg("docker-desktop");
+        command
+    };
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    hide_window(&mut command);
+    // Windows 下 Docker Desktop 为常驻 GUI 进程，不等待其退出
+    #[cfg(target_os = "windows")]
+    let launched = command.spawn().map(|_| true);
+    #[cfg(not(target_os = "windows"))]
+    let launched = command.status().map(|status| status.success());
+    match launched {
+        Ok(true) => Ok(()),
+        Ok(false) => Err("启动 Docker Desktop 失败".to_string()),
+        Err(err) => Err(format!("启动 Docker Desktop 失败: {err}")),
+    }
+}
+
+/// 轮询等待 Docker daemon 就绪，每次轮询时回调已等待的时长；超时返回 false
+pub(super) fn wait_docker_daemon<F>(timeout: Duration, mut on_wait: F) -> bool
+where
+    F: FnMut(Duration),
+{
+    let started = Instant::now();
+    loop {
+        if docker_daemon_running() {
+            return true;
+        }
+        let elapsed = started.elapsed();
+        if elapsed >= timeout {
+            return false;
+        }
+        on_wait(elapsed);
+        thread::sleep(Duration::from_secs(2));
+    }
+}
+
+/// 单块 GPU 的显存信息（MiB）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct GpuMemory {
+    pub total_mib: u64,
+    pub free_mib: u64,
+}
+
+/// 通过 nvidia-smi 查询 GPU 显存；命令不可用时返回 None（由调用方决定是否跳过检查）
+pub(crate) fn query_gpu_memory() -> Option<Vec<GpuMemory>> {
+    let mut command = Command::new("nvidia-smi");
+    command
+        .arg("--query-gpu=memory.total,memory.free")
+        .arg("--format=csv,noheader,nounits");
+    hide_window(&mut command);
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_gpu_memory(&String::from_utf8_lossy(&output.stdout)))
+}
+
+pub(super) fn parse_gpu_memory(output: &str) -> Vec<GpuMemory> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut values = line.split(',').map(|value| value.trim().parse::<u64>());
+            let total_mib = values.next()?.ok()?;
+            let free_mib = values.next()?.ok()?;
+            Some(GpuMemory {
+                total_mib,
+                free_mib,
+            })
+        })
+        .collect()
+}
+
+/// 检查首块 GPU（vLLM 默认使用的设备）能否满足模型显存需求。
+/// vLLM 启动时要求按 `utilization` 比例预留的显存全部空闲，否则直接 OOM 退出
+pub(super) fn check_vram(
+    gpus: &[GpuMemory],
+    required_mib: u64,
+    utilization: f32,
+) -> Result<(), String> {
+    let Some(gpu) = gpus.first() else {
+        return Err("未检测到 NVIDIA GPU，vLLM 模型需要 CUDA GPU 才能运行".to_string());
+    };
+    let to_gb = |mib: u64| mib as f64 / 1024.0;
+    if gpu.total_mib < required_mib {
+        return Err(format!(
+            "显存不足：该模型需要约 {:.1} GB 显存，检测到的 GPU 仅有 {:.1} GB",
+            to_gb(required_mib),
+            to_gb(gpu.total_mib)
+        ));
+    }
+    let reserved_mib = (gpu.total_mib as f64 * f64::from(utilization)).ceil() as u64;
+    let needed_mib = reserved_mib.max(required_mib);
+    if gpu.free_mib < needed_mib {
+        return Err(format!(
+            "显存不足：vLLM 需要约 {:.1} GB 空闲显存，当前仅空闲 {:.1} GB，请关闭其他占用显存的程序后重试",
+            to_gb(needed_mib),
+            to_gb(gpu.free_mib)
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -327,4 +464,38 @@ mod tests {
         let missing = PathBuf::from("/nonexistent/state.json");
         assert_eq!(read_selected_hub(&missing), None);
     }
+
+    #[test]
+    fn parse_gpu_memory_reads_nvidia_smi_csv() {
+        let gpus = parse_gpu_memory("24576, 20000\n8192, 512\ninvalid\n");
+        assert_eq!(
+            gpus,
+            vec![
+                GpuMemory {
+                    total_mib: 24576,
+                    free_mib: 20000
+                },
+                GpuMemory {
+                    total_mib: 8192,
+                    free_mib: 512
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn check_vram_reports_total_and_free_shortfalls() {
+        let gpu = |total_mib, free_mib| GpuMemory {
+            total_mib,
+            free_mib,
+        };
+        assert!(check_vram(&[gpu(24576, 24000)], 10240, 0.8).is_ok());
+        assert!(check_vram(&[gpu(8192, 8000)], 10240, 0.8)
+            .unwrap_err()
+            .contains("10.0 GB"));
+        assert!(check_vram(&[gpu(24576, 4096)], 10240, 0.8)
+            .unwrap_err()
+            .contains("4.0 GB"));
+        assert!(check_vram(&[], 10240, 0.8).is_err());
+    }
 }
@@ -1,21 +1,26 @@
 use super::docker_utils::{
-    bind_mount, docker_command, docker_container_running, docker_image_exists,
-    get_container_label, hide_window, is_missing_container_error, normalize_log_line,
-    normalize_publish_host, parse_host_and_port, read_selected_hub, remove_container_if_exists,
-    run_command_streaming, start_container,
+    bind_mount, check_vram, docker_command, docker_container_running, docker_image_exists,
+    get_container_label, hide_window, is_missing_container_error, launch_docker_desktop,
+    normalize_log_line, normalize_publish_host, parse_host_and_port, query_gpu_memory,
+    read_selected_hub, remove_container_if_exists, run_command_streaming, start_container,
+    wait_docker_daemon,
 };
 use super::{
+    client,
     model::{
-        docker_container_name, is_vllm_local_model, legacy_container_names,
-        normalize_local_model, resolve_vllm_model_id, runtime_container_name,
-        runtime_image_tag, service_start_timeout, spec_for_local_model, LocalRuntimeKind,
-        CONTAINER_LABEL_MODEL_ID, CONTAINER_LABEL_MODEL_KEY, LOCAL_MODEL_SENSEVOICE,
-        LOCAL_MODEL_VOXTRAL,
+        docker_container_name, estimated_vram_mib, is_vllm_local_model, legacy_container_names,
+        normalize_local_model, normalize_vllm_quantization, resolve_vllm_model_id,
+        runtime_container_name, runtime_image_tag, service_start_timeout, spec_for_local_model,
+        LocalRuntimeKind, CONTAINER_LABEL_DEVICE, CONTAINER_LABEL_MODEL_ID,
+        CONTAINER_LABEL_MODEL_KEY, LOCAL_MODEL_SENSEVOICE, LOCAL_MODEL_VOXTRAL,
+        PREPARE_CONTAINER_NAME, VLLM_QUANTIZATION_FP8, VLLM_QUANTIZATION_NONE,
     },
     native_runtime, SenseVoiceError,
 };
+use crate::presence;
 use crate::sensevoice::worker::{WorkerEvent, WorkerJob};
-use crate::settings::SettingsStore;
+use crate::settings::{BatteryPolicy, SenseVoiceHealthSettings, SenseVoiceSettings, SettingsStore};
+use crate::system_state::{self, PowerSource};
 use crate::AppState;
 use serde::Serialize;
 use serde_json::Value;
@@ -47,12 +52,11 @@ const VLLM_CONFIG_DIR_NAME: &str = "vllm-config";
 
 const VLLM_INTERNAL_PORT: u16 = 8000;
 const VLLM_REQUIRED_DEVICE: &str = "cuda";
+const CPU_DEVICE: &str = "cpu";
 const VLLM_GPU_MEMORY_UTILIZATION: f32 = 0.8;
 const VOXTRAL_ATTENTION_BACKEND: &str = "TRITON_ATTN";
-const HEALTH_REQUEST_TIMEOUT_SECS: u64 = 2;
-const HEALTH_MONITOR_WARN_SECS: u64 = 120;
-const HEALTH_MONITOR_INTERVAL_MILLIS: u64 = 1000;
 const DOCKER_BUILD_TIMEOUT_SECS: u64 = 40 * 60;
+const DOCKER_DESKTOP_START_TIMEOUT_SECS: u64 = 120;
 const IMAGE_STAMP_FILE: &str = "image.stamp";
 const WORKER_ARG: &str = "--sensevoice-worker";
 const WORKER_JOB_FILE_ARG: &str = "--job-file";
@@ -245,6 +249,10 @@ impl SenseVoiceManager {
             state_file: paths.state_file.to_string_lossy().to_string(),
             image_tag: runtime_image_tag(local_model).to_string(),
             container_name: runtime_container_name(local_model).to_string(),
+            auto_start_docker: sensevoice.auto_start_docker,
+            api_token: store
+                .ensure_sensevoice_api_token()
+                .map_err(|err| SenseVoiceError::Settings(err.to_string()))?,
         };
         self.spawn_prepare_worker(app, store, &job)?;
         self.status(store)
@@ -285,17 +293,27 @@ impl SenseVoiceManager {
                 .path()
                 .app_local_data_dir()
                 .map(|d| d.join("sensevoice").join("runtime").join(VLLM_CONFIG_DIR_NAME));
-            let (loaded_key, loaded_id) = config_dir_result
-                .ok()
-                .and_then(|dir| read_vllm_config_model(&dir))
+            let config_dir = config_dir_result.ok();
+            let (loaded_key, loaded_id) = config_dir
+                .as_deref()
+                .and_then(read_vllm_config_model)
                 .or_else(|| {
                     let k = get_container_label(container_name, CONTAINER_LABEL_MODEL_KEY);
                     let i = get_container_label(container_name, CONTAINER_LABEL_MODEL_ID);
                     Some((k?, i?))
                 })
                 .unwrap_or_default();
-            let expected_model_id = resolve_vllm_model_id(local_model, &sensevoice.model_id);
-            if loaded_key == local_model && loaded_id == expected_model_id {
+            let loaded_quantization = config_dir
+                .as_deref()
+                .map(read_vllm_config_quantization)
+                .unwrap_or_else(|| VLLM_QUANTIZATION_NONE.to_string());
+            let quantization = normalize_vllm_quantization(local_model, &sensevoice.quantization);
+            let expected_model_id =
+                resolve_vllm_model_id(local_model, &sensevoice.model_id, quantization);
+            if loaded_key == local_model
+                && loaded_id == expected_model_id
+                && loaded_quantization == quantization
+            {
                 self.start_in_progress = false;
                 self.start_cancel_flag.store(false, Ordering::Relaxed);
                 return self.status(store);
@@ -558,6 +576,11 @@ impl SenseVoiceManager {
         runtime_state == RuntimeState::Running || runtime_state == RuntimeState::Paused
     }
 
+    /// 读取缓存的容器运行状态，不调用 docker
+    pub fn is_container_running_cached(&self) -> bool {
+        self.container_running_cache.load(Ordering::Relaxed)
+    }
+
     fn is_prepare_running(&mut self) -> bool {
         self.reconcile_prepare_task();
         self.prepare_child.is_some() || self.native_prepare_in_progress.load(Ordering::Relaxed)
@@ -616,6 +639,34 @@ impl SenseVoiceManager {
         }
     }
 
+    /// 取消正在进行的准备任务：结束后台 worker 及其下载容器，并将状态回退到准备前
+    pub fn cancel_prepare(
+        &mut self,
+        app: &AppHandle,
+        store: &SettingsStore,
+    ) -> Result<SenseVoiceStatus, SenseVoiceError> {
+        self.reconcile_prepare_task();
+        if self.prepare_child.is_none() {
+            if self.native_prepare_in_progress.load(Ordering::Relaxed) {
+                return Err(SenseVoiceError::Config(
+                    "原生模型下载暂不支持取消".to_string(),
+                ));
+            }
+            return self.status(store);
+        }
+        self.stop_prepare_task();
+        // worker 被结束后 docker CLI 随之断开，但已启动的容器仍会继续下载，需要显式删除
+        let _ = remove_container_if_exists(PREPARE_CONTAINER_NAME);
+        let installed = store
+            .load_sensevoice()
+            .map_err(|err| SenseVoiceError::Settings(err.to_string()))?
+            .installed;
+        let download_state = if installed { "ready" } else { "idle" };
+        self.update_state(store, download_state, "", None, None)?;
+        self.emit_progress(app, "cancelled", "Preparation cancelled", None);
+        self.status(store)
+    }
+
     fn spawn_prepare_worker(
         &mut self,
         app: &AppHandle,
@@ -845,8 +896,11 @@ pub fn ensure_service_ready_blocking(
         if matches!(
             docker_container_state(container_name),
             Ok(RuntimeState::Running)
-        ) && is_service_http_ready(&sensevoice.service_url, is_vllm_local_model(local_model))
-        {
+        ) && is_service_http_ready(
+            &sensevoice.service_url,
+            is_vllm_local_model(local_model),
+            &sensevoice.health,
+        ) {
             return Ok(());
         }
     }
@@ -867,12 +921,16 @@ pub fn ensure_service_ready_blocking(
 }
 
 /// 仅用于 ensure_service_ready_blocking 的 HTTP 健康探测（短超时，失败即认为未就绪）。
-fn is_service_http_ready(service_url: &str, is_vllm: bool) -> bool {
+fn is_service_http_ready(
+    service_url: &str,
+    is_vllm: bool,
+    health: &SenseVoiceHealthSettings,
+) -> bool {
     let trimmed = service_url.trim().trim_end_matches('/');
     if trimmed.is_empty() {
         return false;
     }
-    let client = health_client();
+    let client = health_client(health);
     let health_url = format!("{trimmed}/health");
     let Ok(response) = client.get(&health_url).send() else {
         return false;
@@ -902,7 +960,7 @@ fn wait_for_service_ready(
             if spec.runtime_kind == LocalRuntimeKind::Native {
                 return Ok(());
             }
-            if is_service_http_ready(&sensevoice.service_url, is_vllm) {
+            if is_service_http_ready(&sensevoice.service_url, is_vllm, &sensevoice.health) {
                 return Ok(());
             }
         }
@@ -912,7 +970,7 @@ fn wait_for_service_ready(
         }
         // 次路径：Docker 容器确实就绪但 state 尚未更新
         if spec.runtime_kind == LocalRuntimeKind::Docker
-            && is_service_http_ready(&sensevoice.service_url, is_vllm)
+            && is_service_http_ready(&sensevoice.service_url, is_vllm, &sensevoice.health)
         {
             return Ok(());
         }
@@ -1069,7 +1127,8 @@ fn run_startup_task(
                     Arc::clone(&manager.container_paused_cache),
                 ));
             }
-            ensure_docker_available()?;
+            let forced_cpu = apply_battery_policy(&app, local_model, &mut sensevoice)?;
+            ensure_docker_available(&app, sensevoice.auto_start_docker)?;
             check_start_cancelled(&cancel_flag)?;
             let (host, port) = parse_host_and_port(&sensevoice.service_url)
                 .map_err(SenseVoiceError::Url)?;
@@ -1094,7 +1153,9 @@ fn run_startup_task(
             // 单容器模式：检查当前容器配置的模型是否匹配（含具体变体 ID）
             // 优先读取宿主机配置文件（容器内模型调度的真实来源），回退到 Docker labels
             let mut effective_container_state = container_state;
-            let expected_model_id = resolve_vllm_model_id(local_model, &sensevoice.model_id);
+            let quantization = normalize_vllm_quantization(local_model, &sensevoice.quantization);
+            let expected_model_id =
+                resolve_vllm_model_id(local_model, &sensevoice.model_id, quantization);
             let config_dir = vllm_config_dir(&paths.runtime_dir);
             if container_state != RuntimeState::Stopped {
                 let (loaded_key, loaded_id) = read_vllm_config_model(&config_dir)
@@ -1106,8 +1167,13 @@ fn run_startup_task(
                     })
                     .unwrap_or_default();
                 let key_matches = loaded_key == local_model;
-                let id_matches = loaded_id == expected_model_id;
-                if !key_matches || !id_matches {
+                let id_matches = loaded_id == expected_model_id
+                    && read_vllm_config_quantization(&config_dir) == quantization;
+                // 电池策略可能临时改用 CPU；无设备标签的旧容器视为按设置中的设备创建
+                let device_matches = is_vllm_local_model(local_model)
+                    || get_container_label(container_name, CONTAINER_LABEL_DEVICE)
+                        .map_or(!forced_cpu, |device| device == sensevoice.device);
+                if !key_matches || !id_matches || !device_matches {
                     // 模型不匹配：判断是否可以容器内切换
                     let old_is_vllm = is_vllm_local_model(&loaded_key);
                     let new_is_vllm = is_vllm_local_model(local_model);
@@ -1137,9 +1203,10 @@ fn run_startup_task(
                             &config_dir,
                             normalize_local_model(local_model),
                             &expected_model_id,
+                            quantization,
                             port,
                             VLLM_GPU_MEMORY_UTILIZATION,
-                            &vllm_extra_args(local_model),
+                            &vllm_extra_args(local_model, quantization),
                         )?;
                         // 容器已处于 exited 状态，走 docker start 路径
                         effective_container_state = RuntimeState::Exited;
@@ -1196,7 +1263,8 @@ fn run_startup_task(
                             wait_service_reachable(
                                 container_name,
                                 &sensevoice.service_url,
-                                service_start_timeout(local_model),
+                                &sensevoice.health,
+                                startup_timeout(local_model, &sensevoice.health),
                                 &current_log_path,
                                 &current_runtime_tail,
                                 &cancel_flag,
@@ -1276,7 +1344,8 @@ fn run_startup_task(
                             wait_service_reachable(
                                 container_name,
                                 &sensevoice.service_url,
-                                service_start_timeout(local_model),
+                                &sensevoice.health,
+                                startup_timeout(local_model, &sensevoice.health),
                                 &current_log_path,
                                 &current_runtime_tail,
                                 &cancel_flag,
@@ -1341,7 +1410,8 @@ fn run_startup_task(
                     wait_service_reachable(
                         container_name,
                         &sensevoice.service_url,
-                        service_start_timeout(local_model),
+                        &sensevoice.health,
+                        startup_timeout(local_model, &sensevoice.health),
                         &current_log_path,
                         &current_runtime_tail,
                         &cancel_flag,
@@ -1373,6 +1443,9 @@ fn run_startup_task(
             let _ = remove_container_if_exists(container_name);
             if local_model == LOCAL_MODEL_SENSEVOICE {
                 let hub = read_selected_hub(&paths.state_file).unwrap_or_else(|| "hf".to_string());
+                let api_token = store
+                    .ensure_sensevoice_api_token()
+                    .map_err(|err| SenseVoiceError::Settings(err.to_string()))?;
                 run_service_container(
                     container_name,
                     &publish_host,
@@ -1381,9 +1454,13 @@ fn run_startup_task(
                     &sensevoice.model_id,
                     &sensevoice.device,
                     &hub,
+                    &api_token,
                 )?;
             } else {
-                let model_id = resolve_vllm_model_id(local_model, &sensevoice.model_id);
+                let quantization =
+                    normalize_vllm_quantization(local_model, &sensevoice.quantization);
+                let model_id =
+                    resolve_vllm_model_id(local_model, &sensevoice.model_id, quantization);
                 run_vllm_service_container(
                     local_model,
                     container_name,
@@ -1391,6 +1468,7 @@ fn run_startup_task(
                     port,
                     &paths.models_dir,
                     &model_id,
+                    quantization,
                     &config_dir,
                 )?;
             }
@@ -1419,7 +1497,8 @@ fn run_startup_task(
             wait_service_reachable(
                 container_name,
                 &sensevoice.service_url,
-                service_start_timeout(local_model),
+                &sensevoice.health,
+                startup_timeout(local_model, &sensevoice.health),
                 &current_log_path,
                 &current_runtime_tail,
                 &cancel_flag,
@@ -1489,6 +1568,94 @@ fn run_startup_task(
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BatteryDecision {
+    Proceed,
+    UseCpu,
+    Refuse,
+}
+
+/// 按供电状态与策略决定 GPU 运行时能否启动；`cpu_capable` 表示模型支持 CPU 推理
+fn battery_decision(
+    policy: BatteryPolicy,
+    power: PowerSource,
+    uses_gpu: bool,
+    cpu_capable: bool,
+) -> BatteryDecision {
+    if power != PowerSource::Battery || !uses_gpu {
+        return BatteryDecision::Proceed;
+    }
+    match policy {
+        BatteryPolicy::Allow => BatteryDecision::Proceed,
+        BatteryPolicy::PreferCpu if cpu_capable => BatteryDecision::UseCpu,
+        BatteryPolicy::Refuse | BatteryPolicy::PreferCpu => BatteryDecision::Refuse,
+    }
+}
+
+/// 电池供电时按策略拒绝启动或改用 CPU，决策通过进度事件告知前端；返回是否临时改用了 CPU
+fn apply_battery_policy(
+    app: &AppHandle,
+    local_model: &str,
+    sensevoice: &mut SenseVoiceSettings,
+) -> Result<bool, SenseVoiceError> {
+    let is_vllm = is_vllm_local_model(local_model);
+    let uses_gpu = is_vllm || sensevoice.device != CPU_DEVICE;
+    let decision = battery_decision(
+        sensevoice.battery_policy,
+        system_state::detect_power_source(),
+        uses_gpu,
+        !is_vllm,
+    );
+    match decision {
+        BatteryDecision::Proceed => Ok(false),
+        BatteryDecision::UseCpu => {
+            emit_progress_payload(
+                app,
+                "battery",
+                "On battery power, starting local runtime on CPU",
+                None,
+                None,
+                None,
+                None,
+            );
+            sensevoice.device = CPU_DEVICE.to_string();
+            Ok(true)
+        }
+        BatteryDecision::Refuse => {
+            emit_progress_payload(
+                app,
+                "battery",
+                "On battery power, GPU runtime was not started",
+                None,
+                None,
+                None,
+                None,
+            );
+            Err(SenseVoiceError::Config(
+                "当前使用电池供电，已按设置拒绝启动 GPU 本地模型".to_string(),
+            ))
+        }
+    }
+}
+
+/// 服务就绪后按设置发送预热请求；失败仅记录日志，不影响服务状态
+fn warm_up_service(store: &SettingsStore) {
+    let Ok(settings) = store.load() else {
+        return;
+    };
+    if !settings.sensevoice.warmup_on_start {
+        return;
+    }
+    // 用户离开时不预热，首次转写时再加载模型
+    if presence::is_away(&settings.presence) {
+        return;
+    }
+    if let Err(_err) = client::warm_up(&settings) {
+        #[cfg(debug_assertions)]
+        eprintln!("[sensevoice] 模型预热失败: {_err}");
+    }
+}
+
 fn spawn_health_monitor(
     app: AppHandle,
     store: SettingsStore,
@@ -1501,7 +1668,11 @@ fn spawn_health_monitor(
     paused_cache: Arc<AtomicBool>,
 ) {
     thread::spawn(move || {
-        let client = health_client();
+        let health = store
+            .load_sensevoice()
+            .map(|sensevoice| sensevoice.health)
+            .unwrap_or_default();
+        let client = health_client(&health);
         let local_model = normalize_local_model(&local_model).to_string();
         let container_name = runtime_container_name(&local_model).to_string();
         let is_vllm_model = is_vllm_local_model(&local_model);
@@ -1576,6 +1747,7 @@ fn spawn_health_monitor(
                             None,
                             None,
                         );
+                        warm_up_service(&store);
                         return;
                     }
                     if last_warmup_emit.elapsed() >= Duration::from_secs(3) {
@@ -1593,7 +1765,7 @@ fn spawn_health_monitor(
                 }
             }
 
-            if !warned && started.elapsed() >= Duration::from_secs(HEALTH_MONITOR_WARN_SECS) {
+            if !warned && started.elapsed() >= Duration::from_secs(health.warmup_warn_secs) {
                 emit_progress_payload(
                     &app,
                     "warmup",
@@ -1606,7 +1778,9 @@ fn spawn_health_monitor(
                 warned = true;
             }
 
-            thread::sleep(Duration::from_millis(HEALTH_MONITOR_INTERVAL_MILLIS));
+            thread::sleep(crate::low_resource::poll_interval(Duration::from_millis(
+                health.interval_ms,
+            )));
         }
     });
 }
@@ -1614,13 +1788,14 @@ fn spawn_health_monitor(
 fn wait_service_reachable(
     container_name: &str,
     service_url: &str,
+    health: &SenseVoiceHealthSettings,
     timeout: Duration,
     log_path: &Path,
     runtime_tail: &Arc<Mutex<VecDeque<String>>>,
     cancel_flag: &Arc<AtomicBool>,
 ) -> Result<(), SenseVoiceError> {
     let url = format!("{}/health", service_url.trim_end_matches('/'));
-    let client = health_client();
+    let client = health_client(health);
     let started = Instant::now();
 
     while started.elapsed() < timeout {
@@ -1760,6 +1935,15 @@ fn finish_start_task(app: &AppHandle, cancel_flag: &Arc<AtomicBool>) {
     }
 }
 
+/// 服务启动超时：设置为 0 时使用模型默认值
+fn startup_timeout(local_model: &str, health: &SenseVoiceHealthSettings) -> Duration {
+    if health.startup_timeout_secs > 0 {
+        Duration::from_secs(health.startup_timeout_secs)
+    } else {
+        service_start_timeout(local_model)
+    }
+}
+
 fn check_start_cancelled(cancel_flag: &Arc<AtomicBool>) -> Result<(), SenseVoiceError> {
     if cancel_flag.load(Ordering::Relaxed) {
         return Err(SenseVoiceError::Process(START_CANCELLED_MARKER.to_string()));
@@ -1771,9 +1955,9 @@ fn is_start_cancelled_error(err: &SenseVoiceError) -> bool {
     matches!(err, SenseVoiceError::Process(message) if message == START_CANCELLED_MARKER)
 }
 
-fn health_client() -> reqwest::blocking::Client {
+fn health_client(health: &SenseVoiceHealthSettings) -> reqwest::blocking::Client {
     reqwest::blocking::Client::builder()
-        .timeout(Duration::from_secs(HEALTH_REQUEST_TIMEOUT_SECS))
+        .timeout(Duration::from_secs(health.request_timeout_secs))
         .build()
         .unwrap_or_else(|_| reqwest::blocking::Client::new())
 }
@@ -1965,7 +2149,7 @@ fn write_runtime_files(paths: &SenseVoicePaths) -> Result<(), SenseVoiceError> {
     Ok(())
 }
 
-fn ensure_docker_available() -> Result<(), SenseVoiceError> {
+fn ensure_docker_available(app: &AppHandle, auto_start: bool) -> Result<(), SenseVoiceError> {
     let mut version = docker_command();
     version
         .arg("version")
@@ -1990,10 +2174,57 @@ fn ensure_docker_available() -> Result<(), SenseVoiceError> {
         .map_err(|err| SenseVoiceError::Config(format!("无法连接 Docker daemon: {err}")))?;
     if !output.status.success() {
         let detail = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if !auto_start {
+            return Err(SenseVoiceError::Config(format!(
+                "Docker daemon 未运行，请先启动 Docker Desktop: {detail}"
+            )));
+        }
+        start_docker_desktop(app)?;
+    }
+    Ok(())
+}
+
+/// Docker daemon 未运行时启动 Docker Desktop，并在等待就绪期间持续上报进度
+fn start_docker_desktop(app: &AppHandle) -> Result<(), SenseVoiceError> {
+    emit_progress_payload(
+        app,
+        "docker",
+        "Starting Docker Desktop",
+        None,
+        None,
+        None,
+        None,
+    );
+    launch_docker_desktop().map_err(|err| {
+        SenseVoiceError::Config(format!("{err}，请手动启动 Docker Desktop 后重试"))
+    })?;
+    let timeout = Duration::from_secs(DOCKER_DESKTOP_START_TIMEOUT_SECS);
+    let ready = wait_docker_daemon(timeout, |elapsed| {
+        emit_progress_payload(
+            app,
+            "docker",
+            "Waiting for Docker daemon",
+            None,
+            Some(format!("{}s / {}s", elapsed.as_secs(), timeout.as_secs())),
+            None,
+            None,
+        );
+    });
+    if !ready {
         return Err(SenseVoiceError::Config(format!(
-            "Docker daemon 未运行，请先启动 Docker Desktop: {detail}"
+            "Docker Desktop 已启动，但 daemon 在 {} 秒内未就绪，请稍后重试",
+            timeout.as_secs()
         )));
     }
+    emit_progress_payload(
+        app,
+        "docker",
+        "Docker daemon is ready",
+        None,
+        None,
+        None,
+        None,
+    );
     Ok(())
 }
 
@@ -2094,6 +2325,7 @@ fn runtime_stamp() -> String {
     format!("{:x}", hasher.finish())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run_service_container(
     container_name: &str,
     publish_host: &str,
@@ -2102,6 +2334,7 @@ fn run_service_container(
     model_id: &str,
     device: &str,
     hub: &str,
+    api_token: &str,
 ) -> Result<(), SenseVoiceError> {
     fs::create_dir_all(model_dir).map_err(|err| SenseVoiceError::Io(err.to_string()))?;
     let mut command = docker_command();
@@ -2114,6 +2347,8 @@ fn run_service_container(
         .arg(format!("{CONTAINER_LABEL_MODEL_KEY}={LOCAL_MODEL_SENSEVOICE}"))
         .arg("--label")
         .arg(format!("{CONTAINER_LABEL_MODEL_ID}={model_id}"))
+        .arg("--label")
+        .arg(format!("{CONTAINER_LABEL_DEVICE}={device}"))
         .arg("-p")
         .arg(format!("{publish_host}:{port}:{port}"))
         .arg("--mount")
@@ -2130,6 +2365,8 @@ fn run_service_container(
         .arg("SENSEVOICE_HOST=0.0.0.0")
         .arg("-e")
         .arg(format!("SENSEVOICE_PORT={port}"))
+        .arg("-e")
+        .arg(format!("SENSEVOICE_API_TOKEN={api_token}"))
         .arg(runtime_image_tag(LOCAL_MODEL_SENSEVOICE));
 
     hide_window(&mut command);
@@ -2147,6 +2384,7 @@ fn run_service_container(
     )))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run_vllm_service_container(
     local_model: &str,
     container_name: &str,
@@ -2154,17 +2392,20 @@ fn run_vllm_service_container(
     host_port: u16,
     model_dir: &Path,
     model_id: &str,
+    quantization: &str,
     config_dir: &Path,
 ) -> Result<(), SenseVoiceError> {
+    preflight_vllm_vram(local_model, model_id, quantization)?;
     fs::create_dir_all(model_dir).map_err(|err| SenseVoiceError::Io(err.to_string()))?;
     // 写入 entrypoint.sh 和 model.conf 到宿主机配置目录
     write_vllm_config(
         config_dir,
         normalize_local_model(local_model),
         model_id,
+        quantization,
         VLLM_INTERNAL_PORT,
         VLLM_GPU_MEMORY_UTILIZATION,
-        &vllm_extra_args(local_model),
+        &vllm_extra_args(local_model, quantization),
     )?;
     let mut gpu_command = docker_command();
     gpu_command
@@ -2211,6 +2452,24 @@ fn run_vllm_service_container(
     )))
 }
 
+/// 启动 vLLM 容器前检查显存，避免等到启动超时才在日志中看到 CUDA OOM
+fn preflight_vllm_vram(
+    local_model: &str,
+    model_id: &str,
+    quantization: &str,
+) -> Result<(), SenseVoiceError> {
+    let Some(gpus) = query_gpu_memory() else {
+        // 宿主机没有 nvidia-smi（如部分远程 Docker 环境）时跳过预检，交由容器启动判断
+        return Ok(());
+    };
+    check_vram(
+        &gpus,
+        estimated_vram_mib(local_model, model_id, quantization),
+        VLLM_GPU_MEMORY_UTILIZATION,
+    )
+    .map_err(SenseVoiceError::Process)
+}
+
 fn docker_output_detail(output: &std::process::Output) -> String {
     let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
     if !stderr.is_empty() {
@@ -2589,6 +2848,7 @@ fn write_vllm_config(
     config_dir: &Path,
     model_key: &str,
     model_id: &str,
+    quantization: &str,
     port: u16,
     gpu_mem: f32,
     extra_args: &str,
@@ -2600,7 +2860,7 @@ fn write_vllm_config(
         .map_err(|err| SenseVoiceError::Io(err.to_string()))?;
     // model.conf（bash source 格式，纯 LF；含空格的值必须加引号）
     let conf = format!(
-        "MODEL_KEY='{model_key}'\nMODEL_ID='{model_id}'\nVLLM_PORT={port}\nVLLM_GPU_MEM={gpu_mem}\nVLLM_EXTRA_ARGS='{extra_args}'\n"
+        "MODEL_KEY='{model_key}'\nMODEL_ID='{model_id}'\nVLLM_QUANTIZATION='{quantization}'\nVLLM_PORT={port}\nVLLM_GPU_MEM={gpu_mem}\nVLLM_EXTRA_ARGS='{extra_args}'\n"
     );
     fs::write(config_dir.join("model.conf"), conf)
         .map_err(|err| SenseVoiceError::Io(err.to_string()))?;
@@ -2622,6 +2882,19 @@ fn read_vllm_config_model(config_dir: &Path) -> Option<(String, String)> {
     Some((model_key?, model_id?))
 }
 
+/// 读取配置文件中的量化选项；旧版配置没有该字段，视为不量化
+fn read_vllm_config_quantization(config_dir: &Path) -> String {
+    fs::read_to_string(config_dir.join("model.conf"))
+        .ok()
+        .and_then(|content| {
+            content
+                .lines()
+                .find_map(|line| line.strip_prefix("VLLM_QUANTIZATION="))
+                .map(strip_shell_quotes)
+        })
+        .unwrap_or_else(|| VLLM_QUANTIZATION_NONE.to_string())
+}
+
 /// 去除 bash 单/双引号包裹
 fn strip_shell_quotes(s: &str) -> String {
     let trimmed = s.trim();
@@ -2634,13 +2907,19 @@ fn strip_shell_quotes(s: &str) -> String {
     }
 }
 
-/// 构建 vLLM 模型的 extra_args 字符串
-fn vllm_extra_args(local_model: &str) -> String {
-    if local_model == LOCAL_MODEL_VOXTRAL {
+/// 构建 vLLM 模型的 extra_args 字符串。
+/// AWQ/GPTQ 预量化权重由 vLLM 根据 checkpoint 配置自动识别（并优先选用 Marlin 内核），
+/// 只有在线 FP8 量化需要显式指定
+fn vllm_extra_args(local_model: &str, quantization: &str) -> String {
+    let mut args = if local_model == LOCAL_MODEL_VOXTRAL {
         format!("--attention-backend {VOXTRAL_ATTENTION_BACKEND}")
     } else {
         "--max-model-len 12288".to_string()
+    };
+    if quantization == VLLM_QUANTIZATION_FP8 {
+        args.push_str(" --quantization fp8");
     }
+    args
 }
 
 fn is_startup_complete_line(line: &str) -> bool {
@@ -2735,7 +3014,38 @@ fn docker_exec_health_check(container: &str, port: u16) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_health_ready_field, parse_vllm_models_response_ready};
+    use super::{
+        battery_decision, parse_health_ready_field, parse_vllm_models_response_ready,
+        BatteryDecision, BatteryPolicy, PowerSource,
+    };
+
+    #[test]
+    fn battery_decision_only_applies_to_gpu_runtimes_on_battery() {
+        assert_eq!(
+            battery_decision(BatteryPolicy::Refuse, PowerSource::Ac, true, true),
+            BatteryDecision::Proceed
+        );
+        assert_eq!(
+            battery_decision(BatteryPolicy::Refuse, PowerSource::Battery, false, true),
+            BatteryDecision::Proceed
+        );
+        assert_eq!(
+            battery_decision(BatteryPolicy::Allow, PowerSource::Battery, true, true),
+            BatteryDecision::Proceed
+        );
+        assert_eq!(
+            battery_decision(BatteryPolicy::Refuse, PowerSource::Battery, true, true),
+            BatteryDecision::Refuse
+        );
+        assert_eq!(
+            battery_decision(BatteryPolicy::PreferCpu, PowerSource::Battery, true, true),
+            BatteryDecision::UseCpu
+        );
+        assert_eq!(
+            battery_decision(BatteryPolicy::PreferCpu, PowerSource::Battery, true, false),
+            BatteryDecision::Refuse
+        );
+    }
 
     #[test]
     fn parse_health_ready_field_returns_true() {
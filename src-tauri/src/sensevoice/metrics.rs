@@ -0,0 +1,173 @@
+//! 本地运行时容器的资源占用采集
+//!
+//! 通过 `docker stats` 读取容器 CPU 与内存占用，通过 nvidia-smi 读取 GPU 利用率，
+//! 供设置页展示转写变慢的原因。
+
+use super::docker_utils::{docker_command, hide_window};
+use super::model::docker_container_name;
+use crate::AppState;
+use serde::Serialize;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const RUNTIME_METRICS_EVENT: &str = "sensevoice-runtime-metrics";
+/// 后台采集间隔；`docker stats --no-stream` 本身约需 1-2 秒
+const METRICS_INTERVAL_SECS: u64 = 5;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeMetrics {
+    pub cpu_percent: f32,
+    pub memory_used_bytes: u64,
+    pub memory_limit_bytes: u64,
+    pub memory_percent: f32,
+    /// 首块 GPU 的整机利用率（nvidia-smi 无法按容器区分），未检测到 GPU 时为 None
+    pub gpu_utilization_percent: Option<f32>,
+    pub gpu_memory_used_mib: Option<u64>,
+}
+
+/// 采集运行时容器的资源占用；容器未运行时返回 None
+pub fn current(app: &AppHandle) -> Result<Option<RuntimeMetrics>, String> {
+    if !is_runtime_running(app) {
+        return Ok(None);
+    }
+    collect(docker_container_name()).map(Some)
+}
+
+/// 后台定期采集并推送 `sensevoice-runtime-metrics` 事件，容器未运行或主窗口隐藏时不调用 docker
+pub fn spawn_monitor(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_secs(METRICS_INTERVAL_SECS));
+        if crate::low_resource::is_active() || !is_runtime_running(&app) {
+            continue;
+        }
+        match collect(docker_container_name()) {
+            Ok(metrics) => {
+                let _ = app.emit(RUNTIME_METRICS_EVENT, metrics);
+            }
+            Err(_err) => {
+                #[cfg(debug_assertions)]
+                eprintln!("[sensevoice] 采集运行时资源占用失败: {_err}");
+            }
+        }
+    });
+}
+
+fn is_runtime_running(app: &AppHandle) -> bool {
+    let state = app.state::<AppState>();
+    // 管理器被启动流程长时间占用时跳过本次采集，避免阻塞
+    let Ok(manager) = state.sensevoice_manager.try_lock() else {
+        return false;
+    };
+    manager.is_container_running_cached()
+}
+
+fn collect(container_name: &str) -> Result<RuntimeMetrics, String> {
+    let mut command = docker_command();
+    command
+        .arg("stats")
+        .arg("--no-stream")
+        .arg("--format")
+        .arg("{{.CPUPerc}}|{{.MemUsage}}|{{.MemPerc}}")
+        .arg(container_name);
+    hide_window(&mut command);
+    let output = command
+        .output()
+        .map_err(|err| format!("执行 docker stats 失败: {err}"))?;
+    if !output.status.success() {
+        let detail = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(format!("docker stats 执行失败: {detail}"));
+    }
+    let mut metrics = parse_docker_stats(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| "无法解析 docker stats 输出".to_string())?;
+    if let Some((utilization, memory_used_mib)) = query_gpu_utilization() {
+        metrics.gpu_utilization_percent = Some(utilization);
+        metrics.gpu_memory_used_mib = Some(memory_used_mib);
+    }
+    Ok(metrics)
+}
+
+/// 解析 `CPU%|已用 / 上限|内存%` 格式的单行输出
+fn parse_docker_stats(output: &str) -> Option<RuntimeMetrics> {
+    let line = output.lines().find(|line| !line.trim().is_empty())?;
+    let mut fields = line.split('|').map(str::trim);
+    let cpu_percent = parse_percent(fields.next()?)?;
+    let (used, limit) = fields.next()?.split_once('/')?;
+    let memory_percent = parse_percent(fields.next()?)?;
+    Some(RuntimeMetrics {
+        cpu_percent,
+        memory_used_bytes: parse_size(used)?,
+        memory_limit_bytes: parse_size(limit)?,
+        memory_percent,
+        ..RuntimeMetrics::default()
+    })
+}
+
+fn parse_percent(value: &str) -> Option<f32> {
+    value.trim().trim_end_matches('%').trim().parse().ok()
+}
+
+/// 解析 docker 输出的容量（如 `1.5GiB`、`512MB`、`0B`）
+fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let split = value
+        .find(|ch: char| ch.is_ascii_alphabetic())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number = number.trim().parse::<f64>().ok()?;
+    let multiplier: f64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1.0,
+        "kb" => 1e3,
+        "mb" => 1e6,
+        "gb" => 1e9,
+        "tb" => 1e12,
+        "kib" => 1024.0,
+        "mib" => 1024f64.powi(2),
+        "gib" => 1024f64.powi(3),
+        "tib" => 1024f64.powi(4),
+        _ => return None,
+    };
+    Some((number * multiplier).round() as u64)
+}
+
+/// 查询首块 GPU 的利用率（%）与已用显存（MiB）
+fn query_gpu_utilization() -> Option<(f32, u64)> {
+    let mut command = Command::new("nvidia-smi");
+    command
+        .arg("--query-gpu=utilization.gpu,memory.used")
+        .arg("--format=csv,noheader,nounits");
+    hide_window(&mut command);
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (utilization, memory_used) = stdout.lines().next()?.split_once(',')?;
+    Some((
+        utilization.trim().parse().ok()?,
+        memory_used.trim().parse().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_docker_stats_reads_cpu_and_memory() {
+        let metrics = parse_docker_stats("153.27%|1.5GiB / 15.54GiB|9.65%\n").unwrap();
+        assert_eq!(metrics.cpu_percent, 153.27);
+        assert_eq!(metrics.memory_used_bytes, 1_610_612_736);
+        assert_eq!(metrics.memory_limit_bytes, 16_685_947_945);
+        assert_eq!(metrics.memory_percent, 9.65);
+        assert_eq!(metrics.gpu_utilization_percent, None);
+
+        assert_eq!(parse_size("0B"), Some(0));
+        assert_eq!(parse_size("512kB"), Some(512_000));
+        assert_eq!(parse_size("12 MiB"), Some(12_582_912));
+        assert_eq!(parse_size("1.2XB"), None);
+        assert!(parse_docker_stats("--|-- / --|--").is_none());
+    }
+}
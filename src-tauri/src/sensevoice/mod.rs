@@ -1,6 +1,7 @@
 pub mod client;
 pub mod docker_utils;
 pub mod manager;
+pub mod metrics;
 pub mod model;
 pub mod native_runtime;
 pub mod sherpa;
@@ -8,6 +9,7 @@ pub mod worker;
 
 pub use manager::{ensure_service_ready_blocking, SenseVoiceManager, SenseVoiceStatus};
 
+use crate::provider_error::ProviderFailure;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -26,4 +28,6 @@ pub enum SenseVoiceError {
     Url(String),
     #[error("SenseVoice 设置读写失败: {0}")]
     Settings(String),
+    #[error("SenseVoice 拒绝请求: {0}")]
+    Rejected(#[from] ProviderFailure),
 }
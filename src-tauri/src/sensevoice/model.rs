@@ -18,14 +18,24 @@ pub const QWEN3_ASR_ALLOWED_MODEL_IDS: [&str; 3] = [
     "Qwen/Qwen3-ForcedAligner-0.6B",
 ];
 
+/// vLLM 模型的量化选项
+pub const VLLM_QUANTIZATION_NONE: &str = "none";
+pub const VLLM_QUANTIZATION_AWQ: &str = "awq";
+pub const VLLM_QUANTIZATION_GPTQ: &str = "gptq";
+pub const VLLM_QUANTIZATION_FP8: &str = "fp8";
+
 const SENSEVOICE_IMAGE_TAG: &str = "vtt-sensevoice:local";
 const VLLM_IMAGE_TAG: &str = "vllm/vllm-openai:nightly";
 /// 所有 Docker 模型共享同一个容器名
 pub const DOCKER_CONTAINER_NAME: &str = "vtt-docker-service";
+/// 模型下载（prepare）容器名，取消准备任务时据此清理
+pub const PREPARE_CONTAINER_NAME: &str = "vtt-sensevoice-prepare";
 /// 用于标记容器所属模型的 Docker label key
 pub const CONTAINER_LABEL_MODEL_KEY: &str = "vtt.model-key";
 /// 用于标记容器所加载的具体模型 ID（区分同一模型系列的不同变体）
 pub const CONTAINER_LABEL_MODEL_ID: &str = "vtt.model-id";
+/// 用于标记 SenseVoice 容器创建时使用的推理设备（cpu/cuda/auto）
+pub const CONTAINER_LABEL_DEVICE: &str = "vtt.device";
 const SERVICE_START_TIMEOUT_SECS: u64 = 90;
 const VLLM_SERVICE_START_TIMEOUT_SECS: u64 = 5 * 60;
 /// 旧版单独容器名，用于迁移清理
@@ -167,11 +177,51 @@ pub fn service_start_timeout(value: &str) -> Duration {
     Duration::from_secs(spec_for_local_model(value).startup_timeout_secs)
 }
 
-pub fn resolve_vllm_model_id(local_model: &str, model_id: &str) -> String {
-    match normalize_local_model(local_model) {
-        LOCAL_MODEL_VOXTRAL => DEFAULT_VOXTRAL_MODEL_ID.to_string(),
-        LOCAL_MODEL_QWEN3_ASR => normalize_qwen3_asr_model_id(model_id).to_string(),
-        _ => DEFAULT_QWEN3_ASR_MODEL_ID.to_string(),
+/// 解析 vLLM 实际加载的模型 ID；AWQ/GPTQ 使用 Qwen 发布的预量化权重（`-AWQ`/`-GPTQ-Int4` 后缀）
+pub fn resolve_vllm_model_id(local_model: &str, model_id: &str, quantization: &str) -> String {
+    let base = match normalize_local_model(local_model) {
+        LOCAL_MODEL_VOXTRAL => DEFAULT_VOXTRAL_MODEL_ID,
+        LOCAL_MODEL_QWEN3_ASR => normalize_qwen3_asr_model_id(model_id),
+        _ => DEFAULT_QWEN3_ASR_MODEL_ID,
+    };
+    match normalize_vllm_quantization(local_model, quantization) {
+        VLLM_QUANTIZATION_AWQ => format!("{base}-AWQ"),
+        VLLM_QUANTIZATION_GPTQ => format!("{base}-GPTQ-Int4"),
+        _ => base.to_string(),
+    }
+}
+
+/// 规范化量化选项，模型不支持的取值回退为不量化。
+/// Voxtral 没有官方预量化权重，仅支持 vLLM 在线 FP8 量化
+pub fn normalize_vllm_quantization(local_model: &str, value: &str) -> &'static str {
+    let supported: &[&'static str] = match normalize_local_model(local_model) {
+        LOCAL_MODEL_VOXTRAL => &[VLLM_QUANTIZATION_FP8],
+        LOCAL_MODEL_QWEN3_ASR => &[
+            VLLM_QUANTIZATION_AWQ,
+            VLLM_QUANTIZATION_GPTQ,
+            VLLM_QUANTIZATION_FP8,
+        ],
+        _ => &[],
+    };
+    let value = value.trim();
+    supported
+        .iter()
+        .copied()
+        .find(|candidate| candidate.eq_ignore_ascii_case(value))
+        .unwrap_or(VLLM_QUANTIZATION_NONE)
+}
+
+/// vLLM 模型权重加推理缓存的大致显存需求（MiB），用于启动前的显存预检
+pub fn estimated_vram_mib(local_model: &str, model_id: &str, quantization: &str) -> u64 {
+    let full_precision = match normalize_local_model(local_model) {
+        LOCAL_MODEL_VOXTRAL => 10 * 1024,
+        LOCAL_MODEL_QWEN3_ASR if model_id.contains("-0.6B") => 4 * 1024,
+        _ => 6 * 1024,
+    };
+    match normalize_vllm_quantization(local_model, quantization) {
+        VLLM_QUANTIZATION_AWQ | VLLM_QUANTIZATION_GPTQ => full_precision / 2,
+        VLLM_QUANTIZATION_FP8 => full_precision * 3 / 4,
+        _ => full_precision,
     }
 }
 
@@ -183,3 +233,33 @@ pub fn normalize_qwen3_asr_model_id(model_id: &str) -> &str {
         .find(|candidate| *candidate == trimmed)
         .unwrap_or(DEFAULT_QWEN3_ASR_MODEL_ID)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantization_maps_to_prequantized_model_ids() {
+        assert_eq!(
+            resolve_vllm_model_id(LOCAL_MODEL_QWEN3_ASR, "Qwen/Qwen3-ASR-0.6B", "AWQ"),
+            "Qwen/Qwen3-ASR-0.6B-AWQ"
+        );
+        assert_eq!(
+            resolve_vllm_model_id(LOCAL_MODEL_QWEN3_ASR, "", "gptq"),
+            "Qwen/Qwen3-ASR-1.7B-GPTQ-Int4"
+        );
+        // FP8 为在线量化，沿用原始权重
+        assert_eq!(
+            resolve_vllm_model_id(LOCAL_MODEL_VOXTRAL, "", "fp8"),
+            DEFAULT_VOXTRAL_MODEL_ID
+        );
+        assert_eq!(
+            normalize_vllm_quantization(LOCAL_MODEL_VOXTRAL, "awq"),
+            VLLM_QUANTIZATION_NONE
+        );
+        assert_eq!(
+            estimated_vram_mib(LOCAL_MODEL_VOXTRAL, DEFAULT_VOXTRAL_MODEL_ID, "fp8"),
+            7680
+        );
+    }
+}
@@ -1,3 +1,4 @@
+import hmac
 import os
 import sys
 import tempfile
@@ -6,7 +7,8 @@ import traceback
 from contextlib import asynccontextmanager
 from pathlib import Path
 
-from fastapi import FastAPI, File, Form, HTTPException, UploadFile
+from fastapi import FastAPI, File, Form, HTTPException, Request, UploadFile
+from fastapi.responses import JSONResponse
 
 MODEL = None
 POSTPROCESS = None
@@ -17,6 +19,9 @@ MODEL_LOCK = threading.Lock()
 LOG_LOCK = threading.Lock()
 HF_DEFAULT_MODEL_ID = "FunAudioLLM/SenseVoiceSmall"
 MS_DEFAULT_MODEL_ID = "iic/SenseVoiceSmall"
+API_TOKEN = os.getenv("SENSEVOICE_API_TOKEN", "")
+# 健康检查不返回转写内容，保持匿名可访问以便探活
+PUBLIC_PATHS = {"/health"}
 
 
 def log(message: str):
@@ -150,6 +155,16 @@ async def lifespan(_app: FastAPI):
 app = FastAPI(lifespan=lifespan)
 
 
+@app.middleware("http")
+async def require_api_token(request: Request, call_next):
+    if API_TOKEN and request.url.path not in PUBLIC_PATHS:
+        authorization = request.headers.get("authorization", "")
+        scheme, _, token = authorization.partition(" ")
+        if scheme.lower() != "bearer" or not hmac.compare_digest(token.strip(), API_TOKEN):
+            return JSONResponse(status_code=401, content={"detail": "unauthorized"})
+    return await call_next(request)
+
+
 @app.get("/health")
 def health():
     with MODEL_LOCK:
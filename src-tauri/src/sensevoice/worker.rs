@@ -1,8 +1,9 @@
 use super::docker_utils::{
     bind_mount, docker_command, docker_container_running, docker_image_exists, hide_window,
-    normalize_log_line, normalize_publish_host, parse_host_and_port, read_selected_hub,
-    remove_container_if_exists, run_command_streaming,
+    launch_docker_desktop, normalize_log_line, normalize_publish_host, parse_host_and_port,
+    read_selected_hub, remove_container_if_exists, run_command_streaming, wait_docker_daemon,
 };
+use super::model::PREPARE_CONTAINER_NAME;
 use super::sherpa;
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -14,6 +15,7 @@ use std::time::{Duration, Instant};
 
 const SERVICE_START_TIMEOUT_SECS: u64 = 90;
 const DOCKER_BUILD_TIMEOUT_SECS: u64 = 40 * 60;
+const DOCKER_DESKTOP_START_TIMEOUT_SECS: u64 = 120;
 const MODEL_DOWNLOAD_TIMEOUT_SECS: u64 = 60 * 60;
 const IMAGE_STAMP_FILE: &str = "image.stamp";
 const LOCAL_MODEL_SENSEVOICE: &str = "sensevoice";
@@ -36,6 +38,10 @@ pub struct WorkerJob {
     pub state_file: String,
     pub image_tag: String,
     pub container_name: String,
+    #[serde(default)]
+    pub auto_start_docker: bool,
+    #[serde(default)]
+    pub api_token: String,
 }
 
 fn default_local_model() -> String {
@@ -106,7 +112,7 @@ fn run_prepare_job(job: &WorkerJob) -> Result<(), String> {
     emit_state("preparing", "", None, None);
 
     if is_vllm_local_model(local_model) {
-        ensure_docker_available()?;
+        ensure_docker_available(job.auto_start_docker)?;
         let model_display = vllm_model_display_name(local_model);
         emit_progress(
             "install",
@@ -173,7 +179,7 @@ fn run_prepare_job(job: &WorkerJob) -> Result<(), String> {
         return Ok(());
     }
 
-    ensure_docker_available()?;
+    ensure_docker_available(job.auto_start_docker)?;
     emit_progress(
         "install",
         "Building Docker image",
@@ -239,7 +245,7 @@ fn run_prepare_job(job: &WorkerJob) -> Result<(), String> {
     Ok(())
 }
 
-fn ensure_docker_available() -> Result<(), String> {
+fn ensure_docker_available(auto_start: bool) -> Result<(), String> {
     let mut version = docker_command();
     version
         .arg("version")
@@ -264,9 +270,31 @@ fn ensure_docker_available() -> Result<(), String> {
         .map_err(|err| format!("无法连接 Docker daemon: {err}"))?;
     if !output.status.success() {
         let detail = String::from_utf8_lossy(&output.stderr).trim().to_string();
-        return Err(format!(
-            "Docker daemon 未运行，请先启动 Docker Desktop: {detail}"
-        ));
+        if !auto_start {
+            return Err(format!(
+                "Docker daemon 未运行，请先启动 Docker Desktop: {detail}"
+            ));
+        }
+        emit_progress("docker", "Starting Docker Desktop", None, None, None, None);
+        launch_docker_desktop()
+            .map_err(|err| format!("{err}，请手动启动 Docker Desktop 后重试"))?;
+        let timeout = Duration::from_secs(DOCKER_DESKTOP_START_TIMEOUT_SECS);
+        let ready = wait_docker_daemon(timeout, |elapsed| {
+            emit_progress(
+                "docker",
+                "Waiting for Docker daemon",
+                None,
+                Some(format!("{}s / {}s", elapsed.as_secs(), timeout.as_secs())),
+                None,
+                None,
+            );
+        });
+        if !ready {
+            return Err(format!(
+                "Docker Desktop 已启动，但 daemon 在 {} 秒内未就绪，请稍后重试",
+                timeout.as_secs()
+            ));
+        }
     }
     Ok(())
 }
@@ -378,10 +406,14 @@ where
         fs::write(state_file, "{}").map_err(|err| format!("初始化状态文件失败: {err}"))?;
     }
 
+    // 清理上次被取消或异常退出时残留的同名下载容器
+    let _ = remove_container_if_exists(PREPARE_CONTAINER_NAME);
     let mut command = docker_command();
     command
         .arg("run")
         .arg("--rm")
+        .arg("--name")
+        .arg(PREPARE_CONTAINER_NAME)
         .arg("--mount")
         .arg(bind_mount(model_dir, "/models"))
         .arg("--mount")
@@ -429,6 +461,7 @@ fn start_service(job: &WorkerJob) -> Result<(), String> {
         &job.model_id,
         &job.device,
         &hub,
+        &job.api_token,
     )?;
 
     wait_health(
@@ -447,6 +480,7 @@ fn run_service_container(
     model_id: &str,
     device: &str,
     hub: &str,
+    api_token: &str,
 ) -> Result<(), String> {
     fs::create_dir_all(model_dir).map_err(|err| format!("创建模型目录失败: {err}"))?;
     let mut command = docker_command();
@@ -471,6 +505,8 @@ fn run_service_container(
         .arg("SENSEVOICE_HOST=0.0.0.0")
         .arg("-e")
         .arg(format!("SENSEVOICE_PORT={port}"))
+        .arg("-e")
+        .arg(format!("SENSEVOICE_API_TOKEN={api_token}"))
         .arg(image_tag);
     hide_window(&mut command);
     let output = command
@@ -1,8 +1,9 @@
 use aes_gcm::aead::Aead;
 use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
 use base64::{engine::general_purpose, Engine as _};
-use rand::RngCore;
+use rand::{Rng, RngCore};
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Manager};
 use tauri_plugin_store::StoreExt;
@@ -96,6 +97,7 @@ impl SettingsStore {
         merged.sensevoice.installed = current.sensevoice.installed;
         merged.sensevoice.download_state = current.sensevoice.download_state.clone();
         merged.sensevoice.last_error = current.sensevoice.last_error.clone();
+        merged.sensevoice.api_token = current.sensevoice.api_token.clone();
         let normalized = normalize_settings(&merged);
         validate_settings(&normalized)?;
         self.persist_settings(&normalized)?;
@@ -117,6 +119,21 @@ impl SettingsStore {
         self.persist_settings(&settings)
     }
 
+    /// 读取本地推理服务的访问令牌，尚未生成时随机生成并持久化
+    pub fn ensure_sensevoice_api_token(&self) -> Result<String, SettingsError> {
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut settings = self.load()?;
+        if !settings.sensevoice.api_token.is_empty() {
+            return Ok(settings.sensevoice.api_token);
+        }
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let token = general_purpose::URL_SAFE_NO_PAD.encode(bytes);
+        settings.sensevoice.api_token = token.clone();
+        self.persist_settings(&settings)?;
+        Ok(token)
+    }
+
     pub fn save_sensevoice_editable(
         &self,
         sensevoice: &SenseVoiceSettings,
@@ -130,6 +147,7 @@ impl SettingsStore {
         merged.model_id = sensevoice.model_id.clone();
         merged.language = sensevoice.language.clone();
         merged.device = sensevoice.device.clone();
+        merged.quantization = sensevoice.quantization.clone();
         normalize_sensevoice_settings(&mut merged);
         validate_sensevoice_settings(&merged)?;
         // Runtime-managed fields are preserved from the persisted settings and must not
@@ -138,6 +156,104 @@ impl SettingsStore {
         self.persist_settings(&settings)
     }
 
+    /// 新增或按 ID 覆盖听写模板
+    pub fn save_dictation_template(
+        &self,
+        template: &DictationTemplate,
+    ) -> Result<Vec<DictationTemplate>, SettingsError> {
+        self.update_templates(|settings| {
+            match settings
+                .templates
+                .iter_mut()
+                .find(|existing| existing.id == template.id)
+            {
+                Some(existing) => *existing = template.clone(),
+                None => settings.templates.push(template.clone()),
+            }
+        })
+    }
+
+    /// 删除模板；若删除的是当前启用的模板，同时取消启用
+    pub fn delete_dictation_template(
+        &self,
+        id: &str,
+    ) -> Result<Vec<DictationTemplate>, SettingsError> {
+        self.update_templates(|settings| {
+            settings.templates.retain(|template| template.id != id);
+            if settings.active_template_id == id {
+                settings.active_template_id.clear();
+            }
+        })
+    }
+
+    pub fn set_active_dictation_template(&self, id: Option<&str>) -> Result<(), SettingsError> {
+        self.update_templates(|settings| {
+            settings.active_template_id = id.unwrap_or_default().to_string();
+        })
+        .map(|_| ())
+    }
+
+    /// 新增或按设备名称覆盖输入设备偏好
+    pub fn save_device_profile(
+        &self,
+        profile: &DeviceProfile,
+    ) -> Result<Vec<DeviceProfile>, SettingsError> {
+        self.update_device_profiles(|profiles| {
+            match profiles
+                .iter_mut()
+                .find(|existing| existing.device_name == profile.device_name)
+            {
+                Some(existing) => *existing = profile.clone(),
+                None => profiles.push(profile.clone()),
+            }
+        })
+    }
+
+    pub fn delete_device_profile(
+        &self,
+        device_name: &str,
+    ) -> Result<Vec<DeviceProfile>, SettingsError> {
+        self.update_device_profiles(|profiles| {
+            profiles.retain(|profile| profile.device_name != device_name);
+        })
+    }
+
+    fn update_device_profiles(
+        &self,
+        update: impl FnOnce(&mut Vec<DeviceProfile>),
+    ) -> Result<Vec<DeviceProfile>, SettingsError> {
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut settings = self.load()?;
+        update(&mut settings.recording.device_profiles);
+        validate_settings(&settings)?;
+        self.persist_settings(&settings)?;
+        Ok(settings.recording.device_profiles)
+    }
+
+    /// 在写锁内更新从用户修改中学习到的记录，避免与界面保存设置互相覆盖
+    pub fn update_corrections(
+        &self,
+        update: impl FnOnce(&mut CorrectionSettings),
+    ) -> Result<CorrectionSettings, SettingsError> {
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut settings = self.load()?;
+        update(&mut settings.corrections);
+        self.persist_settings(&settings)?;
+        Ok(settings.corrections)
+    }
+
+    fn update_templates(
+        &self,
+        update: impl FnOnce(&mut Settings),
+    ) -> Result<Vec<DictationTemplate>, SettingsError> {
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut settings = self.load()?;
+        update(&mut settings);
+        validate_settings(&settings)?;
+        self.persist_settings(&settings)?;
+        Ok(settings.templates)
+    }
+
     pub fn load_transcription_history(
         &self,
     ) -> Result<Vec<TranscriptionHistoryItem>, SettingsError> {
@@ -179,43 +295,77 @@ impl SettingsStore {
         self.persist_transcription_history(&history)
     }
 
+    /// 按 ID 替换已有历史记录；不存在时插入到最前
+    pub fn replace_transcription_history_item(
+        &self,
+        item: TranscriptionHistoryItem,
+    ) -> Result<(), SettingsError> {
+        let mut history = self.load_transcription_history()?;
+        match history.iter_mut().find(|existing| existing.id == item.id) {
+            Some(existing) => *existing = item,
+            None => history.insert(0, item),
+        }
+        if history.len() > MAX_TRANSCRIPTION_HISTORY_ITEMS {
+            history.truncate(MAX_TRANSCRIPTION_HISTORY_ITEMS);
+        }
+        self.persist_transcription_history(&history)
+    }
+
     pub fn clear_transcription_history(&self) -> Result<(), SettingsError> {
         self.persist_transcription_history(&[])
     }
 
     pub fn load_deferred_update_version(&self) -> Result<Option<String>, SettingsError> {
+        Ok(self
+            .load_updater_state()?
+            .deferred_version
+            .filter(|value| !value.trim().is_empty()))
+    }
+
+    pub fn save_deferred_update_version(&self, version: Option<&str>) -> Result<(), SettingsError> {
+        let mut state = self.load_updater_state()?;
+        state.deferred_version = version
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(ToString::to_string);
+        self.persist_updater_state(&state)
+    }
+
+    /// 分阶段发布使用的本机分桶（0-99），首次读取时随机生成并持久化
+    pub fn load_or_create_rollout_bucket(&self) -> Result<u8, SettingsError> {
+        let mut state = self.load_updater_state()?;
+        if let Some(bucket) = state.rollout_bucket.filter(|bucket| *bucket < 100) {
+            return Ok(bucket);
+        }
+        let bucket = rand::thread_rng().gen_range(0..100);
+        state.rollout_bucket = Some(bucket);
+        self.persist_updater_state(&state)?;
+        Ok(bucket)
+    }
+
+    /// 读取更新状态；缺失或无法解密时视为空状态
+    fn load_updater_state(&self) -> Result<UpdaterState, SettingsError> {
         let store = self
             .app
             .store(SETTINGS_FILE)
             .map_err(|err| SettingsError::Store(err.to_string()))?;
         let Some(payload) = store.get(UPDATER_STATE_STORE_KEY) else {
-            return Ok(None);
+            return Ok(UpdaterState::default());
         };
         let Some(encoded) = payload.as_str() else {
-            return Ok(None);
+            return Ok(UpdaterState::default());
         };
         let key = self.load_or_create_key()?;
         let decrypted = match decrypt_payload(encoded, &key) {
             Ok(value) => value,
-            Err(_) => return Ok(None),
+            Err(_) => return Ok(UpdaterState::default()),
         };
-        let state = match serde_json::from_str::<UpdaterState>(&decrypted) {
-            Ok(value) => value,
-            Err(_) => return Ok(None),
-        };
-        Ok(state
-            .deferred_version
-            .filter(|value| !value.trim().is_empty()))
+        Ok(serde_json::from_str::<UpdaterState>(&decrypted).unwrap_or_default())
     }
 
-    pub fn save_deferred_update_version(&self, version: Option<&str>) -> Result<(), SettingsError> {
-        let deferred_version = version
-            .map(str::trim)
-            .filter(|value| !value.is_empty())
-            .map(ToString::to_string);
-        let state = UpdaterState { deferred_version };
+    fn persist_updater_state(&self, state: &UpdaterState) -> Result<(), SettingsError> {
         let json =
-            serde_json::to_string(&state).map_err(|err| SettingsError::Serde(err.to_string()))?;
+            serde_json::to_string(state).map_err(|err| SettingsError::Serde(err.to_string()))?;
         let key = self.load_or_create_key()?;
         let encrypted = encrypt_payload(&json, &key)?;
         let store = self
@@ -273,13 +423,54 @@ impl SettingsStore {
         Ok(())
     }
 
-    fn load_or_create_key(&self) -> Result<[u8; 32], SettingsError> {
+    /// 应用数据目录（不存在时自动创建）
+    pub fn app_data_dir(&self) -> Result<PathBuf, SettingsError> {
         let dir = self
             .app
             .path()
             .app_data_dir()
             .map_err(|err| SettingsError::PathResolve(err.to_string()))?;
         fs::create_dir_all(&dir).map_err(|err| SettingsError::Io(err.to_string()))?;
+        Ok(dir)
+    }
+
+    /// 本机数据目录（不随漫游配置文件同步，不存在时自动创建），存放模型与录音等大体积数据
+    pub fn app_local_data_dir(&self) -> Result<PathBuf, SettingsError> {
+        let dir = self
+            .app
+            .path()
+            .app_local_data_dir()
+            .map_err(|err| SettingsError::PathResolve(err.to_string()))?;
+        fs::create_dir_all(&dir).map_err(|err| SettingsError::Io(err.to_string()))?;
+        Ok(dir)
+    }
+
+    /// 留存录音的子目录：默认位于本机数据目录，开启 `roam_recordings` 时位于随配置文件漫游的
+    /// 应用数据目录；另一位置中残留的录音（切换设置或从旧版本升级）会移动过来
+    pub fn recordings_dir(&self, name: &str) -> Result<PathBuf, SettingsError> {
+        let roam = self.load()?.recording.roam_recordings;
+        let local = self.app_local_data_dir()?.join(name);
+        let roaming = self.app_data_dir()?.join(name);
+        let (dir, other) = if roam {
+            (roaming, local)
+        } else {
+            (local, roaming)
+        };
+        fs::create_dir_all(&dir).map_err(|err| SettingsError::Io(err.to_string()))?;
+        // 非 Windows 平台两者通常为同一目录
+        if dir != other {
+            migrate_dir(&other, &dir);
+        }
+        Ok(dir)
+    }
+
+    /// 设置加密密钥，同时用于留存录音的加密
+    pub fn encryption_key(&self) -> Result<[u8; 32], SettingsError> {
+        self.load_or_create_key()
+    }
+
+    fn load_or_create_key(&self) -> Result<[u8; 32], SettingsError> {
+        let dir = self.app_data_dir()?;
         let key_path = dir.join(SETTINGS_KEY_FILE);
         if key_path.exists() {
             let data =
@@ -300,6 +491,25 @@ impl SettingsStore {
     }
 }
 
+/// 将 `from` 中的文件移动到 `to`，目标已存在同名文件时保留原文件；跨卷时退化为复制后删除
+fn migrate_dir(from: &Path, to: &Path) {
+    let Ok(entries) = fs::read_dir(from) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        if !source.is_file() || target.exists() {
+            continue;
+        }
+        if fs::rename(&source, &target).is_err() && fs::copy(&source, &target).is_ok() {
+            let _ = fs::remove_file(&source);
+        }
+    }
+    // 目录非空（如有同名冲突）时保留
+    let _ = fs::remove_dir(from);
+}
+
 fn encrypt_payload(plain: &str, key: &[u8; 32]) -> Result<String, SettingsError> {
     let mut nonce_bytes = [0u8; 12];
     rand::thread_rng().fill_bytes(&mut nonce_bytes);
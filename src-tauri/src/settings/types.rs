@@ -11,6 +11,7 @@ pub(crate) const VOXTRAL_REQUIRED_DEVICE: &str = "cuda";
 pub(crate) const QWEN3_ASR_REQUIRED_DEVICE: &str = "cuda";
 pub(crate) const STOP_MODE_STOP: &str = "stop";
 pub(crate) const STOP_MODE_PAUSE: &str = "pause";
+pub(crate) const VLLM_QUANTIZATION_NONE: &str = "none";
 pub(crate) const DEFAULT_SENSEVOICE_MODEL_ID: &str = "FunAudioLLM/SenseVoiceSmall";
 pub(crate) const DEFAULT_SHERPA_ONNX_SENSEVOICE_MODEL_ID: &str =
     "sherpa-onnx-sense-voice-zh-en-ja-ko-yue-int8-2025-09-09";
@@ -29,6 +30,9 @@ pub struct Settings {
     pub recording: RecordingSettings,
     #[serde(default)]
     pub provider: TranscriptionProvider,
+    /// 切换到本地模型前使用的云端提供商，托盘关闭“使用本地模型”时恢复
+    #[serde(default)]
+    pub last_cloud_provider: TranscriptionProvider,
     pub openai: OpenAiSettings,
     #[serde(default)]
     pub text_processing: TextProcessingSettings,
@@ -41,12 +45,47 @@ pub struct Settings {
     #[serde(default = "default_triggers")]
     pub triggers: Vec<TriggerCard>,
     #[serde(default)]
+    pub trigger_packs: TriggerPackSettings,
+    #[serde(default)]
     pub output: OutputSettings,
     pub appearance: AppearanceSettings,
     #[serde(default)]
     pub startup: StartupSettings,
     #[serde(default)]
     pub history: HistorySettings,
+    #[serde(default)]
+    pub provider_rules: ProviderRulesSettings,
+    #[serde(default)]
+    pub duration_routing: DurationRoutingSettings,
+    #[serde(default)]
+    pub offline: OfflineSettings,
+    #[serde(default)]
+    pub redaction: RedactionSettings,
+    #[serde(default)]
+    pub templates: Vec<DictationTemplate>,
+    /// 当前启用的听写模板 ID，为空时按普通听写处理
+    #[serde(default)]
+    pub active_template_id: String,
+    /// 按用途命名的转写提示词预设
+    #[serde(default = "default_prompt_presets")]
+    pub prompt_presets: Vec<PromptPreset>,
+    /// 默认使用的提示词预设 ID，为空时只使用转写设置中的提示词；预设快捷键开始的录音使用对应预设
+    #[serde(default)]
+    pub active_prompt_preset_id: String,
+    #[serde(default)]
+    pub language_routing: LanguageRoutingSettings,
+    #[serde(default)]
+    pub audio_pipeline: AudioPipelineSettings,
+    #[serde(default)]
+    pub context_carryover: ContextCarryoverSettings,
+    #[serde(default)]
+    pub wake_on_speech: WakeOnSpeechSettings,
+    #[serde(default)]
+    pub presence: PresenceSettings,
+    #[serde(default)]
+    pub tts: TtsSettings,
+    #[serde(default)]
+    pub corrections: CorrectionSettings,
 }
 
 impl Default for Settings {
@@ -54,11 +93,47 @@ impl Default for Settings {
         Self {
             shortcut: ShortcutSettings {
                 key: "CommandOrControl+Shift+Space".to_string(),
+                paste_result_keys: Vec::new(),
+                wake_toggle_key: String::new(),
+                cancel_key: String::new(),
+                literal_key: String::new(),
             },
             recording: RecordingSettings {
                 segment_seconds: 60,
+                segment_overlap_ms: 0,
+                segment_retry_attempts: default_segment_retry_attempts(),
+                failed_segment_placeholder: default_failed_segment_placeholder(),
+                segment_retry_queue: true,
+                scratch_dir: String::new(),
+                takeover: RecordingTakeover::default(),
+                input_device: String::new(),
+                mix_input_devices: Vec::new(),
+                countdown_ms: 0,
+                device_profiles: Vec::new(),
+                buffer_frames: 0,
+                max_duration_seconds: default_max_duration_seconds(),
+                end_of_speech: EndOfSpeechSettings::default(),
+                noise_suppression: false,
+                auto_gain: false,
+                auto_gain_target_db: default_auto_gain_target_db(),
+                silence_trim: SilenceTrimSettings::default(),
+                storage_quota_mb: 0,
+                keep_original_format: false,
+                upload_format: UploadFormat::default(),
+                roam_recordings: false,
+                keep_raw_audio: false,
+                raw_audio_retention_days: default_raw_audio_retention_days(),
+                pre_roll: false,
+                pre_roll_ms: default_pre_roll_ms(),
+                capture_system_audio: false,
+                mic_track_label: default_mic_track_label(),
+                system_track_label: default_system_track_label(),
+                monitor: false,
+                monitor_volume: default_monitor_volume(),
+                keep_stream_warm: false,
             },
             provider: TranscriptionProvider::default(),
+            last_cloud_provider: TranscriptionProvider::default(),
             openai: OpenAiSettings {
                 api_base: default_openai_api_base(),
                 api_key: "".to_string(),
@@ -88,10 +163,65 @@ impl Default for Settings {
             },
             startup: StartupSettings::default(),
             history: HistorySettings::default(),
+            provider_rules: ProviderRulesSettings::default(),
+            duration_routing: DurationRoutingSettings::default(),
+            offline: OfflineSettings::default(),
+            redaction: RedactionSettings::default(),
+            templates: Vec::new(),
+            active_template_id: String::new(),
+            prompt_presets: default_prompt_presets(),
+            active_prompt_preset_id: String::new(),
+            language_routing: LanguageRoutingSettings::default(),
+            trigger_packs: TriggerPackSettings::default(),
+            audio_pipeline: AudioPipelineSettings::default(),
+            context_carryover: ContextCarryoverSettings::default(),
+            wake_on_speech: WakeOnSpeechSettings::default(),
+            presence: PresenceSettings::default(),
+            tts: TtsSettings::default(),
+            corrections: CorrectionSettings::default(),
         }
     }
 }
 
+fn default_prompt_presets() -> Vec<PromptPreset> {
+    let preset = |id: &str, name: &str, prompt: &str, hotwords: &[&str]| PromptPreset {
+        id: id.to_string(),
+        name: name.to_string(),
+        prompt: prompt.to_string(),
+        hotwords: hotwords.iter().map(|word| word.to_string()).collect(),
+        shortcut: String::new(),
+    };
+    vec![
+        preset(
+            "medical",
+            "Medical",
+            "Clinical dictation: chief complaint, history of present illness, assessment and plan. \
+             Hypertension, tachycardia, 5 mg b.i.d., CT, MRI, ECG.",
+            &["CT", "MRI", "ECG"],
+        ),
+        preset(
+            "legal",
+            "Legal",
+            "Legal dictation: the plaintiff, the defendant, pursuant to Section 2(a), \
+             indemnification, force majeure, hereinafter referred to as the Agreement.",
+            &[],
+        ),
+        preset(
+            "programming",
+            "Programming",
+            "Programming discussion: API, JSON, HTTP, async/await, TypeScript, Rust, Git, \
+             pull request, null pointer, camelCase, snake_case.",
+            &["API", "JSON", "TypeScript", "Rust", "GitHub"],
+        ),
+        preset(
+            "casual",
+            "Casual",
+            "Hey, so yeah, I was thinking we could grab lunch later. Sounds good!",
+            &[],
+        ),
+    ]
+}
+
 fn default_triggers() -> Vec<TriggerCard> {
     vec![
         TriggerCard {
@@ -103,6 +233,12 @@ fn default_triggers() -> Vec<TriggerCard> {
             keyword: "translate".to_string(),
             prompt_template: "Translate the following content to {value}.".to_string(),
             variables: vec!["English".to_string()],
+            active_app_pattern: String::new(),
+            text_backend: String::new(),
+            response_language: String::new(),
+            pick_variable: false,
+            cooldown_seconds: 0,
+            require_confirmation: false,
         },
         TriggerCard {
             id: "polish".to_string(),
@@ -113,6 +249,12 @@ fn default_triggers() -> Vec<TriggerCard> {
             keyword: "polish".to_string(),
             prompt_template: "Polish the following content into {value}.".to_string(),
             variables: vec!["spoken style".to_string()],
+            active_app_pattern: String::new(),
+            text_backend: String::new(),
+            response_language: String::new(),
+            pick_variable: false,
+            cooldown_seconds: 0,
+            require_confirmation: false,
         },
     ]
 }
@@ -125,6 +267,30 @@ fn default_text_model() -> String {
     "gpt-4o-mini".to_string()
 }
 
+fn default_claude_text_settings() -> TextSettings {
+    TextSettings {
+        api_base: "https://api.anthropic.com/v1".to_string(),
+        model: "claude-3-5-haiku-latest".to_string(),
+        ..TextSettings::default()
+    }
+}
+
+fn default_ollama_text_settings() -> TextSettings {
+    TextSettings {
+        api_base: "http://127.0.0.1:11434".to_string(),
+        model: "qwen2.5:7b".to_string(),
+        ..TextSettings::default()
+    }
+}
+
+fn default_local_text_settings() -> TextSettings {
+    TextSettings {
+        api_base: "http://127.0.0.1:8080/v1".to_string(),
+        model: "local-model".to_string(),
+        ..TextSettings::default()
+    }
+}
+
 fn default_text_temperature() -> f32 {
     0.6
 }
@@ -137,16 +303,298 @@ fn default_text_top_p() -> f32 {
     1.0
 }
 
+/// 转写前的音频预处理链，按 `steps` 顺序执行
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioPipelineSettings {
+    pub enabled: bool,
+    #[serde(default)]
+    pub steps: Vec<AudioPipelineStep>,
+}
+
+impl Default for AudioPipelineSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            steps: vec![
+                AudioPipelineStep::Trim {
+                    threshold_db: -50.0,
+                    padding_ms: 200,
+                },
+                AudioPipelineStep::Normalize { target_db: -3.0 },
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum AudioPipelineStep {
+    /// 裁掉首尾低于阈值（dBFS）的静音，保留 `padding_ms` 余量
+    #[serde(rename_all = "camelCase")]
+    Trim { threshold_db: f32, padding_ms: u32 },
+    /// 噪声门：衰减 RMS 低于阈值（dBFS）的片段
+    #[serde(rename_all = "camelCase")]
+    Denoise { threshold_db: f32 },
+    /// 峰值归一化到目标电平（dBFS）
+    #[serde(rename_all = "camelCase")]
+    Normalize { target_db: f32 },
+    /// 重采样到目标采样率，可选混合为单声道
+    #[serde(rename_all = "camelCase")]
+    Resample { sample_rate: u32, mono: bool },
+    /// 按倍率压缩时长（WSOLA，音高不变），缩短长录音的上传与转写耗时
+    Tempo { rate: f32 },
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ShortcutSettings {
     pub key: String,
+    /// 重新粘贴最近结果的快捷键，第 i 个对应第 i+1 条最近结果
+    #[serde(default)]
+    pub paste_result_keys: Vec<String>,
+    /// 布防/撤防语音唤醒录音的快捷键，留空不注册
+    #[serde(default)]
+    pub wake_toggle_key: String,
+    /// 取消当前录音并丢弃音频的快捷键，留空不注册
+    #[serde(default)]
+    pub cancel_key: String,
+    /// 字面听写快捷键，用法与录音快捷键相同，结果按本地语法转换为数字、符号与标识符；留空不注册
+    #[serde(default)]
+    pub literal_key: String,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RecordingSettings {
     pub segment_seconds: u64,
+    /// 相邻分段的重叠时长（毫秒），跨越分段边界的句子在两段中都完整出现，合并时去掉重复的词；
+    /// 0 表示不重叠
+    #[serde(default)]
+    pub segment_overlap_ms: u32,
+    /// 单段转写失败后的重试次数
+    #[serde(default = "default_segment_retry_attempts")]
+    pub segment_retry_attempts: u32,
+    /// 单段重试仍失败时插入的占位文本，留空则直接跳过该段
+    #[serde(default = "default_failed_segment_placeholder")]
+    pub failed_segment_placeholder: String,
+    /// 单段重试仍失败时将音频保存到持久重试队列，在后台按退避补转写并回填历史记录
+    #[serde(default = "default_true")]
+    pub segment_retry_queue: bool,
+    /// 录音分段临时目录，留空使用系统临时目录
+    #[serde(default)]
+    pub scratch_dir: String,
+    #[serde(default)]
+    pub takeover: RecordingTakeover,
+    /// 输入设备名称，留空使用系统默认设备；设备断开时自动回退到默认设备
+    #[serde(default)]
+    pub input_device: String,
+    /// 同时录制并混入同一录音的其他输入设备（如耳麦 + 桌面麦克风），增益取各设备的偏好设置；
+    /// 录音时未连接的设备跳过
+    #[serde(default)]
+    pub mix_input_devices: Vec<String>,
+    /// 按下快捷键到开始录音之间的倒计时（毫秒），0 表示立即开始
+    #[serde(default)]
+    pub countdown_ms: u32,
+    /// 按输入设备保存的录音偏好，使用该设备录音时自动应用
+    #[serde(default)]
+    pub device_profiles: Vec<DeviceProfile>,
+    /// 采集缓冲区帧数，0 表示使用驱动默认值；较小的缓冲区可缩短录音开头的空白，
+    /// 适合专业声卡，过小可能导致爆音
+    #[serde(default)]
+    pub buffer_frames: u32,
+    /// 单次录音时长上限（秒），达到后自动结束录音并送入转写，避免误触导致内存无限增长
+    #[serde(default = "default_max_duration_seconds")]
+    pub max_duration_seconds: u32,
+    #[serde(default)]
+    pub end_of_speech: EndOfSpeechSettings,
+    /// 分段前对录音做频谱噪声门降噪，抑制风扇、空调等平稳噪声
+    #[serde(default)]
+    pub noise_suppression: bool,
+    /// 分段写入前将每段峰值归一化到目标电平，改善音量过小的麦克风的识别效果
+    #[serde(default)]
+    pub auto_gain: bool,
+    /// 自动增益的目标峰值电平（dBFS）
+    #[serde(default = "default_auto_gain_target_db")]
+    pub auto_gain_target_db: f32,
+    #[serde(default)]
+    pub silence_trim: SilenceTrimSettings,
+    /// 队列与临时目录中录音的总占用上限（MB），超出时从最旧的队列录音开始删除；0 表示不限
+    #[serde(default)]
+    pub storage_quota_mb: u64,
+    /// 分段保持录音设备的原始格式；默认混为单声道并降采样到 16 kHz 以减少上传数据量
+    #[serde(default)]
+    pub keep_original_format: bool,
+    /// 分段上传格式；仅对接受压缩格式的提供商生效，其余提供商始终使用 WAV
+    #[serde(default)]
+    pub upload_format: UploadFormat,
+    /// 留存录音随漫游配置文件同步；默认保存在本机数据目录，避免企业漫游配置同步大量音频
+    #[serde(default)]
+    pub roam_recordings: bool,
+    /// 将每次听写的完整录音加密存档到 `recordings/` 并记录在历史记录中，供回放与重新转写
+    #[serde(default)]
+    pub keep_raw_audio: bool,
+    /// 存档录音的保留天数，0 表示永久保留
+    #[serde(default = "default_raw_audio_retention_days")]
+    pub raw_audio_retention_days: u32,
+    /// 预录：未录音时保持麦克风打开，在内存中保留最近一段音频并在开始录音时拼接到开头，
+    /// 避免按下快捷键前的第一个字被截掉；需用户显式开启，缓冲音频不落盘、不上传
+    #[serde(default)]
+    pub pre_roll: bool,
+    /// 预录保留的时长（毫秒）
+    #[serde(default = "default_pre_roll_ms")]
+    pub pre_roll_ms: u32,
+    /// 同时录制系统音频（回环，目前仅 Windows），与麦克风分轨转写以区分会议中的说话方
+    #[serde(default)]
+    pub capture_system_audio: bool,
+    /// 分轨转写时麦克风音轨的说话方标签
+    #[serde(default = "default_mic_track_label")]
+    pub mic_track_label: String,
+    /// 分轨转写时系统音频音轨的说话方标签
+    #[serde(default = "default_system_track_label")]
+    pub system_track_label: String,
+    /// 录音监听（侧音）：录音时以低音量把麦克风音频回放到默认输出设备，用于确认使用的麦克风；
+    /// 与系统音频录制同时开启时不生效，避免回放混入对方音轨
+    #[serde(default)]
+    pub monitor: bool,
+    /// 录音监听音量（%）
+    #[serde(default = "default_monitor_volume")]
+    pub monitor_volume: u32,
+    /// 保持录音流预热：两次录音之间不关闭输入流，只停止写入，开始录音时无需重新打开设备，
+    /// 减少开头的延迟；开启后麦克风在空闲时也保持打开
+    #[serde(default)]
+    pub keep_stream_warm: bool,
+}
+
+impl RecordingSettings {
+    /// 查找指定设备的偏好设置
+    pub fn device_profile(&self, device_name: &str) -> Option<&DeviceProfile> {
+        self.device_profiles
+            .iter()
+            .find(|profile| profile.device_name == device_name)
+    }
+}
+
+/// 单个输入设备的录音偏好；cpal 不提供稳定的设备 ID，以设备名称作为标识
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceProfile {
+    pub device_name: String,
+    /// 录音增益（dB），0 表示不调整
+    #[serde(default)]
+    pub gain_db: f32,
+    /// 录音结束后对该设备的录音做噪声门降噪
+    #[serde(default)]
+    pub denoise: bool,
+    /// 首选采样率，0 或设备不支持时使用设备默认采样率
+    #[serde(default)]
+    pub sample_rate: u32,
+}
+
+/// 录音进行中再次开始录音（如两个快捷键先后按下）时的处理方式
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RecordingTakeover {
+    /// 保持当前录音，忽略本次开始
+    #[default]
+    Ignore,
+    /// 丢弃当前录音并重新开始
+    Restart,
+    /// 结束当前录音并送入转写队列，随后开始新的录音
+    Queue,
+}
+
+/// 录音分段上传的音频格式
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum UploadFormat {
+    #[default]
+    Wav,
+    /// Ogg 封装的 Opus，体积约为 WAV 的 1/10
+    OggOpus,
+}
+
+/// 分段写入前裁剪静音：去掉每段首尾的静音，并把超过 `max_pause_ms` 的停顿缩短到该时长，
+/// 减少上传数据量，也避免提供商对长时间静音臆造文字
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SilenceTrimSettings {
+    pub enabled: bool,
+    /// 判定为静音的电平阈值（dBFS）
+    pub threshold_db: f32,
+    /// 句中停顿保留的最长时长
+    pub max_pause_ms: u32,
+}
+
+impl Default for SilenceTrimSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold_db: -45.0,
+            max_pause_ms: 1000,
+        }
+    }
+}
+
+fn default_segment_retry_attempts() -> u32 {
+    2
+}
+
+/// 说话结束自动停止：累计说话达到 `min_speech_ms` 后，再持续静音 `trailing_silence_ms` 才结束录音，
+/// 句中思考的停顿不会像单纯的静音超时那样提前结束录音
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EndOfSpeechSettings {
+    pub enabled: bool,
+    /// 判定为说话的电平阈值（dBFS）
+    pub threshold_db: f32,
+    /// 说话后持续静音该时长判定说话结束
+    pub trailing_silence_ms: u32,
+    /// 累计说话时长达到该值后才允许自动结束
+    pub min_speech_ms: u32,
+}
+
+impl Default for EndOfSpeechSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold_db: -40.0,
+            trailing_silence_ms: 2000,
+            min_speech_ms: 1500,
+        }
+    }
+}
+
+fn default_max_duration_seconds() -> u32 {
+    1800
+}
+
+fn default_auto_gain_target_db() -> f32 {
+    -3.0
+}
+
+fn default_pre_roll_ms() -> u32 {
+    1500
+}
+
+fn default_raw_audio_retention_days() -> u32 {
+    30
+}
+
+fn default_mic_track_label() -> String {
+    "我".to_string()
+}
+
+fn default_system_track_label() -> String {
+    "对方".to_string()
+}
+
+fn default_monitor_volume() -> u32 {
+    20
+}
+
+fn default_failed_segment_placeholder() -> String {
+    "[…]".to_string()
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -182,6 +630,13 @@ pub struct TextProcessingSettings {
     pub provider: TextProcessingProvider,
     #[serde(default)]
     pub openai: TextSettings,
+    #[serde(default = "default_claude_text_settings")]
+    pub claude: TextSettings,
+    #[serde(default = "default_ollama_text_settings")]
+    pub ollama: TextSettings,
+    /// 本地 OpenAI 兼容服务（llama.cpp server、LM Studio 等）
+    #[serde(default = "default_local_text_settings")]
+    pub local: TextSettings,
 }
 
 impl Default for TextProcessingSettings {
@@ -189,6 +644,9 @@ impl Default for TextProcessingSettings {
         Self {
             provider: TextProcessingProvider::default(),
             openai: TextSettings::default(),
+            claude: default_claude_text_settings(),
+            ollama: default_ollama_text_settings(),
+            local: default_local_text_settings(),
         }
     }
 }
@@ -237,22 +695,381 @@ pub struct TriggerCard {
     pub keyword: String,
     pub prompt_template: String,
     pub variables: Vec<String>,
+    /// 自动应用时限定的前台应用（匹配应用名或窗口标题的正则），为空时不限制
+    #[serde(default)]
+    pub active_app_pattern: String,
+    /// 使用的文本生成后端名称，为空时使用文本处理设置中的提供商
+    #[serde(default)]
+    pub text_backend: String,
+    /// 要求输出使用的语种代码（如 `zh`、`en`），为空时不限制
+    #[serde(default)]
+    pub response_language: String,
+    /// 关键词命中但未提到变量值时弹出快速选择，而不是使用第一个变量
+    #[serde(default)]
+    pub pick_variable: bool,
+    /// 应用后的冷却时间（秒），期间不再触发；0 表示不限制
+    #[serde(default)]
+    pub cooldown_seconds: u32,
+    /// 应用前弹窗确认，用于改写幅度大或调用成本高的卡片
+    #[serde(default)]
+    pub require_confirmation: bool,
+}
+
+/// 从网址安装的触发卡片包
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerPackSettings {
+    /// 信任的发布者公钥（Base64 编码的 Ed25519 公钥），只安装由这些公钥签名的卡片包
+    pub trusted_keys: Vec<String>,
+}
+
+/// 按转写文本语种路由到触发卡片
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageRoutingSettings {
+    pub enabled: bool,
+    #[serde(default)]
+    pub rules: Vec<LanguageRoute>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageRoute {
+    /// 语种代码，如 `zh`、`en`、`ja`、`ko`
+    pub language: String,
+    pub trigger_id: String,
+}
+
+/// 转写提示词预设：`prompt` 加在转写设置的提示词之前，`hotwords` 并入火山引擎热词
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptPreset {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub prompt: String,
+    #[serde(default)]
+    pub hotwords: Vec<String>,
+    /// 使用该预设开始录音的快捷键，用法与录音快捷键相同；留空不注册
+    #[serde(default)]
+    pub shortcut: String,
+}
+
+/// 听写模板，`body` 中的 `{slot}` 由口述内容抽取填充
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DictationTemplate {
+    pub id: String,
+    pub title: String,
+    pub body: String,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OutputSettings {
     pub remove_newlines: bool,
+    /// 低置信度阈值（0~1），为 0 时关闭
+    #[serde(default)]
+    pub confidence_threshold: f32,
+    /// 低于阈值时仅写入剪贴板，由用户确认后手动粘贴
+    #[serde(default)]
+    pub confirm_low_confidence: bool,
+    #[serde(default)]
+    pub itn: ItnSettings,
+    #[serde(default)]
+    pub punctuation: PunctuationSettings,
+    /// 旧版独立的中英文间空格开关，加载时迁移到 `punctuation.cjk_latin_spacing`
+    #[serde(default, rename = "panguSpacing", skip_serializing)]
+    pub legacy_pangu_spacing: bool,
+    /// 内部保留的最近粘贴结果条数，为 0 时不保留
+    #[serde(default = "default_paste_history_size")]
+    pub paste_history_size: usize,
+    /// 粘贴前在置顶窗口中显示结果，可编辑后再粘贴、复制或丢弃
+    #[serde(default)]
+    pub confirm_before_paste: bool,
+    /// 每秒最多自动粘贴的次数，超出时合并后续输出延后粘贴；0 表示不限制
+    #[serde(default = "default_max_pastes_per_second")]
+    pub max_pastes_per_second: u32,
+    /// 转写完成后自动粘贴；关闭时只写入剪贴板
+    #[serde(default = "default_true")]
+    pub paste_after_transcribe: bool,
+    /// 焦点在密码输入框时不自动粘贴，只写入剪贴板并提示
+    #[serde(default = "default_true")]
+    pub guard_secure_fields: bool,
 }
 
 impl Default for OutputSettings {
     fn default() -> Self {
         Self {
             remove_newlines: false,
+            confidence_threshold: 0.0,
+            confirm_low_confidence: false,
+            itn: ItnSettings::default(),
+            punctuation: PunctuationSettings::default(),
+            legacy_pangu_spacing: false,
+            paste_history_size: default_paste_history_size(),
+            confirm_before_paste: false,
+            max_pastes_per_second: default_max_pastes_per_second(),
+            paste_after_transcribe: true,
+            guard_secure_fields: true,
+        }
+    }
+}
+
+fn default_paste_history_size() -> usize {
+    10
+}
+
+fn default_max_pastes_per_second() -> u32 {
+    2
+}
+
+/// 连续听写的上下文延续：把最近的听写文本作为下一次转写的提示词
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextCarryoverSettings {
+    pub enabled: bool,
+    /// 保留的最大字符数
+    #[serde(default = "default_context_max_chars")]
+    pub max_chars: usize,
+    /// 同时作为触发词文本处理的参考上下文
+    #[serde(default = "default_true")]
+    pub include_in_text_processing: bool,
+    /// 录音开始时的前台窗口标题作为提示词，与 `enabled` 相互独立
+    #[serde(default)]
+    pub use_window_title: bool,
+}
+
+impl Default for ContextCarryoverSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_chars: default_context_max_chars(),
+            include_in_text_processing: true,
+            use_window_title: false,
         }
     }
 }
 
+fn default_context_max_chars() -> usize {
+    200
+}
+
+/// 语音唤醒自动录音：布防后本地监听输入电平，检测到说话自动开始录音，持续静音后停止
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WakeOnSpeechSettings {
+    /// 判定为说话的电平阈值（dBFS）
+    pub threshold_db: f32,
+    /// 电平持续高于阈值该时长后开始录音
+    pub start_ms: u32,
+    /// 电平持续低于阈值该时长后停止录音
+    pub stop_silence_ms: u32,
+    /// 单次自动录音的最长时长
+    pub max_recording_seconds: u32,
+}
+
+impl Default for WakeOnSpeechSettings {
+    fn default() -> Self {
+        Self {
+            threshold_db: -35.0,
+            start_ms: 200,
+            stop_silence_ms: 1500,
+            max_recording_seconds: 120,
+        }
+    }
+}
+
+/// 离开电脑（锁屏或长时间无输入）时暂停语音唤醒、本地服务自动启动与预热
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresenceSettings {
+    pub pause_when_away: bool,
+    /// 无键鼠输入超过该分钟数视为离开
+    pub idle_minutes: u32,
+}
+
+impl Default for PresenceSettings {
+    fn default() -> Self {
+        Self {
+            pause_when_away: true,
+            idle_minutes: 10,
+        }
+    }
+}
+
+/// 粘贴完成后朗读最终文本
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsSettings {
+    pub enabled: bool,
+    pub provider: TtsProvider,
+    /// 系统语音名称或 OpenAI 音色，为空时使用默认值
+    #[serde(default)]
+    pub voice: String,
+    #[serde(default = "default_tts_openai_model")]
+    pub openai_model: String,
+}
+
+impl Default for TtsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: TtsProvider::default(),
+            voice: String::new(),
+            openai_model: default_tts_openai_model(),
+        }
+    }
+}
+
+fn default_tts_openai_model() -> String {
+    "gpt-4o-mini-tts".to_string()
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TtsProvider {
+    /// 系统自带语音（Windows SAPI、macOS say、Linux speech-dispatcher/espeak）
+    #[default]
+    Platform,
+    /// OpenAI 语音合成，复用语音转写的 API 地址与密钥
+    Openai,
+}
+
+/// 从用户对粘贴结果的修改中学习替换规则与热词
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorrectionSettings {
+    pub enabled: bool,
+    /// 同一修改出现达到该次数后才自动应用
+    pub min_occurrences: u32,
+    #[serde(default)]
+    pub entries: Vec<CorrectionEntry>,
+}
+
+impl Default for CorrectionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_occurrences: 2,
+            entries: Vec::new(),
+        }
+    }
+}
+
+/// 一条“原文 → 修改后”记录及其出现次数
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CorrectionEntry {
+    pub original: String,
+    pub corrected: String,
+    pub count: u32,
+}
+
+/// 逆文本标准化（口语数字转阿拉伯数字）选项
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItnSettings {
+    pub enabled: bool,
+    /// 中文数字：“一千零五” → “1005”
+    #[serde(default = "default_true")]
+    pub chinese_numerals: bool,
+    /// 英文数词：“twenty three” → “23”
+    #[serde(default = "default_true")]
+    pub english_numbers: bool,
+}
+
+impl Default for ItnSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            chinese_numerals: true,
+            english_numbers: true,
+        }
+    }
+}
+
+/// 输出标点风格，统一中英混排转写结果的标点
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PunctuationSettings {
+    pub enabled: bool,
+    #[serde(default)]
+    pub width: PunctuationWidth,
+    /// 英文列表末项前的逗号（牛津逗号）
+    #[serde(default)]
+    pub serial_comma: SerialComma,
+    /// 中文与英文、数字之间的空格；不受 `enabled` 影响，触发词与模板的输出同样处理
+    #[serde(default)]
+    pub cjk_latin_spacing: CjkLatinSpacing,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PunctuationWidth {
+    /// 随前后文字：中文旁用全角，英文旁用半角
+    #[default]
+    Auto,
+    FullWidth,
+    HalfWidth,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SerialComma {
+    #[default]
+    Keep,
+    Add,
+    Remove,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CjkLatinSpacing {
+    #[default]
+    Keep,
+    Add,
+    Remove,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 本地脱敏：粘贴与写入历史前屏蔽词表中的词与个人信息
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionSettings {
+    pub enabled: bool,
+    #[serde(default)]
+    pub wordlist: Vec<String>,
+    #[serde(default = "default_true")]
+    pub emails: bool,
+    #[serde(default = "default_true")]
+    pub phone_numbers: bool,
+    #[serde(default = "default_true")]
+    pub credit_cards: bool,
+    #[serde(default = "default_redaction_replacement")]
+    pub replacement: String,
+}
+
+impl Default for RedactionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            wordlist: Vec::new(),
+            emails: true,
+            phone_numbers: true,
+            credit_cards: true,
+            replacement: default_redaction_replacement(),
+        }
+    }
+}
+
+fn default_redaction_replacement() -> String {
+    "***".to_string()
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AppearanceSettings {
@@ -267,6 +1084,8 @@ pub struct StartupSettings {
     pub auto_check_updates: bool,
     #[serde(default = "default_auto_install_updates_on_quit")]
     pub auto_install_updates_on_quit: bool,
+    #[serde(default)]
+    pub update_channel: UpdateChannel,
 }
 
 impl Default for StartupSettings {
@@ -275,10 +1094,20 @@ impl Default for StartupSettings {
             launch_on_boot: false,
             auto_check_updates: default_auto_check_updates(),
             auto_install_updates_on_quit: default_auto_install_updates_on_quit(),
+            update_channel: UpdateChannel::default(),
         }
     }
 }
 
+/// 更新发布通道
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
 fn default_auto_check_updates() -> bool {
     true
 }
@@ -291,14 +1120,128 @@ fn default_auto_install_updates_on_quit() -> bool {
 #[serde(rename_all = "camelCase")]
 pub struct HistorySettings {
     pub enabled: bool,
+    #[serde(default)]
+    pub event_payload: HistoryEventPayload,
 }
 
 impl Default for HistorySettings {
     fn default() -> Self {
-        Self { enabled: false }
+        Self {
+            enabled: false,
+            event_payload: HistoryEventPayload::default(),
+        }
     }
 }
 
+/// 历史记录事件携带的内容；长文本较多时可只发送摘要，详情通过 `get_history_item` 获取
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryEventPayload {
+    #[default]
+    Full,
+    Summary,
+}
+
+/// 按电源/网络状态自动切换转写提供商的规则，按顺序匹配，首条命中生效
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderRulesSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub rules: Vec<ProviderRule>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderRule {
+    pub id: String,
+    #[serde(default)]
+    pub power: PowerCondition,
+    #[serde(default)]
+    pub network: NetworkCondition,
+    pub provider: TranscriptionProvider,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerCondition {
+    #[default]
+    Any,
+    Ac,
+    Battery,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkCondition {
+    #[default]
+    Any,
+    Online,
+    Offline,
+}
+
+/// 按录音时长自动选择提供商：短录音走低延迟提供商，长录音走成本更低的批量提供商。
+/// 提供商为空时该档位保持当前提供商
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DurationRoutingSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 不超过该时长（秒）的录音视为短录音
+    #[serde(default = "default_short_max_seconds")]
+    pub short_max_seconds: f32,
+    #[serde(default)]
+    pub short_provider: Option<TranscriptionProvider>,
+    /// 不少于该时长（秒）的录音视为长录音
+    #[serde(default = "default_long_min_seconds")]
+    pub long_min_seconds: f32,
+    #[serde(default)]
+    pub long_provider: Option<TranscriptionProvider>,
+}
+
+impl Default for DurationRoutingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            short_max_seconds: default_short_max_seconds(),
+            short_provider: None,
+            long_min_seconds: default_long_min_seconds(),
+            long_provider: None,
+        }
+    }
+}
+
+fn default_short_max_seconds() -> f32 {
+    3.0
+}
+
+fn default_long_min_seconds() -> f32 {
+    60.0
+}
+
+/// 云端提供商在离线时的处理方式
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineSettings {
+    #[serde(default)]
+    pub fallback: OfflineFallback,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OfflineFallback {
+    /// 不做网络探测，保持原有行为
+    #[default]
+    Disabled,
+    /// 探测到离线时直接失败并给出明确提示
+    Fail,
+    /// 探测到离线时回退到本地模型
+    Local,
+    /// 保存录音，待网络或本地模型可用后自动补转写
+    Queue,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TriggerMatch {
@@ -314,13 +1257,18 @@ pub struct TriggerMatch {
 pub enum TriggerMatchMode {
     Keyword,
     Auto,
+    /// 按识别出的语种路由触发
+    Language,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TranscriptionHistoryStatus {
     Success,
+    /// 部分分段转写失败，文本中以占位符代替；重试队列补全后改为成功
+    Partial,
     Failed,
+    Pending,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -342,7 +1290,75 @@ pub struct TranscriptionHistoryItem {
     pub trigger_matches: Vec<TriggerMatch>,
     #[serde(default)]
     pub alignment: Option<TranscriptionAlignment>,
+    #[serde(default)]
+    pub confidence: Option<f32>,
+    #[serde(default)]
+    pub metadata: SpeechMetadata,
+    /// 触发词改写时原文与最终文本的词级差异，未改写时为空
+    #[serde(default)]
+    pub diff: Vec<TextDiffSpan>,
     pub error_message: Option<String>,
+    /// 实际使用的转写提供商（已应用规则与按时长路由），旧记录为空
+    #[serde(default)]
+    pub provider: Option<TranscriptionProvider>,
+    #[serde(default)]
+    pub model: String,
+    /// 录音切分后的分段数
+    #[serde(default)]
+    pub segment_count: usize,
+    #[serde(default)]
+    pub stage_latency: StageLatency,
+    /// 存档的原始录音，未开启存档或旧记录为空
+    #[serde(default)]
+    pub audio_path: Option<String>,
+    /// 重新转写时对应的原历史记录 ID
+    #[serde(default)]
+    pub source_id: Option<String>,
+}
+
+/// 单次听写各阶段耗时（毫秒）
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StageLatency {
+    /// 录音分段与逐段转写（含重试）
+    pub transcription_ms: u64,
+    /// ITN、脱敏与触发词/模板处理
+    pub text_processing_ms: u64,
+    /// 写入剪贴板与模拟粘贴
+    pub output_ms: u64,
+}
+
+/// 摘要预览保留的最大字符数
+pub const HISTORY_SUMMARY_PREVIEW_CHARS: usize = 200;
+
+/// 历史记录事件的摘要载荷
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionHistorySummary {
+    pub id: String,
+    pub timestamp_ms: u64,
+    pub status: TranscriptionHistoryStatus,
+    /// 最终文本的前 [`HISTORY_SUMMARY_PREVIEW_CHARS`] 个字符
+    pub preview: String,
+    pub truncated: bool,
+}
+
+impl TranscriptionHistoryItem {
+    pub fn summary(&self) -> TranscriptionHistorySummary {
+        let text = if self.final_text.is_empty() {
+            &self.transcription_text
+        } else {
+            &self.final_text
+        };
+        let preview: String = text.chars().take(HISTORY_SUMMARY_PREVIEW_CHARS).collect();
+        TranscriptionHistorySummary {
+            id: self.id.clone(),
+            timestamp_ms: self.timestamp_ms,
+            status: self.status.clone(),
+            truncated: preview.len() < text.len(),
+            preview,
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -354,6 +1370,52 @@ pub struct TranscriptionAlignment {
     pub durations_ms: Vec<u64>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDiffSpan {
+    pub kind: TextDiffKind,
+    pub text: String,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TextDiffKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// 提供商随转写返回的语种、情绪与声学事件等附加信息
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeechMetadata {
+    #[serde(default)]
+    pub language: Option<String>,
+    /// 情绪标签（如 `happy`、`sad`），未识别时为空
+    #[serde(default)]
+    pub emotion: Option<String>,
+    /// 声学事件（如 `laughter`、`applause`）
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+impl SpeechMetadata {
+    /// 合并后续分段的信息：语种与情绪取首个非空值，事件去重追加
+    pub fn merge(&mut self, other: SpeechMetadata) {
+        if self.language.is_none() {
+            self.language = other.language;
+        }
+        if self.emotion.is_none() {
+            self.emotion = other.emotion;
+        }
+        for event in other.events {
+            if !self.events.contains(&event) {
+                self.events.push(event);
+            }
+        }
+    }
+}
+
 #[derive(Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum TranscriptionProvider {
@@ -405,6 +1467,21 @@ impl TranscriptionProvider {
 pub enum TextProcessingProvider {
     #[default]
     Openai,
+    Claude,
+    Ollama,
+    Local,
+}
+
+impl TextProcessingProvider {
+    /// 文本生成后端注册表中的名称
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Openai => "openai",
+            Self::Claude => "claude",
+            Self::Ollama => "ollama",
+            Self::Local => "local",
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -415,6 +1492,16 @@ pub struct VolcengineSettings {
     pub use_streaming: bool,
     pub use_fast: bool,
     pub language: String,
+    /// 控制台中创建的热词表名称
+    #[serde(default)]
+    pub boosting_table_name: String,
+    /// 随请求直接发送的热词，用于纠正产品名等专有名词
+    #[serde(default)]
+    pub hotwords: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enable_punctuation: bool,
+    #[serde(default = "default_true")]
+    pub enable_itn: bool,
 }
 
 impl Default for VolcengineSettings {
@@ -425,6 +1512,10 @@ impl Default for VolcengineSettings {
             use_streaming: false,
             use_fast: false,
             language: "zh-CN".to_string(),
+            boosting_table_name: String::new(),
+            hotwords: Vec::new(),
+            enable_punctuation: true,
+            enable_itn: true,
         }
     }
 }
@@ -445,6 +1536,77 @@ pub struct SenseVoiceSettings {
     pub device: String,
     pub download_state: String,
     pub last_error: String,
+    #[serde(default)]
+    pub health: SenseVoiceHealthSettings,
+    /// Docker daemon 未运行时自动启动 Docker Desktop 并等待就绪
+    #[serde(default = "default_true")]
+    pub auto_start_docker: bool,
+    /// 本地推理服务的访问令牌，首次启动容器时自动生成
+    #[serde(default)]
+    pub api_token: String,
+    /// 服务就绪后发送一段静音音频预热模型，避免首次听写过慢
+    #[serde(default = "default_true")]
+    pub warmup_on_start: bool,
+    /// vLLM 模型的量化方式（none/awq/gptq/fp8），用于在小显存 GPU 上运行
+    #[serde(default = "default_vllm_quantization")]
+    pub quantization: String,
+    /// 使用电池供电时启动 GPU 本地模型的策略
+    #[serde(default)]
+    pub battery_policy: BatteryPolicy,
+}
+
+/// 电池供电时的本地模型策略：vLLM 等 GPU 运行时耗电极快
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum BatteryPolicy {
+    /// 不受供电状态影响
+    #[default]
+    Allow,
+    /// 拒绝启动需要 GPU 的运行时
+    Refuse,
+    /// 可用 CPU 推理的模型改用 CPU 启动，仅支持 GPU 的模型拒绝启动
+    PreferCpu,
+}
+
+/// 本地服务健康检查参数：慢速机器可延长超时，笔记本可降低轮询频率以减少唤醒
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SenseVoiceHealthSettings {
+    /// 服务运行期间的健康检查间隔（毫秒）
+    #[serde(default = "default_health_interval_ms")]
+    pub interval_ms: u64,
+    /// 单次健康检查请求超时（秒）
+    #[serde(default = "default_health_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+    /// 模型预热超过该时长（秒）时提示用户
+    #[serde(default = "default_health_warmup_warn_secs")]
+    pub warmup_warn_secs: u64,
+    /// 服务启动超时（秒），为 0 时使用各模型的默认值
+    #[serde(default)]
+    pub startup_timeout_secs: u64,
+}
+
+impl Default for SenseVoiceHealthSettings {
+    fn default() -> Self {
+        Self {
+            interval_ms: default_health_interval_ms(),
+            request_timeout_secs: default_health_request_timeout_secs(),
+            warmup_warn_secs: default_health_warmup_warn_secs(),
+            startup_timeout_secs: 0,
+        }
+    }
+}
+
+fn default_health_interval_ms() -> u64 {
+    1000
+}
+
+fn default_health_request_timeout_secs() -> u64 {
+    2
+}
+
+fn default_health_warmup_warn_secs() -> u64 {
+    120
 }
 
 fn default_local_model() -> String {
@@ -455,6 +1617,10 @@ fn default_stop_mode() -> String {
     STOP_MODE_PAUSE.to_string()
 }
 
+fn default_vllm_quantization() -> String {
+    VLLM_QUANTIZATION_NONE.to_string()
+}
+
 pub(crate) fn default_sensevoice_language() -> String {
     "auto".to_string()
 }
@@ -472,6 +1638,12 @@ impl Default for SenseVoiceSettings {
             device: "auto".to_string(),
             download_state: "idle".to_string(),
             last_error: String::new(),
+            health: SenseVoiceHealthSettings::default(),
+            auto_start_docker: true,
+            api_token: String::new(),
+            warmup_on_start: true,
+            quantization: default_vllm_quantization(),
+            battery_policy: BatteryPolicy::default(),
         }
     }
 }
@@ -534,6 +1706,8 @@ pub struct AliyunParaformerSettings {
 pub(crate) struct UpdaterState {
     #[serde(default)]
     pub deferred_version: Option<String>,
+    #[serde(default)]
+    pub rollout_bucket: Option<u8>,
 }
 
 #[cfg(test)]
@@ -567,4 +1741,65 @@ mod tests {
         let json = serde_json::to_string(&provider).unwrap();
         assert_eq!(json, "\"openai\"");
     }
+
+    #[test]
+    fn recording_takeover_defaults_to_ignore_for_older_settings() {
+        let recording: RecordingSettings =
+            serde_json::from_str(r#"{"segmentSeconds":60}"#).unwrap();
+        assert_eq!(recording.takeover, RecordingTakeover::Ignore);
+        let json = serde_json::to_string(&RecordingTakeover::Restart).unwrap();
+        assert_eq!(json, "\"restart\"");
+    }
+
+    #[test]
+    fn silence_trim_fills_missing_fields_with_defaults() {
+        let trim: SilenceTrimSettings = serde_json::from_str(r#"{"enabled":true}"#).unwrap();
+        let expected = SilenceTrimSettings {
+            enabled: true,
+            ..SilenceTrimSettings::default()
+        };
+        assert_eq!(trim, expected);
+    }
+
+    #[test]
+    fn history_summary_truncates_by_chars() {
+        let item = TranscriptionHistoryItem {
+            id: "history-1".to_string(),
+            timestamp_ms: 1,
+            status: TranscriptionHistoryStatus::Success,
+            transcription_text: "原文".to_string(),
+            final_text: "字".repeat(HISTORY_SUMMARY_PREVIEW_CHARS + 1),
+            model_group: String::new(),
+            transcription_elapsed_ms: 0,
+            recording_duration_ms: 0,
+            triggered: false,
+            triggered_by_keyword: false,
+            trigger_matches: Vec::new(),
+            alignment: None,
+            confidence: None,
+            metadata: SpeechMetadata::default(),
+            diff: Vec::new(),
+            error_message: None,
+            provider: None,
+            model: String::new(),
+            segment_count: 0,
+            stage_latency: StageLatency::default(),
+            audio_path: None,
+            source_id: None,
+        };
+        let summary = item.summary();
+        assert_eq!(
+            summary.preview.chars().count(),
+            HISTORY_SUMMARY_PREVIEW_CHARS
+        );
+        assert!(summary.truncated);
+
+        let item = TranscriptionHistoryItem {
+            final_text: String::new(),
+            ..item
+        };
+        let summary = item.summary();
+        assert_eq!(summary.preview, "原文");
+        assert!(!summary.truncated);
+    }
 }
\ No newline at end of file
@@ -1,12 +1,35 @@
-use crate::sensevoice::model::supports_sherpa_onnx_target;
+use crate::sensevoice::model::{normalize_vllm_quantization, supports_sherpa_onnx_target};
 use url::Url;
 
 use super::storage::SettingsError;
 use super::types::*;
 
-pub(crate) fn normalize_text_processing_settings(settings: &mut Settings) {
-    settings.text_processing.provider = TextProcessingProvider::Openai;
+/// 上下文延续保留的最大字符数上限，过长的提示词会被转写接口截断
+const MAX_CONTEXT_CARRYOVER_CHARS: usize = 2000;
+/// 录音倒计时上限，更长的等待不如手动开始
+const MAX_RECORDING_COUNTDOWN_MS: u32 = 5000;
+/// 设备增益上限（dB），更大的增益只会放大噪声与削波
+const MAX_DEVICE_GAIN_DB: f32 = 24.0;
+/// 设备首选采样率的允许范围
+const DEVICE_SAMPLE_RATE_RANGE: std::ops::RangeInclusive<u32> = 8_000..=192_000;
+/// 采集缓冲区帧数的允许范围
+const BUFFER_FRAMES_RANGE: std::ops::RangeInclusive<u32> = 16..=8192;
+/// 单次录音时长上限的允许范围（秒）
+const MAX_DURATION_SECONDS_RANGE: std::ops::RangeInclusive<u32> = 10..=14_400;
+/// 自动增益目标电平的允许范围（dBFS）
+const AUTO_GAIN_TARGET_DB_RANGE: std::ops::RangeInclusive<f32> = -30.0..=0.0;
+/// 裁剪静音时保留的最长停顿的允许范围（毫秒）
+const MAX_PAUSE_MS_RANGE: std::ops::RangeInclusive<u32> = 200..=10_000;
+/// 单个分段转写失败后的重试次数上限，每次重试的等待递增，过多会让整段听写长时间卡住
+const MAX_SEGMENT_RETRY_ATTEMPTS: u32 = 5;
+/// 相邻分段重叠时长的上限（毫秒）
+const MAX_SEGMENT_OVERLAP_MS: u32 = 5_000;
+/// 预录时长的允许范围（毫秒），更长的预录只会带入无关的环境音
+const PRE_ROLL_MS_RANGE: std::ops::RangeInclusive<u32> = 250..=3000;
+/// 变速倍率的允许范围，更快的语音识别准确率明显下降
+const TEMPO_RATE_RANGE: std::ops::RangeInclusive<f32> = 1.25..=1.5;
 
+pub(crate) fn normalize_text_processing_settings(settings: &mut Settings) {
     if settings.text_processing.openai.api_base.trim().is_empty() {
         settings.text_processing.openai.api_base = settings.openai.api_base.clone();
     }
@@ -32,8 +55,19 @@ pub(crate) fn normalize_text_processing_settings(settings: &mut Settings) {
     }
 }
 
+/// 旧版的盘古之白开关并入标点设置中的中英文间空格，避免两个开关先后处理、结果互相抵消
+pub(crate) fn normalize_output_settings(output: &mut OutputSettings) {
+    if std::mem::take(&mut output.legacy_pangu_spacing)
+        && output.punctuation.cjk_latin_spacing == CjkLatinSpacing::Keep
+    {
+        output.punctuation.cjk_latin_spacing = CjkLatinSpacing::Add;
+    }
+}
+
 pub(crate) fn normalize_sensevoice_settings(sensevoice: &mut SenseVoiceSettings) {
     sensevoice.stop_mode = normalize_stop_mode(&sensevoice.stop_mode).to_string();
+    sensevoice.quantization =
+        normalize_vllm_quantization(&sensevoice.local_model, &sensevoice.quantization).to_string();
     if sensevoice
         .local_model
         .eq_ignore_ascii_case(LOCAL_MODEL_VOXTRAL)
@@ -133,6 +167,7 @@ pub(crate) fn normalize_settings(settings: &Settings) -> Settings {
     normalize_sensevoice_settings(&mut normalized.sensevoice);
     normalize_aliyun_settings(&mut normalized.aliyun, &normalized.provider);
     normalize_text_processing_settings(&mut normalized);
+    normalize_output_settings(&mut normalized.output);
     normalized
 }
 
@@ -170,11 +205,272 @@ pub(crate) fn validate_settings(settings: &Settings) -> Result<(), SettingsError
         }
     }
 
+    for card in &settings.triggers {
+        let pattern = card.active_app_pattern.trim();
+        if !pattern.is_empty() && regex::Regex::new(pattern).is_err() {
+            return Err(SettingsError::Serde(format!(
+                "触发卡片的应用匹配规则不是有效的正则: {}",
+                card.title
+            )));
+        }
+    }
+
+    for rule in &settings.language_routing.rules {
+        if !settings.triggers.iter().any(|card| card.id == rule.trigger_id) {
+            return Err(SettingsError::Serde(format!(
+                "语种路由指向的触发卡片不存在: {}",
+                rule.trigger_id
+            )));
+        }
+    }
+
+    validate_duration_routing(&settings.duration_routing)?;
+    validate_templates(settings)?;
+    validate_prompt_presets(settings)?;
+    validate_recording_settings(&settings.recording)?;
+    validate_audio_pipeline_settings(&settings.audio_pipeline)?;
+    validate_wake_on_speech_settings(&settings.wake_on_speech)?;
+    if settings.presence.pause_when_away && settings.presence.idle_minutes == 0 {
+        return Err(SettingsError::Serde(
+            "离开判定的空闲分钟数必须大于 0".to_string(),
+        ));
+    }
+    if settings.corrections.enabled && settings.corrections.min_occurrences == 0 {
+        return Err(SettingsError::Serde(
+            "自动应用修改所需的次数必须大于 0".to_string(),
+        ));
+    }
+    if settings.context_carryover.enabled
+        && !(1..=MAX_CONTEXT_CARRYOVER_CHARS).contains(&settings.context_carryover.max_chars)
+    {
+        return Err(SettingsError::Serde(format!(
+            "上下文保留字符数需在 1 到 {MAX_CONTEXT_CARRYOVER_CHARS} 之间"
+        )));
+    }
     validate_sensevoice_settings(&settings.sensevoice)?;
     validate_aliyun_settings(settings)?;
     Ok(())
 }
 
+fn validate_duration_routing(routing: &DurationRoutingSettings) -> Result<(), SettingsError> {
+    if !routing.enabled {
+        return Ok(());
+    }
+    if routing.short_max_seconds <= 0.0 || routing.long_min_seconds <= 0.0 {
+        return Err(SettingsError::Serde(
+            "按时长路由的阈值必须大于 0".to_string(),
+        ));
+    }
+    if routing.short_provider.is_some()
+        && routing.long_provider.is_some()
+        && routing.long_min_seconds <= routing.short_max_seconds
+    {
+        return Err(SettingsError::Serde(
+            "长录音阈值必须大于短录音阈值".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_templates(settings: &Settings) -> Result<(), SettingsError> {
+    for (index, template) in settings.templates.iter().enumerate() {
+        if template.id.trim().is_empty() {
+            return Err(SettingsError::Serde("模板 ID 不能为空".to_string()));
+        }
+        if template.body.trim().is_empty() {
+            return Err(SettingsError::Serde(format!(
+                "模板内容不能为空: {}",
+                template.title
+            )));
+        }
+        if settings.templates[..index]
+            .iter()
+            .any(|other| other.id == template.id)
+        {
+            return Err(SettingsError::Serde(format!(
+                "模板 ID 重复: {}",
+                template.id
+            )));
+        }
+    }
+    let active_id = settings.active_template_id.trim();
+    if !active_id.is_empty()
+        && !settings
+            .templates
+            .iter()
+            .any(|template| template.id == active_id)
+    {
+        return Err(SettingsError::Serde(format!(
+            "启用的模板不存在: {active_id}"
+        )));
+    }
+    Ok(())
+}
+
+fn validate_prompt_presets(settings: &Settings) -> Result<(), SettingsError> {
+    for (index, preset) in settings.prompt_presets.iter().enumerate() {
+        if preset.id.trim().is_empty() {
+            return Err(SettingsError::Serde("提示词预设 ID 不能为空".to_string()));
+        }
+        if settings.prompt_presets[..index]
+            .iter()
+            .any(|other| other.id == preset.id)
+        {
+            return Err(SettingsError::Serde(format!(
+                "提示词预设 ID 重复: {}",
+                preset.id
+            )));
+        }
+    }
+    let active_id = settings.active_prompt_preset_id.trim();
+    if !active_id.is_empty()
+        && !settings
+            .prompt_presets
+            .iter()
+            .any(|preset| preset.id == active_id)
+    {
+        return Err(SettingsError::Serde(format!(
+            "启用的提示词预设不存在: {active_id}"
+        )));
+    }
+    Ok(())
+}
+
+fn validate_recording_settings(recording: &RecordingSettings) -> Result<(), SettingsError> {
+    let scratch_dir = recording.scratch_dir.trim();
+    if !scratch_dir.is_empty() && !std::path::Path::new(scratch_dir).is_absolute() {
+        return Err(SettingsError::Serde(
+            "Recording scratch directory must be an absolute path".to_string(),
+        ));
+    }
+    if recording.countdown_ms > MAX_RECORDING_COUNTDOWN_MS {
+        return Err(SettingsError::Serde(format!(
+            "录音倒计时不能超过 {MAX_RECORDING_COUNTDOWN_MS} 毫秒"
+        )));
+    }
+    if recording.buffer_frames != 0 && !BUFFER_FRAMES_RANGE.contains(&recording.buffer_frames) {
+        return Err(SettingsError::Serde(format!(
+            "采集缓冲区需在 {} 到 {} 帧之间",
+            BUFFER_FRAMES_RANGE.start(),
+            BUFFER_FRAMES_RANGE.end()
+        )));
+    }
+    if !MAX_DURATION_SECONDS_RANGE.contains(&recording.max_duration_seconds) {
+        return Err(SettingsError::Serde(format!(
+            "录音时长上限需在 {} 到 {} 秒之间",
+            MAX_DURATION_SECONDS_RANGE.start(),
+            MAX_DURATION_SECONDS_RANGE.end()
+        )));
+    }
+    if !AUTO_GAIN_TARGET_DB_RANGE.contains(&recording.auto_gain_target_db) {
+        return Err(SettingsError::Serde(format!(
+            "自动增益目标电平需在 {} 到 {} dBFS 之间",
+            AUTO_GAIN_TARGET_DB_RANGE.start(),
+            AUTO_GAIN_TARGET_DB_RANGE.end()
+        )));
+    }
+    if !PRE_ROLL_MS_RANGE.contains(&recording.pre_roll_ms) {
+        return Err(SettingsError::Serde(format!(
+            "预录时长需在 {} 到 {} 毫秒之间",
+            PRE_ROLL_MS_RANGE.start(),
+            PRE_ROLL_MS_RANGE.end()
+        )));
+    }
+    let end_of_speech = &recording.end_of_speech;
+    if !(-80.0..0.0).contains(&end_of_speech.threshold_db) {
+        return Err(SettingsError::Serde(
+            "说话结束检测阈值需在 -80 到 0 dB 之间".to_string(),
+        ));
+    }
+    if end_of_speech.trailing_silence_ms == 0 {
+        return Err(SettingsError::Serde(
+            "说话结束检测的静音时长必须大于 0".to_string(),
+        ));
+    }
+    if recording.segment_retry_attempts > MAX_SEGMENT_RETRY_ATTEMPTS {
+        return Err(SettingsError::Serde(format!(
+            "分段重试次数需在 0 到 {MAX_SEGMENT_RETRY_ATTEMPTS} 次之间"
+        )));
+    }
+    if recording.segment_overlap_ms > MAX_SEGMENT_OVERLAP_MS
+        || u64::from(recording.segment_overlap_ms) * 2 >= recording.segment_seconds.max(1) * 1000
+    {
+        return Err(SettingsError::Serde(format!(
+            "分段重叠时长不能超过 {MAX_SEGMENT_OVERLAP_MS} 毫秒，且需小于分段时长的一半"
+        )));
+    }
+    let silence_trim = &recording.silence_trim;
+    if !(-80.0..0.0).contains(&silence_trim.threshold_db) {
+        return Err(SettingsError::Serde(
+            "静音裁剪阈值需在 -80 到 0 dB 之间".to_string(),
+        ));
+    }
+    if !MAX_PAUSE_MS_RANGE.contains(&silence_trim.max_pause_ms) {
+        return Err(SettingsError::Serde(format!(
+            "保留的最长停顿需在 {} 到 {} 毫秒之间",
+            MAX_PAUSE_MS_RANGE.start(),
+            MAX_PAUSE_MS_RANGE.end()
+        )));
+    }
+    for profile in &recording.device_profiles {
+        validate_device_profile(profile)?;
+    }
+    Ok(())
+}
+
+fn validate_device_profile(profile: &DeviceProfile) -> Result<(), SettingsError> {
+    if profile.device_name.trim().is_empty() {
+        return Err(SettingsError::Serde("设备名称不能为空".to_string()));
+    }
+    if !(-MAX_DEVICE_GAIN_DB..=MAX_DEVICE_GAIN_DB).contains(&profile.gain_db) {
+        return Err(SettingsError::Serde(format!(
+            "设备增益需在 -{MAX_DEVICE_GAIN_DB} 到 {MAX_DEVICE_GAIN_DB} dB 之间"
+        )));
+    }
+    if profile.sample_rate != 0 && !DEVICE_SAMPLE_RATE_RANGE.contains(&profile.sample_rate) {
+        return Err(SettingsError::Serde(format!(
+            "设备采样率需在 {} 到 {} Hz 之间",
+            DEVICE_SAMPLE_RATE_RANGE.start(),
+            DEVICE_SAMPLE_RATE_RANGE.end()
+        )));
+    }
+    Ok(())
+}
+
+fn validate_wake_on_speech_settings(wake: &WakeOnSpeechSettings) -> Result<(), SettingsError> {
+    if !(-80.0..0.0).contains(&wake.threshold_db) {
+        return Err(SettingsError::Serde(
+            "语音唤醒阈值需在 -80 到 0 dB 之间".to_string(),
+        ));
+    }
+    if wake.stop_silence_ms == 0 || wake.max_recording_seconds == 0 {
+        return Err(SettingsError::Serde(
+            "语音唤醒的静音时长与最长录音时长必须大于 0".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_audio_pipeline_settings(pipeline: &AudioPipelineSettings) -> Result<(), SettingsError> {
+    for step in &pipeline.steps {
+        let valid = match *step {
+            AudioPipelineStep::Trim { threshold_db, .. }
+            | AudioPipelineStep::Denoise { threshold_db } => threshold_db < 0.0,
+            AudioPipelineStep::Normalize { target_db } => target_db <= 0.0,
+            AudioPipelineStep::Resample { sample_rate, .. } => {
+                (8_000..=48_000).contains(&sample_rate)
+            }
+            AudioPipelineStep::Tempo { rate } => TEMPO_RATE_RANGE.contains(&rate),
+        };
+        if !valid {
+            return Err(SettingsError::Serde(format!(
+                "音频预处理步骤参数无效: {step:?}"
+            )));
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn validate_sensevoice_settings(
     sensevoice: &SenseVoiceSettings,
 ) -> Result<(), SettingsError> {
@@ -289,6 +585,12 @@ pub(crate) fn validate_sensevoice_settings(
             "Sherpa-ONNX SenseVoice currently runs on CPU only".to_string(),
         ));
     }
+    let health = &sensevoice.health;
+    if health.interval_ms < 200 || health.request_timeout_secs == 0 {
+        return Err(SettingsError::Serde(
+            "健康检查间隔不能小于 200 毫秒，请求超时不能为 0".to_string(),
+        ));
+    }
     Ok(())
 }
 
@@ -371,6 +673,25 @@ mod tests {
         assert!(settings.openai.legacy_text.is_none());
     }
 
+    #[test]
+    fn normalize_output_migrates_legacy_pangu_spacing() {
+        let mut output: OutputSettings =
+            serde_json::from_str(r#"{"removeNewlines":false,"panguSpacing":true}"#).unwrap();
+        normalize_output_settings(&mut output);
+        assert_eq!(output.punctuation.cjk_latin_spacing, CjkLatinSpacing::Add);
+        assert!(!output.legacy_pangu_spacing);
+        let json = serde_json::to_string(&output).unwrap();
+        assert!(!json.contains("panguSpacing"));
+
+        output.legacy_pangu_spacing = true;
+        output.punctuation.cjk_latin_spacing = CjkLatinSpacing::Remove;
+        normalize_output_settings(&mut output);
+        assert_eq!(
+            output.punctuation.cjk_latin_spacing,
+            CjkLatinSpacing::Remove
+        );
+    }
+
     #[test]
     fn normalize_text_processing_backfills_auth_from_transcription_openai_settings() {
         let mut settings = Settings::default();
@@ -414,4 +735,13 @@ mod tests {
         // Custom text processing key must NOT be overwritten by transcription key
         assert_eq!(settings.text_processing.openai.api_key, "text-only-key");
     }
+
+    #[test]
+    fn segment_retry_attempts_are_capped() {
+        let mut recording = Settings::default().recording;
+        recording.segment_retry_attempts = MAX_SEGMENT_RETRY_ATTEMPTS;
+        assert!(validate_recording_settings(&recording).is_ok());
+        recording.segment_retry_attempts = MAX_SEGMENT_RETRY_ATTEMPTS + 1;
+        assert!(validate_recording_settings(&recording).is_err());
+    }
 }
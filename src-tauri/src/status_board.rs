@@ -0,0 +1,140 @@
+//! 状态浮窗的录音与转写任务看板
+//!
+//! 上一条录音仍在转写时即可开始下一次录音。浮窗按任务 ID 跟踪各转写任务，按
+//! “当前录音状态 + 待完成转写数”合成显示，某个任务结束时不会覆盖仍在进行的录音。
+
+use crate::jobs::JobId;
+use crate::status_native::StatusType;
+use std::sync::Mutex;
+
+const TRANSCRIBING_TEXT: &str = "正在转写";
+
+static BOARD: Mutex<Board> = Mutex::new(Board::new());
+
+struct Board {
+    /// 录音（含倒计时、暂停与静音提示）状态，未在录音时为 None
+    recording: Option<(StatusType, String)>,
+    /// 按提交顺序排列的转写任务及其当前提示
+    jobs: Vec<(JobId, String)>,
+}
+
+impl Board {
+    const fn new() -> Self {
+        Self {
+            recording: None,
+            jobs: Vec::new(),
+        }
+    }
+
+    fn begin_job(&mut self, job: JobId) {
+        self.recording = None;
+        self.jobs.push((job, TRANSCRIBING_TEXT.to_string()));
+    }
+
+    fn update_job(&mut self, job: JobId, text: &str) {
+        if let Some((_, current)) = self.jobs.iter_mut().find(|(id, _)| *id == job) {
+            *current = text.to_string();
+        }
+    }
+
+    fn finish_job(&mut self, job: JobId) {
+        self.jobs.retain(|(id, _)| *id != job);
+    }
+
+    fn overlay(&self) -> Option<(StatusType, String)> {
+        let pending = self.jobs.len();
+        if let Some((status_type, text)) = &self.recording {
+            if pending == 0 {
+                return Some((*status_type, text.clone()));
+            }
+            return Some((*status_type, format!("{text} · {pending} 条转写中")));
+        }
+        // 任务按提交顺序依次转写，队首即为正在转写的任务
+        let (_, text) = self.jobs.first()?;
+        if pending == 1 {
+            return Some((StatusType::Transcribing, text.clone()));
+        }
+        Some((
+            StatusType::Transcribing,
+            format!("{text} · 另有 {} 条待转写", pending - 1),
+        ))
+    }
+}
+
+fn board() -> std::sync::MutexGuard<'static, Board> {
+    BOARD.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 更新录音状态
+pub fn set_recording(status_type: StatusType, text: &str) {
+    board().recording = Some((status_type, text.to_string()));
+}
+
+/// 录音被丢弃，不产生转写任务
+pub fn clear_recording() {
+    board().recording = None;
+}
+
+/// 录音结束并送入转写：清除录音状态并登记新任务
+pub fn begin_job(job: JobId) {
+    board().begin_job(job);
+}
+
+/// 更新进行中任务的提示，如离线回退到本地模型
+pub fn update_job(job: JobId, text: &str) {
+    board().update_job(job, text);
+}
+
+pub fn finish_job(job: JobId) {
+    board().finish_job(job);
+}
+
+/// 录音或转写仍在进行时返回应显示的合成状态
+pub fn overlay() -> Option<(StatusType, String)> {
+    board().overlay()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlay_shows_recording_alongside_pending_jobs() {
+        let mut board = Board::new();
+        assert_eq!(board.overlay(), None);
+
+        board.recording = Some((StatusType::Recording, "正在录音".to_string()));
+        let first = JobId(1);
+        board.begin_job(first);
+        assert_eq!(
+            board.overlay(),
+            Some((StatusType::Transcribing, "正在转写".to_string()))
+        );
+
+        board.recording = Some((StatusType::Recording, "正在录音".to_string()));
+        assert_eq!(
+            board.overlay(),
+            Some((StatusType::Recording, "正在录音 · 1 条转写中".to_string()))
+        );
+
+        let second = JobId(2);
+        board.begin_job(second);
+        board.update_job(first, "离线，使用本地模型转写");
+        assert_eq!(
+            board.overlay(),
+            Some((
+                StatusType::Transcribing,
+                "离线，使用本地模型转写 · 另有 1 条待转写".to_string()
+            ))
+        );
+
+        board.finish_job(first);
+        board.finish_job(first);
+        assert_eq!(
+            board.overlay(),
+            Some((StatusType::Transcribing, "正在转写".to_string()))
+        );
+        board.finish_job(second);
+        assert_eq!(board.overlay(), None);
+    }
+}
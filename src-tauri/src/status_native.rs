@@ -14,6 +14,7 @@ pub enum StatusType {
     Transcribing = 1,
     Completed = 2,
     Error = 3,
+    Paused = 4,
 }
 
 #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
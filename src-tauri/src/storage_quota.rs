@@ -0,0 +1,191 @@
+//! 录音存储用量统计与配额
+//!
+//! 统计离线待转写队列、失败分段重试队列、原始录音存档与录音临时目录占用的磁盘空间。设置了配额时，
+//! 按修改时间从旧到新删除队列与存档中的录音，直到总用量回到配额以内；临时目录中的分段与溢出文件
+//! 由录音流程自行清理，且目录可能由用户指定，只统计不删除。
+
+use crate::audio_processing;
+use crate::deferred_queue;
+use crate::recording_archive;
+use crate::segment_retry;
+use crate::settings::{Settings, SettingsStore};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// 录音临时目录中由本应用创建的文件前缀
+const SCRATCH_FILE_PREFIXES: &[&str] = &["segment-", "spill-", "decrypted-"];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageUsage {
+    /// 离线待转写队列
+    pub deferred_bytes: u64,
+    /// 失败分段重试队列
+    pub retry_queue_bytes: u64,
+    /// 原始录音存档
+    pub archive_bytes: u64,
+    /// 录音临时目录
+    pub scratch_bytes: u64,
+    pub total_bytes: u64,
+    /// 配额（字节），为空表示不限
+    pub quota_bytes: Option<u64>,
+}
+
+/// 配额清理结果
+#[derive(Default)]
+pub struct Eviction {
+    /// 被删除录音的离线队列项 ID（同时是历史记录 ID）
+    pub deferred_ids: Vec<String>,
+}
+
+struct StoredFile {
+    path: PathBuf,
+    bytes: u64,
+    modified: SystemTime,
+}
+
+pub fn usage(store: &SettingsStore, settings: &Settings) -> StorageUsage {
+    let deferred_bytes = total_bytes(&list_files(deferred_queue::queue_dir(store).ok()));
+    let retry_queue_bytes = total_bytes(&list_files(segment_retry::queue_dir(store).ok()));
+    let archive_bytes = total_bytes(&list_files(recording_archive::archive_dir(store).ok()));
+    let scratch_dir = audio_processing::resolve_scratch_dir(&settings.recording.scratch_dir);
+    let scratch_bytes = total_bytes(
+        &list_files(Some(scratch_dir))
+            .into_iter()
+            .filter(|file| is_scratch_file(&file.path))
+            .collect::<Vec<_>>(),
+    );
+    StorageUsage {
+        deferred_bytes,
+        retry_queue_bytes,
+        archive_bytes,
+        scratch_bytes,
+        total_bytes: deferred_bytes + retry_queue_bytes + archive_bytes + scratch_bytes,
+        quota_bytes: quota_bytes(settings),
+    }
+}
+
+/// 总用量超过配额时从最旧的队列录音开始删除
+pub fn enforce(store: &SettingsStore, settings: &Settings) -> Eviction {
+    let Some(quota) = quota_bytes(settings) else {
+        return Eviction::default();
+    };
+    let usage = usage(store, settings);
+    if usage.total_bytes <= quota {
+        return Eviction::default();
+    }
+    let deferred_dir = deferred_queue::queue_dir(store).ok();
+    let mut candidates = list_files(deferred_dir.clone());
+    candidates.extend(list_files(segment_retry::queue_dir(store).ok()));
+    candidates.extend(list_files(recording_archive::archive_dir(store).ok()));
+    candidates.retain(|file| file.path.extension().is_some_and(|ext| ext == "wav"));
+
+    let mut eviction = Eviction::default();
+    for file in select_evictions(candidates, usage.total_bytes, quota) {
+        if fs::remove_file(&file.path).is_err() {
+            continue;
+        }
+        // 重试队列的条目在音频缺失时由队列自行清理，被删除的存档在回放时提示；离线队列需回填历史记录状态
+        if file.path.parent() == deferred_dir.as_deref() {
+            if let Some(id) = file.path.file_stem().and_then(|stem| stem.to_str()) {
+                eviction.deferred_ids.push(id.to_string());
+            }
+        }
+    }
+    eviction
+}
+
+fn quota_bytes(settings: &Settings) -> Option<u64> {
+    let quota_mb = settings.recording.storage_quota_mb;
+    (quota_mb > 0).then_some(quota_mb.saturating_mul(1024 * 1024))
+}
+
+/// 按修改时间从旧到新选出需删除的文件，直到总用量不超过配额
+fn select_evictions(mut files: Vec<StoredFile>, total: u64, quota: u64) -> Vec<StoredFile> {
+    files.sort_by_key(|file| file.modified);
+    let mut remaining = total;
+    files
+        .into_iter()
+        .take_while(|file| {
+            if remaining <= quota {
+                return false;
+            }
+            remaining = remaining.saturating_sub(file.bytes);
+            true
+        })
+        .collect()
+}
+
+fn list_files(dir: Option<PathBuf>) -> Vec<StoredFile> {
+    let Some(entries) = dir.and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            metadata.is_file().then(|| StoredFile {
+                path: entry.path(),
+                bytes: metadata.len(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            })
+        })
+        .collect()
+}
+
+fn total_bytes(files: &[StoredFile]) -> u64 {
+    files.iter().map(|file| file.bytes).sum()
+}
+
+fn is_scratch_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| {
+            (name.ends_with(".wav") || name.ends_with(".ogg"))
+                && SCRATCH_FILE_PREFIXES
+                    .iter()
+                    .any(|prefix| name.starts_with(prefix))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn file(name: &str, bytes: u64, age_secs: u64) -> StoredFile {
+        StoredFile {
+            path: PathBuf::from(name),
+            bytes,
+            modified: SystemTime::UNIX_EPOCH + Duration::from_secs(1_000 - age_secs),
+        }
+    }
+
+    #[test]
+    fn select_evictions_removes_oldest_until_within_quota() {
+        let files = vec![
+            file("new.wav", 40, 10),
+            file("old.wav", 30, 300),
+            file("mid.wav", 50, 100),
+        ];
+        let evicted = select_evictions(files, 120, 60)
+            .into_iter()
+            .map(|file| file.path)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            evicted,
+            vec![PathBuf::from("old.wav"), PathBuf::from("mid.wav")]
+        );
+        assert!(select_evictions(vec![file("a.wav", 10, 1)], 10, 10).is_empty());
+    }
+
+    #[test]
+    fn only_app_created_scratch_files_are_counted() {
+        assert!(is_scratch_file(Path::new("/tmp/segment-1-0.wav")));
+        assert!(is_scratch_file(Path::new("/tmp/spill-ab.wav")));
+        assert!(!is_scratch_file(Path::new("/tmp/interview.wav")));
+        assert!(!is_scratch_file(Path::new("/tmp/segment-notes.txt")));
+    }
+}
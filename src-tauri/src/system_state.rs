@@ -0,0 +1,194 @@
+//! 系统电源、网络与磁盘状态探测
+//!
+//! 供提供商切换规则、录音临时目录空间检查等使用。各平台实现保持轻量，
+//! 探测失败时返回 `Unknown`/`None`。
+
+use std::net::{SocketAddr, TcpStream};
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// 网络连通性探测的单次连接超时
+const NETWORK_PROBE_TIMEOUT: Duration = Duration::from_millis(800);
+/// 网络探测目标（Cloudflare / 阿里公共 DNS 的 443 端口），任一可连接即视为在线
+const NETWORK_PROBE_ADDRS: [&str; 2] = ["1.1.1.1:443", "223.5.5.5:443"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    Unknown,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SystemState {
+    pub power: PowerSource,
+    pub online: bool,
+}
+
+/// 探测当前电源与网络状态；`probe_network` 为 false 时跳过网络探测（最长需等待一次连接超时），
+/// 视为在线
+pub fn detect(probe_network: bool) -> SystemState {
+    SystemState {
+        power: detect_power_source(),
+        online: !probe_network || is_network_online(),
+    }
+}
+
+/// 通过 TCP 连接公共地址判断网络是否可用。
+/// 各目标并行探测，任一成功立即返回，最坏耗时为单次超时。
+pub fn is_network_online() -> bool {
+    let (sender, receiver) = mpsc::channel();
+    for addr in NETWORK_PROBE_ADDRS
+        .iter()
+        .filter_map(|addr| addr.parse::<SocketAddr>().ok())
+    {
+        let sender = sender.clone();
+        thread::spawn(move || {
+            let reachable = TcpStream::connect_timeout(&addr, NETWORK_PROBE_TIMEOUT).is_ok();
+            let _ = sender.send(reachable);
+        });
+    }
+    drop(sender);
+    receiver.iter().any(|reachable| reachable)
+}
+
+#[cfg(target_os = "linux")]
+pub fn detect_power_source() -> PowerSource {
+    use std::fs;
+
+    let Ok(entries) = fs::read_dir("/sys/class/power_supply") else {
+        return PowerSource::Unknown;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let kind = fs::read_to_string(path.join("type")).unwrap_or_default();
+        if kind.trim() != "Battery" {
+            continue;
+        }
+        let status = fs::read_to_string(path.join("status")).unwrap_or_default();
+        if status.trim().eq_ignore_ascii_case("discharging") {
+            return PowerSource::Battery;
+        }
+    }
+    // 没有放电中的电池（包括无电池的台式机）视为接通电源
+    PowerSource::Ac
+}
+
+#[cfg(target_os = "macos")]
+pub fn detect_power_source() -> PowerSource {
+    let Ok(output) = std::process::Command::new("pmset").arg("-g").arg("batt").output() else {
+        return PowerSource::Unknown;
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if stdout.contains("'Battery Power'") {
+        PowerSource::Battery
+    } else if stdout.contains("'AC Power'") {
+        PowerSource::Ac
+    } else {
+        PowerSource::Unknown
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn detect_power_source() -> PowerSource {
+    #[repr(C)]
+    #[derive(Default)]
+    #[allow(dead_code)]
+    struct SystemPowerStatus {
+        ac_line_status: u8,
+        battery_flag: u8,
+        battery_life_percent: u8,
+        system_status_flag: u8,
+        battery_life_time: u32,
+        battery_full_life_time: u32,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+    }
+
+    let mut status = SystemPowerStatus::default();
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return PowerSource::Unknown;
+    }
+    match status.ac_line_status {
+        0 => PowerSource::Battery,
+        1 => PowerSource::Ac,
+        _ => PowerSource::Unknown,
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub fn detect_power_source() -> PowerSource {
+    PowerSource::Unknown
+}
+
+/// 查询路径所在磁盘对当前用户可用的剩余空间（字节）
+#[cfg(target_os = "windows")]
+pub fn available_disk_space(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetDiskFreeSpaceExW(
+            directory: *const u16,
+            free_bytes_available: *mut u64,
+            total_bytes: *mut u64,
+            total_free_bytes: *mut u64,
+        ) -> i32;
+    }
+
+    let wide = path
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect::<Vec<u16>>();
+    let mut available = 0u64;
+    let mut total = 0u64;
+    let mut total_free = 0u64;
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, &mut total, &mut total_free)
+    };
+    (ok != 0).then_some(available)
+}
+
+/// 查询路径所在磁盘对当前用户可用的剩余空间（字节）
+#[cfg(unix)]
+pub fn available_disk_space(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::mem::MaybeUninit;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+    if unsafe { libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    let stat = unsafe { stat.assume_init() };
+    // f_bavail 为非特权用户可用的块数，块大小以 f_frsize 为准；
+    // 两者在 macOS 上是 32 位整数，在 Linux 上已是 u64
+    #[allow(clippy::useless_conversion)]
+    let available = u64::from(stat.f_bavail).saturating_mul(u64::from(stat.f_frsize));
+    Some(available)
+}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+pub fn available_disk_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn available_disk_space_reports_temp_dir() {
+        let available = super::available_disk_space(&std::env::temp_dir());
+        assert!(available.is_some_and(|bytes| bytes > 0));
+        assert_eq!(
+            super::available_disk_space(std::path::Path::new("/nonexistent/vtt-keyboard")),
+            None
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! 听写模板：按模板中的 `{slot}` 槽位，由文本模型从口述内容中抽取各槽位的值，
+//! 再填回模板生成结构化文本（如缺陷报告、会议纪要）。
+
+use crate::settings::{DictationTemplate, Settings};
+use crate::text_backend::{TextBackend, TextBackendError};
+use regex::{Captures, Regex};
+use serde_json::{Map, Value};
+use std::sync::OnceLock;
+
+fn slot_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\{([^{}\s]+)\}").expect("slot pattern"))
+}
+
+/// 当前启用的模板；未设置或 ID 不存在时返回 None
+pub fn active_template(settings: &Settings) -> Option<&DictationTemplate> {
+    let id = settings.active_template_id.trim();
+    if id.is_empty() {
+        return None;
+    }
+    settings.templates.iter().find(|template| template.id == id)
+}
+
+/// 按出现顺序列出模板中的槽位名（去重）
+pub fn slot_names(body: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for captures in slot_pattern().captures_iter(body) {
+        let name = captures[1].to_string();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+pub fn fill_template(
+    backend: &dyn TextBackend,
+    template: &DictationTemplate,
+    dictation: &str,
+) -> Result<String, TextBackendError> {
+    let slots = slot_names(&template.body);
+    if slots.is_empty() {
+        return Ok(template.body.clone());
+    }
+    let response = backend.generate(dictation, &build_instructions(&slots))?;
+    let values = parse_slot_values(&response)?;
+    Ok(render(&template.body, &values))
+}
+
+fn build_instructions(slots: &[String]) -> String {
+    format!(
+        "Extract the following fields from the user's dictation: {}.\n\
+         Reply with a single JSON object whose keys are exactly these field names \
+         and whose values are strings in the dictation's language. \
+         Use an empty string for fields that are not mentioned. Do not add any other text.",
+        slots.join(", ")
+    )
+}
+
+/// 解析模型返回的 JSON 对象，兼容 Markdown 代码块包裹与前后多余文字
+fn parse_slot_values(response: &str) -> Result<Map<String, Value>, TextBackendError> {
+    let start = response.find('{');
+    let end = response.rfind('}');
+    let (Some(start), Some(end)) = (start, end) else {
+        return Err(TextBackendError::Parse(
+            "模板槽位结果不是 JSON 对象".to_string(),
+        ));
+    };
+    if end < start {
+        return Err(TextBackendError::Parse(
+            "模板槽位结果不是 JSON 对象".to_string(),
+        ));
+    }
+    serde_json::from_str::<Map<String, Value>>(&response[start..=end])
+        .map_err(|err| TextBackendError::Parse(format!("模板槽位结果解析失败: {err}")))
+}
+
+/// 填充槽位；模型未给出的槽位替换为空字符串
+fn render(body: &str, values: &Map<String, Value>) -> String {
+    slot_pattern()
+        .replace_all(body, |captures: &Captures| match values.get(&captures[1]) {
+            Some(Value::String(value)) => value.trim().to_string(),
+            Some(Value::Null) | None => String::new(),
+            Some(value) => value.to_string(),
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_names_are_unique_and_ordered() {
+        let body = "Bug: {title}\nSteps: {steps}\nExpected: {expected}\nSee {title}";
+        assert_eq!(slot_names(body), vec!["title", "steps", "expected"]);
+    }
+
+    #[test]
+    fn render_fills_slots_from_fenced_json() {
+        let response = "```json\n{\"steps\": \"点击登录\", \"expected\": null}\n```";
+        let values = parse_slot_values(response).expect("parse");
+        assert_eq!(
+            render("步骤: {steps}; 预期: {expected}", &values),
+            "步骤: 点击登录; 预期: "
+        );
+    }
+
+    #[test]
+    fn parse_slot_values_rejects_non_json() {
+        assert!(parse_slot_values("no fields here").is_err());
+    }
+}
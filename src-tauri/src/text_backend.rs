@@ -0,0 +1,267 @@
+//! 文本生成统一接口
+//!
+//! 将 OpenAI、Claude、Ollama 与本地 OpenAI 兼容服务抽象为 `TextBackend` trait，
+//! 触发词、听写模板等按名称从 [`TEXT_BACKEND_REGISTRY`] 选择后端。
+
+use crate::openai::{self, OpenAiError};
+use crate::settings::{TextProcessingProvider, TextProcessingSettings, TextSettings};
+use reqwest::blocking::{Client, RequestBuilder};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+/// Claude Messages API 版本头
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+#[derive(Debug, Error)]
+pub enum TextBackendError {
+    #[error(transparent)]
+    OpenAi(#[from] OpenAiError),
+    #[error("文本生成配置缺失: {0}")]
+    Config(String),
+    #[error("文本生成请求失败: {0}")]
+    Request(String),
+    #[error("文本生成响应解析失败: {0}")]
+    Parse(String),
+}
+
+/// 文本生成后端统一接口
+pub trait TextBackend {
+    /// 以 `instructions` 为系统指令处理 `input`，返回生成的文本
+    fn generate(&self, input: &str, instructions: &str) -> Result<String, TextBackendError>;
+
+    /// 该后端设置中配置的系统指令
+    fn default_instructions(&self) -> &str;
+}
+
+// ── OpenAI（Responses API）──────────────────────────────────────
+
+pub struct OpenAiTextBackend {
+    settings: TextSettings,
+}
+
+impl TextBackend for OpenAiTextBackend {
+    fn generate(&self, input: &str, instructions: &str) -> Result<String, TextBackendError> {
+        Ok(openai::generate_text(&self.settings, input, instructions)?)
+    }
+
+    fn default_instructions(&self) -> &str {
+        &self.settings.instructions
+    }
+}
+
+// ── Claude（Messages API）──────────────────────────────────────
+
+pub struct ClaudeTextBackend {
+    settings: TextSettings,
+}
+
+impl TextBackend for ClaudeTextBackend {
+    fn generate(&self, input: &str, instructions: &str) -> Result<String, TextBackendError> {
+        let api_key = self.settings.api_key.trim();
+        if api_key.is_empty() {
+            return Err(TextBackendError::Config(
+                "Claude API Key 不能为空".to_string(),
+            ));
+        }
+        let mut body = json!({
+            "model": self.settings.model,
+            "max_tokens": self.settings.max_output_tokens,
+            "temperature": self.settings.temperature,
+            "messages": [{ "role": "user", "content": input }],
+        });
+        if !instructions.is_empty() {
+            body["system"] = json!(instructions);
+        }
+        let request = Client::new()
+            .post(endpoint(&self.settings, "messages"))
+            .header("x-api-key", api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION);
+        extract_claude_text(&send_json(request, &body)?)
+    }
+
+    fn default_instructions(&self) -> &str {
+        &self.settings.instructions
+    }
+}
+
+// ── Ollama ─────────────────────────────────────────────────────
+
+pub struct OllamaTextBackend {
+    settings: TextSettings,
+}
+
+impl TextBackend for OllamaTextBackend {
+    fn generate(&self, input: &str, instructions: &str) -> Result<String, TextBackendError> {
+        let body = json!({
+            "model": self.settings.model,
+            "messages": chat_messages(input, instructions),
+            "stream": false,
+            "options": {
+                "temperature": self.settings.temperature,
+                "top_p": self.settings.top_p,
+                "num_predict": self.settings.max_output_tokens,
+            },
+        });
+        let request = Client::new().post(endpoint(&self.settings, "api/chat"));
+        let value = send_json(request, &body)?;
+        value
+            .pointer("/message/content")
+            .and_then(|val| val.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| TextBackendError::Parse("响应中未找到文本输出".to_string()))
+    }
+
+    fn default_instructions(&self) -> &str {
+        &self.settings.instructions
+    }
+}
+
+// ── 本地 OpenAI 兼容服务（Chat Completions）──────────────────────
+
+pub struct LocalTextBackend {
+    settings: TextSettings,
+}
+
+impl TextBackend for LocalTextBackend {
+    fn generate(&self, input: &str, instructions: &str) -> Result<String, TextBackendError> {
+        let body = json!({
+            "model": self.settings.model,
+            "messages": chat_messages(input, instructions),
+            "temperature": self.settings.temperature,
+            "top_p": self.settings.top_p,
+            "max_tokens": self.settings.max_output_tokens,
+        });
+        let mut request = Client::new().post(endpoint(&self.settings, "chat/completions"));
+        // 本地服务通常无需鉴权，填写了 Key 时才携带
+        let api_key = self.settings.api_key.trim();
+        if !api_key.is_empty() {
+            request = request.bearer_auth(api_key);
+        }
+        let value = send_json(request, &body)?;
+        value
+            .pointer("/choices/0/message/content")
+            .and_then(|val| val.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| TextBackendError::Parse("响应中未找到文本输出".to_string()))
+    }
+
+    fn default_instructions(&self) -> &str {
+        &self.settings.instructions
+    }
+}
+
+// ── 后端注册表 ─────────────────────────────────────────────────
+
+type BackendFactory = fn(&TextProcessingSettings) -> Box<dyn TextBackend>;
+
+/// 文本生成后端注册表；新增后端只需实现 [`TextBackend`] 并在此登记名称
+const TEXT_BACKEND_REGISTRY: &[(&str, BackendFactory)] = &[
+    (TextProcessingProvider::Openai.as_str(), |settings| {
+        Box::new(OpenAiTextBackend {
+            settings: settings.openai.clone(),
+        })
+    }),
+    (TextProcessingProvider::Claude.as_str(), |settings| {
+        Box::new(ClaudeTextBackend {
+            settings: settings.claude.clone(),
+        })
+    }),
+    (TextProcessingProvider::Ollama.as_str(), |settings| {
+        Box::new(OllamaTextBackend {
+            settings: settings.ollama.clone(),
+        })
+    }),
+    (TextProcessingProvider::Local.as_str(), |settings| {
+        Box::new(LocalTextBackend {
+            settings: settings.local.clone(),
+        })
+    }),
+];
+
+/// 按名称从注册表选择后端；名称为空时使用设置中的提供商
+pub fn resolve(
+    settings: &TextProcessingSettings,
+    name: &str,
+) -> Result<Box<dyn TextBackend>, TextBackendError> {
+    let name = match name.trim() {
+        "" => settings.provider.as_str(),
+        value => value,
+    };
+    TEXT_BACKEND_REGISTRY
+        .iter()
+        .find(|(entry, _)| entry.eq_ignore_ascii_case(name))
+        .map(|(_, factory)| factory(settings))
+        .ok_or_else(|| TextBackendError::Config(format!("未知的文本生成后端: {name}")))
+}
+
+fn endpoint(settings: &TextSettings, path: &str) -> String {
+    format!("{}/{path}", settings.api_base.trim().trim_end_matches('/'))
+}
+
+fn chat_messages(input: &str, instructions: &str) -> Vec<Value> {
+    let mut messages = Vec::new();
+    if !instructions.is_empty() {
+        messages.push(json!({ "role": "system", "content": instructions }));
+    }
+    messages.push(json!({ "role": "user", "content": input }));
+    messages
+}
+
+fn send_json(request: RequestBuilder, body: &Value) -> Result<Value, TextBackendError> {
+    let response = request
+        .json(body)
+        .send()
+        .map_err(|err| TextBackendError::Request(err.to_string()))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        return Err(TextBackendError::Request(format!("{status}: {body}")));
+    }
+    response
+        .json()
+        .map_err(|err| TextBackendError::Parse(err.to_string()))
+}
+
+/// 拼接 Claude 响应中所有 `text` 类型的内容块
+fn extract_claude_text(value: &Value) -> Result<String, TextBackendError> {
+    let blocks = value
+        .get("content")
+        .and_then(|val| val.as_array())
+        .ok_or_else(|| TextBackendError::Parse("响应中未找到文本输出".to_string()))?;
+    Ok(blocks
+        .iter()
+        .filter(|block| block.get("type").and_then(|val| val.as_str()) == Some("text"))
+        .filter_map(|block| block.get("text").and_then(|val| val.as_str()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_falls_back_to_configured_provider_and_rejects_unknown_names() {
+        let mut settings = TextProcessingSettings::default();
+        settings.provider = TextProcessingProvider::Claude;
+        settings.claude.instructions = "claude".to_string();
+        settings.ollama.instructions = "ollama".to_string();
+        let backend = resolve(&settings, "").unwrap();
+        assert_eq!(backend.default_instructions(), "claude");
+        let backend = resolve(&settings, " Ollama ").unwrap();
+        assert_eq!(backend.default_instructions(), "ollama");
+        assert!(resolve(&settings, "gemini").is_err());
+    }
+
+    #[test]
+    fn extract_claude_text_joins_text_blocks() {
+        let value = json!({
+            "content": [
+                { "type": "text", "text": "Hello" },
+                { "type": "tool_use", "id": "x" },
+                { "type": "text", "text": " world" },
+            ]
+        });
+        assert_eq!(extract_claude_text(&value).unwrap(), "Hello world");
+        assert!(extract_claude_text(&json!({})).is_err());
+    }
+}
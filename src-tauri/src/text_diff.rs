@@ -0,0 +1,143 @@
+//! 转写原文与最终文本之间的词级差异
+//!
+//! 英文等按单词切分，中日韩文字按单字切分，空白与标点单独成段，
+//! 用最长公共子序列求出差异并合并相邻的同类片段，供界面标注模型改动之处。
+
+use crate::settings::{TextDiffKind, TextDiffSpan};
+
+/// 词元数乘积超过该值时不再逐词比较，直接视为整段替换，避免占用过多内存
+const MAX_DIFF_CELLS: usize = 1_000_000;
+
+pub fn diff_words(original: &str, revised: &str) -> Vec<TextDiffSpan> {
+    let old_tokens = tokenize(original);
+    let new_tokens = tokenize(revised);
+    let mut spans = Vec::new();
+    if old_tokens.len().saturating_mul(new_tokens.len()) > MAX_DIFF_CELLS {
+        push_span(&mut spans, TextDiffKind::Delete, original);
+        push_span(&mut spans, TextDiffKind::Insert, revised);
+        return spans;
+    }
+
+    // lcs[i][j]：old_tokens[i..] 与 new_tokens[j..] 的最长公共子序列长度
+    let columns = new_tokens.len() + 1;
+    let mut lcs = vec![0u32; (old_tokens.len() + 1) * columns];
+    for i in (0..old_tokens.len()).rev() {
+        for j in (0..new_tokens.len()).rev() {
+            lcs[i * columns + j] = if old_tokens[i] == new_tokens[j] {
+                lcs[(i + 1) * columns + j + 1] + 1
+            } else {
+                lcs[(i + 1) * columns + j].max(lcs[i * columns + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < old_tokens.len() && j < new_tokens.len() {
+        if old_tokens[i] == new_tokens[j] {
+            push_span(&mut spans, TextDiffKind::Equal, old_tokens[i]);
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * columns + j] >= lcs[i * columns + j + 1] {
+            push_span(&mut spans, TextDiffKind::Delete, old_tokens[i]);
+            i += 1;
+        } else {
+            push_span(&mut spans, TextDiffKind::Insert, new_tokens[j]);
+            j += 1;
+        }
+    }
+    for token in &old_tokens[i..] {
+        push_span(&mut spans, TextDiffKind::Delete, token);
+    }
+    for token in &new_tokens[j..] {
+        push_span(&mut spans, TextDiffKind::Insert, token);
+    }
+    spans
+}
+
+fn push_span(spans: &mut Vec<TextDiffSpan>, kind: TextDiffKind, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match spans.last_mut() {
+        Some(last) if last.kind == kind => last.text.push_str(text),
+        _ => spans.push(TextDiffSpan {
+            kind,
+            text: text.to_string(),
+        }),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum TokenClass {
+    Word,
+    Space,
+    Single,
+}
+
+fn classify(ch: char) -> TokenClass {
+    if ch.is_whitespace() {
+        TokenClass::Space
+    } else if ch.is_alphanumeric() && u32::from(ch) < 0x2E80 {
+        // 0x2E80 之后为中日韩文字，逐字比较
+        TokenClass::Word
+    } else {
+        TokenClass::Single
+    }
+}
+
+fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut current: Option<TokenClass> = None;
+    for (index, ch) in text.char_indices() {
+        let class = classify(ch);
+        let continues = current == Some(class) && class != TokenClass::Single;
+        if !continues && index > start {
+            tokens.push(&text[start..index]);
+            start = index;
+        }
+        current = Some(class);
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(spans: &[TextDiffSpan]) -> String {
+        spans
+            .iter()
+            .map(|span| match span.kind {
+                TextDiffKind::Equal => span.text.clone(),
+                TextDiffKind::Insert => format!("[+{}]", span.text),
+                TextDiffKind::Delete => format!("[-{}]", span.text),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn diff_words_marks_replaced_words() {
+        let spans = diff_words("send the report tomorrow", "send the final report today");
+        assert_eq!(
+            render(&spans),
+            "send the [+final ]report [-tomorrow][+today]"
+        );
+    }
+
+    #[test]
+    fn diff_words_compares_cjk_per_character() {
+        let spans = diff_words("今天开会", "明天开会。");
+        assert_eq!(render(&spans), "[-今][+明]天开会[+。]");
+    }
+
+    #[test]
+    fn identical_text_is_single_equal_span() {
+        let spans = diff_words("hello world", "hello world");
+        assert_eq!(spans.len(), 1);
+        assert!(spans[0].kind == TextDiffKind::Equal);
+    }
+}
@@ -1,13 +1,20 @@
 //! 转写引擎统一接口
 //!
 //! 将所有转写引擎（OpenAI, 火山引擎, 阿里云 ASR/Paraformer, SenseVoice）
-//! 抽象为统一的 `TranscriptionEngine` trait，按云端/本地(Docker/Native)分类。
+//! 抽象为统一的 `TranscriptionBackend` trait，按云端/本地(Docker/Native)分类。
+//! 各提供商在 [`BACKEND_REGISTRY`] 中登记，引擎创建与离线回退都经由注册表查找，
+//! 新增提供商无需修改调用方的分支。
 
 use crate::aliyun_realtime::{self, AliyunRealtimeError};
+use crate::events;
 use crate::openai::{self, OpenAiError};
+use crate::provider_error::ProviderFailure;
 use crate::sensevoice::{self, SenseVoiceError};
-use crate::settings::{Settings, TranscriptionAlignment, TranscriptionProvider};
+use crate::settings::{
+    Settings, SpeechMetadata, TranscriptionAlignment, TranscriptionProvider,
+};
 use crate::volcengine::{self, VolcengineError};
+use serde::Serialize;
 use std::path::Path;
 use thiserror::Error;
 
@@ -17,6 +24,29 @@ use thiserror::Error;
 pub struct TranscriptionResult {
     pub text: String,
     pub alignment: Option<TranscriptionAlignment>,
+    /// 提供商返回的整体置信度（0~1），不支持时为空
+    pub confidence: Option<f32>,
+    /// 提供商返回的句级时间片段（相对于音频开头），不支持时为空
+    pub segments: Vec<TranscriptSegment>,
+    /// 提供商识别出的语种、情绪与声学事件，不支持时为空
+    pub metadata: SpeechMetadata,
+}
+
+/// 带时间范围的一段转写文本
+#[derive(Clone)]
+pub struct TranscriptSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// 由逐 token 的对数概率计算整体置信度（token 概率的平均值）
+pub fn confidence_from_logprobs(logprobs: &[f64]) -> Option<f32> {
+    if logprobs.is_empty() {
+        return None;
+    }
+    let sum: f64 = logprobs.iter().map(|logprob| logprob.exp()).sum();
+    Some((sum / logprobs.len() as f64).clamp(0.0, 1.0) as f32)
 }
 
 // ── 统一错误类型 ──────────────────────────────────────────────
@@ -37,6 +67,24 @@ pub enum TranscriptionError {
     SenseVoice(#[from] SenseVoiceError),
 }
 
+impl TranscriptionError {
+    /// 提供商以常见 HTTP 状态拒绝请求时的归类
+    pub fn failure(&self) -> Option<ProviderFailure> {
+        match self {
+            Self::OpenAi(OpenAiError::Rejected(failure))
+            | Self::Volcengine(VolcengineError::Rejected(failure))
+            | Self::Aliyun(AliyunRealtimeError::Rejected(failure))
+            | Self::SenseVoice(SenseVoiceError::Rejected(failure)) => Some(*failure),
+            _ => None,
+        }
+    }
+
+    /// 提供商拒绝请求（密钥无效、额度不足等）时重试只会重复失败
+    pub fn is_retryable(&self) -> bool {
+        self.failure().is_none_or(ProviderFailure::is_retryable)
+    }
+}
+
 // ── 引擎环境分类 ──────────────────────────────────────────────
 
 /// 引擎运行环境分类
@@ -57,35 +105,180 @@ pub enum LocalRuntime {
     Native,
 }
 
+/// 引擎能力声明，调用方按能力而非提供商分支决定行为
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendCapabilities {
+    /// 识别过程中推送中间结果
+    pub streaming: bool,
+    /// 返回句级时间片段
+    pub segments: bool,
+    /// 返回逐 token 时间对齐
+    pub alignment: bool,
+    /// 无需网络即可转写
+    pub offline: bool,
+    /// 接受 Ogg Opus 等压缩格式的上传
+    pub compressed_upload: bool,
+}
+
+/// 引擎可用的设置项，供设置界面隐藏与当前提供商无关的字段
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendOptions {
+    /// 支持的语言代码，为空表示自动识别或不限
+    pub languages: Vec<&'static str>,
+    /// 单次请求的音频时长上限（秒），为空表示不限
+    pub max_audio_seconds: Option<u32>,
+    /// 单次请求的音频文件大小上限（字节），为空表示不限；超过时自动缩短分段
+    pub max_upload_bytes: Option<u64>,
+    /// 生效的设置项，与提供商设置的字段名一致
+    pub options: Vec<&'static str>,
+}
+
+/// 提供商能力描述，由 `get_provider_capabilities` 命令返回
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCapabilities {
+    pub provider: TranscriptionProvider,
+    #[serde(flatten)]
+    pub capabilities: BackendCapabilities,
+    #[serde(flatten)]
+    pub options: BackendOptions,
+}
+
 // ── 引擎 trait ────────────────────────────────────────────────
 
 /// 转写引擎统一接口
-pub trait TranscriptionEngine {
+pub trait TranscriptionBackend {
     /// 转写音频文件
     fn transcribe(&self, audio_path: &Path) -> Result<TranscriptionResult, TranscriptionError>;
 
+    /// 转写音频文件并通过 `on_partial(text, is_final)` 推送中间结果；
+    /// 不支持流式的引擎在完成后推送一次最终结果
+    fn stream(
+        &self,
+        audio_path: &Path,
+        on_partial: &mut dyn FnMut(&str, bool),
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        let result = self.transcribe(audio_path)?;
+        on_partial(&result.text, true);
+        Ok(result)
+    }
+
+    /// 返回引擎能力
+    fn capabilities(&self) -> BackendCapabilities;
+
+    /// 返回引擎支持的语言、时长上限与生效的设置项
+    fn options(&self) -> BackendOptions {
+        BackendOptions::default()
+    }
+
+    /// 校验配置（凭据、安装状态等）是否足以发起转写，不发起网络请求
+    fn validate(&self) -> Result<(), TranscriptionError>;
+
     /// 返回引擎的模型组名称（用于历史记录展示）
     fn model_group(&self) -> String;
 
+    /// 返回模型名称，默认取模型组名称中 “/” 之后的部分
+    fn model_name(&self) -> String {
+        let group = self.model_group();
+        group
+            .split_once(" / ")
+            .map_or(group.as_str(), |(_, model)| model)
+            .to_string()
+    }
+
     /// 返回引擎运行环境分类
     fn environment(&self) -> EngineEnvironment;
 }
 
 // ── OpenAI 引擎 ───────────────────────────────────────────────
 
+/// Whisper 系列支持的语言（ISO 639-1）
+const OPENAI_LANGUAGES: &[&str] = &[
+    "af", "ar", "az", "be", "bg", "bs", "ca", "cs", "cy", "da", "de", "el", "en", "es", "et", "fa",
+    "fi", "fr", "gl", "he", "hi", "hr", "hu", "hy", "id", "is", "it", "ja", "kk", "kn", "ko", "lt",
+    "lv", "mi", "mk", "mr", "ms", "ne", "nl", "no", "pl", "pt", "ro", "ru", "sk", "sl", "sr", "sv",
+    "sw", "ta", "th", "tl", "tr", "uk", "ur", "vi", "zh",
+];
+/// gpt-4o 系列单次请求的时长上限
+const OPENAI_MAX_AUDIO_SECONDS: u32 = 1500;
+/// 转写接口的上传文件大小上限（25 MB）
+const OPENAI_MAX_UPLOAD_BYTES: u64 = 25 * 1024 * 1024;
+
 pub struct OpenAiEngine {
     settings: Settings,
 }
 
-impl TranscriptionEngine for OpenAiEngine {
+impl TranscriptionBackend for OpenAiEngine {
     fn transcribe(&self, audio_path: &Path) -> Result<TranscriptionResult, TranscriptionError> {
-        let text = openai::transcribe_audio(&self.settings, audio_path)?;
+        let result = openai::transcribe_audio(&self.settings, audio_path)?;
         Ok(TranscriptionResult {
-            text,
-            alignment: None,
+            text: result.text,
+            alignment: result.alignment,
+            confidence: result.confidence,
+            segments: result.segments,
+            metadata: SpeechMetadata {
+                language: result.language,
+                ..SpeechMetadata::default()
+            },
         })
     }
 
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            streaming: self.settings.openai.speech_to_text.stream,
+            segments: true,
+            alignment: true,
+            compressed_upload: true,
+            ..BackendCapabilities::default()
+        }
+    }
+
+    fn options(&self) -> BackendOptions {
+        let model = self.settings.openai.speech_to_text.model.trim();
+        let options = if model.contains("diarize") {
+            vec![
+                "language",
+                "responseFormat",
+                "temperature",
+                "chunkingStrategy",
+                "knownSpeakerNames",
+                "knownSpeakerReferences",
+                "stream",
+            ]
+        } else if model.starts_with("gpt-4o") {
+            vec![
+                "language",
+                "prompt",
+                "responseFormat",
+                "temperature",
+                "chunkingStrategy",
+                "include",
+                "stream",
+            ]
+        } else {
+            vec![
+                "language",
+                "prompt",
+                "responseFormat",
+                "temperature",
+                "timestampGranularities",
+            ]
+        };
+        BackendOptions {
+            languages: OPENAI_LANGUAGES.to_vec(),
+            max_audio_seconds: Some(OPENAI_MAX_AUDIO_SECONDS),
+            max_upload_bytes: Some(OPENAI_MAX_UPLOAD_BYTES),
+            options,
+        }
+    }
+
+    fn validate(&self) -> Result<(), TranscriptionError> {
+        openai::ensure_auth(&self.settings.openai)?;
+        Ok(())
+    }
+
     fn model_group(&self) -> String {
         let model = self.settings.openai.speech_to_text.model.trim();
         let display = if model.is_empty() { "-" } else { model };
@@ -106,15 +299,57 @@ pub struct VolcengineEngine {
     settings: Settings,
 }
 
-impl TranscriptionEngine for VolcengineEngine {
+impl TranscriptionBackend for VolcengineEngine {
     fn transcribe(&self, audio_path: &Path) -> Result<TranscriptionResult, TranscriptionError> {
-        let text = volcengine::transcribe_audio(&self.settings, audio_path)?;
+        self.stream(audio_path, &mut events::emit_partial_transcript)
+    }
+
+    fn stream(
+        &self,
+        audio_path: &Path,
+        on_partial: &mut dyn FnMut(&str, bool),
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        let result = volcengine::transcribe_audio(&self.settings, audio_path, on_partial)?;
         Ok(TranscriptionResult {
-            text,
+            text: result.text,
             alignment: None,
+            confidence: result.confidence,
+            segments: Vec::new(),
+            metadata: SpeechMetadata::default(),
         })
     }
 
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            streaming: self.settings.volcengine.use_streaming,
+            // 流式识别按 PCM 样本分块发送，只接受 WAV
+            compressed_upload: !self.settings.volcengine.use_streaming,
+            ..BackendCapabilities::default()
+        }
+    }
+
+    fn options(&self) -> BackendOptions {
+        BackendOptions {
+            languages: vec!["zh-CN", "en-US", "ja-JP", "ko-KR"],
+            max_audio_seconds: None,
+            max_upload_bytes: None,
+            options: vec![
+                "language",
+                "useStreaming",
+                "useFast",
+                "boostingTableName",
+                "hotwords",
+                "enablePunctuation",
+                "enableItn",
+            ],
+        }
+    }
+
+    fn validate(&self) -> Result<(), TranscriptionError> {
+        volcengine::ensure_config(&self.settings.volcengine)?;
+        Ok(())
+    }
+
     fn model_group(&self) -> String {
         if self.settings.volcengine.use_streaming {
             format!("Volcengine / {VOLCENGINE_STREAMING_CLUSTER}")
@@ -138,15 +373,36 @@ pub struct AliyunAsrEngine {
     settings: Settings,
 }
 
-impl TranscriptionEngine for AliyunAsrEngine {
+impl TranscriptionBackend for AliyunAsrEngine {
     fn transcribe(&self, audio_path: &Path) -> Result<TranscriptionResult, TranscriptionError> {
         let text = aliyun_realtime::transcribe_asr(&self.settings, audio_path)?;
         Ok(TranscriptionResult {
             text,
             alignment: None,
+            confidence: None,
+            segments: Vec::new(),
+            metadata: SpeechMetadata::default(),
         })
     }
 
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities::default()
+    }
+
+    fn options(&self) -> BackendOptions {
+        BackendOptions {
+            languages: vec!["zh", "en", "ja"],
+            max_audio_seconds: None,
+            max_upload_bytes: None,
+            options: vec!["vocabularyId"],
+        }
+    }
+
+    fn validate(&self) -> Result<(), TranscriptionError> {
+        aliyun_realtime::ensure_asr_config(&self.settings)?;
+        Ok(())
+    }
+
     fn model_group(&self) -> String {
         format!("Aliyun ASR / {ALIYUN_ASR_MODEL}")
     }
@@ -164,15 +420,36 @@ pub struct AliyunParaformerEngine {
     settings: Settings,
 }
 
-impl TranscriptionEngine for AliyunParaformerEngine {
+impl TranscriptionBackend for AliyunParaformerEngine {
     fn transcribe(&self, audio_path: &Path) -> Result<TranscriptionResult, TranscriptionError> {
         let text = aliyun_realtime::transcribe_paraformer(&self.settings, audio_path)?;
         Ok(TranscriptionResult {
             text,
             alignment: None,
+            confidence: None,
+            segments: Vec::new(),
+            metadata: SpeechMetadata::default(),
         })
     }
 
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities::default()
+    }
+
+    fn options(&self) -> BackendOptions {
+        BackendOptions {
+            languages: vec!["zh", "en", "ja", "yue", "ko", "de", "fr", "ru"],
+            max_audio_seconds: None,
+            max_upload_bytes: None,
+            options: vec!["languageHints", "vocabularyId"],
+        }
+    }
+
+    fn validate(&self) -> Result<(), TranscriptionError> {
+        aliyun_realtime::ensure_paraformer_config(&self.settings)?;
+        Ok(())
+    }
+
     fn model_group(&self) -> String {
         format!("Aliyun Paraformer / {ALIYUN_PARAFORMER_MODEL}")
     }
@@ -202,15 +479,55 @@ impl SenseVoiceEngine {
     }
 }
 
-impl TranscriptionEngine for SenseVoiceEngine {
+impl TranscriptionBackend for SenseVoiceEngine {
     fn transcribe(&self, audio_path: &Path) -> Result<TranscriptionResult, TranscriptionError> {
         let result = sensevoice::client::transcribe_audio(&self.settings, audio_path)?;
         Ok(TranscriptionResult {
             text: result.text,
             alignment: result.alignment,
+            confidence: result.confidence,
+            segments: Vec::new(),
+            metadata: result.metadata,
         })
     }
 
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            alignment: true,
+            offline: true,
+            ..BackendCapabilities::default()
+        }
+    }
+
+    fn options(&self) -> BackendOptions {
+        let local_model = normalize_local_model(&self.settings.sensevoice.local_model);
+        let spec = spec_for_local_model(local_model);
+        let languages = match local_model {
+            "voxtral" => vec!["en", "es", "fr", "pt", "hi", "de", "nl", "it"],
+            "qwen3-asr" => Vec::new(),
+            _ => vec!["zh", "en", "ja", "ko", "yue"],
+        };
+        let options = [
+            ("language", spec.supports_language),
+            ("device", spec.supports_device),
+            ("serviceUrl", spec.supports_service_url),
+        ]
+        .into_iter()
+        .filter_map(|(option, supported)| supported.then_some(option))
+        .collect();
+        BackendOptions {
+            languages,
+            max_audio_seconds: None,
+            max_upload_bytes: None,
+            options,
+        }
+    }
+
+    fn validate(&self) -> Result<(), TranscriptionError> {
+        sensevoice::client::ensure_ready(&self.settings)?;
+        Ok(())
+    }
+
     fn model_group(&self) -> String {
         let model_id = self.settings.sensevoice.model_id.trim();
         let display_id = if model_id.is_empty() { "-" } else { model_id };
@@ -231,26 +548,71 @@ impl TranscriptionEngine for SenseVoiceEngine {
     }
 }
 
-// ── 引擎工厂 ──────────────────────────────────────────────────
+// ── 提供商注册表 ──────────────────────────────────────────────
+
+type BackendFactory = fn(&Settings) -> Box<dyn TranscriptionBackend>;
 
-/// 根据设置创建对应的转写引擎
-pub fn create_engine(settings: &Settings) -> Box<dyn TranscriptionEngine> {
-    match settings.provider {
-        TranscriptionProvider::Openai => Box::new(OpenAiEngine {
+/// 提供商注册表；新增提供商只需实现 [`TranscriptionBackend`] 并在此登记，
+/// 顺序即离线回退时的候选顺序
+const BACKEND_REGISTRY: &[(TranscriptionProvider, BackendFactory)] = &[
+    (TranscriptionProvider::Openai, |settings| {
+        Box::new(OpenAiEngine {
             settings: settings.clone(),
-        }),
-        TranscriptionProvider::Volcengine => Box::new(VolcengineEngine {
+        })
+    }),
+    (TranscriptionProvider::Volcengine, |settings| {
+        Box::new(VolcengineEngine {
             settings: settings.clone(),
-        }),
-        TranscriptionProvider::AliyunAsr => Box::new(AliyunAsrEngine {
+        })
+    }),
+    (TranscriptionProvider::AliyunAsr, |settings| {
+        Box::new(AliyunAsrEngine {
             settings: settings.clone(),
-        }),
-        TranscriptionProvider::AliyunParaformer => Box::new(AliyunParaformerEngine {
+        })
+    }),
+    (TranscriptionProvider::AliyunParaformer, |settings| {
+        Box::new(AliyunParaformerEngine {
             settings: settings.clone(),
-        }),
-        TranscriptionProvider::Sensevoice => Box::new(SenseVoiceEngine {
+        })
+    }),
+    (TranscriptionProvider::Sensevoice, |settings| {
+        Box::new(SenseVoiceEngine {
             settings: settings.clone(),
-        }),
+        })
+    }),
+];
+
+/// 根据设置从注册表创建对应的转写引擎；未登记的提供商退回首个登记项
+pub fn create_backend(settings: &Settings) -> Box<dyn TranscriptionBackend> {
+    let (_, factory) = BACKEND_REGISTRY
+        .iter()
+        .find(|(provider, _)| *provider == settings.provider)
+        .unwrap_or(&BACKEND_REGISTRY[0]);
+    factory(settings)
+}
+
+/// 离线回退：按注册顺序返回首个无需网络且配置就绪的提供商
+pub fn offline_fallback_provider(settings: &Settings) -> Option<TranscriptionProvider> {
+    BACKEND_REGISTRY.iter().find_map(|(provider, factory)| {
+        let mut candidate = settings.clone();
+        candidate.provider = provider.clone();
+        let backend = factory(&candidate);
+        (backend.capabilities().offline && backend.validate().is_ok()).then(|| provider.clone())
+    })
+}
+
+/// 查询指定提供商在当前设置下的能力，供设置界面按能力显示字段
+pub fn provider_capabilities(
+    settings: &Settings,
+    provider: TranscriptionProvider,
+) -> ProviderCapabilities {
+    let mut candidate = settings.clone();
+    candidate.provider = provider.clone();
+    let backend = create_backend(&candidate);
+    ProviderCapabilities {
+        provider,
+        capabilities: backend.capabilities(),
+        options: backend.options(),
     }
 }
 
@@ -260,39 +622,46 @@ mod tests {
     use crate::settings::Settings;
 
     #[test]
-    fn create_engine_returns_correct_environment() {
+    fn confidence_from_logprobs_averages_token_probabilities() {
+        assert_eq!(confidence_from_logprobs(&[]), None);
+        let confidence = confidence_from_logprobs(&[0.0, (0.5f64).ln()]).unwrap();
+        assert!((confidence - 0.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn create_backend_returns_correct_environment() {
         let mut settings = Settings::default();
 
         settings.provider = TranscriptionProvider::Openai;
-        let engine = create_engine(&settings);
+        let engine = create_backend(&settings);
         assert_eq!(engine.environment(), EngineEnvironment::Cloud);
 
         settings.provider = TranscriptionProvider::Volcengine;
-        let engine = create_engine(&settings);
+        let engine = create_backend(&settings);
         assert_eq!(engine.environment(), EngineEnvironment::Cloud);
 
         settings.provider = TranscriptionProvider::AliyunAsr;
-        let engine = create_engine(&settings);
+        let engine = create_backend(&settings);
         assert_eq!(engine.environment(), EngineEnvironment::Cloud);
 
         settings.provider = TranscriptionProvider::AliyunParaformer;
-        let engine = create_engine(&settings);
+        let engine = create_backend(&settings);
         assert_eq!(engine.environment(), EngineEnvironment::Cloud);
     }
 
     #[test]
-    fn create_engine_model_group_matches_legacy() {
+    fn create_backend_model_group_matches_legacy() {
         let mut settings = Settings::default();
 
         settings.provider = TranscriptionProvider::Openai;
         settings.openai.speech_to_text.model = "gpt-4o-transcribe".to_string();
-        let engine = create_engine(&settings);
+        let engine = create_backend(&settings);
         assert_eq!(engine.model_group(), "OpenAI / gpt-4o-transcribe");
 
         settings.provider = TranscriptionProvider::Volcengine;
         settings.volcengine.use_streaming = true;
         settings.volcengine.use_fast = false;
-        let engine = create_engine(&settings);
+        let engine = create_backend(&settings);
         assert_eq!(
             engine.model_group(),
             "Volcengine / volcengine_streaming_common"
@@ -301,18 +670,18 @@ mod tests {
         settings.provider = TranscriptionProvider::Volcengine;
         settings.volcengine.use_streaming = false;
         settings.volcengine.use_fast = false;
-        let engine = create_engine(&settings);
+        let engine = create_backend(&settings);
         assert_eq!(
             engine.model_group(),
             "Volcengine / volcengine_input_common"
         );
 
         settings.provider = TranscriptionProvider::AliyunAsr;
-        let engine = create_engine(&settings);
+        let engine = create_backend(&settings);
         assert_eq!(engine.model_group(), "Aliyun ASR / fun-asr-realtime");
 
         settings.provider = TranscriptionProvider::AliyunParaformer;
-        let engine = create_engine(&settings);
+        let engine = create_backend(&settings);
         assert_eq!(
             engine.model_group(),
             "Aliyun Paraformer / paraformer-realtime-v2"
@@ -322,10 +691,42 @@ mod tests {
         settings.sensevoice.local_model = "voxtral".to_string();
         settings.sensevoice.model_id =
             "mistralai/Voxtral-Mini-4B-Realtime-2602".to_string();
-        let engine = create_engine(&settings);
+        let engine = create_backend(&settings);
         assert_eq!(
             engine.model_group(),
             "Voxtral / mistralai/Voxtral-Mini-4B-Realtime-2602"
         );
     }
+
+    #[test]
+    fn provider_capabilities_follow_openai_model() {
+        let mut settings = Settings::default();
+        settings.openai.speech_to_text.model = "whisper-1".to_string();
+        let whisper = provider_capabilities(&settings, TranscriptionProvider::Openai);
+        assert!(whisper.options.options.contains(&"timestampGranularities"));
+        assert!(!whisper.options.options.contains(&"stream"));
+
+        settings.openai.speech_to_text.model = "gpt-4o-transcribe-diarize".to_string();
+        let diarize = provider_capabilities(&settings, TranscriptionProvider::Openai);
+        assert!(diarize.options.options.contains(&"knownSpeakerNames"));
+        assert!(!diarize.options.options.contains(&"prompt"));
+
+        let sensevoice = provider_capabilities(&settings, TranscriptionProvider::Sensevoice);
+        assert!(sensevoice.capabilities.offline);
+        assert_eq!(sensevoice.options.max_audio_seconds, None);
+    }
+
+    #[test]
+    fn offline_fallback_provider_requires_ready_local_backend() {
+        let mut settings = Settings::default();
+        settings.provider = TranscriptionProvider::Openai;
+        assert!(offline_fallback_provider(&settings).is_none());
+
+        settings.sensevoice.enabled = true;
+        settings.sensevoice.installed = true;
+        assert!(offline_fallback_provider(&settings) == Some(TranscriptionProvider::Sensevoice));
+
+        settings.sensevoice.download_state = "running".to_string();
+        assert!(offline_fallback_provider(&settings).is_none());
+    }
 }
@@ -1,9 +1,20 @@
-use crate::processing;
+use crate::audio_processing;
+use crate::deferred_queue::{self, RetryOutcome};
+use crate::jobs::{self, JobId};
+use crate::processing::{self, ProcessingMode, ProcessingOutcome};
+use crate::provider_error::ProviderFailure;
 use crate::recorder::RecordedAudio;
+use crate::recording_archive;
+use crate::segment_retry;
 use crate::sensevoice::ensure_service_ready_blocking;
 use crate::settings::{
-    SettingsStore, TranscriptionHistoryItem, TranscriptionHistoryStatus, TranscriptionProvider,
+    HistoryEventPayload, SettingsStore, TranscriptionHistoryItem, TranscriptionHistoryStatus,
+    TranscriptionProvider,
 };
+use crate::storage_quota;
+use crate::text_diff;
+use serde::Serialize;
+use std::path::Path;
 use std::sync::mpsc;
 use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -13,8 +24,13 @@ use tauri::{AppHandle, Emitter};
 /// vLLM 模型装载可能耗时数分钟，给足 8 分钟的上限以便在系统重启后自动恢复。
 const SENSEVOICE_READY_TIMEOUT: Duration = Duration::from_secs(8 * 60);
 
+/// 离线待转写队列的检查间隔
+const DEFERRED_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
 enum DispatchMessage {
-    Process(RecordedAudio),
+    /// 录音、处理模式与录音指定的提示词预设 ID
+    Process(RecordedAudio, ProcessingMode, Option<String>),
+    RetryDeferred,
     Shutdown,
 }
 
@@ -26,79 +42,432 @@ pub struct TranscriptionDispatcher {
 impl TranscriptionDispatcher {
     pub fn new(app: AppHandle, store: SettingsStore) -> Self {
         let (sender, receiver) = mpsc::channel::<DispatchMessage>();
-        let dispatcher_app = app.clone();
         let worker = thread::spawn(move || {
             while let Ok(message) = receiver.recv() {
                 match message {
-                    DispatchMessage::Process(recording) => {
-                        // 在真正调用转写引擎前，若当前使用的是 SenseVoice 本地服务，
-                        // 自动检查 Docker 容器/原生模型状态并按需创建/恢复/启动。
-                        // 这样可以在系统重启等情况下自动恢复容器，无需用户手动点击"启动服务"。
-                        if let Err(err) = ensure_sensevoice_runtime_ready(&dispatcher_app, &store) {
-                            #[cfg(debug_assertions)]
-                            eprintln!("SenseVoice 运行时自动恢复失败: {err}");
-                        }
-                        let outcome = processing::handle_recording(&store, recording);
-                        if !outcome.is_success() {
-                            #[cfg(debug_assertions)]
-                            {
-                                if let Some(error_message) = outcome.error_message.as_ref() {
-                                    eprintln!("录音处理失败: {error_message}");
-                                }
-                            }
-                            processing::emit_status("error");
-                        }
-
-                        if !outcome.history_enabled {
-                            continue;
-                        }
-
-                        let item = TranscriptionHistoryItem {
-                            id: create_history_id(),
-                            timestamp_ms: now_timestamp_ms(),
-                            status: if outcome.is_success() {
-                                TranscriptionHistoryStatus::Success
-                            } else {
-                                TranscriptionHistoryStatus::Failed
-                            },
-                            transcription_text: outcome.transcription_text,
-                            final_text: outcome.final_text,
-                            model_group: outcome.model_group,
-                            transcription_elapsed_ms: outcome.transcription_elapsed_ms,
-                            recording_duration_ms: outcome.recording_duration_ms,
-                            triggered: outcome.triggered,
-                            triggered_by_keyword: outcome.triggered_by_keyword,
-                            trigger_matches: outcome.trigger_matches,
-                            alignment: outcome.alignment,
-                            error_message: outcome.error_message,
-                        };
-
-                        if let Err(_err) = store.append_transcription_history(item.clone()) {
-                            #[cfg(debug_assertions)]
-                            eprintln!("写入历史记录失败: {_err}");
-                            continue;
-                        }
-
-                        if let Err(_err) = app.emit("transcription-history-appended", &item) {
-                            #[cfg(debug_assertions)]
-                            eprintln!("发送历史记录事件失败: {_err}");
-                        }
+                    DispatchMessage::Process(recording, mode, prompt_preset) => {
+                        process_recording(&app, &store, recording, mode, prompt_preset);
+                    }
+                    DispatchMessage::RetryDeferred => {
+                        retry_deferred_recordings(&app, &store);
+                        retry_failed_segments(&app, &store);
+                        enforce_storage_quota(&app, &store);
                     }
                     DispatchMessage::Shutdown => break,
                 }
             }
         });
 
+        let retry_sender = sender.clone();
+        thread::spawn(move || loop {
+            thread::sleep(DEFERRED_RETRY_INTERVAL);
+            if retry_sender.send(DispatchMessage::RetryDeferred).is_err() {
+                break;
+            }
+        });
+
         Self {
             sender,
             worker: Some(worker),
         }
     }
 
-    pub fn enqueue(&self, recording: RecordedAudio) -> Result<(), String> {
+    /// 送入一段已结束的录音并返回任务 ID，任务按提交顺序依次转写；录音期间也可提交上一段录音。
+    /// `literal` 为 true 时按字面听写处理，`prompt_preset` 为录音指定的提示词预设
+    pub fn enqueue(
+        &self,
+        recording: RecordedAudio,
+        literal: bool,
+        prompt_preset: Option<String>,
+    ) -> Result<JobId, String> {
+        let job = processing::begin_job();
+        let mode = if literal {
+            ProcessingMode::Literal(job)
+        } else {
+            ProcessingMode::Dictation(job)
+        };
         self.sender
-            .send(DispatchMessage::Process(recording))
-            .map_err(|_| "转写任务线程不可用".to_string())
+            .send(DispatchMessage::Process(recording, mode, prompt_preset))
+            .map_err(|_| {
+                let message = "转写任务线程不可用".to_string();
+                jobs::set_error(job, &message);
+                processing::emit_job_status(job, "error");
+                message
+            })?;
+        Ok(job)
+    }
+}
+
+fn process_recording(
+    app: &AppHandle,
+    store: &SettingsStore,
+    recording: RecordedAudio,
+    mode: ProcessingMode,
+    prompt_preset: Option<String>,
+) {
+    let Some(job) = mode.job() else {
+        return;
+    };
+    // 在真正调用转写引擎前，若当前使用的是 SenseVoice 本地服务，
+    // 自动检查 Docker 容器/原生模型状态并按需创建/恢复/启动。
+    // 这样可以在系统重启等情况下自动恢复容器，无需用户手动点击"启动服务"。
+    if let Err(_err) = ensure_sensevoice_runtime_ready(app, store) {
+        #[cfg(debug_assertions)]
+        eprintln!("SenseVoice 运行时自动恢复失败: {_err}");
+    }
+    let archived = recording_archive::save(store, &recording).unwrap_or_else(|_err| {
+        #[cfg(debug_assertions)]
+        eprintln!("存档原始录音失败: {_err}");
+        None
+    });
+    let outcome = processing::handle_recording(store, recording, mode, prompt_preset.as_deref());
+    if !outcome.is_success() {
+        if let Some(error_message) = outcome.error_message.as_ref() {
+            #[cfg(debug_assertions)]
+            eprintln!("录音处理失败: {error_message}");
+            jobs::set_error(job, error_message);
+        }
+        // 提供商拒绝请求时状态浮窗显示对应的处理建议
+        let status = outcome.failure.map_or("error", ProviderFailure::status);
+        processing::emit_job_status(job, status);
+    }
+
+    // 补转写的结果与存档录音都记录在历史记录中，未开启历史记录时无处回填
+    if !outcome.history_enabled {
+        segment_retry::discard(&outcome.failed_segments);
+        if let Some(path) = &archived {
+            recording_archive::remove(path);
+        }
+        return;
+    }
+
+    let id = outcome.deferred_id.clone().unwrap_or_else(create_history_id);
+    let failed_segments = outcome.failed_segments.clone();
+    let mut item = build_history_item(id, outcome);
+    item.audio_path = archived
+        .as_ref()
+        .map(|path| path.to_string_lossy().into_owned());
+    if let Err(_err) = store.append_transcription_history(item.clone()) {
+        #[cfg(debug_assertions)]
+        eprintln!("写入历史记录失败: {_err}");
+        segment_retry::discard(&failed_segments);
+        if let Some(path) = &archived {
+            recording_archive::remove(path);
+        }
+        return;
+    }
+    if !failed_segments.is_empty() {
+        if let Err(_err) =
+            segment_retry::enqueue(store, &item.id, &failed_segments, now_timestamp_ms())
+        {
+            #[cfg(debug_assertions)]
+            eprintln!("保存失败分段到重试队列失败: {_err}");
+            segment_retry::discard(&failed_segments);
+        }
+    }
+
+    jobs::set_history_id(job, &item.id);
+    emit_history_event(
+        app,
+        store,
+        "transcription-history-appended",
+        &item,
+        Some(job),
+    );
+}
+
+/// 补转写离线期间保存的录音；成功后删除音频文件并更新对应的待处理历史记录，
+/// 失败的录音保留在队列中按退避重试，不阻塞后续录音
+fn retry_deferred_recordings(app: &AppHandle, store: &SettingsStore) {
+    let pending = deferred_queue::pending(store);
+    let now_ms = now_timestamp_ms();
+    if !pending.iter().any(|item| item.is_due(now_ms)) {
+        return;
+    }
+    let Ok(settings) = store.load() else {
+        return;
+    };
+    if !deferred_queue::provider_available(&settings) {
+        return;
+    }
+    if let Err(_err) = ensure_sensevoice_runtime_ready(app, store) {
+        #[cfg(debug_assertions)]
+        eprintln!("SenseVoice 运行时自动恢复失败: {_err}");
+    }
+
+    let scratch_dir = audio_processing::resolve_scratch_dir(&settings.recording.scratch_dir);
+    deferred_queue::retry_due(pending, now_ms, |deferred| {
+        let recording = match deferred_queue::load(store, deferred, &scratch_dir) {
+            Ok(value) => value,
+            Err(_err) => {
+                #[cfg(debug_assertions)]
+                eprintln!("读取待转写录音失败 {}: {_err}", deferred.id);
+                return RetryOutcome::LoadFailed;
+            }
+        };
+        let outcome =
+            processing::handle_recording(store, recording, ProcessingMode::Background, None);
+        if !outcome.is_success() {
+            #[cfg(debug_assertions)]
+            eprintln!(
+                "待转写录音处理失败 {}: {}",
+                deferred.id,
+                outcome.error_message.as_deref().unwrap_or_default()
+            );
+            return RetryOutcome::Failed;
+        }
+
+        if outcome.history_enabled {
+            let mut item = build_history_item(deferred.id.clone(), outcome);
+            item.audio_path = history_audio_path(store, &item.id);
+            match store.replace_transcription_history_item(item.clone()) {
+                Ok(()) => {
+                    emit_history_event(app, store, "transcription-history-updated", &item, None)
+                }
+                Err(_err) => {
+                    #[cfg(debug_assertions)]
+                    eprintln!("更新历史记录失败: {_err}");
+                }
+            }
+        }
+        RetryOutcome::Completed
+    });
+}
+
+/// 以历史记录的存档录音重新转写，结果作为关联原记录的新历史记录追加；
+/// `provider` 为 None 时按当前设置选择提供商
+pub fn retranscribe_history_item(
+    app: &AppHandle,
+    store: &SettingsStore,
+    id: &str,
+    provider: Option<TranscriptionProvider>,
+) -> Result<TranscriptionHistoryItem, String> {
+    let settings = store.load().map_err(|err| err.to_string())?;
+    let existing = store
+        .load_transcription_history()
+        .map_err(|err| err.to_string())?
+        .into_iter()
+        .find(|item| item.id == id)
+        .ok_or_else(|| format!("未找到历史记录: {id}"))?;
+    let audio_path = existing
+        .audio_path
+        .ok_or_else(|| "该历史记录没有存档录音".to_string())?;
+    let scratch_dir = audio_processing::resolve_scratch_dir(&settings.recording.scratch_dir);
+    let recording = recording_archive::load(store, Path::new(&audio_path), &scratch_dir)?;
+    if let Err(_err) = ensure_sensevoice_runtime_ready(app, store) {
+        #[cfg(debug_assertions)]
+        eprintln!("SenseVoice 运行时自动恢复失败: {_err}");
+    }
+    let outcome = processing::handle_recording_with_provider(
+        store,
+        recording,
+        ProcessingMode::Background,
+        None,
+        provider,
+    );
+    segment_retry::discard(&outcome.failed_segments);
+    if !outcome.is_success() {
+        return Err(outcome
+            .error_message
+            .unwrap_or_else(|| "重新转写失败".to_string()));
+    }
+    // 新记录与原记录共用存档录音，删除历史记录不会删除存档
+    let mut item = build_history_item(create_history_id(), outcome);
+    item.audio_path = Some(audio_path);
+    item.source_id = Some(existing.id);
+    store
+        .append_transcription_history(item.clone())
+        .map_err(|err| err.to_string())?;
+    emit_history_event(app, store, "transcription-history-appended", &item, None);
+    Ok(item)
+}
+
+/// 历史记录中已有的存档录音路径，补转写替换记录时保留
+fn history_audio_path(store: &SettingsStore, id: &str) -> Option<String> {
+    store
+        .load_transcription_history()
+        .ok()?
+        .into_iter()
+        .find(|item| item.id == id)?
+        .audio_path
+}
+
+/// 补转写重试队列中到期的失败分段，成功后替换对应历史记录中的占位符；
+/// 同一历史记录的分段按顺序处理，前一段未到期或失败时后续分段留待下一轮
+fn retry_failed_segments(app: &AppHandle, store: &SettingsStore) {
+    let pending = segment_retry::pending(store);
+    let now_ms = now_timestamp_ms();
+    if !pending.iter().any(|item| item.is_due(now_ms)) {
+        return;
+    }
+    let Ok(settings) = store.load() else {
+        return;
+    };
+    if !deferred_queue::provider_available(&settings) {
+        return;
+    }
+    let Ok(mut history) = store.load_transcription_history() else {
+        return;
+    };
+    let placeholder = settings.recording.failed_segment_placeholder.trim();
+    let scratch_dir = audio_processing::resolve_scratch_dir(&settings.recording.scratch_dir);
+    let mut blocked: Vec<String> = Vec::new();
+    // 各历史记录尚未补全的分段，全部补全后记录改为成功
+    let mut outstanding = pending
+        .iter()
+        .map(|segment| segment.history_id.clone())
+        .collect::<Vec<_>>();
+    for segment in pending {
+        if blocked.contains(&segment.history_id) {
+            continue;
+        }
+        // 历史记录已被删除或清空时无处回填
+        let history_id = &segment.history_id;
+        let Some(item) = history.iter_mut().find(|item| &item.id == history_id) else {
+            segment_retry::remove(&segment);
+            continue;
+        };
+        if !segment.is_due(now_ms) {
+            blocked.push(segment.history_id.clone());
+            continue;
+        }
+        let recovered = segment_retry::decrypt(store, &segment, &scratch_dir)
+            .and_then(|audio| processing::transcribe_retained_segment(&settings, audio.path()));
+        let text = match recovered {
+            Ok(value) => value,
+            Err(_err) => {
+                #[cfg(debug_assertions)]
+                eprintln!(
+                    "重试队列分段转写失败 {}#{}: {_err}",
+                    segment.history_id, segment.segment_index
+                );
+                segment_retry::mark_failed(&segment, now_ms);
+                blocked.push(segment.history_id.clone());
+                continue;
+            }
+        };
+        segment_retry::remove(&segment);
+        item.transcription_text =
+            segment_retry::fill_placeholder(&item.transcription_text, placeholder, &text);
+        item.final_text = segment_retry::fill_placeholder(&item.final_text, placeholder, &text);
+        if let Some(position) = outstanding.iter().position(|id| id == &item.id) {
+            outstanding.remove(position);
+        }
+        if !outstanding.contains(&item.id) {
+            item.status = TranscriptionHistoryStatus::Success;
+        }
+        let item = item.clone();
+        if let Err(_err) = store.replace_transcription_history_item(item.clone()) {
+            #[cfg(debug_assertions)]
+            eprintln!("更新历史记录失败: {_err}");
+            continue;
+        }
+        emit_history_event(app, store, "transcription-history-updated", &item, None);
+    }
+}
+
+/// 录音总用量超过配额时删除最旧的队列录音，被删除的离线录音对应的历史记录标记为失败
+fn enforce_storage_quota(app: &AppHandle, store: &SettingsStore) {
+    let Ok(settings) = store.load() else {
+        return;
+    };
+    let eviction = storage_quota::enforce(store, &settings);
+    if eviction.deferred_ids.is_empty() {
+        return;
+    }
+    let Ok(history) = store.load_transcription_history() else {
+        return;
+    };
+    for mut item in history
+        .into_iter()
+        .filter(|item| eviction.deferred_ids.contains(&item.id))
+    {
+        item.status = TranscriptionHistoryStatus::Failed;
+        item.error_message = Some("录音超出存储配额，已被删除".to_string());
+        if let Err(_err) = store.replace_transcription_history_item(item.clone()) {
+            #[cfg(debug_assertions)]
+            eprintln!("更新历史记录失败: {_err}");
+            continue;
+        }
+        emit_history_event(app, store, "transcription-history-updated", &item, None);
+    }
+}
+
+/// 历史记录事件负载，新增记录时附带产生它的转写任务 ID
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HistoryEvent<T> {
+    #[serde(flatten)]
+    item: T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    job_id: Option<JobId>,
+}
+
+/// 按设置发送完整历史记录或摘要，避免长文本占用 IPC
+fn emit_history_event(
+    app: &AppHandle,
+    store: &SettingsStore,
+    event: &str,
+    item: &TranscriptionHistoryItem,
+    job_id: Option<JobId>,
+) {
+    let payload = store
+        .load()
+        .map(|settings| settings.history.event_payload)
+        .unwrap_or_default();
+    let result = match payload {
+        HistoryEventPayload::Full => app.emit(event, HistoryEvent { item, job_id }),
+        HistoryEventPayload::Summary => app.emit(
+            event,
+            HistoryEvent {
+                item: item.summary(),
+                job_id,
+            },
+        ),
+    };
+    if let Err(_err) = result {
+        #[cfg(debug_assertions)]
+        eprintln!("发送历史记录事件失败: {_err}");
+    }
+}
+
+fn build_history_item(id: String, outcome: ProcessingOutcome) -> TranscriptionHistoryItem {
+    let diff = if outcome.triggered && outcome.transcription_text != outcome.final_text {
+        text_diff::diff_words(&outcome.transcription_text, &outcome.final_text)
+    } else {
+        Vec::new()
+    };
+    TranscriptionHistoryItem {
+        id,
+        timestamp_ms: now_timestamp_ms(),
+        status: if outcome.is_deferred() {
+            TranscriptionHistoryStatus::Pending
+        } else if outcome.is_success() && outcome.failed_segment_count > 0 {
+            TranscriptionHistoryStatus::Partial
+        } else if outcome.is_success() {
+            TranscriptionHistoryStatus::Success
+        } else {
+            TranscriptionHistoryStatus::Failed
+        },
+        transcription_text: outcome.transcription_text,
+        final_text: outcome.final_text,
+        model_group: outcome.model_group,
+        transcription_elapsed_ms: outcome.transcription_elapsed_ms,
+        recording_duration_ms: outcome.recording_duration_ms,
+        triggered: outcome.triggered,
+        triggered_by_keyword: outcome.triggered_by_keyword,
+        trigger_matches: outcome.trigger_matches,
+        alignment: outcome.alignment,
+        confidence: outcome.confidence,
+        metadata: outcome.metadata,
+        diff,
+        error_message: outcome.error_message,
+        provider: outcome.provider,
+        model: outcome.model,
+        segment_count: outcome.segment_count,
+        stage_latency: outcome.stage_latency,
+        audio_path: None,
+        source_id: None,
     }
 }
 
@@ -0,0 +1,310 @@
+//! 触发卡片包：从网址安装他人分享的触发卡片
+//!
+//! 卡片包是带签名的 JSON：`payload` 为卡片包正文的 JSON 文本，`sha256` 为正文的十六进制摘要，
+//! `signature` 为发布者以 Ed25519 私钥对正文的签名（Base64），`publicKey` 为对应公钥（Base64）。
+//! 安装时先校验摘要与签名，且公钥必须在“信任的发布者公钥”中，再合并到现有卡片：
+//! 卡片 ID 加上发布者前缀，只会更新同一发布者此前安装的卡片，不会覆盖用户自己的卡片；
+//! 安装的卡片一律关闭自动应用，由用户确认后再开启。
+
+use crate::settings::{Settings, SettingsError, SettingsStore, TriggerCard};
+use base64::{engine::general_purpose, Engine as _};
+use ring::digest::{digest, SHA256};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::time::Duration;
+use thiserror::Error;
+
+/// 卡片包大小上限
+const MAX_PACK_BYTES: u64 = 1024 * 1024;
+/// 安装的卡片 ID 前缀，其后为发布者公钥摘要与卡片包内的原始 ID
+const PACK_ID_PREFIX: &str = "pack";
+/// 卡片 ID 中保留的发布者公钥摘要长度（十六进制字符）
+const PUBLISHER_ID_CHARS: usize = 16;
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Error)]
+pub enum TriggerPackError {
+    #[error("卡片包网址无效: {0}")]
+    InvalidUrl(String),
+    #[error("下载卡片包失败: {0}")]
+    Download(String),
+    #[error("卡片包格式错误: {0}")]
+    Format(String),
+    #[error("卡片包摘要不匹配，内容可能已被篡改")]
+    HashMismatch,
+    #[error("卡片包签名无效")]
+    InvalidSignature,
+    #[error("卡片包发布者不受信任，请先在设置中添加其公钥: {0}")]
+    UntrustedKey(String),
+    #[error(transparent)]
+    Settings(#[from] SettingsError),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignedPack {
+    payload: String,
+    sha256: String,
+    signature: String,
+    public_key: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerPack {
+    pub name: String,
+    pub triggers: Vec<TriggerCard>,
+    /// 发布者公钥摘要，校验签名后填入，用作卡片 ID 的命名空间
+    #[serde(skip)]
+    pub publisher: String,
+}
+
+/// 合并结果
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackMergeSummary {
+    pub added: usize,
+    pub updated: usize,
+    /// 与锁定卡片 ID 冲突而跳过的卡片
+    pub skipped: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledTriggerPack {
+    pub name: String,
+    #[serde(flatten)]
+    pub summary: PackMergeSummary,
+    /// 合并并保存后的设置
+    pub settings: Settings,
+}
+
+/// 下载、校验卡片包并合并保存到设置
+pub fn install(store: &SettingsStore, url: &str) -> Result<InstalledTriggerPack, TriggerPackError> {
+    let bytes = download(url)?;
+    let mut settings = store.load()?;
+    let pack = verify(&bytes, &settings.trigger_packs.trusted_keys)?;
+    let name = pack.name.clone();
+    let summary = merge(&mut settings.triggers, pack);
+    let settings = store.save_user_settings(&settings)?;
+    Ok(InstalledTriggerPack {
+        name,
+        summary,
+        settings,
+    })
+}
+
+/// 下载卡片包原文
+pub fn download(url: &str) -> Result<Vec<u8>, TriggerPackError> {
+    let parsed =
+        url::Url::parse(url.trim()).map_err(|err| TriggerPackError::InvalidUrl(err.to_string()))?;
+    if !matches!(parsed.scheme(), "https" | "http") {
+        return Err(TriggerPackError::InvalidUrl(url.to_string()));
+    }
+    let client = reqwest::blocking::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()
+        .map_err(|err| TriggerPackError::Download(err.to_string()))?;
+    let response = client
+        .get(parsed)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| TriggerPackError::Download(err.to_string()))?;
+    let mut bytes = Vec::new();
+    response
+        .take(MAX_PACK_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|err| TriggerPackError::Download(err.to_string()))?;
+    if bytes.len() as u64 > MAX_PACK_BYTES {
+        return Err(TriggerPackError::Download("卡片包过大".to_string()));
+    }
+    Ok(bytes)
+}
+
+/// 校验摘要、签名与发布者公钥后解析卡片包
+pub fn verify(bytes: &[u8], trusted_keys: &[String]) -> Result<TriggerPack, TriggerPackError> {
+    let signed: SignedPack =
+        serde_json::from_slice(bytes).map_err(|err| TriggerPackError::Format(err.to_string()))?;
+    let payload = signed.payload.as_bytes();
+    let actual = to_hex(digest(&SHA256, payload).as_ref());
+    if !actual.eq_ignore_ascii_case(signed.sha256.trim()) {
+        return Err(TriggerPackError::HashMismatch);
+    }
+
+    let public_key = signed.public_key.trim();
+    if !trusted_keys.iter().any(|key| key.trim() == public_key) {
+        return Err(TriggerPackError::UntrustedKey(public_key.to_string()));
+    }
+    let decode = |value: &str| {
+        general_purpose::STANDARD
+            .decode(value.trim())
+            .map_err(|_| TriggerPackError::InvalidSignature)
+    };
+    let key = decode(public_key)?;
+    let signature = decode(&signed.signature)?;
+    UnparsedPublicKey::new(&ED25519, &key)
+        .verify(payload, &signature)
+        .map_err(|_| TriggerPackError::InvalidSignature)?;
+
+    let mut pack: TriggerPack = serde_json::from_str(&signed.payload)
+        .map_err(|err| TriggerPackError::Format(err.to_string()))?;
+    let mut publisher = to_hex(digest(&SHA256, &key).as_ref());
+    publisher.truncate(PUBLISHER_ID_CHARS);
+    pack.publisher = publisher;
+    Ok(pack)
+}
+
+/// 按带发布者前缀的卡片 ID 合并到现有卡片；安装的卡片均可编辑、删除，且不会自动应用
+pub fn merge(triggers: &mut Vec<TriggerCard>, pack: TriggerPack) -> PackMergeSummary {
+    let mut summary = PackMergeSummary::default();
+    for mut card in pack.triggers {
+        card.id = format!("{PACK_ID_PREFIX}:{}:{}", pack.publisher, card.id);
+        card.locked = false;
+        card.auto_apply = false;
+        match triggers.iter_mut().find(|existing| existing.id == card.id) {
+            Some(existing) if existing.locked => summary.skipped += 1,
+            Some(existing) => {
+                *existing = card;
+                summary.updated += 1;
+            }
+            None => {
+                triggers.push(card);
+                summary.added += 1;
+            }
+        }
+    }
+    summary
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+    use serde_json::json;
+
+    fn card_json(id: &str, keyword: &str) -> serde_json::Value {
+        json!({
+            "id": id,
+            "title": keyword,
+            "enabled": true,
+            "autoApply": false,
+            "locked": true,
+            "keyword": keyword,
+            "promptTemplate": "Rewrite as {value}.",
+            "variables": ["email"],
+        })
+    }
+
+    fn sign(payload: &str) -> (Vec<u8>, String) {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public_key = general_purpose::STANDARD.encode(pair.public_key().as_ref());
+        let signed = json!({
+            "payload": payload,
+            "sha256": to_hex(digest(&SHA256, payload.as_bytes()).as_ref()),
+            "signature": general_purpose::STANDARD.encode(pair.sign(payload.as_bytes()).as_ref()),
+            "publicKey": public_key,
+        });
+        (serde_json::to_vec(&signed).unwrap(), public_key)
+    }
+
+    #[test]
+    fn verify_checks_hash_signature_and_trusted_key() {
+        let payload =
+            json!({ "name": "Mail", "triggers": [card_json("mail", "邮件")] }).to_string();
+        let (bytes, public_key) = sign(&payload);
+        let trusted = vec![public_key];
+
+        let pack = verify(&bytes, &trusted).unwrap();
+        assert_eq!(pack.name, "Mail");
+        assert_eq!(pack.triggers[0].keyword, "邮件");
+        assert!(matches!(
+            verify(&bytes, &[]),
+            Err(TriggerPackError::UntrustedKey(_))
+        ));
+
+        let tampered = String::from_utf8(bytes.clone())
+            .unwrap()
+            .replace("Rewrite", "Delete");
+        assert!(matches!(
+            verify(tampered.as_bytes(), &trusted),
+            Err(TriggerPackError::HashMismatch)
+        ));
+
+        // 摘要随内容一起改写时由签名发现篡改
+        let forged_payload = payload.replace("Rewrite", "Delete");
+        let mut forged: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        forged["sha256"] = json!(to_hex(digest(&SHA256, forged_payload.as_bytes()).as_ref()));
+        forged["payload"] = json!(forged_payload);
+        assert!(matches!(
+            verify(forged.to_string().as_bytes(), &trusted),
+            Err(TriggerPackError::InvalidSignature)
+        ));
+    }
+
+    fn pack(triggers: Vec<serde_json::Value>) -> TriggerPack {
+        let mut pack: TriggerPack =
+            serde_json::from_value(json!({ "name": "Pack", "triggers": triggers })).unwrap();
+        pack.publisher = "0123456789abcdef".to_string();
+        pack
+    }
+
+    #[test]
+    fn merge_namespaces_ids_and_updates_previous_install() {
+        let mut triggers = Settings::default().triggers;
+        let count = triggers.len();
+        let summary = merge(&mut triggers, pack(vec![card_json("mail", "邮件")]));
+        assert_eq!(
+            summary,
+            PackMergeSummary {
+                added: 1,
+                updated: 0,
+                skipped: 0
+            }
+        );
+        assert_eq!(triggers.len(), count + 1);
+        let mail = triggers
+            .iter()
+            .find(|card| card.id == "pack:0123456789abcdef:mail")
+            .unwrap();
+        assert!(!mail.locked);
+
+        assert_eq!(
+            merge(&mut triggers, pack(vec![card_json("mail", "写邮件")])).updated,
+            1
+        );
+        assert_eq!(
+            triggers
+                .iter()
+                .filter(|card| card.id.ends_with(":mail"))
+                .map(|card| card.keyword.as_str())
+                .collect::<Vec<_>>(),
+            vec!["写邮件"]
+        );
+    }
+
+    #[test]
+    fn merge_never_overwrites_user_cards_or_enables_auto_apply() {
+        let mut triggers = Settings::default().triggers;
+        let user_card = triggers[0].clone();
+        let mut colliding = card_json(&user_card.id, "改写");
+        colliding["autoApply"] = json!(true);
+
+        let summary = merge(&mut triggers, pack(vec![colliding]));
+        assert_eq!(summary.added, 1);
+        let original = triggers
+            .iter()
+            .find(|card| card.id == user_card.id)
+            .unwrap();
+        assert_eq!(original.keyword, user_card.keyword);
+        let installed = triggers.last().unwrap();
+        assert_ne!(installed.id, user_card.id);
+        assert!(!installed.auto_apply);
+    }
+}
@@ -1,13 +1,24 @@
-use crate::openai::{self, OpenAiError};
+use crate::active_window::{self, ActiveWindow};
+use crate::dictation_context;
+use crate::language_id;
 use crate::settings::{Settings, TriggerCard, TriggerMatch, TriggerMatchMode};
+use crate::text_backend::{self, TextBackendError};
+use crate::variable_picker;
 use regex::Regex;
 use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 
 const VALUE_PLACEHOLDER: &str = "{value}";
+/// 输出语种不符时的重试次数
+const RESPONSE_LANGUAGE_RETRIES: usize = 1;
 const SENTENCE_DELIMITERS: [char; 12] = [
     ',', '，', '。', '.', '!', '！', '?', '？', ';', '；', ':', '：',
 ];
 
+/// 各卡片上次应用的时间，用于冷却
+static COOLDOWNS: Mutex<Cooldowns> = Mutex::new(Cooldowns::new());
+
 pub struct TriggerResult {
     pub output: String,
     pub triggered: bool,
@@ -37,26 +48,71 @@ impl RegexCache {
     }
 }
 
+struct Cooldowns {
+    applied: Vec<(String, Instant)>,
+}
+
+impl Cooldowns {
+    const fn new() -> Self {
+        Self {
+            applied: Vec::new(),
+        }
+    }
+
+    fn is_cooling(&self, card: &TriggerCard, now: Instant) -> bool {
+        if card.cooldown_seconds == 0 {
+            return false;
+        }
+        let cooldown = Duration::from_secs(u64::from(card.cooldown_seconds));
+        self.applied
+            .iter()
+            .any(|(id, applied_at)| *id == card.id && now.duration_since(*applied_at) < cooldown)
+    }
+
+    fn record(&mut self, card_id: &str, now: Instant) {
+        match self.applied.iter_mut().find(|(id, _)| id == card_id) {
+            Some((_, applied_at)) => *applied_at = now,
+            None => self.applied.push((card_id.to_string(), now)),
+        }
+    }
+}
+
+fn cooldowns() -> MutexGuard<'static, Cooldowns> {
+    COOLDOWNS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
 pub fn apply_triggers(
     settings: &Settings,
     input: &str,
     _log: &dyn Fn(&str),
-) -> Result<TriggerResult, OpenAiError> {
+) -> Result<TriggerResult, TextBackendError> {
     let sentences = split_sentences(input);
     let mut output = input.to_string();
     let mut triggered = false;
     let mut triggered_by_keyword = false;
     let mut trigger_matches = Vec::new();
     let mut cache = RegexCache::new();
+    // 前台窗口仅在有卡片限定应用时探测一次
+    let mut active_window: Option<Option<ActiveWindow>> = None;
+    let routed_ids = routed_trigger_ids(settings, input);
+    let context_instructions = dictation_context::llm_instructions(&settings.context_carryover);
 
     for card in settings.triggers.iter().filter(|card| card.enabled) {
-        let matched = match_card(card, &sentences, &mut cache).or_else(|| {
-            if card.auto_apply {
-                first_non_empty_variable(card).map(|value| (value, false))
-            } else {
-                None
+        // 冷却中的卡片不参与匹配，也不会弹出快速选择
+        if cooldowns().is_cooling(card, Instant::now()) {
+            #[cfg(debug_assertions)]
+            {
+                _log(&format!("触发卡片 {} 冷却中，跳过", card.id));
             }
-        });
+            continue;
+        }
+        let routed = routed_ids.contains(&card.id.as_str());
+        let matched = match match_card(card, &sentences, &mut cache) {
+            // 关键词命中但快速选择被取消时不应用该卡片
+            Some(value) => value.map(|value| (value, true)),
+            None => auto_variable(card, routed, &mut active_window, &mut cache)
+                .map(|value| (value, false)),
+        };
 
         if let Some((value, matched_by_keyword)) = matched {
             #[cfg(debug_assertions)]
@@ -68,11 +124,20 @@ pub fn apply_triggers(
                     value,
                     if matched_by_keyword {
                         "keyword"
+                    } else if routed {
+                        "language"
                     } else {
                         "auto"
                     }
                 ));
             }
+            if card.require_confirmation && !variable_picker::confirm(&card.title, &value) {
+                #[cfg(debug_assertions)]
+                {
+                    _log(&format!("触发卡片 {} 未确认，跳过", card.id));
+                }
+                continue;
+            }
             let cleaned = if matched_by_keyword {
                 remove_trigger_phrase(&output, &card.keyword, &mut cache)
             } else {
@@ -83,14 +148,41 @@ pub fn apply_triggers(
                 .replace("{value}", &value)
                 .replace("{language}", &value)
                 .replace("{style}", &value);
-            let instructions =
-                merge_instructions(&settings.text_processing.openai.instructions, &prompt);
-            output =
-                openai::generate_text(&settings.text_processing.openai, &cleaned, &instructions)?;
+            let backend = text_backend::resolve(&settings.text_processing, &card.text_backend)?;
+            let instructions = merge_instructions(
+                &merge_instructions(backend.default_instructions(), &prompt),
+                &context_instructions,
+            );
+            let response_language = card.response_language.trim();
+            let instructions = merge_instructions(
+                &instructions,
+                &response_language_instructions(response_language),
+            );
+            output = backend.generate(&cleaned, &instructions)?;
+            for _ in 0..RESPONSE_LANGUAGE_RETRIES {
+                if !response_language_mismatch(response_language, &output) {
+                    break;
+                }
+                #[cfg(debug_assertions)]
+                {
+                    _log(&format!(
+                        "触发卡片 {} 输出语种不符（要求 {}），重试",
+                        card.id, response_language
+                    ));
+                }
+                let retry_instructions = merge_instructions(
+                    &instructions,
+                    &format!(
+                        "上一次的回复使用了错误的语种。只能使用语种代码 {response_language} 对应的语言输出。"
+                    ),
+                );
+                output = backend.generate(&cleaned, &retry_instructions)?;
+            }
             #[cfg(debug_assertions)]
             {
                 _log(&format!("触发卡片 {} 结果: {}", card.id, output));
             }
+            cooldowns().record(&card.id, Instant::now());
             if matched_by_keyword {
                 triggered_by_keyword = true;
             }
@@ -101,6 +193,8 @@ pub fn apply_triggers(
                 matched_value: value,
                 mode: if matched_by_keyword {
                     TriggerMatchMode::Keyword
+                } else if routed {
+                    TriggerMatchMode::Language
                 } else {
                     TriggerMatchMode::Auto
                 },
@@ -117,6 +211,23 @@ pub fn apply_triggers(
     })
 }
 
+/// 按识别出的语种查找路由规则，命中的卡片视同自动应用
+fn routed_trigger_ids<'a>(settings: &'a Settings, input: &str) -> Vec<&'a str> {
+    if !settings.language_routing.enabled {
+        return Vec::new();
+    }
+    let Some(language) = language_id::detect(input) else {
+        return Vec::new();
+    };
+    settings
+        .language_routing
+        .rules
+        .iter()
+        .filter(|rule| language_id::matches(&rule.language, language))
+        .map(|rule| rule.trigger_id.as_str())
+        .collect()
+}
+
 fn split_sentences(input: &str) -> Vec<String> {
     input
         .split(SENTENCE_DELIMITERS)
@@ -125,15 +236,49 @@ fn split_sentences(input: &str) -> Vec<String> {
         .collect()
 }
 
+/// 关键词命中时返回 `Some`，内层为使用的变量值，快速选择被取消时为 None
 fn match_card(
     card: &TriggerCard,
     sentences: &[String],
     cache: &mut RegexCache,
-) -> Option<(String, bool)> {
+) -> Option<Option<String>> {
     let sentence = find_keyword_sentence(card, sentences, cache)?;
-    let value = match_variable_in_sentence(sentence, &card.variables)
-        .or_else(|| first_non_empty_variable(card))?;
-    Some((value, true))
+    Some(match_variable_in_sentence(sentence, &card.variables).or_else(|| missing_variable(card)))
+}
+
+/// 未命中关键词时，自动应用或按语种路由的卡片使用第一个变量
+fn auto_variable(
+    card: &TriggerCard,
+    routed: bool,
+    active_window: &mut Option<Option<ActiveWindow>>,
+    cache: &mut RegexCache,
+) -> Option<String> {
+    if !card.auto_apply && !routed {
+        return None;
+    }
+    let pattern = card.active_app_pattern.trim();
+    if !pattern.is_empty() {
+        let window = active_window.get_or_insert_with(active_window::detect);
+        if !app_pattern_matches(pattern, window.as_ref(), cache) {
+            return None;
+        }
+    }
+    first_non_empty_variable(card)
+}
+
+/// 口述中没有提到变量值：开启快速选择且有多个变量时由用户选择，否则使用第一个变量
+fn missing_variable(card: &TriggerCard) -> Option<String> {
+    let variables = card
+        .variables
+        .iter()
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if card.pick_variable && variables.len() > 1 {
+        return variable_picker::pick(&card.title, &variables);
+    }
+    variables.into_iter().next()
 }
 
 fn find_keyword_sentence<'a>(
@@ -189,6 +334,21 @@ fn match_variable_in_sentence(sentence: &str, variables: &[String]) -> Option<St
         .map(|(_, _, value)| value)
 }
 
+/// 应用匹配规则为忽略大小写的正则，匹配应用名或窗口标题任一即可
+fn app_pattern_matches(
+    pattern: &str,
+    window: Option<&ActiveWindow>,
+    cache: &mut RegexCache,
+) -> bool {
+    let Some(window) = window else {
+        return false;
+    };
+    let Some(regex) = cache.get_or_compile(&format!("(?i){pattern}")) else {
+        return false;
+    };
+    regex.is_match(&window.app_name) || regex.is_match(&window.title)
+}
+
 fn first_non_empty_variable(card: &TriggerCard) -> Option<String> {
     card.variables
         .iter()
@@ -290,6 +450,22 @@ fn normalize_match_char(ch: char) -> Option<char> {
     }
 }
 
+/// 追加到指令末尾的输出语种要求，未配置时为空
+fn response_language_instructions(language: &str) -> String {
+    if language.is_empty() {
+        return String::new();
+    }
+    format!("无论输入是什么语种，都必须使用语种代码 {language} 对应的语言输出结果。")
+}
+
+/// 输出语种可识别且与要求不符时返回 true；无法识别的语种不做校验
+fn response_language_mismatch(language: &str, output: &str) -> bool {
+    if language.is_empty() || !language_id::is_detectable(language) {
+        return false;
+    }
+    language_id::detect(output).is_some_and(|detected| !language_id::matches(language, detected))
+}
+
 fn merge_instructions(base: &str, extra: &str) -> String {
     if base.trim().is_empty() {
         return extra.to_string();
@@ -303,6 +479,7 @@ fn merge_instructions(base: &str, extra: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::settings::LanguageRoute;
 
     fn build_card(keyword: &str, variables: &[&str]) -> TriggerCard {
         TriggerCard {
@@ -314,6 +491,12 @@ mod tests {
             keyword: keyword.to_string(),
             prompt_template: "template {value}".to_string(),
             variables: variables.iter().map(|value| value.to_string()).collect(),
+            active_app_pattern: String::new(),
+            text_backend: String::new(),
+            response_language: String::new(),
+            pick_variable: false,
+            cooldown_seconds: 0,
+            require_confirmation: false,
         }
     }
 
@@ -348,7 +531,36 @@ mod tests {
         let sentences = split_sentences("请帮我润色一下");
         let mut cache = RegexCache::new();
         let matched = match_card(&card, &sentences, &mut cache);
-        assert_eq!(matched, Some(("口语".to_string(), true)));
+        assert_eq!(matched, Some(Some("口语".to_string())));
+    }
+
+    #[test]
+    fn pick_variable_uses_the_only_variable_without_asking() {
+        let mut card = build_card("润色", &["口语", " "]);
+        card.pick_variable = true;
+        let sentences = split_sentences("请帮我润色一下");
+        let mut cache = RegexCache::new();
+        let matched = match_card(&card, &sentences, &mut cache);
+        assert_eq!(matched, Some(Some("口语".to_string())));
+    }
+
+    #[test]
+    fn cooldown_blocks_card_until_elapsed() {
+        let mut card = build_card("润色", &["口语"]);
+        let mut cooldowns = Cooldowns::new();
+        let start = Instant::now();
+        cooldowns.record(&card.id, start);
+        assert!(!cooldowns.is_cooling(&card, start));
+
+        card.cooldown_seconds = 30;
+        assert!(cooldowns.is_cooling(&card, start + Duration::from_secs(29)));
+        assert!(!cooldowns.is_cooling(&card, start + Duration::from_secs(30)));
+
+        let other = TriggerCard {
+            id: "other".to_string(),
+            ..card.clone()
+        };
+        assert!(!cooldowns.is_cooling(&other, start));
     }
 
     #[test]
@@ -357,6 +569,56 @@ mod tests {
         let sentences = split_sentences("帮我翻译为日文");
         let mut cache = RegexCache::new();
         let matched = match_card(&card, &sentences, &mut cache);
-        assert_eq!(matched, Some(("日文".to_string(), true)));
+        assert_eq!(matched, Some(Some("日文".to_string())));
+    }
+
+    #[test]
+    fn app_pattern_matches_app_name_or_title_case_insensitively() {
+        let window = ActiveWindow {
+            app_name: "slack.exe".to_string(),
+            title: "General - Acme".to_string(),
+        };
+        let mut cache = RegexCache::new();
+        assert!(app_pattern_matches("^Slack", Some(&window), &mut cache));
+        assert!(app_pattern_matches("acme", Some(&window), &mut cache));
+        assert!(!app_pattern_matches("outlook", Some(&window), &mut cache));
+        assert!(!app_pattern_matches("slack", None, &mut cache));
+    }
+
+    #[test]
+    fn language_routes_select_cards_for_detected_language() {
+        let mut settings = Settings::default();
+        settings.language_routing.enabled = true;
+        settings.language_routing.rules = vec![
+            LanguageRoute {
+                language: "zh".to_string(),
+                trigger_id: "polish".to_string(),
+            },
+            LanguageRoute {
+                language: "en".to_string(),
+                trigger_id: "grammar".to_string(),
+            },
+        ];
+        assert_eq!(
+            routed_trigger_ids(&settings, "今天下午开会"),
+            vec!["polish"]
+        );
+        assert_eq!(
+            routed_trigger_ids(&settings, "see you tomorrow"),
+            vec!["grammar"]
+        );
+
+        settings.language_routing.enabled = false;
+        assert!(routed_trigger_ids(&settings, "今天下午开会").is_empty());
+    }
+
+    #[test]
+    fn response_language_mismatch_only_checks_detectable_languages() {
+        assert!(response_language_mismatch("zh-CN", "See you tomorrow"));
+        assert!(!response_language_mismatch("zh-CN", "明天见"));
+        assert!(!response_language_mismatch("", "See you tomorrow"));
+        // 无法识别的语种不做校验，避免误判后无意义地重试
+        assert!(!response_language_mismatch("fr", "À demain"));
+        assert!(!response_language_mismatch("en", "123"));
     }
 }
@@ -0,0 +1,221 @@
+//! 最终文本朗读（TTS）
+//!
+//! 粘贴完成后朗读结果，便于不看屏幕时确认听写内容。系统语音调用各平台自带命令，
+//! OpenAI 语音合成返回 WAV 后同样交给系统播放器，均不引入额外的音频依赖。
+
+use crate::settings::{OpenAiSettings, TtsProvider, TtsSettings};
+use reqwest::blocking::Client;
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use thiserror::Error;
+
+/// OpenAI 语音合成未指定音色时使用的默认音色
+const DEFAULT_OPENAI_VOICE: &str = "alloy";
+
+#[derive(Debug, Error)]
+pub enum TtsError {
+    #[error("语音合成请求失败: {0}")]
+    Request(String),
+    #[error("语音合成配置缺失: {0}")]
+    Config(String),
+    #[error("语音播放失败: {0}")]
+    Playback(String),
+}
+
+#[derive(Serialize)]
+struct SpeechRequest<'a> {
+    model: &'a str,
+    input: &'a str,
+    voice: &'a str,
+    response_format: &'a str,
+}
+
+/// 在后台线程朗读文本，失败仅记录日志，不影响听写流程
+pub fn speak_in_background(settings: &TtsSettings, openai: &OpenAiSettings, text: &str) {
+    if !settings.enabled || text.trim().is_empty() {
+        return;
+    }
+    let settings = settings.clone();
+    let openai = openai.clone();
+    let text = text.to_string();
+    thread::spawn(move || {
+        if let Err(_err) = speak(&settings, &openai, &text) {
+            #[cfg(debug_assertions)]
+            eprintln!("[tts] 朗读失败: {_err}");
+        }
+    });
+}
+
+/// 按设置的提供商朗读文本，阻塞至播放结束
+pub fn speak(settings: &TtsSettings, openai: &OpenAiSettings, text: &str) -> Result<(), TtsError> {
+    match settings.provider {
+        TtsProvider::Platform => speak_platform(text, settings.voice.trim()),
+        TtsProvider::Openai => speak_openai(settings, openai, text),
+    }
+}
+
+fn speak_openai(
+    settings: &TtsSettings,
+    openai: &OpenAiSettings,
+    text: &str,
+) -> Result<(), TtsError> {
+    if openai.api_key.trim().is_empty() {
+        return Err(TtsError::Config("API Key 不能为空".to_string()));
+    }
+    let voice = match settings.voice.trim() {
+        "" => DEFAULT_OPENAI_VOICE,
+        voice => voice,
+    };
+    let request = SpeechRequest {
+        model: settings.openai_model.trim(),
+        input: text,
+        voice,
+        response_format: "wav",
+    };
+    let url = format!("{}/audio/speech", openai.api_base.trim_end_matches('/'));
+    let response = Client::new()
+        .post(url)
+        .bearer_auth(openai.api_key.trim())
+        .json(&request)
+        .send()
+        .map_err(|err| TtsError::Request(err.to_string()))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        return Err(TtsError::Request(format!("{status}: {body}")));
+    }
+    let bytes = response
+        .bytes()
+        .map_err(|err| TtsError::Request(err.to_string()))?;
+    let path = std::env::temp_dir().join("vtt-keyboard-tts.wav");
+    fs::write(&path, &bytes).map_err(|err| TtsError::Playback(err.to_string()))?;
+    let result = play_wav(&path);
+    let _ = fs::remove_file(&path);
+    result
+}
+
+#[cfg(target_os = "windows")]
+fn speak_platform(text: &str, voice: &str) -> Result<(), TtsError> {
+    let select_voice = if voice.is_empty() {
+        String::new()
+    } else {
+        format!("$s.SelectVoice({});", powershell_literal(voice))
+    };
+    // 文本经标准输入传入，避免命令行转义与长度限制
+    let script = format!(
+        "Add-Type -AssemblyName System.Speech;\
+         $s = New-Object System.Speech.Synthesis.SpeechSynthesizer;\
+         {select_voice}$s.Speak([Console]::In.ReadToEnd())"
+    );
+    run_with_stdin(
+        Command::new("powershell").args(["-NoProfile", "-Command", &script]),
+        text,
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn speak_platform(text: &str, voice: &str) -> Result<(), TtsError> {
+    let mut command = Command::new("say");
+    if !voice.is_empty() {
+        command.args(["-v", voice]);
+    }
+    // `say -f -` 从标准输入读取文本
+    run_with_stdin(command.args(["-f", "-"]), text)
+}
+
+#[cfg(target_os = "linux")]
+fn speak_platform(text: &str, voice: &str) -> Result<(), TtsError> {
+    let mut command = Command::new("spd-say");
+    command.arg("-w");
+    if !voice.is_empty() {
+        command.args(["-y", voice]);
+    }
+    match run_with_stdin(command.arg("-e"), text) {
+        Ok(()) => Ok(()),
+        // 未安装 speech-dispatcher 时退回 espeak
+        Err(_) => run_with_stdin(Command::new("espeak").arg("--stdin"), text),
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn speak_platform(_text: &str, _voice: &str) -> Result<(), TtsError> {
+    Err(TtsError::Playback("当前平台不支持系统语音".to_string()))
+}
+
+#[cfg(target_os = "windows")]
+fn play_wav(path: &Path) -> Result<(), TtsError> {
+    let script = format!(
+        "(New-Object Media.SoundPlayer {}).PlaySync()",
+        powershell_literal(&path.to_string_lossy())
+    );
+    run_with_stdin(
+        Command::new("powershell").args(["-NoProfile", "-Command", &script]),
+        "",
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn play_wav(path: &Path) -> Result<(), TtsError> {
+    run_with_stdin(Command::new("afplay").arg(path), "")
+}
+
+#[cfg(target_os = "linux")]
+fn play_wav(path: &Path) -> Result<(), TtsError> {
+    run_with_stdin(Command::new("paplay").arg(path), "")
+        .or_else(|_| run_with_stdin(Command::new("aplay").arg(path), ""))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn play_wav(_path: &Path) -> Result<(), TtsError> {
+    Err(TtsError::Playback("当前平台不支持音频播放".to_string()))
+}
+
+/// 运行命令并写入标准输入，等待其退出
+fn run_with_stdin(command: &mut Command, input: &str) -> Result<(), TtsError> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(0x0800_0000);
+    }
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| TtsError::Playback(err.to_string()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|err| TtsError::Playback(err.to_string()))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|err| TtsError::Playback(err.to_string()))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(TtsError::Playback(
+        String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    ))
+}
+
+/// PowerShell 单引号字符串字面量，内部单引号需成对转义
+#[cfg_attr(not(any(target_os = "windows", test)), allow(dead_code))]
+fn powershell_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn powershell_literal_escapes_single_quotes() {
+        assert_eq!(powershell_literal("Microsoft Zira"), "'Microsoft Zira'");
+        assert_eq!(powershell_literal("it's"), "'it''s'");
+    }
+}
@@ -1,10 +1,16 @@
-use crate::{settings::SettingsStore, AppState};
+use crate::settings::{SettingsStore, UpdateChannel};
+use crate::AppState;
 use serde::Serialize;
+use serde_json::Value;
 use std::fmt::{Debug, Display};
 use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_updater::{Update, UpdaterExt};
+use url::Url;
 
 const UPDATE_STATUS_EVENT: &str = "update-status-changed";
+/// beta 通道的更新清单；stable 通道使用 tauri.conf.json 中配置的端点
+const BETA_UPDATE_ENDPOINT: &str =
+    "https://github.com/youtonghy/VTT-keyboard/releases/download/beta/latest.json";
 
 #[derive(Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -168,6 +174,22 @@ fn update_metadata(update: &Update) -> (Option<String>, Option<String>) {
     (notes, pub_date)
 }
 
+fn update_channel(store: &SettingsStore) -> UpdateChannel {
+    store
+        .load()
+        .map(|settings| settings.startup.update_channel)
+        .unwrap_or_default()
+}
+
+/// 清单中的 `rollout`（0-100）为分阶段发布比例，本机分桶落在比例内才提示更新；
+/// 未声明时视为全量发布
+fn rollout_allows(manifest: &Value, bucket: u8) -> bool {
+    manifest
+        .get("rollout")
+        .and_then(|value| value.as_f64())
+        .is_none_or(|percent| f64::from(bucket) < percent)
+}
+
 fn should_auto_check(store: &SettingsStore) -> bool {
     store
         .load()
@@ -248,8 +270,14 @@ async fn check_for_updates(
     // callback runs just before exit(0), giving us a chance to do critical cleanup.
     let app_for_exit = app.clone();
     let store_for_exit = store.clone();
-    let update = app
-        .updater_builder()
+    let mut builder = app.updater_builder();
+    if update_channel(&store) == UpdateChannel::Beta {
+        let endpoint = Url::parse(BETA_UPDATE_ENDPOINT).map_err(|err| err.to_string())?;
+        builder = builder
+            .endpoints(vec![endpoint])
+            .map_err(|err| stringify_update_error(&app, "set_update_endpoint", err))?;
+    }
+    let update = builder
         .on_before_exit(move || {
             app_for_exit.cleanup_before_exit();
             let _ = store_for_exit.save_deferred_update_version(None);
@@ -264,6 +292,9 @@ async fn check_for_updates(
         .check()
         .await
         .map_err(|err| stringify_update_error(&app, "check_remote_update", err))?;
+    // 手动检查不受分阶段发布限制
+    let rollout_bucket = store.load_or_create_rollout_bucket().unwrap_or(0);
+    let update = update.filter(|update| force || rollout_allows(&update.raw_json, rollout_bucket));
 
     match update {
         Some(update) => {
@@ -459,6 +490,28 @@ pub fn install_downloaded_update(app: &AppHandle) -> Result<(), String> {
     }
 }
 
+/// 安装更新：已下载时直接安装，仅检测到更新时先下载再安装
+pub fn install_update(app: &AppHandle) -> Result<(), String> {
+    let (downloaded, pending) = with_manager(app, |manager| {
+        (
+            manager.downloaded_package.is_some(),
+            manager.pending_update.is_some(),
+        )
+    })?;
+    if downloaded {
+        let state = app.state::<AppState>();
+        if let Ok(mut manager) = state.sensevoice_manager.lock() {
+            manager.pause_runtime_for_exit(app);
+        }
+        return install_downloaded_update(app);
+    }
+    if !pending {
+        return Err("当前没有可安装的更新".to_string());
+    }
+    download_pending_update(app.clone(), true);
+    Ok(())
+}
+
 pub fn handle_settings_changed(app: AppHandle, store: SettingsStore) {
     if !should_auto_check(&store) {
         if let Ok(status) = with_manager(&app, |manager| {
@@ -490,3 +543,17 @@ pub fn maybe_install_on_quit(app: &AppHandle, store: &SettingsStore) -> Result<b
     install_downloaded_update(app)?;
     Ok(true)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn rollout_allows_buckets_below_percentage() {
+        assert!(rollout_allows(&json!({ "version": "1.0.0" }), 99));
+        assert!(rollout_allows(&json!({ "rollout": 25 }), 24));
+        assert!(!rollout_allows(&json!({ "rollout": 25 }), 25));
+        assert!(!rollout_allows(&json!({ "rollout": 0 }), 0));
+    }
+}
@@ -0,0 +1,121 @@
+//! 触发卡片变量快速选择与应用确认窗口
+//!
+//! 关键词命中但口述中没有提到变量值时，开启快速选择的卡片会弹出置顶的小窗口列出各变量，
+//! 由用户点选或按数字键选择，而不是直接使用第一个变量；要求确认的卡片在应用前用同一窗口
+//! 询问是否应用。处理线程阻塞等待结果，取消或超时则不应用该卡片。
+//! 窗口与主界面共用前端入口，按窗口标签渲染。
+
+use crate::events;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+
+pub const VARIABLE_PICKER_WINDOW_LABEL: &str = "variable-picker";
+/// 窗口已打开时推送新的选择请求
+pub const VARIABLE_PICK_EVENT: &str = "variable-pick";
+/// 等待用户选择的最长时间
+const PICK_TIMEOUT: Duration = Duration::from_secs(15);
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+static PENDING: Mutex<Option<(VariablePick, Sender<Option<usize>>)>> = Mutex::new(None);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariablePick {
+    pub id: u64,
+    /// 触发卡片标题
+    pub title: String,
+    pub variables: Vec<String>,
+    /// 确认应用：`variables` 只有将要使用的变量值，选择它即为确认
+    pub confirm: bool,
+}
+
+/// 显示选择窗口并等待选择结果；取消、超时或窗口无法显示时返回 None
+pub fn pick(title: &str, variables: &[String]) -> Option<String> {
+    let index = request(title, variables.to_vec(), false)?;
+    variables.get(index).cloned()
+}
+
+/// 询问是否以 `value` 应用卡片；取消、超时或窗口无法显示时视为不应用
+pub fn confirm(title: &str, value: &str) -> bool {
+    request(title, vec![value.to_string()], true) == Some(0)
+}
+
+fn request(title: &str, variables: Vec<String>, confirm: bool) -> Option<usize> {
+    let app = events::app_handle()?;
+    let request = VariablePick {
+        id: NEXT_ID.fetch_add(1, Ordering::SeqCst) + 1,
+        title: title.to_string(),
+        variables,
+        confirm,
+    };
+    let (sender, receiver) = mpsc::channel();
+    // 覆盖尚未完成的请求时丢弃其发送端，之前的等待随即以取消结束
+    *PENDING.lock().unwrap_or_else(|e| e.into_inner()) = Some((request.clone(), sender));
+
+    if let Err(_err) = show(app, &request) {
+        #[cfg(debug_assertions)]
+        eprintln!("[variable-picker] 显示窗口失败: {_err}");
+        take_pending(app, request.id);
+        return None;
+    }
+    let index = receiver.recv_timeout(PICK_TIMEOUT).ok().flatten();
+    take_pending(app, request.id);
+    index
+}
+
+fn show(app: &AppHandle, request: &VariablePick) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(VARIABLE_PICKER_WINDOW_LABEL) {
+        let _ = app.emit_to(VARIABLE_PICKER_WINDOW_LABEL, VARIABLE_PICK_EVENT, request);
+        window.show().map_err(|err| err.to_string())?;
+        window.set_focus().map_err(|err| err.to_string())?;
+        return Ok(());
+    }
+    WebviewWindowBuilder::new(app, VARIABLE_PICKER_WINDOW_LABEL, WebviewUrl::default())
+        .title("vtt-keyboard")
+        .inner_size(320.0, 260.0)
+        .always_on_top(true)
+        .decorations(false)
+        .skip_taskbar(true)
+        .resizable(false)
+        .focused(true)
+        .build()
+        .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+pub fn pending() -> Option<VariablePick> {
+    PENDING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|(request, _)| request.clone())
+}
+
+/// 提交选择结果，`index` 为 None 表示取消
+pub fn resolve(id: u64, index: Option<usize>) {
+    let pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((request, sender)) = pending.as_ref() {
+        if request.id == id {
+            let _ = sender.send(index);
+        }
+    }
+}
+
+/// 清除对应的请求；没有新的请求等待时隐藏窗口
+fn take_pending(app: &AppHandle, id: u64) {
+    let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+    if pending
+        .as_ref()
+        .is_some_and(|(request, _)| request.id != id)
+    {
+        return;
+    }
+    *pending = None;
+    if let Some(window) = app.get_webview_window(VARIABLE_PICKER_WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+}
@@ -4,12 +4,16 @@
 //! - 录音文件识别 (HTTP POST)
 //! - 流式识别 (WebSocket)
 
+use crate::audio_inspect::{self, AudioFormat};
+use crate::audio_pipeline;
+use crate::provider_error::ProviderFailure;
 use crate::settings::{Settings, VolcengineSettings};
 use base64::{engine::general_purpose, Engine as _};
 use hound::WavReader;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::fs;
+use std::ops::Range;
 use std::path::Path;
 use thiserror::Error;
 use tungstenite::{connect, Message};
@@ -39,6 +43,8 @@ pub enum VolcengineError {
     Io(String),
     #[error("WebSocket 错误: {0}")]
     WebSocket(String),
+    #[error("火山引擎拒绝请求: {0}")]
+    Rejected(#[from] ProviderFailure),
 }
 
 /// 录音文件识别请求
@@ -67,6 +73,9 @@ struct UserInfo {
 struct AudioInfo {
     data: String,
     format: String,
+    /// 容器内的编码，Ogg 封装时为 `opus`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    codec: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     rate: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -78,6 +87,11 @@ struct RequestInfo {
     sequence: i32,
     #[serde(skip_serializing_if = "Option::is_none")]
     version: Option<String>,
+    workflow: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    boosting_table_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    corpus: Option<Value>,
 }
 
 /// 录音文件识别响应
@@ -85,8 +99,14 @@ struct RequestInfo {
 struct FileAsrResponse {
     code: i32,
     message: String,
+    /// 旧版接口为字符串，新版为 `[{ text, confidence, utterances }]` 数组
     #[serde(default)]
-    result: Option<String>,
+    result: Option<Value>,
+}
+
+pub struct VolcengineTranscription {
+    pub text: String,
+    pub confidence: Option<f32>,
 }
 
 #[derive(Clone, Copy)]
@@ -98,19 +118,26 @@ struct AudioMetadata {
 
 /// 转写音频文件
 ///
-/// 根据设置选择使用录音文件识别或流式识别
-pub fn transcribe_audio(settings: &Settings, audio_path: &Path) -> Result<String, VolcengineError> {
+/// 根据设置选择使用录音文件识别或流式识别；流式识别时通过 `on_partial` 回调中间结果
+pub fn transcribe_audio(
+    settings: &Settings,
+    audio_path: &Path,
+    on_partial: &mut dyn FnMut(&str, bool),
+) -> Result<VolcengineTranscription, VolcengineError> {
     ensure_config(&settings.volcengine)?;
 
     if settings.volcengine.use_streaming {
-        transcribe_streaming(settings, audio_path)
+        transcribe_streaming(settings, audio_path, on_partial)
     } else {
         transcribe_file(settings, audio_path)
     }
 }
 
 /// 录音文件识别 (HTTP POST)
-fn transcribe_file(settings: &Settings, audio_path: &Path) -> Result<String, VolcengineError> {
+fn transcribe_file(
+    settings: &Settings,
+    audio_path: &Path,
+) -> Result<VolcengineTranscription, VolcengineError> {
     let file_bytes = fs::read(audio_path).map_err(|e| VolcengineError::Io(e.to_string()))?;
     let audio_base64 = general_purpose::STANDARD.encode(&file_bytes);
 
@@ -127,6 +154,7 @@ fn transcribe_file(settings: &Settings, audio_path: &Path) -> Result<String, Vol
         },
         audio: AudioInfo {
             data: audio_base64,
+            codec: (audio_format == AudioFormat::Ogg.extension()).then(|| "opus".to_string()),
             format: audio_format,
             rate: audio_meta.map(|meta| meta.sample_rate),
             language: Some(settings.volcengine.language.clone()),
@@ -138,6 +166,9 @@ fn transcribe_file(settings: &Settings, audio_path: &Path) -> Result<String, Vol
             } else {
                 None
             },
+            workflow: build_workflow(&settings.volcengine),
+            boosting_table_name: boosting_table_name(&settings.volcengine),
+            corpus: build_corpus(&settings.volcengine),
         },
     };
 
@@ -156,6 +187,9 @@ fn transcribe_file(settings: &Settings, audio_path: &Path) -> Result<String, Vol
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().unwrap_or_default();
+        if let Some(failure) = ProviderFailure::classify(status.as_u16(), &body) {
+            return Err(failure.into());
+        }
         return Err(VolcengineError::Request(format!("{}: {}", status, body)));
     }
 
@@ -173,11 +207,22 @@ fn transcribe_file(settings: &Settings, audio_path: &Path) -> Result<String, Vol
         )));
     }
 
-    Ok(asr_response.result.unwrap_or_default())
+    Ok(asr_response
+        .result
+        .as_ref()
+        .map(parse_result)
+        .unwrap_or(VolcengineTranscription {
+            text: String::new(),
+            confidence: None,
+        }))
 }
 
 /// 流式识别 (WebSocket)
-fn transcribe_streaming(settings: &Settings, audio_path: &Path) -> Result<String, VolcengineError> {
+fn transcribe_streaming(
+    settings: &Settings,
+    audio_path: &Path,
+    on_partial: &mut dyn FnMut(&str, bool),
+) -> Result<VolcengineTranscription, VolcengineError> {
     let file_bytes = fs::read(audio_path).map_err(|e| VolcengineError::Io(e.to_string()))?;
     let (audio_format, audio_meta) = detect_audio_info(audio_path);
 
@@ -210,27 +255,24 @@ fn transcribe_streaming(settings: &Settings, audio_path: &Path) -> Result<String
         }
     }
 
-    // 分块发送音频数据
-    let chunk_size = compute_chunk_size(audio_meta);
-    let mut offset = 0;
-    let mut sequence = 1;
-
-    while offset < file_bytes.len() {
-        let end = (offset + chunk_size).min(file_bytes.len());
-        let chunk = &file_bytes[offset..end];
-        let is_last = end >= file_bytes.len();
-
-        let audio_msg = build_audio_message(chunk, sequence, is_last);
+    // 分块发送音频数据，PCM 数据在静音处分块
+    let samples = read_pcm_samples(audio_path, audio_meta);
+    let ranges = stream_chunk_ranges(file_bytes.len(), samples.as_deref(), audio_meta);
+    let chunk_count = ranges.len();
+    for (index, range) in ranges.into_iter().enumerate() {
+        let sequence = index as i32 + 1;
+        let is_last = index + 1 == chunk_count;
+        let audio_msg = build_audio_message(&file_bytes[range], sequence, is_last);
         socket
             .send(Message::Text(audio_msg))
             .map_err(|e| VolcengineError::WebSocket(e.to_string()))?;
-
-        offset = end;
-        sequence += 1;
     }
 
     // 收集识别结果
-    let mut final_text = String::new();
+    let mut final_result = VolcengineTranscription {
+        text: String::new(),
+        confidence: None,
+    };
     loop {
         let msg = socket
             .read()
@@ -252,17 +294,21 @@ fn transcribe_streaming(settings: &Settings, audio_path: &Path) -> Result<String
                     }
                 }
 
-                // 提取识别结果
-                if let Some(result) = resp.get("result").and_then(|v| v.as_str()) {
-                    final_text = result.to_string();
-                }
-
-                // 检查是否结束
-                if resp
+                let is_last = resp
                     .get("is_last")
                     .and_then(|v| v.as_bool())
-                    .unwrap_or(false)
-                {
+                    .unwrap_or(false);
+
+                // 提取识别结果，文本有变化时推送中间结果
+                if let Some(result) = resp.get("result") {
+                    let parsed = parse_result(result);
+                    if !parsed.text.is_empty() && parsed.text != final_result.text {
+                        on_partial(&parsed.text, is_last);
+                    }
+                    final_result = parsed;
+                }
+
+                if is_last {
                     break;
                 }
             }
@@ -272,7 +318,33 @@ fn transcribe_streaming(settings: &Settings, audio_path: &Path) -> Result<String
     }
 
     let _ = socket.close(None);
-    Ok(final_text)
+    Ok(final_result)
+}
+
+/// 解析识别结果，兼容字符串与 `[{ text, confidence }]` 两种格式。
+/// 服务端未计算置信度时返回 0，此时视为不可用。
+fn parse_result(result: &Value) -> VolcengineTranscription {
+    if let Some(text) = result.as_str() {
+        return VolcengineTranscription {
+            text: text.to_string(),
+            confidence: None,
+        };
+    }
+    let item = result
+        .as_array()
+        .and_then(|items| items.first())
+        .unwrap_or(result);
+    let text = item
+        .get("text")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let confidence = item
+        .get("confidence")
+        .and_then(|v| v.as_f64())
+        .filter(|value| *value > 0.0)
+        .map(|value| value.clamp(0.0, 1.0) as f32);
+    VolcengineTranscription { text, confidence }
 }
 
 /// 构建流式识别握手消息
@@ -285,7 +357,7 @@ fn build_streaming_handshake(
     let channels = metadata.map(|meta| meta.channels).unwrap_or(1);
     let bits = metadata.map(|meta| meta.bits_per_sample).unwrap_or(16);
 
-    let handshake = json!({
+    let mut handshake = json!({
         "app": {
             "appid": settings.volcengine.app_id,
             "cluster": STREAMING_CLUSTER,
@@ -296,7 +368,7 @@ fn build_streaming_handshake(
         },
         "request": {
             "reqid": crate::util::timestamp_id(),
-            "workflow": "audio_in,resample,partition,vad,fe,decode,itn,nlu_punctuate",
+            "workflow": build_workflow(&settings.volcengine),
             "sequence": 1,
             "nbest": 1,
             "show_utterances": true
@@ -313,9 +385,48 @@ fn build_streaming_handshake(
             "use_fast": settings.volcengine.use_fast
         }
     });
+    if let Some(name) = boosting_table_name(&settings.volcengine) {
+        handshake["request"]["boosting_table_name"] = json!(name);
+    }
+    if let Some(corpus) = build_corpus(&settings.volcengine) {
+        handshake["request"]["corpus"] = corpus;
+    }
     handshake.to_string()
 }
 
+/// 按设置拼接处理流程，关闭标点或 ITN 时去掉对应环节
+fn build_workflow(settings: &VolcengineSettings) -> String {
+    let mut steps = vec!["audio_in", "resample", "partition", "vad", "fe", "decode"];
+    if settings.enable_itn {
+        steps.push("itn");
+    }
+    if settings.enable_punctuation {
+        steps.push("nlu_punctuate");
+    }
+    steps.join(",")
+}
+
+fn boosting_table_name(settings: &VolcengineSettings) -> Option<String> {
+    let name = settings.boosting_table_name.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// 热词以 `{"hotwords":[{"word":...}]}` 形式的 JSON 字符串放入 `corpus.context`
+fn build_corpus(settings: &VolcengineSettings) -> Option<Value> {
+    let hotwords = settings
+        .hotwords
+        .iter()
+        .map(|word| word.trim())
+        .filter(|word| !word.is_empty())
+        .map(|word| json!({ "word": word }))
+        .collect::<Vec<_>>();
+    if hotwords.is_empty() {
+        return None;
+    }
+    let context = json!({ "hotwords": hotwords }).to_string();
+    Some(json!({ "context": context }))
+}
+
 /// 构建音频数据消息
 fn build_audio_message(chunk: &[u8], sequence: i32, is_last: bool) -> String {
     let msg = json!({
@@ -330,15 +441,12 @@ fn build_audio_message(chunk: &[u8], sequence: i32, is_last: bool) -> String {
     msg.to_string()
 }
 
-/// 检测音频格式与元信息
+/// 按文件内容检测音频格式与元信息
 fn detect_audio_info(path: &Path) -> (String, Option<AudioMetadata>) {
-    let format = path
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .map(|ext| ext.to_lowercase())
-        .unwrap_or_else(|| "wav".to_string());
+    let detected = audio_inspect::detect_format(path).unwrap_or(AudioFormat::Wav);
+    let format = detected.extension().to_string();
 
-    if format != "wav" {
+    if detected != AudioFormat::Wav {
         return (format, None);
     }
 
@@ -357,6 +465,63 @@ fn detect_audio_info(path: &Path) -> (String, Option<AudioMetadata>) {
     )
 }
 
+/// 读取 16-bit WAV 的 PCM 样本，用于按能量分块；其他格式返回 None
+fn read_pcm_samples(path: &Path, metadata: Option<AudioMetadata>) -> Option<Vec<i16>> {
+    if metadata?.bits_per_sample != 16 {
+        return None;
+    }
+    WavReader::open(path)
+        .ok()?
+        .into_samples::<i16>()
+        .collect::<Result<Vec<_>, _>>()
+        .ok()
+}
+
+/// 流式发送的分块字节范围：有 PCM 样本时在块长上限内选择能量最低处切分，
+/// 否则按固定字节切分。各范围首尾相接覆盖整个文件
+fn stream_chunk_ranges(
+    file_len: usize,
+    samples: Option<&[i16]>,
+    metadata: Option<AudioMetadata>,
+) -> Vec<Range<usize>> {
+    let chunk_size = compute_chunk_size(metadata);
+    let fixed = || {
+        (0..file_len)
+            .step_by(chunk_size)
+            .map(|start| start..(start + chunk_size).min(file_len))
+            .collect()
+    };
+    let (Some(samples), Some(meta)) = (samples, metadata) else {
+        return fixed();
+    };
+    let frame_bytes = usize::from(meta.channels.max(1)) * 2;
+    let data_len = samples.len() * 2;
+    if data_len == 0 || data_len > file_len {
+        return fixed();
+    }
+    // data 块通常位于文件末尾，其前为 WAV 头
+    let data_offset = file_len - data_len;
+    let max_frames = (chunk_size / frame_bytes).max(1);
+    let points = audio_pipeline::quiet_split_points(
+        samples,
+        meta.channels,
+        meta.sample_rate,
+        max_frames,
+        max_frames / 2,
+    );
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for frame in points {
+        let end = data_offset + frame * frame_bytes;
+        ranges.push(start..end);
+        start = end;
+    }
+    if let Some(last) = ranges.last_mut() {
+        last.end = file_len;
+    }
+    ranges
+}
+
 fn compute_chunk_size(metadata: Option<AudioMetadata>) -> usize {
     let Some(meta) = metadata else {
         return 3200;
@@ -374,7 +539,7 @@ fn compute_chunk_size(metadata: Option<AudioMetadata>) -> usize {
 }
 
 /// 验证配置
-fn ensure_config(settings: &VolcengineSettings) -> Result<(), VolcengineError> {
+pub fn ensure_config(settings: &VolcengineSettings) -> Result<(), VolcengineError> {
     if settings.app_id.trim().is_empty() {
         return Err(VolcengineError::Config("App ID 不能为空".to_string()));
     }
@@ -383,3 +548,73 @@ fn ensure_config(settings: &VolcengineSettings) -> Result<(), VolcengineError> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_result_supports_string_and_array_formats() {
+        let legacy = parse_result(&json!("你好"));
+        assert_eq!(legacy.text, "你好");
+        assert_eq!(legacy.confidence, None);
+
+        let modern = parse_result(&json!([{ "text": "hello", "confidence": 0.92 }]));
+        assert_eq!(modern.text, "hello");
+        assert!((modern.confidence.unwrap() - 0.92).abs() < 1e-6);
+
+        let uncomputed = parse_result(&json!([{ "text": "hello", "confidence": 0 }]));
+        assert_eq!(uncomputed.confidence, None);
+    }
+
+    #[test]
+    fn request_options_follow_settings() {
+        let mut settings = VolcengineSettings {
+            hotwords: vec!["VTT Keyboard".to_string(), "  ".to_string()],
+            ..VolcengineSettings::default()
+        };
+        assert_eq!(
+            build_workflow(&settings),
+            "audio_in,resample,partition,vad,fe,decode,itn,nlu_punctuate"
+        );
+        assert_eq!(
+            build_corpus(&settings),
+            Some(json!({ "context": r#"{"hotwords":[{"word":"VTT Keyboard"}]}"# }))
+        );
+        assert_eq!(boosting_table_name(&settings), None);
+
+        settings.enable_itn = false;
+        settings.enable_punctuation = false;
+        settings.hotwords.clear();
+        assert_eq!(
+            build_workflow(&settings),
+            "audio_in,resample,partition,vad,fe,decode"
+        );
+        assert_eq!(build_corpus(&settings), None);
+    }
+
+    #[test]
+    fn stream_chunk_ranges_cover_file_and_respect_chunk_limit() {
+        let meta = AudioMetadata {
+            sample_rate: 16_000,
+            channels: 1,
+            bits_per_sample: 16,
+        };
+        // 0.25 秒 PCM，0.15 秒处有静音
+        let mut samples = vec![2000i16; 2400];
+        samples.extend(vec![0i16; 320]);
+        samples.extend(vec![2000i16; 1280]);
+        let file_len = 44 + samples.len() * 2;
+        let ranges = stream_chunk_ranges(file_len, Some(&samples), Some(meta));
+        assert_eq!(ranges.first().map(|range| range.start), Some(0));
+        assert_eq!(ranges.last().map(|range| range.end), Some(file_len));
+        assert!(ranges.windows(2).all(|pair| pair[0].end == pair[1].start));
+        assert!(ranges[1..].iter().all(|range| range.len() <= 3200));
+        assert!(ranges
+            .iter()
+            .any(|range| (44 + 4800..44 + 5440).contains(&range.end)));
+
+        let fixed = stream_chunk_ranges(7000, None, None);
+        assert_eq!(fixed, vec![0..3200, 3200..6400, 6400..7000]);
+    }
+}
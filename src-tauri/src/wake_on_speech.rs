@@ -0,0 +1,297 @@
+//! 语音唤醒自动录音
+//!
+//! 布防后在本机持续监听默认输入设备的电平，检测到说话即开始完整录音，
+//! 持续静音后停止并送入转写队列。监听阶段只计算能量，不保存也不上传任何音频。
+
+use crate::dictation_context;
+use crate::literal_mode;
+use crate::presence;
+use crate::processing;
+use crate::prompt_presets;
+use crate::recorder;
+use crate::settings::{RecordingTakeover, WakeOnSpeechSettings};
+use crate::AppState;
+use cpal::traits::{DeviceTrait, StreamTrait};
+use cpal::{FromSample, Sample, Stream, StreamConfig};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+pub const WAKE_ON_SPEECH_EVENT: &str = "wake-on-speech-changed";
+/// 监听线程检查撤防标志的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// 检查用户是否离开（锁屏或空闲）的间隔
+const PRESENCE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WakeOnSpeechState {
+    pub armed: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VadEvent {
+    SpeechStart,
+    SpeechEnd,
+}
+
+/// 基于能量的简易 VAD：电平持续高于阈值 `start_ms` 判定开始说话，
+/// 持续低于阈值 `stop_silence_ms` 判定说话结束
+pub struct EnergyVad {
+    threshold_db: f32,
+    start_ms: u32,
+    stop_silence_ms: u32,
+    speaking: bool,
+    above_ms: u32,
+    below_ms: u32,
+}
+
+impl EnergyVad {
+    pub fn new(settings: &WakeOnSpeechSettings) -> Self {
+        Self {
+            threshold_db: settings.threshold_db,
+            start_ms: settings.start_ms,
+            stop_silence_ms: settings.stop_silence_ms,
+            speaking: false,
+            above_ms: 0,
+            below_ms: 0,
+        }
+    }
+
+    /// 输入一段时长为 `duration_ms` 的音频电平，状态切换时返回事件
+    pub fn push(&mut self, level_db: f32, duration_ms: u32) -> Option<VadEvent> {
+        if level_db >= self.threshold_db {
+            self.above_ms = self.above_ms.saturating_add(duration_ms);
+            self.below_ms = 0;
+        } else {
+            self.below_ms = self.below_ms.saturating_add(duration_ms);
+            self.above_ms = 0;
+        }
+        if !self.speaking && self.above_ms >= self.start_ms {
+            self.speaking = true;
+            return Some(VadEvent::SpeechStart);
+        }
+        if self.speaking && self.below_ms >= self.stop_silence_ms {
+            self.reset();
+            return Some(VadEvent::SpeechEnd);
+        }
+        None
+    }
+
+    pub fn reset(&mut self) {
+        self.speaking = false;
+        self.above_ms = 0;
+        self.below_ms = 0;
+    }
+}
+
+pub struct WakeOnSpeechService {
+    armed: Arc<AtomicBool>,
+}
+
+impl WakeOnSpeechService {
+    pub fn new() -> Self {
+        Self {
+            armed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn is_armed(&self) -> bool {
+        self.armed.load(Ordering::SeqCst)
+    }
+
+    /// 布防时启动监听线程，撤防时由监听线程自行退出；返回设置后的状态
+    pub fn set_armed(&self, app: &AppHandle, armed: bool) -> bool {
+        let was_armed = self.armed.swap(armed, Ordering::SeqCst);
+        if armed && !was_armed {
+            let flag = self.armed.clone();
+            let app = app.clone();
+            thread::spawn(move || {
+                if let Err(_err) = run_monitor(&app, &flag) {
+                    #[cfg(debug_assertions)]
+                    eprintln!("[wake-on-speech] 监听失败: {_err}");
+                    flag.store(false, Ordering::SeqCst);
+                    crate::sync_wake_on_speech(&app, false);
+                }
+            });
+        }
+        armed
+    }
+}
+
+/// 监听线程主体：打开独立的电平监听流，按 VAD 事件启停录音
+fn run_monitor(app: &AppHandle, armed: &AtomicBool) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let settings = state.settings_store.load().map_err(|err| err.to_string())?;
+    let (sender, receiver) = mpsc::channel();
+    // 流须在创建它的线程上保持存活，随本函数返回一起释放
+    let _stream = open_level_stream(&settings.recording.input_device, sender)?;
+    let mut vad = EnergyVad::new(&settings.wake_on_speech);
+    let max_recording =
+        Duration::from_secs(u64::from(settings.wake_on_speech.max_recording_seconds));
+    let mut recording_since: Option<Instant> = None;
+    let mut away = presence::is_away(&settings.presence);
+    let mut presence_checked = Instant::now();
+
+    while armed.load(Ordering::SeqCst) {
+        let Ok((level_db, duration_ms)) = receiver.recv_timeout(POLL_INTERVAL) else {
+            continue;
+        };
+        if presence_checked.elapsed() >= PRESENCE_CHECK_INTERVAL {
+            away = presence::is_away(&settings.presence);
+            presence_checked = Instant::now();
+        }
+        // 离开期间保持布防但不触发新录音，已开始的录音照常按静音结束
+        if away && recording_since.is_none() {
+            vad.reset();
+            continue;
+        }
+        match vad.push(level_db, duration_ms) {
+            Some(VadEvent::SpeechStart) if recording_since.is_none() => {
+                let start = state
+                    .recorder
+                    .start(RecordingTakeover::Ignore, &settings.recording)
+                    .map_err(|err| err.to_string())?;
+                // 用户已通过快捷键开始录音时交由用户结束，不接管
+                if !start.started {
+                    vad.reset();
+                    continue;
+                }
+                literal_mode::set_recording(false);
+                if start.device_changed {
+                    crate::sync_input_device(app, &start.device_name);
+                }
+                dictation_context::capture_window(&settings.context_carryover);
+                processing::emit_recording_status(&start.device_name);
+                crate::spawn_auto_stop(app, &settings.recording);
+                recording_since = Some(Instant::now());
+            }
+            Some(VadEvent::SpeechEnd) => {
+                if recording_since.take().is_some() {
+                    finish_recording(&state)?;
+                }
+            }
+            _ => {
+                if recording_since.is_some_and(|since| since.elapsed() >= max_recording) {
+                    recording_since = None;
+                    vad.reset();
+                    finish_recording(&state)?;
+                }
+            }
+        }
+    }
+    if recording_since.is_some() {
+        finish_recording(&state)?;
+    }
+    Ok(())
+}
+
+fn finish_recording(state: &AppState) -> Result<(), String> {
+    // 用户可能已通过快捷键手动停止本次录音，此时无需再次提交
+    let Ok(audio) = state.recorder.stop() else {
+        return Ok(());
+    };
+    state.transcription_dispatcher.enqueue(
+        audio,
+        literal_mode::take_recording(),
+        prompt_presets::take_recording(),
+    )?;
+    Ok(())
+}
+
+/// 打开录音所用输入设备的监听流，每个回调块的电平（dBFS）与时长通过 `sender` 发出
+fn open_level_stream(
+    input_device: &str,
+    sender: mpsc::Sender<(f32, u32)>,
+) -> Result<Stream, String> {
+    let device = recorder::resolve_input_device(input_device).map_err(|err| err.to_string())?;
+    let input_config = device
+        .default_input_config()
+        .map_err(|err| err.to_string())?;
+    let config: StreamConfig = input_config.clone().into();
+    let samples_per_ms = (config.sample_rate.0 as usize * usize::from(config.channels)) / 1000;
+    let err_fn = |_err| {
+        #[cfg(debug_assertions)]
+        eprintln!("[wake-on-speech] 监听流错误: {_err}");
+    };
+
+    macro_rules! build_stream {
+        ($sample_type:ty) => {
+            device
+                .build_input_stream(
+                    &config,
+                    move |data: &[$sample_type], _| {
+                        let duration_ms = (data.len() / samples_per_ms.max(1)) as u32;
+                        let _ = sender.send((level_db(data), duration_ms));
+                    },
+                    err_fn,
+                    None,
+                )
+                .map_err(|err| err.to_string())?
+        };
+    }
+
+    let stream = recorder::match_sample_format!(
+        input_config.sample_format(),
+        build_stream,
+        Err("不支持的采样格式".to_string())
+    );
+    stream.play().map_err(|err| err.to_string())?;
+    Ok(stream)
+}
+
+/// 计算一段样本的 RMS 电平（dBFS），静音时返回 -100
+fn level_db<T>(data: &[T]) -> f32
+where
+    T: Sample,
+    i16: FromSample<T>,
+{
+    if data.is_empty() {
+        return -100.0;
+    }
+    let sum: f64 = data
+        .iter()
+        .map(|sample| f64::from(i16::from_sample(*sample)).powi(2))
+        .sum();
+    let rms = (sum / data.len() as f64).sqrt() / f64::from(i16::MAX);
+    if rms <= 0.0 {
+        -100.0
+    } else {
+        (20.0 * rms.log10()).max(-100.0) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn energy_vad_starts_and_stops_after_configured_durations() {
+        let settings = WakeOnSpeechSettings {
+            threshold_db: -35.0,
+            start_ms: 200,
+            stop_silence_ms: 500,
+            max_recording_seconds: 60,
+        };
+        let mut vad = EnergyVad::new(&settings);
+        assert_eq!(vad.push(-20.0, 100), None);
+        // 短暂的噪声被静音打断，不触发录音
+        assert_eq!(vad.push(-60.0, 100), None);
+        assert_eq!(vad.push(-20.0, 100), None);
+        assert_eq!(vad.push(-20.0, 100), Some(VadEvent::SpeechStart));
+        assert_eq!(vad.push(-60.0, 400), None);
+        assert_eq!(vad.push(-20.0, 100), None);
+        assert_eq!(vad.push(-60.0, 400), None);
+        assert_eq!(vad.push(-60.0, 100), Some(VadEvent::SpeechEnd));
+    }
+
+    #[test]
+    fn level_db_handles_silence_and_full_scale() {
+        assert_eq!(level_db::<i16>(&[]), -100.0);
+        assert_eq!(level_db(&[0i16; 16]), -100.0);
+        assert!(level_db(&[i16::MAX; 16]).abs() < 0.01);
+    }
+}
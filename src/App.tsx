@@ -3,6 +3,7 @@ import { Tooltip } from "./components/Tooltip";
 import { PromptTemplateEditor } from "./components/PromptTemplateEditor";
 import { NumberWheelInput } from "./components/NumberWheelInput";
 import { SegmentedControl } from "./components/SegmentedControl";
+import { CustomSelect } from "./components/CustomSelect";
 import { Toaster, toast } from "sonner";
 
 import { useCallback, useEffect, useMemo, useState } from "react";
@@ -25,9 +26,20 @@ import { useUpdater } from "./hooks/useUpdater";
 import { useShortcuts } from "./hooks/useShortcuts";
 import { useSettingsSync } from "./hooks/useSettingsSync";
 import { useSenseVoiceManagement } from "./hooks/useSenseVoiceManagement";
+import { useWindowHidden } from "./hooks/useWindowHidden";
+import { useOnboarding } from "./hooks/useOnboarding";
+import type { OnboardingProfile } from "./hooks/useOnboarding";
 import { HistoryDetailDialog } from "./components/HistoryDetailDialog";
-import type { TranscriptionHistoryItem } from "./types/history";
-import type { Settings } from "./types/settings";
+import type {
+  TranscriptionHistoryItem,
+  TranscriptionHistorySummary,
+} from "./types/history";
+import type {
+  DeviceProfile,
+  InputDeviceInfo,
+  Settings,
+  StorageUsage,
+} from "./types/settings";
 
 import { parseList, toErrorMessage } from "./utils";
 import {
@@ -44,6 +56,11 @@ import {
 
 const MAX_HISTORY_ITEMS = 200;
 const HISTORY_PREVIEW_MAX_CHARS = 50;
+const ONBOARDING_PROFILES: { value: OnboardingProfile; labelKey: string }[] = [
+  { value: "cloud", labelKey: "onboarding.profileCloud" },
+  { value: "local-cpu", labelKey: "onboarding.profileLocalCpu" },
+  { value: "local-gpu", labelKey: "onboarding.profileLocalGpu" },
+];
 
 const createId = () =>
   typeof crypto !== "undefined" && "randomUUID" in crypto
@@ -105,21 +122,38 @@ function App() {
   const [historyItems, setHistoryItems] = useState<TranscriptionHistoryItem[]>([]);
   const [selectedHistoryItem, setSelectedHistoryItem] =
     useState<TranscriptionHistoryItem | null>(null);
+  const [inputDevices, setInputDevices] = useState<InputDeviceInfo[]>([]);
+  const [storageUsage, setStorageUsage] = useState<StorageUsage | null>(null);
+  const [triggerPackUrl, setTriggerPackUrl] = useState("");
+  const [triggerPackInstalling, setTriggerPackInstalling] = useState(false);
   const supportsSherpaOnnxSenseVoice =
     appInfo?.supportsSherpaOnnxSenseVoice ?? true;
   const sherpaFallbackActive =
     !supportsSherpaOnnxSenseVoice &&
     normalizeLocalModel(settings?.sensevoice.localModel) === "sherpa-onnx-sensevoice";
 
-  const { draft, updateDraft, handleImport, handleExport } = useSettingsSync({
-    settings,
-    setSettings,
-    saveSettings,
-    syncAutostart,
-    supportsSherpaOnnxSenseVoice,
-  });
+  const { draft, updateDraft, handleImport, handleExport, handleInstallTriggerPack } =
+    useSettingsSync({
+      settings,
+      setSettings,
+      saveSettings,
+      syncAutostart,
+      supportsSherpaOnnxSenseVoice,
+    });
+
+  const onboarding = useOnboarding();
+  const handleApplyProfile = async (kind: OnboardingProfile) => {
+    try {
+      setSettings(await onboarding.applyProfile(kind));
+      toast.success(t("onboarding.applied"));
+    } catch (error) {
+      toast.error(t("onboarding.error", { error: toErrorMessage(error) }));
+    }
+  };
 
-  const isSenseVoiceActive = activeSection === "speech" && draft?.provider === "sensevoice";
+  const windowHidden = useWindowHidden();
+  const isSenseVoiceActive =
+    !windowHidden && activeSection === "speech" && draft?.provider === "sensevoice";
 
   const {
     sensevoiceStatus,
@@ -148,9 +182,39 @@ function App() {
 
   const { isCapturing, setIsCapturing } = useShortcuts(
     draft?.shortcut.key,
-    onShortcutCaptured
+    onShortcutCaptured,
+    draft?.shortcut.pasteResultKeys,
+    draft?.shortcut.wakeToggleKey,
+    draft?.shortcut.cancelKey,
+    draft?.shortcut.literalKey,
+    draft?.promptPresets
   );
 
+  // 窗口隐藏到托盘时释放列表缓存（设置快照保留），重新显示后由各分区的加载逻辑补回
+  useEffect(() => {
+    if (!windowHidden) {
+      return;
+    }
+    setHistoryItems([]);
+    setSelectedHistoryItem(null);
+    setInputDevices([]);
+    setStorageUsage(null);
+  }, [windowHidden]);
+
+  useEffect(() => {
+    if (windowHidden || activeSection !== "recording") {
+      return;
+    }
+    invoke<InputDeviceInfo[]>("list_input_devices")
+      .then(setInputDevices)
+      .catch((error) =>
+        toast.error(t("recording.inputDeviceError", { error: toErrorMessage(error) }))
+      );
+    invoke<StorageUsage>("get_storage_usage")
+      .then(setStorageUsage)
+      .catch(() => setStorageUsage(null));
+  }, [activeSection, t, windowHidden]);
+
   const loadHistory = useCallback(async () => {
     setHistoryLoading(true);
     try {
@@ -194,20 +258,32 @@ function App() {
     void invoke("set_tray_menu", {
       labels: {
         showSettings: t("tray.showSettings"),
+        wakeOnSpeech: t("tray.wakeOnSpeech"),
+        autoPolish: t("tray.autoPolish"),
+        pasteAfterTranscribe: t("tray.pasteAfterTranscribe"),
+        useLocalModel: t("tray.useLocalModel"),
+        microphone: t("tray.microphone"),
         quit: t("tray.quit"),
       },
     });
   }, [i18n.language, t]);
 
   useEffect(() => {
-    const unlisten = listen<TranscriptionHistoryItem>(
+    const unlisten = listen<TranscriptionHistoryItem | TranscriptionHistorySummary>(
       "transcription-history-appended",
-      (event) => {
+      async (event) => {
+        // 摘要事件只携带预览文本，按 ID 拉取完整记录
+        const next =
+          "preview" in event.payload
+            ? await invoke<TranscriptionHistoryItem>("get_history_item", {
+                id: event.payload.id,
+              })
+            : event.payload;
         setHistoryItems((prev) => {
-          if (prev.some((item) => item.id === event.payload.id)) {
+          if (prev.some((item) => item.id === next.id)) {
             return prev;
           }
-          return [event.payload, ...prev].slice(0, MAX_HISTORY_ITEMS);
+          return [next, ...prev].slice(0, MAX_HISTORY_ITEMS);
         });
       }
     );
@@ -216,15 +292,68 @@ function App() {
     };
   }, []);
 
+  useEffect(() => {
+    const unlisten = listen<{
+      deviceName: string;
+      message: string;
+      recovery: "reconnecting" | "recovered" | "failed";
+    }>("recording-error", (event) => {
+      const { deviceName, message, recovery } = event.payload;
+      if (recovery === "reconnecting") {
+        toast.warning(t("recording.streamReconnecting", { device: deviceName, error: message }));
+      } else if (recovery === "recovered") {
+        toast.success(t("recording.streamRecovered", { device: deviceName }));
+      } else {
+        toast.error(t("recording.streamFailed", { error: message }));
+      }
+    });
+    return () => {
+      void unlisten.then((dispose) => dispose());
+    };
+  }, [t]);
+
+  useEffect(() => {
+    const unlisten = listen<{ deviceName: string; kind: "clipping" }>(
+      "recording-warning",
+      (event) => {
+        if (event.payload.kind === "clipping") {
+          toast.warning(t("recording.clippingWarning", { device: event.payload.deviceName }));
+        }
+      }
+    );
+    return () => {
+      void unlisten.then((dispose) => dispose());
+    };
+  }, [t]);
+
+  useEffect(() => {
+    const unlisten = listen<{ maxDurationSeconds: number }>(
+      "recording-limit-reached",
+      (event) => {
+        toast.warning(
+          t("recording.limitReached", {
+            minutes: Math.max(1, Math.round(event.payload.maxDurationSeconds / 60)),
+          })
+        );
+      }
+    );
+    return () => {
+      void unlisten.then((dispose) => dispose());
+    };
+  }, [t]);
+
   useEffect(() => {
     if (activeSection !== "history") {
       setSelectedHistoryItem(null);
       return;
     }
+    if (windowHidden) {
+      return;
+    }
     void loadHistory().catch((error) => {
       toast.error(t("history.loadError", { error: toErrorMessage(error) }));
     });
-  }, [activeSection, loadHistory, t]);
+  }, [activeSection, loadHistory, t, windowHidden]);
 
   // 窗口重新获得焦点时刷新历史，补偿窗口隐藏期间可能丢失的事件
   useEffect(() => {
@@ -263,6 +392,12 @@ function App() {
     keyword: t("triggers.defaultKeyword"),
     promptTemplate: t("triggers.defaultTemplate"),
     variables: parseList(t("triggers.defaultVariables")),
+    activeAppPattern: "",
+    textBackend: "",
+    responseLanguage: "",
+    pickVariable: false,
+    cooldownSeconds: 0,
+    requireConfirmation: false,
   });
 
   const updateTrigger = (
@@ -313,6 +448,26 @@ function App() {
     }
   };
 
+  const updateDeviceProfile = (deviceName: string, patch: Partial<DeviceProfile>) => {
+    updateDraft((prev) => {
+      const existing = prev.recording.deviceProfiles.find(
+        (profile) => profile.deviceName === deviceName
+      ) ?? { deviceName, gainDb: 0, denoise: false, sampleRate: 0 };
+      return {
+        ...prev,
+        recording: {
+          ...prev.recording,
+          deviceProfiles: [
+            ...prev.recording.deviceProfiles.filter(
+              (profile) => profile.deviceName !== deviceName
+            ),
+            { ...existing, ...patch },
+          ],
+        },
+      };
+    });
+  };
+
   if (loading || !draft) {
     return (
       <>
@@ -332,6 +487,15 @@ function App() {
     );
   }
 
+  // 偏好作用于实际录音的设备：未指定时为当前系统默认设备
+  const profileDeviceName =
+    draft.recording.inputDevice ||
+    inputDevices.find((device) => device.isDefault)?.name ||
+    "";
+  const deviceProfile = draft.recording.deviceProfiles.find(
+    (profile) => profile.deviceName === profileDeviceName
+  );
+
   return (
     <>
 
@@ -437,6 +601,25 @@ function App() {
     <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
   </Tooltip>
 </label>
+                  <label className="field">
+                    <span>{t("general.updateChannel")}</span>
+                    <SegmentedControl
+                      value={draft.startup.updateChannel}
+                      onChange={(value) =>
+                        updateDraft((prev) => ({
+                          ...prev,
+                          startup: {
+                            ...prev.startup,
+                            updateChannel: value as Settings["startup"]["updateChannel"],
+                          },
+                        }))
+                      }
+                      options={[
+                        { value: "stable", label: t("general.updateChannelStable") },
+                        { value: "beta", label: t("general.updateChannelBeta") },
+                      ]}
+                    />
+                  </label>
                   <label className="field checkbox">
                     <input
                       type="checkbox"
@@ -456,6 +639,443 @@ function App() {
     <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
   </Tooltip>
 </label>
+                  <label className="field checkbox">
+                    <input
+                      type="checkbox"
+                      checked={draft.output.punctuation.enabled}
+                      onChange={(event) =>
+                        updateDraft((prev) => ({
+                          ...prev,
+                          output: {
+                            ...prev.output,
+                            punctuation: {
+                              ...prev.output.punctuation,
+                              enabled: event.target.checked,
+                            },
+                          },
+                        }))
+                      }
+                    />
+                    <span>{t("general.punctuationStyle")}</span>
+  <Tooltip content={t("general.punctuationStyleHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                  {draft.output.punctuation.enabled ? (
+                    <>
+                      <label className="field">
+                        <span>{t("general.punctuationWidth")}</span>
+                        <SegmentedControl
+                          value={draft.output.punctuation.width}
+                          onChange={(value) =>
+                            updateDraft((prev) => ({
+                              ...prev,
+                              output: {
+                                ...prev.output,
+                                punctuation: {
+                                  ...prev.output.punctuation,
+                                  width: value as Settings["output"]["punctuation"]["width"],
+                                },
+                              },
+                            }))
+                          }
+                          options={[
+                          { value: "auto", label: t("general.punctuationWidthAuto") },
+                          { value: "fullWidth", label: t("general.punctuationWidthFull") },
+                          { value: "halfWidth", label: t("general.punctuationWidthHalf") },
+                          ]}
+                        />
+                      </label>
+                      <label className="field">
+                        <span>{t("general.serialComma")}</span>
+                        <SegmentedControl
+                          value={draft.output.punctuation.serialComma}
+                          onChange={(value) =>
+                            updateDraft((prev) => ({
+                              ...prev,
+                              output: {
+                                ...prev.output,
+                                punctuation: {
+                                  ...prev.output.punctuation,
+                                  serialComma: value as Settings["output"]["punctuation"]["serialComma"],
+                                },
+                              },
+                            }))
+                          }
+                          options={[
+                          { value: "keep", label: t("general.punctuationKeep") },
+                          { value: "add", label: t("general.punctuationAdd") },
+                          { value: "remove", label: t("general.punctuationRemove") },
+                          ]}
+                        />
+                      </label>
+                    </>
+                  ) : null}
+                  <label className="field">
+                    <span>{t("general.cjkLatinSpacing")}</span>
+                    <SegmentedControl
+                      value={draft.output.punctuation.cjkLatinSpacing}
+                      onChange={(value) =>
+                        updateDraft((prev) => ({
+                          ...prev,
+                          output: {
+                            ...prev.output,
+                            punctuation: {
+                              ...prev.output.punctuation,
+                              cjkLatinSpacing: value as Settings["output"]["punctuation"]["cjkLatinSpacing"],
+                            },
+                          },
+                        }))
+                      }
+                      options={[
+                      { value: "keep", label: t("general.punctuationKeep") },
+                      { value: "add", label: t("general.punctuationAdd") },
+                      { value: "remove", label: t("general.punctuationRemove") },
+                      ]}
+                    />
+                  </label>
+                  <label className="field checkbox">
+                    <input
+                      type="checkbox"
+                      checked={draft.output.pasteAfterTranscribe}
+                      onChange={(event) =>
+                        updateDraft((prev) => ({
+                          ...prev,
+                          output: {
+                            ...prev.output,
+                            pasteAfterTranscribe: event.target.checked,
+                          },
+                        }))
+                      }
+                    />
+                    <span>{t("general.pasteAfterTranscribe")}</span>
+  <Tooltip content={t("general.pasteAfterTranscribeHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                  <label className="field checkbox">
+                    <input
+                      type="checkbox"
+                      checked={draft.output.guardSecureFields}
+                      disabled={!draft.output.pasteAfterTranscribe}
+                      onChange={(event) =>
+                        updateDraft((prev) => ({
+                          ...prev,
+                          output: {
+                            ...prev.output,
+                            guardSecureFields: event.target.checked,
+                          },
+                        }))
+                      }
+                    />
+                    <span>{t("general.guardSecureFields")}</span>
+  <Tooltip content={t("general.guardSecureFieldsHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                  <label className="field checkbox">
+                    <input
+                      type="checkbox"
+                      checked={draft.output.confirmBeforePaste}
+                      onChange={(event) =>
+                        updateDraft((prev) => ({
+                          ...prev,
+                          output: {
+                            ...prev.output,
+                            confirmBeforePaste: event.target.checked,
+                          },
+                        }))
+                      }
+                    />
+                    <span>{t("general.confirmBeforePaste")}</span>
+  <Tooltip content={t("general.confirmBeforePasteHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                  <label className="field">
+                    <span>{t("general.maxPastesPerSecond")}</span>
+                    <NumberWheelInput
+  min={0}
+  max={20}
+  value={draft.output.maxPastesPerSecond}
+  onChange={(value) =>
+    updateDraft((prev) => ({
+      ...prev,
+      output: { ...prev.output, maxPastesPerSecond: value },
+    }))
+  }
+/>
+                  </label>
+                  <label className="field checkbox">
+                    <input
+                      type="checkbox"
+                      checked={draft.contextCarryover.useWindowTitle}
+                      onChange={(event) =>
+                        updateDraft((prev) => ({
+                          ...prev,
+                          contextCarryover: {
+                            ...prev.contextCarryover,
+                            useWindowTitle: event.target.checked,
+                          },
+                        }))
+                      }
+                    />
+                    <span>{t("general.useWindowTitle")}</span>
+  <Tooltip content={t("general.useWindowTitleHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                  <label className="field checkbox">
+                    <input
+                      type="checkbox"
+                      checked={draft.presence.pauseWhenAway}
+                      onChange={(event) =>
+                        updateDraft((prev) => ({
+                          ...prev,
+                          presence: {
+                            ...prev.presence,
+                            pauseWhenAway: event.target.checked,
+                          },
+                        }))
+                      }
+                    />
+                    <span>{t("general.pauseWhenAway")}</span>
+  <Tooltip content={t("general.pauseWhenAwayHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                  {draft.presence.pauseWhenAway ? (
+                    <label className="field">
+                      <span>{t("general.idleMinutes")}</span>
+                      <NumberWheelInput
+  min={1}
+  value={draft.presence.idleMinutes}
+  onChange={(value) =>
+    updateDraft((prev) => ({
+      ...prev,
+      presence: { ...prev.presence, idleMinutes: value },
+    }))
+  }
+/>
+                    </label>
+                  ) : null}
+                </SettingsCard>
+                <SettingsCard title={t("tts.title")} description={t("tts.description")}>
+                  <label className="field checkbox">
+                    <input
+                      type="checkbox"
+                      checked={draft.tts.enabled}
+                      onChange={(event) =>
+                        updateDraft((prev) => ({
+                          ...prev,
+                          tts: { ...prev.tts, enabled: event.target.checked },
+                        }))
+                      }
+                    />
+                    <span>{t("tts.enabled")}</span>
+                  </label>
+                  <label className="field">
+                    <span>{t("tts.provider")}</span>
+                    <SegmentedControl
+                      value={draft.tts.provider}
+                      onChange={(value) =>
+                        updateDraft((prev) => ({
+                          ...prev,
+                          tts: {
+                            ...prev.tts,
+                            provider: value as Settings["tts"]["provider"],
+                          },
+                        }))
+                      }
+                      options={[
+                        { value: "platform", label: t("tts.providerPlatform") },
+                        { value: "openai", label: t("tts.providerOpenai") },
+                      ]}
+                    />
+                  </label>
+                  <label className="field">
+                    <span>{t("tts.voice")}</span>
+                    <input
+                      value={draft.tts.voice}
+                      placeholder={t("tts.voicePlaceholder")}
+                      onChange={(event) =>
+                        updateDraft((prev) => ({
+                          ...prev,
+                          tts: { ...prev.tts, voice: event.target.value },
+                        }))
+                      }
+                    />
+                  </label>
+                  {draft.tts.provider === "openai" ? (
+                    <label className="field">
+                      <span>{t("tts.openaiModel")}</span>
+                      <input
+                        value={draft.tts.openaiModel}
+                        onChange={(event) =>
+                          updateDraft((prev) => ({
+                            ...prev,
+                            tts: { ...prev.tts, openaiModel: event.target.value },
+                          }))
+                        }
+                      />
+                    </label>
+                  ) : null}
+                  <div className="button-row">
+                    <button
+                      type="button"
+                      className="secondary"
+                      onClick={() => {
+                        invoke("test_tts", { text: t("tts.sample") }).catch((error) =>
+                          toast.error(t("tts.testError", { error: toErrorMessage(error) }))
+                        );
+                      }}
+                    >
+                      {t("tts.test")}
+                    </button>
+                  </div>
+                </SettingsCard>
+                <SettingsCard
+                  title={t("corrections.title")}
+                  description={t("corrections.description")}
+                >
+                  <label className="field checkbox">
+                    <input
+                      type="checkbox"
+                      checked={draft.corrections.enabled}
+                      onChange={(event) =>
+                        updateDraft((prev) => ({
+                          ...prev,
+                          corrections: { ...prev.corrections, enabled: event.target.checked },
+                        }))
+                      }
+                    />
+                    <span>{t("corrections.enabled")}</span>
+                  </label>
+                  <label className="field">
+                    <span>{t("corrections.minOccurrences")}</span>
+                    <NumberWheelInput
+                      value={draft.corrections.minOccurrences}
+                      min={1}
+                      max={10}
+                      step={1}
+                      onChange={(value) =>
+                        updateDraft((prev) => ({
+                          ...prev,
+                          corrections: { ...prev.corrections, minOccurrences: value },
+                        }))
+                      }
+                    />
+                  </label>
+                  {draft.corrections.entries.length === 0 ? (
+                    <p className="corrections-empty">{t("corrections.empty")}</p>
+                  ) : (
+                    <ul className="corrections-list">
+                      {draft.corrections.entries.map((entry, index) => (
+                        <li key={`${entry.original}→${entry.corrected}`}>
+                          <span className="corrections-pair">
+                            {entry.original} → {entry.corrected}
+                          </span>
+                          <span className="corrections-count">
+                            {entry.count >= draft.corrections.minOccurrences
+                              ? t("corrections.learned")
+                              : t("corrections.count", { count: entry.count })}
+                          </span>
+                          <button
+                            type="button"
+                            className="secondary"
+                            onClick={() =>
+                              updateDraft((prev) => ({
+                                ...prev,
+                                corrections: {
+                                  ...prev.corrections,
+                                  entries: prev.corrections.entries.filter((_, i) => i !== index),
+                                },
+                              }))
+                            }
+                          >
+                            {t("corrections.remove")}
+                          </button>
+                        </li>
+                      ))}
+                    </ul>
+                  )}
+                  {draft.corrections.entries.length > 0 ? (
+                    <div className="button-row">
+                      <button
+                        type="button"
+                        className="secondary"
+                        onClick={() =>
+                          updateDraft((prev) => ({
+                            ...prev,
+                            corrections: { ...prev.corrections, entries: [] },
+                          }))
+                        }
+                      >
+                        {t("corrections.clear")}
+                      </button>
+                    </div>
+                  ) : null}
+                </SettingsCard>
+                <SettingsCard
+                  title={t("onboarding.title")}
+                  description={t("onboarding.description")}
+                >
+                  <div className="button-row">
+                    <button
+                      type="button"
+                      className="secondary"
+                      disabled={onboarding.loading}
+                      onClick={() => {
+                        onboarding.detectCapabilities().catch((error) =>
+                          toast.error(t("onboarding.error", { error: toErrorMessage(error) }))
+                        );
+                      }}
+                    >
+                      {t("onboarding.detect")}
+                    </button>
+                  </div>
+                  {onboarding.loading && onboarding.progress ? (
+                    <div className="sensevoice-hint">{onboarding.progress.message}</div>
+                  ) : null}
+                  {onboarding.capabilities ? (
+                    <>
+                      <div className="sensevoice-hint">
+                        {t("onboarding.summary", {
+                          microphone:
+                            onboarding.capabilities.microphoneName ??
+                            (onboarding.capabilities.microphone
+                              ? t("onboarding.available")
+                              : t("onboarding.unavailable")),
+                          docker: onboarding.capabilities.docker
+                            ? t("onboarding.available")
+                            : t("onboarding.unavailable"),
+                          gpu: onboarding.capabilities.gpu
+                            ? t("onboarding.available")
+                            : t("onboarding.unavailable"),
+                          network: onboarding.capabilities.network
+                            ? t("onboarding.available")
+                            : t("onboarding.unavailable"),
+                        })}
+                      </div>
+                      <div className="button-row">
+                        {ONBOARDING_PROFILES.map((profile) => (
+                          <button
+                            key={profile.value}
+                            type="button"
+                            className={
+                              profile.value === onboarding.capabilities?.recommendedProfile
+                                ? undefined
+                                : "secondary"
+                            }
+                            disabled={onboarding.loading}
+                            onClick={() => void handleApplyProfile(profile.value)}
+                          >
+                            {t(profile.labelKey)}
+                          </button>
+                        ))}
+                      </div>
+                    </>
+                  ) : null}
                 </SettingsCard>
                 <SettingsCard title={t("data.title")} description={t("data.description")}>
                   <div className="button-row">
@@ -499,6 +1119,45 @@ function App() {
     <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={16} /></span>
   </Tooltip>
 </div>
+                <label className="field">
+                  <span>{t("shortcut.wakeToggleKey")}</span>
+                  <input
+                    value={draft.shortcut.wakeToggleKey}
+                    placeholder={t("shortcut.wakeToggleKeyPlaceholder")}
+                    onChange={(event) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        shortcut: { ...prev.shortcut, wakeToggleKey: event.target.value },
+                      }))
+                    }
+                  />
+                </label>
+                <label className="field">
+                  <span>{t("shortcut.cancelKey")}</span>
+                  <input
+                    value={draft.shortcut.cancelKey}
+                    placeholder={t("shortcut.cancelKeyPlaceholder")}
+                    onChange={(event) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        shortcut: { ...prev.shortcut, cancelKey: event.target.value },
+                      }))
+                    }
+                  />
+                </label>
+                <label className="field">
+                  <span>{t("shortcut.literalKey")}</span>
+                  <input
+                    value={draft.shortcut.literalKey}
+                    placeholder={t("shortcut.literalKeyPlaceholder")}
+                    onChange={(event) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        shortcut: { ...prev.shortcut, literalKey: event.target.value },
+                      }))
+                    }
+                  />
+                </label>
               </SettingsCard>
             ) : null}
 
@@ -521,6 +1180,700 @@ function App() {
       },
     }))
   }
+/>
+                </label>
+                <label className="field">
+                  <span>{t("recording.segmentOverlapMs")}</span>
+                  <NumberWheelInput
+  min={0}
+  max={5000}
+  step={500}
+  value={draft.recording.segmentOverlapMs}
+  onChange={(value) =>
+    updateDraft((prev) => ({
+      ...prev,
+      recording: {
+        ...prev.recording,
+        segmentOverlapMs: value,
+      },
+    }))
+  }
+/>
+                </label>
+                <div className="sensevoice-hint">{t("recording.segmentOverlapHint")}</div>
+                <label className="field">
+                  <span>{t("recording.inputDevice")}</span>
+                  <CustomSelect
+                    value={draft.recording.inputDevice}
+                    onChange={(value) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        recording: { ...prev.recording, inputDevice: value },
+                      }))
+                    }
+                    options={[
+                      { value: "", label: t("recording.inputDeviceDefault") },
+                      ...inputDevices.map((device) => ({
+                        value: device.name,
+                        label: device.isDefault
+                          ? t("recording.inputDeviceIsDefault", { name: device.name })
+                          : device.name,
+                      })),
+                      // 已选设备当前未连接时仍保留选项，录音时自动回退到默认设备
+                      ...(draft.recording.inputDevice &&
+                      !inputDevices.some((device) => device.name === draft.recording.inputDevice)
+                        ? [
+                            {
+                              value: draft.recording.inputDevice,
+                              label: t("recording.inputDeviceMissing", {
+                                name: draft.recording.inputDevice,
+                              }),
+                            },
+                          ]
+                        : []),
+                    ]}
+                  />
+                </label>
+                {profileDeviceName && (
+                  <>
+                    <label className="field">
+                      <span>
+                        {t("recording.deviceGainDb", { name: profileDeviceName })}
+                      </span>
+                      <NumberWheelInput
+  min={-24}
+  max={24}
+  step={1}
+  value={deviceProfile?.gainDb ?? 0}
+  onChange={(value) => updateDeviceProfile(profileDeviceName, { gainDb: value })}
+/>
+                    </label>
+                    <label className="field">
+                      <span>{t("recording.deviceSampleRate")}</span>
+                      <CustomSelect
+                        value={String(deviceProfile?.sampleRate ?? 0)}
+                        onChange={(value) =>
+                          updateDeviceProfile(profileDeviceName, { sampleRate: Number(value) })
+                        }
+                        options={[
+                          { value: "0", label: t("recording.deviceSampleRateDefault") },
+                          ...[16000, 44100, 48000].map((rate) => ({
+                            value: String(rate),
+                            label: `${rate} Hz`,
+                          })),
+                        ]}
+                      />
+                    </label>
+                    <label className="field checkbox">
+                      <input
+                        type="checkbox"
+                        checked={deviceProfile?.denoise ?? false}
+                        onChange={(event) =>
+                          updateDeviceProfile(profileDeviceName, {
+                            denoise: event.target.checked,
+                          })
+                        }
+                      />
+                      <span>{t("recording.deviceDenoise")}</span>
+  <Tooltip content={t("recording.deviceDenoiseHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                  </>
+                )}
+                {inputDevices.some((device) => device.name !== profileDeviceName) && (
+                  <div className="field">
+                    <span>{t("recording.mixInputDevices")}</span>
+                    {inputDevices
+                      .filter((device) => device.name !== profileDeviceName)
+                      .map((device) => {
+                        const mixed = draft.recording.mixInputDevices.includes(device.name);
+                        const profile = draft.recording.deviceProfiles.find(
+                          (item) => item.deviceName === device.name
+                        );
+                        return (
+                          <div key={device.name}>
+                            <label className="field checkbox">
+                              <input
+                                type="checkbox"
+                                checked={mixed}
+                                onChange={(event) =>
+                                  updateDraft((prev) => ({
+                                    ...prev,
+                                    recording: {
+                                      ...prev.recording,
+                                      mixInputDevices: event.target.checked
+                                        ? [...prev.recording.mixInputDevices, device.name]
+                                        : prev.recording.mixInputDevices.filter(
+                                            (name) => name !== device.name
+                                          ),
+                                    },
+                                  }))
+                                }
+                              />
+                              <span>{device.name}</span>
+                            </label>
+                            {mixed && (
+                              <label className="field">
+                                <span>{t("recording.deviceGainDb", { name: device.name })}</span>
+                                <NumberWheelInput
+  min={-24}
+  max={24}
+  step={1}
+  value={profile?.gainDb ?? 0}
+  onChange={(value) => updateDeviceProfile(device.name, { gainDb: value })}
+/>
+                              </label>
+                            )}
+                          </div>
+                        );
+                      })}
+                    <div className="sensevoice-hint">{t("recording.mixInputDevicesHint")}</div>
+                  </div>
+                )}
+                <label className="field">
+                  <span>{t("recording.bufferFrames")}</span>
+                  <CustomSelect
+                    value={String(draft.recording.bufferFrames)}
+                    onChange={(value) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        recording: { ...prev.recording, bufferFrames: Number(value) },
+                      }))
+                    }
+                    options={[
+                      { value: "0", label: t("recording.bufferFramesDefault") },
+                      ...[64, 128, 256, 512, 1024].map((frames) => ({
+                        value: String(frames),
+                        label: t("recording.bufferFramesValue", { frames }),
+                      })),
+                    ]}
+                  />
+                  <div className="sensevoice-hint">{t("recording.bufferFramesHint")}</div>
+                </label>
+                <label className="field">
+                  <span>{t("recording.maxDurationSeconds")}</span>
+                  <NumberWheelInput
+  min={10}
+  max={14400}
+  step={60}
+  value={draft.recording.maxDurationSeconds}
+  onChange={(value) =>
+    updateDraft((prev) => ({
+      ...prev,
+      recording: { ...prev.recording, maxDurationSeconds: value },
+    }))
+  }
+/>
+                </label>
+                <label className="field checkbox">
+                  <input
+                    type="checkbox"
+                    checked={draft.recording.endOfSpeech.enabled}
+                    onChange={(event) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        recording: {
+                          ...prev.recording,
+                          endOfSpeech: {
+                            ...prev.recording.endOfSpeech,
+                            enabled: event.target.checked,
+                          },
+                        },
+                      }))
+                    }
+                  />
+                  <span>{t("recording.endOfSpeech")}</span>
+  <Tooltip content={t("recording.endOfSpeechHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                {draft.recording.endOfSpeech.enabled && (
+                  <>
+                    <label className="field">
+                      <span>{t("recording.endOfSpeechThresholdDb")}</span>
+                      <NumberWheelInput
+  min={-80}
+  max={-1}
+  value={draft.recording.endOfSpeech.thresholdDb}
+  onChange={(value) =>
+    updateDraft((prev) => ({
+      ...prev,
+      recording: {
+        ...prev.recording,
+        endOfSpeech: { ...prev.recording.endOfSpeech, thresholdDb: value },
+      },
+    }))
+  }
+/>
+                    </label>
+                    <label className="field">
+                      <span>{t("recording.endOfSpeechTrailingSilenceMs")}</span>
+                      <NumberWheelInput
+  min={100}
+  step={100}
+  value={draft.recording.endOfSpeech.trailingSilenceMs}
+  onChange={(value) =>
+    updateDraft((prev) => ({
+      ...prev,
+      recording: {
+        ...prev.recording,
+        endOfSpeech: { ...prev.recording.endOfSpeech, trailingSilenceMs: value },
+      },
+    }))
+  }
+/>
+                    </label>
+                    <label className="field">
+                      <span>{t("recording.endOfSpeechMinSpeechMs")}</span>
+                      <NumberWheelInput
+  min={0}
+  step={100}
+  value={draft.recording.endOfSpeech.minSpeechMs}
+  onChange={(value) =>
+    updateDraft((prev) => ({
+      ...prev,
+      recording: {
+        ...prev.recording,
+        endOfSpeech: { ...prev.recording.endOfSpeech, minSpeechMs: value },
+      },
+    }))
+  }
+/>
+                    </label>
+                  </>
+                )}
+                <label className="field checkbox">
+                  <input
+                    type="checkbox"
+                    checked={draft.recording.noiseSuppression}
+                    onChange={(event) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        recording: {
+                          ...prev.recording,
+                          noiseSuppression: event.target.checked,
+                        },
+                      }))
+                    }
+                  />
+                  <span>{t("recording.noiseSuppression")}</span>
+  <Tooltip content={t("recording.noiseSuppressionHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                <label className="field checkbox">
+                  <input
+                    type="checkbox"
+                    checked={draft.recording.autoGain}
+                    onChange={(event) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        recording: {
+                          ...prev.recording,
+                          autoGain: event.target.checked,
+                        },
+                      }))
+                    }
+                  />
+                  <span>{t("recording.autoGain")}</span>
+  <Tooltip content={t("recording.autoGainHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                {draft.recording.autoGain && (
+                  <label className="field">
+                    <span>{t("recording.autoGainTargetDb")}</span>
+                    <NumberWheelInput
+  min={-30}
+  max={0}
+  value={draft.recording.autoGainTargetDb}
+  onChange={(value) =>
+    updateDraft((prev) => ({
+      ...prev,
+      recording: { ...prev.recording, autoGainTargetDb: value },
+    }))
+  }
+/>
+                  </label>
+                )}
+                <label className="field checkbox">
+                  <input
+                    type="checkbox"
+                    checked={draft.recording.silenceTrim.enabled}
+                    onChange={(event) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        recording: {
+                          ...prev.recording,
+                          silenceTrim: {
+                            ...prev.recording.silenceTrim,
+                            enabled: event.target.checked,
+                          },
+                        },
+                      }))
+                    }
+                  />
+                  <span>{t("recording.silenceTrim")}</span>
+  <Tooltip content={t("recording.silenceTrimHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                {draft.recording.silenceTrim.enabled && (
+                  <>
+                    <label className="field">
+                      <span>{t("recording.silenceTrimThresholdDb")}</span>
+                      <NumberWheelInput
+  min={-80}
+  max={-1}
+  value={draft.recording.silenceTrim.thresholdDb}
+  onChange={(value) =>
+    updateDraft((prev) => ({
+      ...prev,
+      recording: {
+        ...prev.recording,
+        silenceTrim: { ...prev.recording.silenceTrim, thresholdDb: value },
+      },
+    }))
+  }
+/>
+                    </label>
+                    <label className="field">
+                      <span>{t("recording.silenceTrimMaxPauseMs")}</span>
+                      <NumberWheelInput
+  min={200}
+  max={10000}
+  step={100}
+  value={draft.recording.silenceTrim.maxPauseMs}
+  onChange={(value) =>
+    updateDraft((prev) => ({
+      ...prev,
+      recording: {
+        ...prev.recording,
+        silenceTrim: { ...prev.recording.silenceTrim, maxPauseMs: value },
+      },
+    }))
+  }
+/>
+                    </label>
+                  </>
+                )}
+                <label className="field checkbox">
+                  <input
+                    type="checkbox"
+                    checked={draft.recording.keepOriginalFormat}
+                    onChange={(event) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        recording: {
+                          ...prev.recording,
+                          keepOriginalFormat: event.target.checked,
+                        },
+                      }))
+                    }
+                  />
+                  <span>{t("recording.keepOriginalFormat")}</span>
+  <Tooltip content={t("recording.keepOriginalFormatHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                <label className="field">
+                  <span>{t("recording.uploadFormat")}</span>
+                  <SegmentedControl
+                    value={draft.recording.uploadFormat}
+                    onChange={(value) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        recording: {
+                          ...prev.recording,
+                          uploadFormat: value as Settings["recording"]["uploadFormat"],
+                        },
+                      }))
+                    }
+                    options={[
+                      { value: "wav", label: t("recording.uploadFormatWav") },
+                      { value: "ogg-opus", label: t("recording.uploadFormatOggOpus") },
+                    ]}
+                  />
+                  <div className="sensevoice-hint">{t("recording.uploadFormatHint")}</div>
+                </label>
+                <label className="field checkbox">
+                  <input
+                    type="checkbox"
+                    checked={draft.recording.roamRecordings}
+                    onChange={(event) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        recording: {
+                          ...prev.recording,
+                          roamRecordings: event.target.checked,
+                        },
+                      }))
+                    }
+                  />
+                  <span>{t("recording.roamRecordings")}</span>
+  <Tooltip content={t("recording.roamRecordingsHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                <label className="field checkbox">
+                  <input
+                    type="checkbox"
+                    checked={draft.recording.keepRawAudio}
+                    onChange={(event) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        recording: {
+                          ...prev.recording,
+                          keepRawAudio: event.target.checked,
+                        },
+                      }))
+                    }
+                  />
+                  <span>{t("recording.keepRawAudio")}</span>
+  <Tooltip content={t("recording.keepRawAudioHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                {draft.recording.keepRawAudio && (
+                  <label className="field">
+                    <span>{t("recording.rawAudioRetentionDays")}</span>
+                    <NumberWheelInput
+  min={0}
+  value={draft.recording.rawAudioRetentionDays}
+  onChange={(value) =>
+    updateDraft((prev) => ({
+      ...prev,
+      recording: { ...prev.recording, rawAudioRetentionDays: value },
+    }))
+  }
+/>
+                  </label>
+                )}
+                <label className="field">
+                  <span>{t("recording.storageQuotaMb")}</span>
+                  <NumberWheelInput
+  min={0}
+  step={256}
+  value={draft.recording.storageQuotaMb}
+  onChange={(value) =>
+    updateDraft((prev) => ({
+      ...prev,
+      recording: { ...prev.recording, storageQuotaMb: value },
+    }))
+  }
+/>
+                </label>
+                {storageUsage ? (
+                  <div className="sensevoice-hint">
+                    {t("recording.storageUsage", {
+                      total: formatBytes(storageUsage.totalBytes),
+                      deferred: formatBytes(storageUsage.deferredBytes),
+                      retry: formatBytes(storageUsage.retryQueueBytes),
+                      archive: formatBytes(storageUsage.archiveBytes),
+                      scratch: formatBytes(storageUsage.scratchBytes),
+                    })}
+                  </div>
+                ) : null}
+                <label className="field checkbox">
+                  <input
+                    type="checkbox"
+                    checked={draft.recording.preRoll}
+                    onChange={(event) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        recording: {
+                          ...prev.recording,
+                          preRoll: event.target.checked,
+                        },
+                      }))
+                    }
+                  />
+                  <span>{t("recording.preRoll")}</span>
+  <Tooltip content={t("recording.preRollHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                {draft.recording.preRoll && (
+                  <label className="field">
+                    <span>{t("recording.preRollMs")}</span>
+                    <NumberWheelInput
+  min={250}
+  max={3000}
+  step={250}
+  value={draft.recording.preRollMs}
+  onChange={(value) =>
+    updateDraft((prev) => ({
+      ...prev,
+      recording: { ...prev.recording, preRollMs: value },
+    }))
+  }
+/>
+                  </label>
+                )}
+                <label className="field checkbox">
+                  <input
+                    type="checkbox"
+                    checked={draft.recording.captureSystemAudio}
+                    onChange={(event) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        recording: {
+                          ...prev.recording,
+                          captureSystemAudio: event.target.checked,
+                        },
+                      }))
+                    }
+                  />
+                  <span>{t("recording.captureSystemAudio")}</span>
+  <Tooltip content={t("recording.captureSystemAudioHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                {draft.recording.captureSystemAudio && (
+                  <>
+                    <label className="field">
+                      <span>{t("recording.micTrackLabel")}</span>
+                      <input
+                        value={draft.recording.micTrackLabel}
+                        onChange={(event) =>
+                          updateDraft((prev) => ({
+                            ...prev,
+                            recording: { ...prev.recording, micTrackLabel: event.target.value },
+                          }))
+                        }
+                      />
+                    </label>
+                    <label className="field">
+                      <span>{t("recording.systemTrackLabel")}</span>
+                      <input
+                        value={draft.recording.systemTrackLabel}
+                        onChange={(event) =>
+                          updateDraft((prev) => ({
+                            ...prev,
+                            recording: { ...prev.recording, systemTrackLabel: event.target.value },
+                          }))
+                        }
+                      />
+                    </label>
+                  </>
+                )}
+                <label className="field checkbox">
+                  <input
+                    type="checkbox"
+                    checked={draft.recording.monitor}
+                    onChange={(event) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        recording: {
+                          ...prev.recording,
+                          monitor: event.target.checked,
+                        },
+                      }))
+                    }
+                  />
+                  <span>{t("recording.monitor")}</span>
+  <Tooltip content={t("recording.monitorHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                {draft.recording.monitor && (
+                  <label className="field">
+                    <span>{t("recording.monitorVolume")}</span>
+                    <NumberWheelInput
+  min={0}
+  max={100}
+  step={5}
+  value={draft.recording.monitorVolume}
+  onChange={(value) =>
+    updateDraft((prev) => ({
+      ...prev,
+      recording: { ...prev.recording, monitorVolume: value },
+    }))
+  }
+/>
+                  </label>
+                )}
+                <label className="field checkbox">
+                  <input
+                    type="checkbox"
+                    checked={draft.recording.keepStreamWarm}
+                    onChange={(event) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        recording: {
+                          ...prev.recording,
+                          keepStreamWarm: event.target.checked,
+                        },
+                      }))
+                    }
+                  />
+                  <span>{t("recording.keepStreamWarm")}</span>
+  <Tooltip content={t("recording.keepStreamWarmHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                <label className="field">
+                  <span>{t("recording.countdownMs")}</span>
+                  <NumberWheelInput
+  min={0}
+  max={5000}
+  step={250}
+  value={draft.recording.countdownMs}
+  onChange={(value) =>
+    updateDraft((prev) => ({
+      ...prev,
+      recording: { ...prev.recording, countdownMs: value },
+    }))
+  }
+/>
+                </label>
+                <label className="field">
+                  <span>{t("recording.takeover")}</span>
+                  <SegmentedControl
+                    value={draft.recording.takeover}
+                    onChange={(value) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        recording: {
+                          ...prev.recording,
+                          takeover: value as Settings["recording"]["takeover"],
+                        },
+                      }))
+                    }
+                    options={[
+                      { value: "ignore", label: t("recording.takeoverIgnore") },
+                      { value: "restart", label: t("recording.takeoverRestart") },
+                      { value: "queue", label: t("recording.takeoverQueue") },
+                    ]}
+                  />
+                </label>
+                <label className="field">
+                  <span>{t("recording.wakeThresholdDb")}</span>
+                  <NumberWheelInput
+  min={-80}
+  max={0}
+  value={draft.wakeOnSpeech.thresholdDb}
+  onChange={(value) =>
+    updateDraft((prev) => ({
+      ...prev,
+      wakeOnSpeech: { ...prev.wakeOnSpeech, thresholdDb: value },
+    }))
+  }
+/>
+                </label>
+                <label className="field">
+                  <span>{t("recording.wakeStopSilenceMs")}</span>
+                  <NumberWheelInput
+  min={200}
+  step={100}
+  value={draft.wakeOnSpeech.stopSilenceMs}
+  onChange={(value) =>
+    updateDraft((prev) => ({
+      ...prev,
+      wakeOnSpeech: { ...prev.wakeOnSpeech, stopSilenceMs: value },
+    }))
+  }
 />
                 </label>
               </SettingsCard>
@@ -638,6 +1991,53 @@ function App() {
                           />
                           <span>{t("triggers.autoApply")}</span>
                         </label>
+                        <label className="field checkbox">
+                          <input
+                            type="checkbox"
+                            checked={card.pickVariable}
+                            onChange={(event) =>
+                              updateTrigger(card.id, (prev) => ({
+                                ...prev,
+                                pickVariable: event.target.checked,
+                              }))
+                            }
+                          />
+                          <span>{t("triggers.pickVariable")}</span>
+  <Tooltip content={t("triggers.pickVariableHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                        <label className="field checkbox">
+                          <input
+                            type="checkbox"
+                            checked={card.requireConfirmation}
+                            onChange={(event) =>
+                              updateTrigger(card.id, (prev) => ({
+                                ...prev,
+                                requireConfirmation: event.target.checked,
+                              }))
+                            }
+                          />
+                          <span>{t("triggers.requireConfirmation")}</span>
+  <Tooltip content={t("triggers.requireConfirmationHint")}>
+    <span className="flex items-center cursor-help text-[var(--color-text-secondary)] hover:text-[var(--color-accent-strong)] transition-colors"><Info size={14} /></span>
+  </Tooltip>
+</label>
+                        <label className="field">
+                          <span>{t("triggers.cooldownSeconds")}</span>
+                          <NumberWheelInput
+  min={0}
+  max={3600}
+  value={card.cooldownSeconds}
+  onChange={(value) =>
+    updateTrigger(card.id, (prev) => ({
+      ...prev,
+      cooldownSeconds: value,
+    }))
+  }
+/>
+                          <div className="sensevoice-hint">{t("triggers.cooldownSecondsHint")}</div>
+                        </label>
                         <label className="field">
                           <span>{t("triggers.keyword")}</span>
                           <input
@@ -650,6 +2050,19 @@ function App() {
                             }
                           />
                         </label>
+                        <label className="field">
+                          <span>{t("triggers.responseLanguage")}</span>
+                          <input
+                            value={card.responseLanguage}
+                            placeholder={t("triggers.responseLanguagePlaceholder")}
+                            onChange={(event) =>
+                              updateTrigger(card.id, (prev) => ({
+                                ...prev,
+                                responseLanguage: event.target.value,
+                              }))
+                            }
+                          />
+                        </label>
                         <label className="field">
                           <span>{t("triggers.variables")}</span>
                           <TagInput
@@ -682,6 +2095,45 @@ function App() {
                 <button type="button" className="secondary" onClick={addTrigger}>
                   {t("triggers.add")}
                 </button>
+                <div className="field">
+                  <span>{t("triggers.packUrl")}</span>
+                  <div className="button-row">
+                    <input
+                      value={triggerPackUrl}
+                      placeholder="https://"
+                      onChange={(event) => setTriggerPackUrl(event.target.value)}
+                    />
+                    <button
+                      type="button"
+                      className="secondary"
+                      disabled={triggerPackInstalling || !triggerPackUrl.trim()}
+                      onClick={async () => {
+                        setTriggerPackInstalling(true);
+                        if (await handleInstallTriggerPack(triggerPackUrl.trim())) {
+                          setTriggerPackUrl("");
+                        }
+                        setTriggerPackInstalling(false);
+                      }}
+                    >
+                      {t("triggers.packInstall")}
+                    </button>
+                  </div>
+                  <div className="sensevoice-hint">{t("triggers.packUrlHint")}</div>
+                </div>
+                <label className="field">
+                  <span>{t("triggers.packTrustedKeys")}</span>
+                  <TagInput
+                    values={draft.triggerPacks.trustedKeys}
+                    placeholder={t("triggers.packTrustedKeysPlaceholder")}
+                    onCommit={(nextValues) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        triggerPacks: { ...prev.triggerPacks, trustedKeys: nextValues },
+                      }))
+                    }
+                  />
+                  <div className="sensevoice-hint">{t("triggers.packTrustedKeysHint")}</div>
+                </label>
               </SettingsCard>
             ) : null}
 
@@ -706,6 +2158,25 @@ function App() {
                   />
                   <span>{t("history.enabled")}</span>
                 </label>
+                <label className="field">
+                  <span>{t("history.eventPayload")}</span>
+                  <SegmentedControl
+                    value={draft.history.eventPayload}
+                    onChange={(value) =>
+                      updateDraft((prev) => ({
+                        ...prev,
+                        history: {
+                          ...prev.history,
+                          eventPayload: value as Settings["history"]["eventPayload"],
+                        },
+                      }))
+                    }
+                    options={[
+                      { value: "full", label: t("history.eventPayloadFull") },
+                      { value: "summary", label: t("history.eventPayloadSummary") },
+                    ]}
+                  />
+                </label>
 
                 {historyLoading ? (
                   <div className="history-empty">{t("history.loading")}</div>
@@ -802,6 +2273,15 @@ function App() {
       <HistoryDetailDialog
         item={selectedHistoryItem}
         onClose={() => setSelectedHistoryItem(null)}
+        onRetranscribed={(created) => {
+          setSelectedHistoryItem(created);
+          setHistoryItems((prev) => {
+            if (prev.some((item) => item.id === created.id)) {
+              return prev;
+            }
+            return [created, ...prev].slice(0, MAX_HISTORY_ITEMS);
+          });
+        }}
       />
     </>
   );
@@ -0,0 +1,86 @@
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { toErrorMessage } from "../utils";
+
+interface PendingConfirmation {
+  text: string;
+}
+
+export function ConfirmWindow() {
+  const { t } = useTranslation();
+  const [text, setText] = useState("");
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    void invoke<PendingConfirmation | null>("get_pending_confirmation").then((pending) => {
+      if (pending) {
+        setText(pending.text);
+      }
+    });
+    const unlisten = listen<PendingConfirmation>("confirm-text", (event) => {
+      setText(event.payload.text);
+      setError(null);
+    });
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const run = async (command: string, args?: Record<string, unknown>) => {
+    setBusy(true);
+    setError(null);
+    try {
+      await invoke(command, args);
+    } catch (err) {
+      setError(t("confirm.error", { error: toErrorMessage(err) }));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  useEffect(() => {
+    const handleKeyDown = (event: KeyboardEvent) => {
+      if (event.key === "Escape") {
+        void invoke("confirm_discard");
+      }
+    };
+    window.addEventListener("keydown", handleKeyDown);
+    return () => window.removeEventListener("keydown", handleKeyDown);
+  }, []);
+
+  return (
+    <main className="confirm-window" data-tauri-drag-region>
+      <h4 data-tauri-drag-region>{t("confirm.title")}</h4>
+      <textarea
+        value={text}
+        autoFocus
+        onChange={(event) => setText(event.target.value)}
+      />
+      {error ? <p className="confirm-window-error">{error}</p> : null}
+      <div className="button-row">
+        <button type="button" disabled={busy} onClick={() => void run("confirm_paste", { text })}>
+          {t("confirm.paste")}
+        </button>
+        <button
+          type="button"
+          className="secondary"
+          disabled={busy}
+          onClick={() => void run("confirm_copy", { text })}
+        >
+          {t("confirm.copy")}
+        </button>
+        <button
+          type="button"
+          className="secondary"
+          disabled={busy}
+          onClick={() => void run("confirm_discard")}
+        >
+          {t("confirm.discard")}
+        </button>
+      </div>
+    </main>
+  );
+}
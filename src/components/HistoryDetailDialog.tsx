@@ -1,15 +1,39 @@
 import { X } from "lucide-react";
-import { useEffect } from "react";
+import { useEffect, useRef, useState } from "react";
 import { useTranslation } from "react-i18next";
+import { invoke } from "@tauri-apps/api/core";
+import { toast } from "sonner";
+import { CustomSelect } from "./CustomSelect";
 import type { TranscriptionHistoryItem } from "../types/history";
+import type { TranscriptionProvider } from "../types/settings";
+import { toErrorMessage } from "../utils";
 
 interface HistoryDetailDialogProps {
   item: TranscriptionHistoryItem | null;
   onClose: () => void;
+  onRetranscribed: (item: TranscriptionHistoryItem) => void;
 }
 
-export function HistoryDetailDialog({ item, onClose }: HistoryDetailDialogProps) {
+export function HistoryDetailDialog({
+  item,
+  onClose,
+  onRetranscribed,
+}: HistoryDetailDialogProps) {
   const { t } = useTranslation();
+  const audioRef = useRef<HTMLAudioElement | null>(null);
+  const [playing, setPlaying] = useState(false);
+  const [retranscribing, setRetranscribing] = useState(false);
+  // 为空时按当前设置选择提供商
+  const [retranscribeProvider, setRetranscribeProvider] = useState<TranscriptionProvider | "">("");
+
+  // 切换或关闭记录时停止回放
+  useEffect(() => {
+    return () => {
+      audioRef.current?.pause();
+      audioRef.current = null;
+      setPlaying(false);
+    };
+  }, [item?.id]);
 
   useEffect(() => {
     if (!item) {
@@ -42,8 +66,54 @@ export function HistoryDetailDialog({ item, onClose }: HistoryDetailDialogProps)
     : t("history.unknownModelGroup");
   const transcriptionElapsed = formatMillisecondsAsSeconds(item.transcriptionElapsedMs);
   const recordingDuration = formatMillisecondsAsSeconds(item.recordingDurationMs);
+  const textProcessingElapsed = formatMillisecondsAsSeconds(
+    item.stageLatency?.textProcessingMs
+  );
+  const outputElapsed = formatMillisecondsAsSeconds(item.stageLatency?.outputMs);
   const isFailed = item.status === "failed";
 
+  const handlePlay = async () => {
+    if (playing) {
+      audioRef.current?.pause();
+      audioRef.current = null;
+      setPlaying(false);
+      return;
+    }
+    try {
+      const buffer = await invoke<ArrayBuffer>("get_history_audio", { id: item.id });
+      const url = URL.createObjectURL(new Blob([buffer], { type: "audio/wav" }));
+      const audio = new Audio(url);
+      const finish = () => {
+        URL.revokeObjectURL(url);
+        setPlaying(false);
+      };
+      audio.addEventListener("ended", finish);
+      audio.addEventListener("pause", finish);
+      audioRef.current = audio;
+      setPlaying(true);
+      await audio.play();
+    } catch (error) {
+      setPlaying(false);
+      toast.error(t("history.audioError", { error: toErrorMessage(error) }));
+    }
+  };
+
+  const handleRetranscribe = async () => {
+    setRetranscribing(true);
+    try {
+      const created = await invoke<TranscriptionHistoryItem>("retranscribe_history_item", {
+        id: item.id,
+        provider: retranscribeProvider || null,
+      });
+      onRetranscribed(created);
+      toast.success(t("history.retranscribed"));
+    } catch (error) {
+      toast.error(t("history.retranscribeError", { error: toErrorMessage(error) }));
+    } finally {
+      setRetranscribing(false);
+    }
+  };
+
   return (
     <div className="history-dialog-backdrop" role="presentation" onClick={onClose}>
       <section
@@ -61,6 +131,11 @@ export function HistoryDetailDialog({ item, onClose }: HistoryDetailDialogProps)
                 {t("history.failed")}
               </span>
             )}
+            {item.status === "partial" && (
+              <span className="history-status-badge history-status-partial">
+                {t("history.partial")}
+              </span>
+            )}
             <button type="button" className="history-dialog-close" onClick={onClose}>
               <X size={16} />
             </button>
@@ -116,7 +191,62 @@ export function HistoryDetailDialog({ item, onClose }: HistoryDetailDialogProps)
             <span>{t("history.detailTranscriptionElapsed")} {transcriptionElapsed}</span>
             <span className="history-detail-meta-sep">·</span>
             <span>{t("history.detailRecordingDuration")} {recordingDuration}</span>
+            {item.segmentCount ? (
+              <>
+                <span className="history-detail-meta-sep">·</span>
+                <span>
+                  {t("history.detailSegments")} {item.segmentCount}
+                </span>
+              </>
+            ) : null}
+            {item.sourceId ? (
+              <>
+                <span className="history-detail-meta-sep">·</span>
+                <span>{t("history.detailRetranscribed")}</span>
+              </>
+            ) : null}
+            {item.stageLatency ? (
+              <>
+                <span className="history-detail-meta-sep">·</span>
+                <span>
+                  {t("history.detailTextProcessing")} {textProcessingElapsed}
+                </span>
+                <span className="history-detail-meta-sep">·</span>
+                <span>
+                  {t("history.detailOutput")} {outputElapsed}
+                </span>
+              </>
+            ) : null}
           </div>
+
+          {item.audioPath ? (
+            <div className="button-row">
+              <CustomSelect
+                value={retranscribeProvider}
+                onChange={(value) => setRetranscribeProvider(value as TranscriptionProvider | "")}
+                disabled={retranscribing}
+                options={[
+                  { value: "", label: t("history.retranscribeCurrentProvider") },
+                  { value: "openai", label: "OpenAI" },
+                  { value: "volcengine", label: t("speech.volcengine") },
+                  { value: "aliyun-asr", label: t("speech.aliyunAsr") },
+                  { value: "aliyun-paraformer", label: t("speech.aliyunParaformer") },
+                  { value: "sensevoice", label: t("speech.sensevoice") },
+                ]}
+              />
+              <button type="button" className="secondary" onClick={() => void handlePlay()}>
+                {playing ? t("history.stopAudio") : t("history.playAudio")}
+              </button>
+              <button
+                type="button"
+                className="secondary"
+                disabled={retranscribing}
+                onClick={() => void handleRetranscribe()}
+              >
+                {retranscribing ? t("history.retranscribing") : t("history.retranscribe")}
+              </button>
+            </div>
+          ) : null}
         </div>
       </section>
     </div>
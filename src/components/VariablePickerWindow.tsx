@@ -0,0 +1,86 @@
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+interface VariablePick {
+  id: number;
+  title: string;
+  variables: string[];
+  confirm: boolean;
+}
+
+export function VariablePickerWindow() {
+  const { t } = useTranslation();
+  const [request, setRequest] = useState<VariablePick | null>(null);
+
+  useEffect(() => {
+    void invoke<VariablePick | null>("get_pending_variable_pick").then((pending) => {
+      if (pending) {
+        setRequest(pending);
+      }
+    });
+    const unlisten = listen<VariablePick>("variable-pick", (event) => {
+      setRequest(event.payload);
+    });
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const resolve = (index: number | null) => {
+    if (request) {
+      void invoke("resolve_variable_pick", { id: request.id, index });
+    }
+  };
+
+  useEffect(() => {
+    const handleKeyDown = (event: KeyboardEvent) => {
+      if (event.key === "Escape") {
+        resolve(null);
+        return;
+      }
+      if (event.key === "Enter" && request?.confirm) {
+        resolve(0);
+        return;
+      }
+      const index = Number.parseInt(event.key, 10) - 1;
+      if (request && index >= 0 && index < request.variables.length) {
+        resolve(index);
+      }
+    };
+    window.addEventListener("keydown", handleKeyDown);
+    return () => window.removeEventListener("keydown", handleKeyDown);
+  });
+
+  return (
+    <main className="variable-picker" data-tauri-drag-region>
+      <h4 data-tauri-drag-region>{request?.title}</h4>
+      <div className="variable-picker-options">
+        {request?.variables.map((variable, index) => (
+          <button
+            key={`${request.id}-${index}`}
+            type="button"
+            className="secondary"
+            autoFocus={index === 0}
+            onClick={() => resolve(index)}
+          >
+            {request.confirm ? (
+              <span>{t("triggers.confirmApply", { value: variable })}</span>
+            ) : (
+              <>
+                {index < 9 ? <kbd>{index + 1}</kbd> : null}
+                <span>{variable}</span>
+              </>
+            )}
+          </button>
+        ))}
+      </div>
+      <div className="button-row">
+        <button type="button" className="secondary" onClick={() => resolve(null)}>
+          {t("triggers.pickerCancel")}
+        </button>
+      </div>
+    </main>
+  );
+}
@@ -1,14 +1,22 @@
 import type { TFunction } from "i18next";
+import { toast } from "sonner";
 import { CustomSelect } from "../CustomSelect";
 import { NumberWheelInput } from "../NumberWheelInput";
 import { SettingsCard } from "../SettingsCard";
 import type { SenseVoiceProgress, SenseVoiceStatus } from "../../hooks/useSenseVoice";
-import type { Settings } from "../../types/settings";
-import { parseList, listToString, normalizeAliyunRegion } from "../../utils";
+import { useDictationTest } from "../../hooks/useDictationTest";
+import { useProviderCapabilities } from "../../hooks/useProviderCapabilities";
+import type { PromptPreset, Settings } from "../../types/settings";
+import { parseList, listToString, normalizeAliyunRegion, toErrorMessage } from "../../utils";
 
 const isAliyunProvider = (provider: Settings["provider"]) =>
   provider === "aliyun-asr" || provider === "aliyun-paraformer";
 
+const createPresetId = () =>
+  typeof crypto !== "undefined" && "randomUUID" in crypto
+    ? crypto.randomUUID()
+    : `${Date.now()}-${Math.random().toString(16).slice(2)}`;
+
 interface Option {
   value: string;
   label: string;
@@ -67,6 +75,53 @@ export function SpeechSettingsSection({
   sherpaLanguageOptions,
   qwenVariantOptions,
 }: SpeechSettingsSectionProps) {
+  const capabilities = useProviderCapabilities(
+    draft.provider,
+    draft.openai.speechToText.model
+  );
+  const { result: dictationTest, testing, runTest } = useDictationTest();
+  const handleDictationTest = () => {
+    runTest().catch((error) =>
+      toast.error(t("speech.dictationTestFailed", { error: toErrorMessage(error) }))
+    );
+  };
+  const toSeconds = (ms: number) => (ms / 1000).toFixed(1);
+  // 能力尚未获取时显示全部字段
+  const supports = (option: string) => !capabilities || capabilities.options.includes(option);
+
+  const updatePreset = (id: string, updater: (prev: PromptPreset) => PromptPreset) => {
+    updateDraft((prev) => ({
+      ...prev,
+      promptPresets: prev.promptPresets.map((preset) =>
+        preset.id === id ? updater(preset) : preset
+      ),
+    }));
+  };
+
+  const addPreset = () => {
+    updateDraft((prev) => ({
+      ...prev,
+      promptPresets: [
+        ...prev.promptPresets,
+        {
+          id: createPresetId(),
+          name: t("speech.promptPresetNewName"),
+          prompt: "",
+          hotwords: [],
+          shortcut: "",
+        },
+      ],
+    }));
+  };
+
+  const removePreset = (id: string) => {
+    updateDraft((prev) => ({
+      ...prev,
+      promptPresets: prev.promptPresets.filter((preset) => preset.id !== id),
+      activePromptPresetId: prev.activePromptPresetId === id ? "" : prev.activePromptPresetId,
+    }));
+  };
+
   const localModelOptions: Option[] = [
     {
       value: "sensevoice",
@@ -132,6 +187,42 @@ export function SpeechSettingsSection({
             ]}
           />
         </label>
+        {capabilities ? (
+          <div className="sensevoice-hint">
+            {capabilities.languages.length > 0
+              ? t("speech.capabilityLanguages", { languages: capabilities.languages.join(", ") })
+              : t("speech.capabilityLanguagesAuto")}
+            {capabilities.maxAudioSeconds !== null
+              ? ` · ${t("speech.capabilityMaxAudio", { seconds: capabilities.maxAudioSeconds })}`
+              : null}
+            {capabilities.maxUploadBytes !== null
+              ? ` · ${t("speech.capabilityMaxUpload", {
+                  size: Math.round(capabilities.maxUploadBytes / (1024 * 1024)),
+                })}`
+              : null}
+          </div>
+        ) : null}
+        <div className="button-row">
+          <button
+            type="button"
+            className="secondary"
+            disabled={testing}
+            onClick={handleDictationTest}
+          >
+            {testing ? t("speech.dictationTestRunning") : t("speech.dictationTest")}
+          </button>
+        </div>
+        <div className="sensevoice-hint">
+          {dictationTest
+            ? dictationTest.errorMessage
+              ? t("speech.dictationTestFailed", { error: dictationTest.errorMessage })
+              : t("speech.dictationTestResult", {
+                  text: dictationTest.finalText || t("history.emptyText"),
+                  transcription: toSeconds(dictationTest.transcriptionMs),
+                  postProcessing: toSeconds(dictationTest.postProcessingMs),
+                })
+            : t("speech.dictationTestHint")}
+        </div>
       </SettingsCard>
 
       {draft.provider === "openai" ? (
@@ -179,262 +270,417 @@ export function SpeechSettingsSection({
               }
             />
           </label>
-          <label className="field">
-            <span>{t("speech.language")}</span>
-            <input
-              value={draft.openai.speechToText.language}
-              onChange={(event) =>
-                updateDraft((prev) => ({
-                  ...prev,
-                  openai: {
-                    ...prev.openai,
-                    speechToText: {
-                      ...prev.openai.speechToText,
-                      language: event.target.value,
+          {supports("language") ? (
+            <label className="field">
+              <span>{t("speech.language")}</span>
+              <input
+                value={draft.openai.speechToText.language}
+                onChange={(event) =>
+                  updateDraft((prev) => ({
+                    ...prev,
+                    openai: {
+                      ...prev.openai,
+                      speechToText: {
+                        ...prev.openai.speechToText,
+                        language: event.target.value,
+                      },
                     },
-                  },
-                }))
-              }
-            />
-          </label>
-          <label className="field">
-            <span>{t("speech.prompt")}</span>
-            <input
-              value={draft.openai.speechToText.prompt}
-              onChange={(event) =>
-                updateDraft((prev) => ({
-                  ...prev,
-                  openai: {
-                    ...prev.openai,
-                    speechToText: {
-                      ...prev.openai.speechToText,
-                      prompt: event.target.value,
+                  }))
+                }
+              />
+            </label>
+          ) : null}
+          {supports("prompt") ? (
+            <label className="field">
+              <span>{t("speech.prompt")}</span>
+              <input
+                value={draft.openai.speechToText.prompt}
+                onChange={(event) =>
+                  updateDraft((prev) => ({
+                    ...prev,
+                    openai: {
+                      ...prev.openai,
+                      speechToText: {
+                        ...prev.openai.speechToText,
+                        prompt: event.target.value,
+                      },
                     },
-                  },
-                }))
-              }
-            />
-          </label>
-          <label className="field">
-            <span>{t("speech.responseFormat")}</span>
-            <input
-              value={draft.openai.speechToText.responseFormat}
-              onChange={(event) =>
-                updateDraft((prev) => ({
-                  ...prev,
-                  openai: {
-                    ...prev.openai,
-                    speechToText: {
-                      ...prev.openai.speechToText,
-                      responseFormat: event.target.value,
+                  }))
+                }
+              />
+            </label>
+          ) : null}
+          {supports("responseFormat") ? (
+            <label className="field">
+              <span>{t("speech.responseFormat")}</span>
+              <input
+                value={draft.openai.speechToText.responseFormat}
+                onChange={(event) =>
+                  updateDraft((prev) => ({
+                    ...prev,
+                    openai: {
+                      ...prev.openai,
+                      speechToText: {
+                        ...prev.openai.speechToText,
+                        responseFormat: event.target.value,
+                      },
                     },
-                  },
-                }))
-              }
-            />
-          </label>
-          <label className="field">
-            <span>{t("speech.temperature")}</span>
-            <NumberWheelInput
-              step={0.1}
-              value={draft.openai.speechToText.temperature}
-              onChange={(value) =>
-                updateDraft((prev) => ({
-                  ...prev,
-                  openai: {
-                    ...prev.openai,
-                    speechToText: {
-                      ...prev.openai.speechToText,
-                      temperature: value,
+                  }))
+                }
+              />
+            </label>
+          ) : null}
+          {supports("temperature") ? (
+            <label className="field">
+              <span>{t("speech.temperature")}</span>
+              <NumberWheelInput
+                step={0.1}
+                value={draft.openai.speechToText.temperature}
+                onChange={(value) =>
+                  updateDraft((prev) => ({
+                    ...prev,
+                    openai: {
+                      ...prev.openai,
+                      speechToText: {
+                        ...prev.openai.speechToText,
+                        temperature: value,
+                      },
                     },
-                  },
-                }))
-              }
-            />
-          </label>
-          <label className="field">
-            <span>{t("speech.chunkingStrategy")}</span>
-            <input
-              value={draft.openai.speechToText.chunkingStrategy}
-              onChange={(event) =>
-                updateDraft((prev) => ({
-                  ...prev,
-                  openai: {
-                    ...prev.openai,
-                    speechToText: {
-                      ...prev.openai.speechToText,
-                      chunkingStrategy: event.target.value,
+                  }))
+                }
+              />
+            </label>
+          ) : null}
+          {supports("chunkingStrategy") ? (
+            <label className="field">
+              <span>{t("speech.chunkingStrategy")}</span>
+              <input
+                value={draft.openai.speechToText.chunkingStrategy}
+                onChange={(event) =>
+                  updateDraft((prev) => ({
+                    ...prev,
+                    openai: {
+                      ...prev.openai,
+                      speechToText: {
+                        ...prev.openai.speechToText,
+                        chunkingStrategy: event.target.value,
+                      },
                     },
-                  },
-                }))
+                  }))
+                }
+              />
+            </label>
+          ) : null}
+          {supports("timestampGranularities") ? (
+            <label className="field">
+              <span>{t("speech.timestampGranularities")}</span>
+              <input
+                value={listToString(draft.openai.speechToText.timestampGranularities)}
+                onChange={(event) =>
+                  updateDraft((prev) => ({
+                    ...prev,
+                    openai: {
+                      ...prev.openai,
+                      speechToText: {
+                        ...prev.openai.speechToText,
+                        timestampGranularities: parseList(event.target.value),
+                      },
+                    },
+                  }))
+                }
+              />
+            </label>
+          ) : null}
+          {supports("include") ? (
+            <label className="field">
+              <span>{t("speech.include")}</span>
+              <input
+                value={listToString(draft.openai.speechToText.include)}
+                onChange={(event) =>
+                  updateDraft((prev) => ({
+                    ...prev,
+                    openai: {
+                      ...prev.openai,
+                      speechToText: {
+                        ...prev.openai.speechToText,
+                        include: parseList(event.target.value),
+                      },
+                    },
+                  }))
+                }
+              />
+            </label>
+          ) : null}
+          {supports("knownSpeakerNames") ? (
+            <label className="field">
+              <span>{t("speech.knownSpeakerNames")}</span>
+              <input
+                value={listToString(draft.openai.speechToText.knownSpeakerNames)}
+                onChange={(event) =>
+                  updateDraft((prev) => ({
+                    ...prev,
+                    openai: {
+                      ...prev.openai,
+                      speechToText: {
+                        ...prev.openai.speechToText,
+                        knownSpeakerNames: parseList(event.target.value),
+                      },
+                    },
+                  }))
+                }
+              />
+            </label>
+          ) : null}
+          {supports("knownSpeakerReferences") ? (
+            <label className="field">
+              <span>{t("speech.knownSpeakerReferences")}</span>
+              <input
+                value={listToString(draft.openai.speechToText.knownSpeakerReferences)}
+                onChange={(event) =>
+                  updateDraft((prev) => ({
+                    ...prev,
+                    openai: {
+                      ...prev.openai,
+                      speechToText: {
+                        ...prev.openai.speechToText,
+                        knownSpeakerReferences: parseList(event.target.value),
+                      },
+                    },
+                  }))
+                }
+              />
+            </label>
+          ) : null}
+          {supports("stream") ? (
+            <label className="field checkbox">
+              <input
+                type="checkbox"
+                checked={draft.openai.speechToText.stream}
+                onChange={(event) =>
+                  updateDraft((prev) => ({
+                    ...prev,
+                    openai: {
+                      ...prev.openai,
+                      speechToText: {
+                        ...prev.openai.speechToText,
+                        stream: event.target.checked,
+                      },
+                    },
+                  }))
+                }
+              />
+              <span>{t("speech.stream")}</span>
+            </label>
+          ) : null}
+        </SettingsCard>
+      ) : null}
+
+      {draft.provider === "openai" || draft.provider === "volcengine" ? (
+        <SettingsCard
+          title={t("speech.promptPresets")}
+          description={t("speech.promptPresetsDescription")}
+        >
+          <label className="field">
+            <span>{t("speech.promptPresetActive")}</span>
+            <CustomSelect
+              value={draft.activePromptPresetId}
+              onChange={(value) =>
+                updateDraft((prev) => ({ ...prev, activePromptPresetId: value }))
               }
+              options={[
+                { value: "", label: t("speech.promptPresetNone") },
+                ...draft.promptPresets.map((preset) => ({
+                  value: preset.id,
+                  label: preset.name,
+                })),
+              ]}
             />
           </label>
+          <div className="trigger-list">
+            {draft.promptPresets.map((preset) => (
+              <div key={preset.id} className="trigger-card">
+                <div className="trigger-card-header">
+                  <input
+                    value={preset.name}
+                    onChange={(event) =>
+                      updatePreset(preset.id, (prev) => ({ ...prev, name: event.target.value }))
+                    }
+                  />
+                  <div className="trigger-card-actions">
+                    <button type="button" onClick={() => removePreset(preset.id)}>
+                      {t("speech.promptPresetRemove")}
+                    </button>
+                  </div>
+                </div>
+                <div className="trigger-card-body">
+                  <label className="field">
+                    <span>{t("speech.prompt")}</span>
+                    <input
+                      value={preset.prompt}
+                      onChange={(event) =>
+                        updatePreset(preset.id, (prev) => ({
+                          ...prev,
+                          prompt: event.target.value,
+                        }))
+                      }
+                    />
+                  </label>
+                  <label className="field">
+                    <span>{t("volcengine.hotwords")}</span>
+                    <input
+                      value={listToString(preset.hotwords)}
+                      onChange={(event) =>
+                        updatePreset(preset.id, (prev) => ({
+                          ...prev,
+                          hotwords: parseList(event.target.value),
+                        }))
+                      }
+                    />
+                  </label>
+                  <label className="field">
+                    <span>{t("speech.promptPresetShortcut")}</span>
+                    <input
+                      value={preset.shortcut}
+                      placeholder={t("speech.promptPresetShortcutPlaceholder")}
+                      onChange={(event) =>
+                        updatePreset(preset.id, (prev) => ({
+                          ...prev,
+                          shortcut: event.target.value,
+                        }))
+                      }
+                    />
+                  </label>
+                </div>
+              </div>
+            ))}
+          </div>
+          <button type="button" className="secondary" onClick={addPreset}>
+            {t("speech.promptPresetAdd")}
+          </button>
+        </SettingsCard>
+      ) : null}
+
+      {draft.provider === "volcengine" ? (
+        <SettingsCard title={t("speech.volcengine")}>
           <label className="field">
-            <span>{t("speech.timestampGranularities")}</span>
+            <span>{t("volcengine.appId")}</span>
             <input
-              value={listToString(draft.openai.speechToText.timestampGranularities)}
+              value={draft.volcengine.appId}
               onChange={(event) =>
                 updateDraft((prev) => ({
                   ...prev,
-                  openai: {
-                    ...prev.openai,
-                    speechToText: {
-                      ...prev.openai.speechToText,
-                      timestampGranularities: parseList(event.target.value),
-                    },
-                  },
+                  volcengine: { ...prev.volcengine, appId: event.target.value },
                 }))
               }
             />
           </label>
           <label className="field">
-            <span>{t("speech.include")}</span>
+            <span>{t("volcengine.accessToken")}</span>
             <input
-              value={listToString(draft.openai.speechToText.include)}
+              type="password"
+              value={draft.volcengine.accessToken}
               onChange={(event) =>
                 updateDraft((prev) => ({
                   ...prev,
-                  openai: {
-                    ...prev.openai,
-                    speechToText: {
-                      ...prev.openai.speechToText,
-                      include: parseList(event.target.value),
-                    },
-                  },
+                  volcengine: { ...prev.volcengine, accessToken: event.target.value },
                 }))
               }
             />
           </label>
           <label className="field">
-            <span>{t("speech.knownSpeakerNames")}</span>
-            <input
-              value={listToString(draft.openai.speechToText.knownSpeakerNames)}
-              onChange={(event) =>
+            <span>{t("volcengine.language")}</span>
+            <CustomSelect
+              value={draft.volcengine.language}
+              onChange={(value) =>
                 updateDraft((prev) => ({
                   ...prev,
-                  openai: {
-                    ...prev.openai,
-                    speechToText: {
-                      ...prev.openai.speechToText,
-                      knownSpeakerNames: parseList(event.target.value),
-                    },
-                  },
+                  volcengine: { ...prev.volcengine, language: value },
                 }))
               }
+              options={[
+                { value: "zh-CN", label: t("volcengine.langZhCN") },
+                { value: "zh-TW", label: t("volcengine.langZhTW") },
+                { value: "en-US", label: t("volcengine.langEnUS") },
+                { value: "ja-JP", label: t("volcengine.langJaJP") },
+                { value: "ko-KR", label: t("volcengine.langKoKR") },
+              ]}
             />
           </label>
-          <label className="field">
-            <span>{t("speech.knownSpeakerReferences")}</span>
+          <label className="field checkbox">
             <input
-              value={listToString(draft.openai.speechToText.knownSpeakerReferences)}
+              type="checkbox"
+              checked={draft.volcengine.useStreaming}
               onChange={(event) =>
                 updateDraft((prev) => ({
                   ...prev,
-                  openai: {
-                    ...prev.openai,
-                    speechToText: {
-                      ...prev.openai.speechToText,
-                      knownSpeakerReferences: parseList(event.target.value),
-                    },
-                  },
+                  volcengine: { ...prev.volcengine, useStreaming: event.target.checked },
                 }))
               }
             />
+            <span>{t("volcengine.useStreaming")}</span>
           </label>
           <label className="field checkbox">
             <input
               type="checkbox"
-              checked={draft.openai.speechToText.stream}
+              checked={draft.volcengine.useFast}
               onChange={(event) =>
                 updateDraft((prev) => ({
                   ...prev,
-                  openai: {
-                    ...prev.openai,
-                    speechToText: {
-                      ...prev.openai.speechToText,
-                      stream: event.target.checked,
-                    },
-                  },
+                  volcengine: { ...prev.volcengine, useFast: event.target.checked },
                 }))
               }
             />
-            <span>{t("speech.stream")}</span>
+            <span>{t("volcengine.useFast")}</span>
           </label>
-        </SettingsCard>
-      ) : null}
-
-      {draft.provider === "volcengine" ? (
-        <SettingsCard title={t("speech.volcengine")}>
           <label className="field">
-            <span>{t("volcengine.appId")}</span>
+            <span>{t("volcengine.boostingTableName")}</span>
             <input
-              value={draft.volcengine.appId}
+              value={draft.volcengine.boostingTableName}
               onChange={(event) =>
                 updateDraft((prev) => ({
                   ...prev,
-                  volcengine: { ...prev.volcengine, appId: event.target.value },
+                  volcengine: { ...prev.volcengine, boostingTableName: event.target.value },
                 }))
               }
             />
           </label>
           <label className="field">
-            <span>{t("volcengine.accessToken")}</span>
+            <span>{t("volcengine.hotwords")}</span>
             <input
-              type="password"
-              value={draft.volcengine.accessToken}
+              value={listToString(draft.volcengine.hotwords)}
               onChange={(event) =>
                 updateDraft((prev) => ({
                   ...prev,
-                  volcengine: { ...prev.volcengine, accessToken: event.target.value },
+                  volcengine: { ...prev.volcengine, hotwords: parseList(event.target.value) },
                 }))
               }
             />
           </label>
-          <label className="field">
-            <span>{t("volcengine.language")}</span>
-            <CustomSelect
-              value={draft.volcengine.language}
-              onChange={(value) =>
-                updateDraft((prev) => ({
-                  ...prev,
-                  volcengine: { ...prev.volcengine, language: value },
-                }))
-              }
-              options={[
-                { value: "zh-CN", label: t("volcengine.langZhCN") },
-                { value: "zh-TW", label: t("volcengine.langZhTW") },
-                { value: "en-US", label: t("volcengine.langEnUS") },
-                { value: "ja-JP", label: t("volcengine.langJaJP") },
-                { value: "ko-KR", label: t("volcengine.langKoKR") },
-              ]}
-            />
-          </label>
           <label className="field checkbox">
             <input
               type="checkbox"
-              checked={draft.volcengine.useStreaming}
+              checked={draft.volcengine.enablePunctuation}
               onChange={(event) =>
                 updateDraft((prev) => ({
                   ...prev,
-                  volcengine: { ...prev.volcengine, useStreaming: event.target.checked },
+                  volcengine: { ...prev.volcengine, enablePunctuation: event.target.checked },
                 }))
               }
             />
-            <span>{t("volcengine.useStreaming")}</span>
+            <span>{t("volcengine.enablePunctuation")}</span>
           </label>
           <label className="field checkbox">
             <input
               type="checkbox"
-              checked={draft.volcengine.useFast}
+              checked={draft.volcengine.enableItn}
               onChange={(event) =>
                 updateDraft((prev) => ({
                   ...prev,
-                  volcengine: { ...prev.volcengine, useFast: event.target.checked },
+                  volcengine: { ...prev.volcengine, enableItn: event.target.checked },
                 }))
               }
             />
-            <span>{t("volcengine.useFast")}</span>
+            <span>{t("volcengine.enableItn")}</span>
           </label>
         </SettingsCard>
       ) : null}
@@ -620,7 +866,9 @@ export function SpeechSettingsSection({
                                 ? "paused"
                                 : isWarming
                                   ? "warmup"
-                                  : "";
+                                  : effectiveProgressStage === "battery"
+                                    ? "battery"
+                                    : "";
             const prepareBusy =
               sensevoiceLoading ||
               effectiveProgressStage === "prepare" ||
@@ -689,6 +937,7 @@ export function SpeechSettingsSection({
                             modelId: nextDefaultModelId,
                             language: normalizeSenseVoiceLanguage(prev.sensevoice.language),
                             device: nextDevice,
+                            quantization: "none",
                           },
                         };
                       })
@@ -741,6 +990,60 @@ export function SpeechSettingsSection({
                   </label>
                 ) : null}
 
+                {isQwenSelected || isVoxtralSelected ? (
+                  <label className="field">
+                    <span>{t("sensevoice.quantization")}</span>
+                    <CustomSelect
+                      value={draft.sensevoice.quantization}
+                      onChange={(value) =>
+                        updateDraft((prev) => ({
+                          ...prev,
+                          sensevoice: {
+                            ...prev.sensevoice,
+                            quantization: value as Settings["sensevoice"]["quantization"],
+                          },
+                        }))
+                      }
+                      options={[
+                        { value: "none", label: t("sensevoice.quantizationNone") },
+                        ...(isQwenSelected
+                          ? [
+                              { value: "awq", label: t("sensevoice.quantizationAwq") },
+                              { value: "gptq", label: t("sensevoice.quantizationGptq") },
+                            ]
+                          : []),
+                        { value: "fp8", label: t("sensevoice.quantizationFp8") },
+                      ]}
+                    />
+                  </label>
+                ) : null}
+                {isQwenSelected || isVoxtralSelected ? (
+                  <div className="sensevoice-hint">{t("sensevoice.quantizationHint")}</div>
+                ) : null}
+
+                {!isSherpaSelected ? (
+                  <label className="field">
+                    <span>{t("sensevoice.batteryPolicy")}</span>
+                    <CustomSelect
+                      value={draft.sensevoice.batteryPolicy}
+                      onChange={(value) =>
+                        updateDraft((prev) => ({
+                          ...prev,
+                          sensevoice: {
+                            ...prev.sensevoice,
+                            batteryPolicy: value as Settings["sensevoice"]["batteryPolicy"],
+                          },
+                        }))
+                      }
+                      options={[
+                        { value: "allow", label: t("sensevoice.batteryPolicyAllow") },
+                        { value: "refuse", label: t("sensevoice.batteryPolicyRefuse") },
+                        { value: "prefer-cpu", label: t("sensevoice.batteryPolicyPreferCpu") },
+                      ]}
+                    />
+                  </label>
+                ) : null}
+
                 {!isSherpaSelected ? (
                   <label className="field">
                     <span>{t("sensevoice.serviceUrl")}</span>
@@ -2,7 +2,7 @@ import type { TFunction } from "i18next";
 import { CustomSelect } from "../CustomSelect";
 import { NumberWheelInput } from "../NumberWheelInput";
 import { SettingsCard } from "../SettingsCard";
-import type { Settings } from "../../types/settings";
+import type { Settings, TextSettings } from "../../types/settings";
 
 interface TextProcessingSettingsSectionProps {
   draft: Settings;
@@ -15,13 +15,27 @@ export function TextProcessingSettingsSection({
   t,
   updateDraft,
 }: TextProcessingSettingsSectionProps) {
+  const provider = draft.textProcessing.provider;
+  const active = draft.textProcessing[provider];
+  const updateActive = (patch: Partial<TextSettings>) =>
+    updateDraft((prev) => ({
+      ...prev,
+      textProcessing: {
+        ...prev.textProcessing,
+        [prev.textProcessing.provider]: {
+          ...prev.textProcessing[prev.textProcessing.provider],
+          ...patch,
+        },
+      },
+    }));
+
   return (
     <>
       <SettingsCard title={t("text.title")} description={t("text.description")}>
         <label className="field">
           <span>{t("text.provider")}</span>
           <CustomSelect
-            value={draft.textProcessing.provider}
+            value={provider}
             onChange={(value) =>
               updateDraft((prev) => ({
                 ...prev,
@@ -31,142 +45,74 @@ export function TextProcessingSettingsSection({
                 },
               }))
             }
-            options={[{ value: "openai", label: "OpenAI" }]}
+            options={[
+              { value: "openai", label: "OpenAI" },
+              { value: "claude", label: "Claude" },
+              { value: "ollama", label: "Ollama" },
+              { value: "local", label: t("text.providerLocal") },
+            ]}
           />
         </label>
         <label className="field">
           <span>{t("openai.apiBase")}</span>
           <input
-            value={draft.textProcessing.openai.apiBase}
-            onChange={(event) =>
-              updateDraft((prev) => ({
-                ...prev,
-                textProcessing: {
-                  ...prev.textProcessing,
-                  openai: {
-                    ...prev.textProcessing.openai,
-                    apiBase: event.target.value,
-                  },
-                },
-              }))
-            }
-          />
-        </label>
-        <label className="field">
-          <span>{t("openai.apiKey")}</span>
-          <input
-            type="password"
-            value={draft.textProcessing.openai.apiKey}
-            onChange={(event) =>
-              updateDraft((prev) => ({
-                ...prev,
-                textProcessing: {
-                  ...prev.textProcessing,
-                  openai: {
-                    ...prev.textProcessing.openai,
-                    apiKey: event.target.value,
-                  },
-                },
-              }))
-            }
+            value={active.apiBase}
+            onChange={(event) => updateActive({ apiBase: event.target.value })}
           />
         </label>
+        {provider !== "ollama" && (
+          <label className="field">
+            <span>{t("openai.apiKey")}</span>
+            <input
+              type="password"
+              value={active.apiKey}
+              onChange={(event) => updateActive({ apiKey: event.target.value })}
+            />
+          </label>
+        )}
       </SettingsCard>
 
-      <SettingsCard title={t("text.openaiTitle")}>
+      <SettingsCard title={t("text.modelTitle")}>
         <label className="field">
           <span>{t("text.model")}</span>
           <input
-            value={draft.textProcessing.openai.model}
-            onChange={(event) =>
-              updateDraft((prev) => ({
-                ...prev,
-                textProcessing: {
-                  ...prev.textProcessing,
-                  openai: {
-                    ...prev.textProcessing.openai,
-                    model: event.target.value,
-                  },
-                },
-              }))
-            }
+            value={active.model}
+            onChange={(event) => updateActive({ model: event.target.value })}
           />
         </label>
         <label className="field">
           <span>{t("text.instructions")}</span>
           <input
-            value={draft.textProcessing.openai.instructions}
-            onChange={(event) =>
-              updateDraft((prev) => ({
-                ...prev,
-                textProcessing: {
-                  ...prev.textProcessing,
-                  openai: {
-                    ...prev.textProcessing.openai,
-                    instructions: event.target.value,
-                  },
-                },
-              }))
-            }
+            value={active.instructions}
+            onChange={(event) => updateActive({ instructions: event.target.value })}
           />
         </label>
         <label className="field">
           <span>{t("text.temperature")}</span>
           <NumberWheelInput
             step={0.1}
-            value={draft.textProcessing.openai.temperature}
-            onChange={(value) =>
-              updateDraft((prev) => ({
-                ...prev,
-                textProcessing: {
-                  ...prev.textProcessing,
-                  openai: {
-                    ...prev.textProcessing.openai,
-                    temperature: value,
-                  },
-                },
-              }))
-            }
+            value={active.temperature}
+            onChange={(value) => updateActive({ temperature: value })}
           />
         </label>
         <label className="field">
           <span>{t("text.maxOutputTokens")}</span>
           <NumberWheelInput
             min={1}
-            value={draft.textProcessing.openai.maxOutputTokens}
-            onChange={(value) =>
-              updateDraft((prev) => ({
-                ...prev,
-                textProcessing: {
-                  ...prev.textProcessing,
-                  openai: {
-                    ...prev.textProcessing.openai,
-                    maxOutputTokens: value,
-                  },
-                },
-              }))
-            }
-          />
-        </label>
-        <label className="field">
-          <span>{t("text.topP")}</span>
-          <NumberWheelInput
-            step={0.1}
-            value={draft.textProcessing.openai.topP}
-            onChange={(value) =>
-              updateDraft((prev) => ({
-                ...prev,
-                textProcessing: {
-                  ...prev.textProcessing,
-                  openai: {
-                    ...prev.textProcessing.openai,
-                    topP: value,
-                  },
-                },
-              }))
-            }
+            value={active.maxOutputTokens}
+            onChange={(value) => updateActive({ maxOutputTokens: value })}
           />
         </label>
+        {provider !== "claude" && (
+          <label className="field">
+            <span>{t("text.topP")}</span>
+            <NumberWheelInput
+              step={0.1}
+              value={active.topP}
+              onChange={(value) => updateActive({ topP: value })}
+            />
+          </label>
+        )}
       </SettingsCard>
     </>
   );
@@ -0,0 +1,31 @@
+import { useCallback, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+export interface DictationTestResult {
+  transcriptionText: string;
+  finalText: string;
+  modelGroup: string;
+  recordingMs: number;
+  transcriptionMs: number;
+  postProcessingMs: number;
+  errorMessage: string | null;
+}
+
+export function useDictationTest() {
+  const [result, setResult] = useState<DictationTestResult | null>(null);
+  const [testing, setTesting] = useState(false);
+
+  const runTest = useCallback(async () => {
+    setTesting(true);
+    setResult(null);
+    try {
+      const next = await invoke<DictationTestResult>("test_dictation");
+      setResult(next);
+      return next;
+    } finally {
+      setTesting(false);
+    }
+  }, []);
+
+  return { result, testing, runTest };
+}
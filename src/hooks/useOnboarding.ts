@@ -0,0 +1,59 @@
+import { useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { Settings } from "../types/settings";
+
+export type OnboardingProfile = "cloud" | "local-cpu" | "local-gpu";
+
+export interface Capabilities {
+  microphone: boolean;
+  microphoneName: string | null;
+  docker: boolean;
+  gpu: boolean;
+  gpuMemoryMib: number | null;
+  network: boolean;
+  recommendedProfile: OnboardingProfile;
+}
+
+export interface OnboardingProgress {
+  stage: string;
+  message: string;
+  percent: number;
+}
+
+export function useOnboarding() {
+  const [capabilities, setCapabilities] = useState<Capabilities | null>(null);
+  const [progress, setProgress] = useState<OnboardingProgress | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  useEffect(() => {
+    const unlisten = listen<OnboardingProgress>("onboarding-progress", (event) => {
+      setProgress(event.payload);
+    });
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const detectCapabilities = useCallback(async () => {
+    setLoading(true);
+    try {
+      const next = await invoke<Capabilities>("detect_capabilities");
+      setCapabilities(next);
+      return next;
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  const applyProfile = useCallback(async (kind: OnboardingProfile) => {
+    setLoading(true);
+    try {
+      return await invoke<Settings>("apply_recommended_profile", { kind });
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  return { capabilities, progress, loading, detectCapabilities, applyProfile };
+}
@@ -0,0 +1,39 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { TranscriptionProvider } from "../types/settings";
+
+export interface ProviderCapabilities {
+  provider: TranscriptionProvider;
+  streaming: boolean;
+  segments: boolean;
+  alignment: boolean;
+  offline: boolean;
+  languages: string[];
+  maxAudioSeconds: number | null;
+  maxUploadBytes: number | null;
+  options: string[];
+}
+
+export function useProviderCapabilities(provider: TranscriptionProvider, model: string) {
+  const [capabilities, setCapabilities] = useState<ProviderCapabilities | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    invoke<ProviderCapabilities>("get_provider_capabilities", { provider, model })
+      .then((next) => {
+        if (!cancelled) {
+          setCapabilities(next);
+        }
+      })
+      .catch(() => {
+        if (!cancelled) {
+          setCapabilities(null);
+        }
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [provider, model]);
+
+  return capabilities;
+}
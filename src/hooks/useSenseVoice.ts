@@ -27,6 +27,15 @@ export interface SenseVoiceProgress {
   detail?: string;
 }
 
+export interface RuntimeMetrics {
+  cpuPercent: number;
+  memoryUsedBytes: number;
+  memoryLimitBytes: number;
+  memoryPercent: number;
+  gpuUtilizationPercent: number | null;
+  gpuMemoryUsedMib: number | null;
+}
+
 interface SenseVoiceRuntimeLog {
   stream: "stdout" | "stderr";
   line: string;
@@ -82,6 +91,13 @@ export function useSenseVoice(monitoringEnabled = false) {
     }
   }, []);
 
+  const cancelPrepare = useCallback(async () => {
+    const next = await invoke<SenseVoiceStatus>("cancel_prepare");
+    setStatus(next);
+    setProgress(null);
+    return next;
+  }, []);
+
   const updateSettings = useCallback(async (sensevoice: SenseVoiceSettings) => {
     await invoke("update_sensevoice_settings", { sensevoice });
   }, []);
@@ -194,6 +210,7 @@ export function useSenseVoice(monitoringEnabled = false) {
     loading,
     refreshStatus,
     prepare,
+    cancelPrepare,
     updateSettings,
     startService,
     stopService,
@@ -1,5 +1,6 @@
 import { useCallback, useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import type { Settings } from "../types/settings";
 
 export function useSettings() {
@@ -23,6 +24,16 @@ export function useSettings() {
     loadSettings().finally(() => setLoading(false));
   }, [loadSettings]);
 
+  // Settings changed outside the main window, e.g. from the tray quick toggles
+  useEffect(() => {
+    const unlisten = listen<Settings>("settings-changed", (event) => {
+      setSettings(event.payload);
+    });
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
   return {
     settings,
     setSettings,
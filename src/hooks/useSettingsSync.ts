@@ -13,6 +13,14 @@ import {
   normalizeSenseVoiceDevice,
 } from "../utils/sensevoice";
 
+interface InstalledTriggerPack {
+  name: string;
+  added: number;
+  updated: number;
+  skipped: number;
+  settings: Settings;
+}
+
 interface UseSettingsSyncParams {
   settings: Settings | null;
   setSettings: (s: Settings) => void;
@@ -203,6 +211,25 @@ export function useSettingsSync({
     }
   };
 
+  const handleInstallTriggerPack = async (url: string) => {
+    try {
+      const data = await invoke<InstalledTriggerPack>("install_trigger_pack", { url });
+      setSettings(data.settings);
+      toast.success(
+        t("triggers.packInstalled", {
+          name: data.name,
+          added: data.added,
+          updated: data.updated,
+          skipped: data.skipped,
+        })
+      );
+      return true;
+    } catch (err) {
+      toast.error(t("triggers.packInstallError", { error: toErrorMessage(err) }));
+      return false;
+    }
+  };
+
   const handleExport = async () => {
     const path = await save({
       filters: [{ name: "JSON", extensions: ["json"] }],
@@ -218,5 +245,5 @@ export function useSettingsSync({
     }
   };
 
-  return { draft, updateDraft, handleImport, handleExport };
+  return { draft, updateDraft, handleImport, handleExport, handleInstallTriggerPack };
 }
@@ -49,15 +49,30 @@ const buildShortcut = (event: KeyboardEvent) => {
   return parts.join("+");
 };
 
+export interface PromptPresetShortcut {
+  id: string;
+  shortcut: string;
+}
+
 export function useShortcuts(
   shortcutKey: string | undefined,
-  onShortcutCaptured: (key: string) => void
+  onShortcutCaptured: (key: string) => void,
+  pasteResultKeys: string[] = [],
+  wakeToggleKey = "",
+  cancelKey = "",
+  literalKey = "",
+  promptPresetShortcuts: PromptPresetShortcut[] = []
 ) {
   const { t } = useTranslation();
   const tRef = useRef(t);
   useEffect(() => { tRef.current = t; }, [t]);
 
   const [isCapturing, setIsCapturing] = useState(false);
+  const pasteResultKeysValue = pasteResultKeys.join("\n");
+  const promptPresetShortcutsValue = promptPresetShortcuts
+    .filter((preset) => preset.shortcut)
+    .map((preset) => `${preset.id}\t${preset.shortcut}`)
+    .join("\n");
 
   useEffect(() => {
     if (!shortcutKey) {
@@ -71,10 +86,10 @@ export function useShortcuts(
     let keyDown = false;
     let inFlight = false;
 
-    const doStart = () => {
+    const doStart = (literal = false, promptPreset: string | null = null) => {
       if (inFlight) return;
       inFlight = true;
-      invoke("start_recording")
+      invoke("start_recording", { literal, promptPreset })
         .then(() => {
           logDebug("start_recording ok");
           isRecording = true;
@@ -95,8 +110,8 @@ export function useShortcuts(
       inFlight = true;
       isRecording = false;
       pressStartTime = null;
-      invoke("stop_recording")
-        .then(() => logDebug("stop_recording ok"))
+      invoke<number>("stop_recording")
+        .then((jobId) => logDebug("stop_recording ok", { jobId }))
         .catch((error) => {
           const message = toErrorMessage(error);
           logError("stop_recording failed", message);
@@ -107,6 +122,21 @@ export function useShortcuts(
         .finally(() => { inFlight = false; });
     };
 
+    const doCancel = () => {
+      if (inFlight) return;
+      inFlight = true;
+      isRecording = false;
+      pressStartTime = null;
+      invoke("cancel_recording")
+        .then(() => logDebug("cancel_recording ok"))
+        .catch((error) => {
+          const message = toErrorMessage(error);
+          logError("cancel_recording failed", message);
+          toast.error(tRef.current("shortcut.cancelError", { error: message }));
+        })
+        .finally(() => { inFlight = false; });
+    };
+
     const registerShortcut = async () => {
       try {
         await unregisterAll();
@@ -116,34 +146,42 @@ export function useShortcuts(
         toast.error(tRef.current("shortcut.unregisterError"));
       }
 
-      try {
-        await register(shortcutKey, (event: { state: string }) => {
-          if (!active) {
-            return;
-          }
-          logDebug("event", event.state);
+      const handleRecordingKey = (
+        state: string,
+        literal: boolean,
+        promptPreset: string | null = null
+      ) => {
+        if (!active) {
+          return;
+        }
+        logDebug("event", state);
 
-          if (event.state === "Pressed") {
-            if (keyDown) return;
-            keyDown = true;
+        if (state === "Pressed") {
+          if (keyDown) return;
+          keyDown = true;
 
-            if (!isRecording) {
-              doStart();
-            } else {
-              doStop();
-            }
+          if (!isRecording) {
+            doStart(literal, promptPreset);
+          } else {
+            doStop();
           }
+        }
 
-          if (event.state === "Released") {
-            keyDown = false;
+        if (state === "Released") {
+          keyDown = false;
 
-            if (isRecording && pressStartTime != null) {
-              const duration = Date.now() - pressStartTime;
-              if (duration >= LONG_PRESS_THRESHOLD_MS) {
-                doStop();
-              }
+          if (isRecording && pressStartTime != null) {
+            const duration = Date.now() - pressStartTime;
+            if (duration >= LONG_PRESS_THRESHOLD_MS) {
+              doStop();
             }
           }
+        }
+      };
+
+      try {
+        await register(shortcutKey, (event: { state: string }) => {
+          handleRecordingKey(event.state, false);
         });
         logDebug("register success", shortcutKey);
       } catch (error) {
@@ -155,6 +193,125 @@ export function useShortcuts(
           toast.error(tRef.current("shortcut.registerError", { error: message }));
         }
       }
+
+      if (literalKey && literalKey !== shortcutKey) {
+        try {
+          await register(literalKey, (event: { state: string }) => {
+            handleRecordingKey(event.state, true);
+          });
+          logDebug("register literal shortcut success", literalKey);
+        } catch (error) {
+          const message = toErrorMessage(error);
+          logError("register literal shortcut failed", message);
+          if (isConflictError(message)) {
+            toast.error(tRef.current("shortcut.conflict", { shortcut: literalKey }));
+          } else {
+            toast.error(tRef.current("shortcut.registerError", { error: message }));
+          }
+        }
+      }
+
+      const presetShortcuts = promptPresetShortcutsValue
+        ? promptPresetShortcutsValue.split("\n").map((line) => line.split("\t"))
+        : [];
+      for (const [presetId, presetKey] of presetShortcuts) {
+        if (presetKey === shortcutKey || presetKey === literalKey) {
+          continue;
+        }
+        try {
+          await register(presetKey, (event: { state: string }) => {
+            handleRecordingKey(event.state, false, presetId);
+          });
+          logDebug("register prompt preset shortcut success", presetKey);
+        } catch (error) {
+          const message = toErrorMessage(error);
+          logError("register prompt preset shortcut failed", message);
+          if (isConflictError(message)) {
+            toast.error(tRef.current("shortcut.conflict", { shortcut: presetKey }));
+          } else {
+            toast.error(tRef.current("shortcut.registerError", { error: message }));
+          }
+        }
+      }
+
+      if (wakeToggleKey && wakeToggleKey !== shortcutKey) {
+        try {
+          await register(wakeToggleKey, (event: { state: string }) => {
+            if (!active || event.state !== "Pressed") {
+              return;
+            }
+            invoke<boolean>("toggle_wake_on_speech")
+              .then((armed) => {
+                toast.success(
+                  tRef.current(armed ? "shortcut.wakeArmed" : "shortcut.wakeDisarmed")
+                );
+              })
+              .catch((error) => {
+                const message = toErrorMessage(error);
+                logError("toggle_wake_on_speech failed", message);
+                toast.error(tRef.current("shortcut.registerError", { error: message }));
+              });
+          });
+          logDebug("register wake toggle shortcut success", wakeToggleKey);
+        } catch (error) {
+          const message = toErrorMessage(error);
+          logError("register wake toggle shortcut failed", message);
+          if (isConflictError(message)) {
+            toast.error(tRef.current("shortcut.conflict", { shortcut: wakeToggleKey }));
+          } else {
+            toast.error(tRef.current("shortcut.registerError", { error: message }));
+          }
+        }
+      }
+
+      if (cancelKey && cancelKey !== shortcutKey) {
+        try {
+          await register(cancelKey, (event: { state: string }) => {
+            if (!active || event.state !== "Pressed") {
+              return;
+            }
+            doCancel();
+          });
+          logDebug("register cancel shortcut success", cancelKey);
+        } catch (error) {
+          const message = toErrorMessage(error);
+          logError("register cancel shortcut failed", message);
+          if (isConflictError(message)) {
+            toast.error(tRef.current("shortcut.conflict", { shortcut: cancelKey }));
+          } else {
+            toast.error(tRef.current("shortcut.registerError", { error: message }));
+          }
+        }
+      }
+
+      const pasteKeys = pasteResultKeysValue ? pasteResultKeysValue.split("\n") : [];
+      for (const [index, pasteKey] of pasteKeys.entries()) {
+        if (!pasteKey || pasteKey === shortcutKey) {
+          continue;
+        }
+        try {
+          // Paste on release so held modifiers do not combine with the simulated paste shortcut
+          await register(pasteKey, (event: { state: string }) => {
+            if (!active || event.state !== "Released") {
+              return;
+            }
+            invoke("paste_nth_result", { n: index + 1 }).catch((error) => {
+              const message = toErrorMessage(error);
+              logError("paste_nth_result failed", message);
+              toast.error(tRef.current("shortcut.pasteResultError", { error: message }));
+            });
+          });
+          logDebug("register paste result shortcut success", pasteKey);
+        } catch (error) {
+          const message = toErrorMessage(error);
+          logError("register paste result shortcut failed", message);
+          if (isConflictError(message)) {
+            toast.error(tRef.current("shortcut.conflict", { shortcut: pasteKey }));
+          } else {
+            toast.error(tRef.current("shortcut.registerError", { error: message }));
+          }
+        }
+      }
     };
 
     void registerShortcut();
@@ -168,7 +325,14 @@ export function useShortcuts(
         .then(() => logDebug("unregister all cleanup"))
         .catch((error) => logError("unregister cleanup failed", error));
     };
-  }, [shortcutKey]);
+  }, [
+    shortcutKey,
+    pasteResultKeysValue,
+    wakeToggleKey,
+    cancelKey,
+    literalKey,
+    promptPresetShortcutsValue,
+  ]);
 
   useEffect(() => {
     if (!isCapturing) {
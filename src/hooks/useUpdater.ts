@@ -52,7 +52,7 @@ export function useUpdater() {
   const installUpdate = async () => {
     try {
       setStatus((prev) => ({ ...prev, status: "installing", error: null }));
-      await invoke("install_downloaded_update");
+      await invoke("install_update");
     } catch (error) {
       toast.error(t("updater.installError", { error: toErrorMessage(error) }));
     }
@@ -60,7 +60,7 @@ export function useUpdater() {
 
   const retryUpdateCheck = async () => {
     try {
-      await invoke("retry_update_check");
+      await invoke("check_for_updates");
     } catch (error) {
       toast.error(t("updater.retryError", { error: toErrorMessage(error) }));
     }
@@ -0,0 +1,22 @@
+import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+
+interface WindowVisibilityPayload {
+  hidden: boolean;
+}
+
+// 主窗口隐藏到托盘时为 true，此时界面应释放缓存并暂停轮询
+export function useWindowHidden() {
+  const [hidden, setHidden] = useState(false);
+
+  useEffect(() => {
+    const unlisten = listen<WindowVisibilityPayload>("main-window-visibility", (event) => {
+      setHidden(event.payload.hidden);
+    });
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  return hidden;
+}
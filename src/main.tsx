@@ -1,14 +1,28 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
 import { I18nextProvider } from "react-i18next";
+import { getCurrentWindow } from "@tauri-apps/api/window";
 import App from "./App";
+import { ConfirmWindow } from "./components/ConfirmWindow";
+import { VariablePickerWindow } from "./components/VariablePickerWindow";
 import i18n from "./i18n";
 import "./styles/tailwind.css";
 
+function windowContent(label: string) {
+  switch (label) {
+    case "confirm":
+      return <ConfirmWindow />;
+    case "variable-picker":
+      return <VariablePickerWindow />;
+    default:
+      return <App />;
+  }
+}
+
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
     <I18nextProvider i18n={i18n}>
-      <App />
+      {windowContent(getCurrentWindow().label)}
     </I18nextProvider>
   </React.StrictMode>,
 );
@@ -1,4 +1,6 @@
-export type TriggerMatchMode = "keyword" | "auto";
+import type { TranscriptionProvider } from "./settings";
+
+export type TriggerMatchMode = "keyword" | "auto" | "language";
 
 export interface TriggerMatch {
   triggerId: string;
@@ -8,7 +10,7 @@ export interface TriggerMatch {
   mode: TriggerMatchMode;
 }
 
-export type TranscriptionHistoryStatus = "success" | "failed";
+export type TranscriptionHistoryStatus = "success" | "partial" | "failed" | "pending";
 
 export interface TranscriptionAlignment {
   tokens: string[];
@@ -29,5 +31,51 @@ export interface TranscriptionHistoryItem {
   triggeredByKeyword: boolean;
   triggerMatches: TriggerMatch[];
   alignment?: TranscriptionAlignment;
+  confidence?: number;
+  metadata?: SpeechMetadata;
+  diff?: TextDiffSpan[];
   errorMessage?: string;
+  provider?: TranscriptionProvider | null;
+  model?: string;
+  segmentCount?: number;
+  stageLatency?: StageLatency;
+  audioPath?: string | null;
+  sourceId?: string | null;
+  jobId?: number;
+}
+
+export interface StageLatency {
+  transcriptionMs: number;
+  textProcessingMs: number;
+  outputMs: number;
+}
+
+export interface TranscriptionHistorySummary {
+  id: string;
+  timestampMs: number;
+  status: TranscriptionHistoryStatus;
+  preview: string;
+  truncated: boolean;
+  jobId?: number;
+}
+
+export interface TextDiffSpan {
+  kind: "equal" | "insert" | "delete";
+  text: string;
+}
+
+export interface SpeechMetadata {
+  language?: string;
+  emotion?: string;
+  events: string[];
+}
+
+export type JobState = "queued" | "transcribing" | "completed" | "failed";
+
+export interface JobStatus {
+  id: number;
+  state: JobState;
+  status: string;
+  historyId: string | null;
+  error: string | null;
 }
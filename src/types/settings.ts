@@ -5,30 +5,166 @@ export type TranscriptionProvider =
   | "aliyun-asr"
   | "aliyun-paraformer";
 
-export type TextProcessingProvider = "openai";
+export type TextProcessingProvider = "openai" | "claude" | "ollama" | "local";
 
 export interface Settings {
   shortcut: ShortcutSettings;
   recording: RecordingSettings;
   provider: TranscriptionProvider;
+  lastCloudProvider: TranscriptionProvider;
   openai: OpenAiSettings;
   textProcessing: TextProcessingSettings;
   volcengine: VolcengineSettings;
   sensevoice: SenseVoiceSettings;
   aliyun: AliyunSettings;
   triggers: TriggerCard[];
+  triggerPacks: TriggerPackSettings;
   output: OutputSettings;
   appearance: AppearanceSettings;
   startup: StartupSettings;
   history: HistorySettings;
+  providerRules: ProviderRulesSettings;
+  durationRouting: DurationRoutingSettings;
+  offline: OfflineSettings;
+  redaction: RedactionSettings;
+  templates: DictationTemplate[];
+  activeTemplateId: string;
+  promptPresets: PromptPreset[];
+  activePromptPresetId: string;
+  languageRouting: LanguageRoutingSettings;
+  audioPipeline: AudioPipelineSettings;
+  contextCarryover: ContextCarryoverSettings;
+  wakeOnSpeech: WakeOnSpeechSettings;
+  presence: PresenceSettings;
+  tts: TtsSettings;
+  corrections: CorrectionSettings;
 }
 
+export interface CorrectionSettings {
+  enabled: boolean;
+  minOccurrences: number;
+  entries: CorrectionEntry[];
+}
+
+export interface CorrectionEntry {
+  original: string;
+  corrected: string;
+  count: number;
+}
+
+export interface TtsSettings {
+  enabled: boolean;
+  provider: "platform" | "openai";
+  voice: string;
+  openaiModel: string;
+}
+
+export interface PresenceSettings {
+  pauseWhenAway: boolean;
+  idleMinutes: number;
+}
+
+export interface WakeOnSpeechSettings {
+  thresholdDb: number;
+  startMs: number;
+  stopSilenceMs: number;
+  maxRecordingSeconds: number;
+}
+
+export interface ContextCarryoverSettings {
+  enabled: boolean;
+  maxChars: number;
+  includeInTextProcessing: boolean;
+  useWindowTitle: boolean;
+}
+
+export interface AudioPipelineSettings {
+  enabled: boolean;
+  steps: AudioPipelineStep[];
+}
+
+export type AudioPipelineStep =
+  | { type: "trim"; thresholdDb: number; paddingMs: number }
+  | { type: "denoise"; thresholdDb: number }
+  | { type: "normalize"; targetDb: number }
+  | { type: "resample"; sampleRate: number; mono: boolean }
+  | { type: "tempo"; rate: number };
+
 export interface ShortcutSettings {
   key: string;
+  pasteResultKeys: string[];
+  wakeToggleKey: string;
+  cancelKey: string;
+  literalKey: string;
 }
 
 export interface RecordingSettings {
   segmentSeconds: number;
+  segmentOverlapMs: number;
+  segmentRetryAttempts: number;
+  failedSegmentPlaceholder: string;
+  segmentRetryQueue: boolean;
+  scratchDir: string;
+  takeover: "ignore" | "restart" | "queue";
+  inputDevice: string;
+  mixInputDevices: string[];
+  countdownMs: number;
+  deviceProfiles: DeviceProfile[];
+  bufferFrames: number;
+  maxDurationSeconds: number;
+  endOfSpeech: EndOfSpeechSettings;
+  noiseSuppression: boolean;
+  autoGain: boolean;
+  autoGainTargetDb: number;
+  silenceTrim: SilenceTrimSettings;
+  storageQuotaMb: number;
+  keepOriginalFormat: boolean;
+  uploadFormat: "wav" | "ogg-opus";
+  roamRecordings: boolean;
+  keepRawAudio: boolean;
+  rawAudioRetentionDays: number;
+  preRoll: boolean;
+  preRollMs: number;
+  captureSystemAudio: boolean;
+  micTrackLabel: string;
+  systemTrackLabel: string;
+  monitor: boolean;
+  monitorVolume: number;
+  keepStreamWarm: boolean;
+}
+
+export interface SilenceTrimSettings {
+  enabled: boolean;
+  thresholdDb: number;
+  maxPauseMs: number;
+}
+
+export interface EndOfSpeechSettings {
+  enabled: boolean;
+  thresholdDb: number;
+  trailingSilenceMs: number;
+  minSpeechMs: number;
+}
+
+export interface DeviceProfile {
+  deviceName: string;
+  gainDb: number;
+  denoise: boolean;
+  sampleRate: number;
+}
+
+export interface InputDeviceInfo {
+  name: string;
+  isDefault: boolean;
+}
+
+export interface StorageUsage {
+  deferredBytes: number;
+  retryQueueBytes: number;
+  archiveBytes: number;
+  scratchBytes: number;
+  totalBytes: number;
+  quotaBytes: number | null;
 }
 
 export interface OpenAiSettings {
@@ -54,6 +190,9 @@ export interface SpeechToTextSettings {
 export interface TextProcessingSettings {
   provider: TextProcessingProvider;
   openai: TextSettings;
+  claude: TextSettings;
+  ollama: TextSettings;
+  local: TextSettings;
 }
 
 export interface TextSettings {
@@ -66,6 +205,10 @@ export interface TextSettings {
   instructions: string;
 }
 
+export interface TriggerPackSettings {
+  trustedKeys: string[];
+}
+
 export interface TriggerCard {
   id: string;
   title: string;
@@ -75,10 +218,66 @@ export interface TriggerCard {
   keyword: string;
   promptTemplate: string;
   variables: string[];
+  activeAppPattern: string;
+  textBackend: string;
+  responseLanguage: string;
+  pickVariable: boolean;
+  cooldownSeconds: number;
+  requireConfirmation: boolean;
+}
+
+export interface LanguageRoutingSettings {
+  enabled: boolean;
+  rules: LanguageRoute[];
+}
+
+export interface LanguageRoute {
+  language: string;
+  triggerId: string;
+}
+
+export interface PromptPreset {
+  id: string;
+  name: string;
+  prompt: string;
+  hotwords: string[];
+  shortcut: string;
+}
+
+export interface DictationTemplate {
+  id: string;
+  title: string;
+  body: string;
 }
 
 export interface OutputSettings {
   removeNewlines: boolean;
+  confidenceThreshold: number;
+  confirmLowConfidence: boolean;
+  itn: ItnSettings;
+  punctuation: PunctuationSettings;
+  pasteHistorySize: number;
+  confirmBeforePaste: boolean;
+  maxPastesPerSecond: number;
+  pasteAfterTranscribe: boolean;
+  guardSecureFields: boolean;
+}
+
+export interface ItnSettings {
+  enabled: boolean;
+  chineseNumerals: boolean;
+  englishNumbers: boolean;
+}
+
+export type PunctuationWidth = "auto" | "fullWidth" | "halfWidth";
+
+export type PunctuationAdjustment = "keep" | "add" | "remove";
+
+export interface PunctuationSettings {
+  enabled: boolean;
+  width: PunctuationWidth;
+  serialComma: PunctuationAdjustment;
+  cjkLatinSpacing: PunctuationAdjustment;
 }
 
 export interface AppearanceSettings {
@@ -89,10 +288,44 @@ export interface StartupSettings {
   launchOnBoot: boolean;
   autoCheckUpdates: boolean;
   autoInstallUpdatesOnQuit: boolean;
+  updateChannel: "stable" | "beta";
 }
 
+export type HistoryEventPayload = "full" | "summary";
+
 export interface HistorySettings {
   enabled: boolean;
+  eventPayload: HistoryEventPayload;
+}
+
+export type PowerCondition = "any" | "ac" | "battery";
+
+export type NetworkCondition = "any" | "online" | "offline";
+
+export interface ProviderRule {
+  id: string;
+  power: PowerCondition;
+  network: NetworkCondition;
+  provider: TranscriptionProvider;
+}
+
+export interface ProviderRulesSettings {
+  enabled: boolean;
+  rules: ProviderRule[];
+}
+
+export interface DurationRoutingSettings {
+  enabled: boolean;
+  shortMaxSeconds: number;
+  shortProvider: TranscriptionProvider | null;
+  longMinSeconds: number;
+  longProvider: TranscriptionProvider | null;
+}
+
+export type OfflineFallback = "disabled" | "fail" | "local" | "queue";
+
+export interface OfflineSettings {
+  fallback: OfflineFallback;
 }
 
 export interface VolcengineSettings {
@@ -101,6 +334,10 @@ export interface VolcengineSettings {
   useStreaming: boolean;
   useFast: boolean;
   language: string;
+  boostingTableName: string;
+  hotwords: string[];
+  enablePunctuation: boolean;
+  enableItn: boolean;
 }
 
 export interface SenseVoiceSettings {
@@ -114,6 +351,19 @@ export interface SenseVoiceSettings {
   device: string;
   downloadState: string;
   lastError: string;
+  health: SenseVoiceHealthSettings;
+  autoStartDocker: boolean;
+  apiToken: string;
+  warmupOnStart: boolean;
+  quantization: "none" | "awq" | "gptq" | "fp8";
+  batteryPolicy: "allow" | "refuse" | "prefer-cpu";
+}
+
+export interface SenseVoiceHealthSettings {
+  intervalMs: number;
+  requestTimeoutSecs: number;
+  warmupWarnSecs: number;
+  startupTimeoutSecs: number;
 }
 
 export interface AliyunSettings {
@@ -136,3 +386,12 @@ export interface AliyunParaformerSettings {
   languageHints: string[];
   vocabularyId: string;
 }
+
+export interface RedactionSettings {
+  enabled: boolean;
+  wordlist: string[];
+  emails: boolean;
+  phoneNumbers: boolean;
+  creditCards: boolean;
+  replacement: string;
+}